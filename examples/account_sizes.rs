@@ -0,0 +1,35 @@
+//! Prints the size and rent of every account the program stores.
+//!
+//! Accounts that don't exist yet can be added to the table as `name=on_chain_size` arguments, such as
+//! `cargo run --example account_sizes -- Tournament=512`.
+
+use cruiser::solana_program::native_token::lamports_to_sol;
+use cruiser_tutorial::sizes::{account_sizes, AccountSize};
+use std::env;
+
+fn main() {
+    let mut sizes = account_sizes();
+    for arg in env::args().skip(1) {
+        let (name, size) = arg
+            .split_once('=')
+            .unwrap_or_else(|| panic!("expected `name=on_chain_size`, got `{}`", arg));
+        let size = size
+            .parse()
+            .unwrap_or_else(|_| panic!("`{}` isn't a size in bytes", size));
+        sizes.push(AccountSize::planned(format!("{} (planned)", name), size));
+    }
+
+    println!(
+        "{:<28} {:>10} {:>14} {:>12}",
+        "account", "bytes", "lamports", "SOL"
+    );
+    for size in sizes {
+        println!(
+            "{:<28} {:>10} {:>14} {:>12.6}",
+            size.name,
+            size.data_len,
+            size.rent_exempt,
+            lamports_to_sol(size.rent_exempt)
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Glue for [SPL account compression](https://github.com/solana-labs/solana-program-library/tree/master/account-compression)
+//! Merkle trees, used to archive finished games.
+//!
+//! Only the parts of the compression program's interface this program uses are mirrored here,
+//! so there is no dependency on the SPL crates. Trees are created with the SPL tooling and then
+//! handed over to the [`ArchiveAuthoritySeeder`](crate::pda::ArchiveAuthoritySeeder) PDA.
+
+use cruiser::prelude::*;
+
+/// The SPL account compression program.
+pub const ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    9, 42, 19, 238, 149, 196, 28, 186, 8, 166, 127, 90, 198, 126, 141, 247, 225, 218, 17, 98, 94,
+    29, 100, 19, 127, 143, 79, 35, 131, 3, 127, 20,
+]);
+
+/// The SPL noop program the compression program logs tree changes through.
+pub const NOOP_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    11, 188, 15, 192, 187, 71, 202, 47, 116, 196, 17, 46, 148, 171, 19, 207, 163, 198, 52, 229,
+    220, 23, 234, 203, 3, 205, 26, 35, 205, 126, 120, 124,
+]);
+
+/// The Anchor discriminant of the compression program's `append` instruction.
+const APPEND_DISCRIMINANT: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+
+/// CPI for the compression program's `append`.
+#[derive(Debug)]
+pub struct AppendCPI<'a, AI> {
+    accounts: [MaybeOwned<'a, AI>; 3],
+    data: Vec<u8>,
+}
+impl<'a, AI> AppendCPI<'a, AI> {
+    /// Appends `leaf` to `merkle_tree`. `authority` must be the tree's authority.
+    pub fn new(
+        merkle_tree: impl Into<MaybeOwned<'a, AI>>,
+        authority: impl Into<MaybeOwned<'a, AI>>,
+        noop_program: impl Into<MaybeOwned<'a, AI>>,
+        leaf: [u8; 32],
+    ) -> Self {
+        let mut data = APPEND_DISCRIMINANT.to_vec();
+        data.extend(leaf);
+        Self {
+            accounts: [merkle_tree.into(), authority.into(), noop_program.into()],
+            data,
+        }
+    }
+}
+impl<'a, AI> AppendCPI<'a, AI>
+where
+    AI: ToSolanaAccountMeta,
+{
+    /// Gets the instruction and accounts to call the compression program with.
+    pub fn instruction(
+        self,
+        compression_program: impl Into<MaybeOwned<'a, AI>>,
+    ) -> InstructionAndAccounts<[MaybeOwned<'a, AI>; 4]> {
+        let compression_program = compression_program.into();
+        let instruction = SolanaInstruction {
+            program_id: *compression_program.meta_key(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(MaybeOwned::as_ref)
+                .map(AI::to_solana_account_meta)
+                .collect(),
+            data: self.data,
+        };
+        let [merkle_tree, authority, noop_program] = self.accounts;
+        InstructionAndAccounts {
+            instruction,
+            accounts: [merkle_tree, authority, noop_program, compression_program],
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::custom_error;
+use cruiser::prelude::*;
+
+/// A pool of lamports paid out to claims published as a Merkle root, see [`merkle`](crate::merkle).
+///
+/// The pool is held in this account's own lamports past its rent, so claims are paid straight out of it.
+/// Each claim is made once, recorded by its [`ClaimReceipt`].
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct Distribution {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// Who created the distribution.
+    pub authority: Pubkey,
+    /// The root of the tree of claims.
+    pub root: [u8; 32],
+    /// The lamports put into the pool, the sum of all the claims.
+    pub total: u64,
+    /// The number of claims in the tree.
+    pub claims: u64,
+    /// The lamports claimed so far.
+    pub claimed: u64,
+    /// The number of claims made so far.
+    pub claims_made: u64,
+    /// When the distribution was created.
+    pub created_at: UnixTimestamp,
+}
+impl Distribution {
+    /// Creates a new distribution of `total` lamports over `claims` claims under `root`.
+    pub fn new(authority: &Pubkey, root: [u8; 32], total: u64, claims: u64) -> Self {
+        Self {
+            version: 0,
+            authority: *authority,
+            root,
+            total,
+            claims,
+            claimed: 0,
+            claims_made: 0,
+            created_at: 0,
+        }
+    }
+
+    /// Records the claim at `index` of `amount`, erroring if it would go past the distribution's totals.
+    pub fn claim(&mut self, index: u64, amount: u64) -> CruiserResult<()> {
+        if index >= self.claims {
+            return Err(custom_error!(
+                "claim {} is past the distribution's {} claims",
+                index,
+                self.claims
+            )
+            .into());
+        }
+        let claimed = self
+            .claimed
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= self.total)
+            .ok_or(custom_error!(
+                "claim of {} is past the distribution's total",
+                amount
+            ))?;
+        self.claimed = claimed;
+        self.claims_made += 1;
+        Ok(())
+    }
+}
+
+/// The record of a claim made on a [`Distribution`],
+/// at the [`ClaimReceiptSeeder`](crate::pda::ClaimReceiptSeeder) PDA for the distribution and claim index.
+/// Its existence stops the claim being made again.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct ClaimReceipt {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The distribution claimed from.
+    pub distribution: Pubkey,
+    /// The claim's index in the tree.
+    pub index: u64,
+    /// Who was paid.
+    pub claimant: Pubkey,
+    /// The lamports paid.
+    pub amount: u64,
+    /// When the claim was made.
+    pub claimed_at: UnixTimestamp,
+}
+impl ClaimReceipt {
+    /// Creates the receipt for the claim at `index` of `distribution`.
+    pub fn new(distribution: &Pubkey, index: u64, claimant: &Pubkey, amount: u64) -> Self {
+        Self {
+            version: 0,
+            distribution: *distribution,
+            index,
+            claimant: *claimant,
+            amount,
+            claimed_at: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_claim() {
+        let mut distribution = Distribution::new(&Pubkey::new_unique(), [0; 32], 100, 2);
+        assert!(distribution.claim(2, 10).is_err());
+        assert!(distribution.claim(0, 101).is_err());
+        assert!(distribution.claim(0, 60).is_ok());
+        assert!(distribution.claim(1, 41).is_err());
+        assert!(distribution.claim(1, 40).is_ok());
+        assert_eq!(distribution.claimed, 100);
+        assert_eq!(distribution.claims_made, 2);
+    }
+}
@@ -1,7 +1,11 @@
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
 use cruiser::prelude::*;
+use cruiser::solana_program::hash::{hash, hashv};
+use std::cmp::Ordering;
 
 /// The game board.
-#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
 pub struct Game {
     /// The version of this account. Should always add this for future proofing.
     /// Should be 0 until a new version is added.
@@ -12,31 +16,100 @@ pub struct Game {
     pub player2: Pubkey,
     /// Which player was the creator and entitled to the rent.
     pub creator: Player,
+    /// Whether the first player to move is picked at random when the opponent joins.
+    /// Otherwise [`Player::One`] always moves first.
+    pub random_first_player: bool,
+    /// The Switchboard VRF account that will decide the first player,
+    /// see [`RequestFirstPlayerVrf`](crate::instructions::RequestFirstPlayerVrf).
+    /// The zero key if none is pending.
+    pub first_player_vrf: Pubkey,
+    /// The SHA-256 hash of the code a joiner must know, see [`Game::hash_join_code`].
+    /// All zeros if anyone can join.
+    pub join_code_hash: [u8; 32],
+    /// The profiles invited to join. Unused slots are the zero key, if all are unused anyone can join.
+    /// Ignored if the other player was locked in when the game was created.
+    pub invited: [Pubkey; Game::MAX_INVITED],
     /// The player to take the next move.
     pub next_play: Player,
     /// The bump of the signer that holds the wager.
     pub signer_bump: u8,
-    /// The wager per player in lamports.
+    /// The creator's wager in lamports.
     pub wager: u64,
+    /// The joiner's wager in lamports. Differs from `wager` when the game has odds.
+    pub joiner_wager: u64,
     /// The amount of time in seconds to play a given turn before forfeiting.
-    /// 0 means no time limit.
+    /// 0 means the game is untimed and can't be forfeited.
     pub turn_length: UnixTimestamp,
+    /// How long in seconds the game can wait for a joiner before anyone can sweep it.
+    /// 0 means it waits forever.
+    pub join_ttl: UnixTimestamp,
+    /// When the game was created.
+    pub created_at: UnixTimestamp,
     /// The last turn timestamp. If 0 game is not started.
     pub last_turn: UnixTimestamp,
     /// The last move a player did. If `[3,3]` last move is game start.
     pub last_move: [u8; 2],
-    /// The current board. In RC format.
-    pub board: Board<Board<Space>>,
+    /// The middle board index of the last move. Only used by depth-3 games, where the next move is
+    /// forced onto the middle board at `[last_move_middle][last_move]`.
+    pub last_move_middle: [u8; 2],
+    /// The current board. In RC format. Its shape depends on the game's [`GameVariant`].
+    pub board: GameBoard,
+    /// The optional rules the game is played with.
+    pub rules: RuleSet,
+    /// The automation thread cranking [`CrankForfeit`](crate::instructions::CrankForfeit) for this game.
+    /// The zero key if none is registered.
+    pub forfeit_thread: Pubkey,
+    /// Whether the game is played without wagers. Friendly games have no [`WagerVault`](crate::accounts::WagerVault)
+    /// and move no lamports but still count towards records and elo.
+    pub friendly: bool,
+    /// The move queued by the player waiting on their opponent, see [`QueuePremove`](crate::instructions::QueuePremove).
+    /// [`Game::NO_PREMOVE`] if none is queued.
+    pub premove: MakeMoveData,
+    /// The hash of the move the player to move committed to in a [`RuleSet::commit_reveal`] game, see [`Game::hash_move`].
+    /// All zeros if none is committed.
+    pub move_commitment: [u8; 32],
+    /// The settlement of the game played off-chain, waiting out its dispute window.
+    /// See [`SettleSignedGame`](crate::instructions::SettleSignedGame).
+    pub settlement: PendingSettlement,
+    /// Set once the game is finished by play or settlement, until [`ArchiveGame`](crate::instructions::ArchiveGame) closes it.
+    pub finished: FinishedGame,
+    /// When the locked other player's chance to join runs out, after which the creator can
+    /// [`UnlockGame`](crate::instructions::UnlockGame). 0 if the other player isn't locked or can join any time.
+    pub lock_expires_at: UnixTimestamp,
+    /// Whether the game rejects instructions superseded by a newer version, such as [`MakeMove`](crate::instructions::MakeMove).
+    /// Set from [`ProgramConfig::reject_deprecated_instructions`](crate::accounts::ProgramConfig::reject_deprecated_instructions)
+    /// when the game is created.
+    pub rejects_deprecated: bool,
+    /// The program notified when the game settles, see [`settlement_hook`](crate::settlement_hook).
+    /// Set from [`ProgramConfig::settlement_hook`](crate::accounts::ProgramConfig::settlement_hook) by [`CreateGame`](crate::instructions::CreateGame),
+    /// games against the house have none. The zero key if there is none.
+    pub settlement_hook: Pubkey,
 }
 
 impl Game {
+    /// The maximum number of profiles that can be invited to a game.
+    pub const MAX_INVITED: usize = 8;
+    /// The maximum length in bytes of a [`PostGameMessage`](crate::instructions::PostGameMessage).
+    pub const MAX_MESSAGE_LEN: usize = 140;
+    /// [`Game::premove`] when none is queued.
+    pub const NO_PREMOVE: MakeMoveData = MakeMoveData {
+        big_board: [3, 3],
+        middle_board: [3, 3],
+        small_board: [3, 3],
+    };
+
     /// Creates a new game board.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         player_profile: &Pubkey,
         player: Player,
         signer_bump: u8,
         wager: u64,
+        joiner_wager: u64,
         turn_length: UnixTimestamp,
+        join_ttl: UnixTimestamp,
+        variant: GameVariant,
+        rules: RuleSet,
     ) -> Self {
         Self {
             version: 0,
@@ -52,13 +125,39 @@ impl Game {
                 Pubkey::new_from_array([0; 32])
             },
             creator: player,
+            random_first_player: false,
+            first_player_vrf: Pubkey::new_from_array([0; 32]),
+            join_code_hash: [0; 32],
+            invited: [Pubkey::new_from_array([0; 32]); Self::MAX_INVITED],
             next_play: Player::One,
             signer_bump,
             wager,
+            joiner_wager,
             turn_length,
+            join_ttl,
+            created_at: 0,
             last_turn: 0,
             last_move: [3, 3],
-            board: Default::default(),
+            last_move_middle: [3, 3],
+            board: GameBoard::new(variant),
+            rules,
+            forfeit_thread: Pubkey::new_from_array([0; 32]),
+            friendly: false,
+            premove: Self::NO_PREMOVE,
+            move_commitment: [0; 32],
+            settlement: PendingSettlement::none(),
+            finished: FinishedGame::default(),
+            lock_expires_at: 0,
+            rejects_deprecated: false,
+            settlement_hook: Pubkey::new_from_array([0; 32]),
+        }
+    }
+
+    /// The profile of the player that created the game.
+    pub fn creator_profile(&self) -> &Pubkey {
+        match self.creator {
+            Player::One => &self.player1,
+            Player::Two => &self.player2,
         }
     }
 
@@ -67,16 +166,675 @@ impl Game {
         self.last_turn > 0
     }
 
-    /// Tells whether the other player is valid to join the game.
-    pub fn is_valid_other_player(&self, other_player: &Pubkey) -> bool {
-        match self.creator {
-            Player::One => {
-                self.player2 == *other_player || self.player2 == Pubkey::new_from_array([0; 32])
+    /// The profile playing as `player`, zeroed before the game is joined and once it finishes.
+    pub fn player(&self, player: Player) -> &Pubkey {
+        match player {
+            Player::One => &self.player1,
+            Player::Two => &self.player2,
+        }
+    }
+
+    /// The wager `player` put into the game.
+    pub fn stake(&self, player: Player) -> u64 {
+        if player == self.creator {
+            self.wager
+        } else {
+            self.joiner_wager
+        }
+    }
+
+    /// The rules the game is played with.
+    pub fn variant(&self) -> GameVariant {
+        self.board.variant()
+    }
+
+    /// The player that won the game, if any.
+    pub fn winner(&self) -> Option<Player> {
+        match &self.board {
+            GameBoard::Classic(board) => board.current_winner(),
+            GameBoard::Ultimate(board) => board.current_winner(),
+            GameBoard::Deep(board) => board.current_winner(),
+        }
+    }
+
+    /// Makes a move for `player`, checking for a winner.
+    /// The indexes a variant doesn't use are ignored, see [`GameBoard::get_space`].
+    /// Check the move is valid first, out of range indexes panic.
+    pub fn make_move(
+        &mut self,
+        player: Player,
+        big_board: [u8; 2],
+        middle_board: [u8; 2],
+        small_board: [u8; 2],
+    ) -> CruiserResult<()> {
+        match &mut self.board {
+            GameBoard::Classic(board) => board.make_move(player, (small_board, ())),
+            GameBoard::Ultimate(board) => board.make_move(player, (big_board, (small_board, ()))),
+            GameBoard::Deep(board) => {
+                board.make_move(player, (big_board, (middle_board, (small_board, ()))))
             }
-            Player::Two => {
-                self.player1 == *other_player || self.player1 == Pubkey::new_from_array([0; 32])
+        }
+    }
+
+    /// Plays and clears the queued premove for the player to move, see [`QueuePremove`](crate::instructions::QueuePremove).
+    /// It's dropped instead if it's no longer legal or would end the game, as only its player can give the accounts to pay out.
+    /// If it was played the turn still has to be handed over.
+    pub fn play_premove(&mut self) -> CruiserResult<PremoveOutcome> {
+        let premove = std::mem::replace(&mut self.premove, Self::NO_PREMOVE);
+        if premove == Self::NO_PREMOVE {
+            return Ok(PremoveOutcome::NotQueued);
+        }
+        if !self.is_valid_move(&premove) {
+            return Ok(PremoveOutcome::Illegal(premove));
+        }
+        let board = self.board.clone();
+        self.make_move(
+            self.next_play,
+            premove.big_board,
+            premove.middle_board,
+            premove.small_board,
+        )?;
+        if self.winner().is_some() || self.is_draw() {
+            self.board = board;
+            return Ok(PremoveOutcome::EndsGame(premove));
+        }
+        Ok(PremoveOutcome::Played(premove))
+    }
+
+    /// Where the next move must be played as `(big_board, middle_board)`, [`None`] if anywhere.
+    /// Ultimate games always give a `middle_board` of `[0, 0]`, classic games have no forced board.
+    pub fn forced_board(&self) -> Option<([u8; 2], [u8; 2])> {
+        if self.last_move == [3, 3] {
+            return None;
+        }
+        match &self.board {
+            GameBoard::Classic(_) => None,
+            GameBoard::Ultimate(board) => self
+                .rules
+                .forced_index(index_to_position(self.last_move), 9, |position| match board
+                    .get(position_to_index(position))
+                {
+                    Some(board) if board.is_full() => Some(ClosedBoard::Full),
+                    Some(board) if board.current_winner().is_none() => None,
+                    _ => Some(ClosedBoard::Solved),
+                })
+                .map(|position| (position_to_index(position), [0, 0])),
+            GameBoard::Deep(board) => self
+                .rules
+                .forced_index(
+                    index_to_position(self.last_move_middle) * 9
+                        + index_to_position(self.last_move),
+                    81,
+                    |position| {
+                        // A middle board inside a solved big board counts as solved.
+                        match board
+                            .get(position_to_index(position / 9))
+                            .and_then(|board| board.get(position_to_index(position % 9)))
+                        {
+                            Some(board) if board.is_full() => Some(ClosedBoard::Full),
+                            Some(board) if board.current_winner().is_none() => None,
+                            _ => Some(ClosedBoard::Solved),
+                        }
+                    },
+                )
+                .map(|position| {
+                    (
+                        position_to_index(position / 9),
+                        position_to_index(position % 9),
+                    )
+                }),
+        }
+    }
+
+    /// The player that wins a game with no moves left under [`DrawRule::MostBoards`].
+    /// [`None`] if the game is a plain draw.
+    pub fn draw_winner(&self) -> Option<Player> {
+        if self.rules.draw != DrawRule::MostBoards {
+            return None;
+        }
+        let mut counts = [0usize; 2];
+        let mut count = |winner: Option<Player>| match winner {
+            Some(Player::One) => counts[0] += 1,
+            Some(Player::Two) => counts[1] += 1,
+            None => {}
+        };
+        match &self.board {
+            GameBoard::Classic(_) => {}
+            GameBoard::Ultimate(Board::Unsolved(boards)) => boards
+                .iter()
+                .flatten()
+                .for_each(|board| count(board.current_winner())),
+            GameBoard::Deep(Board::Unsolved(boards)) => boards
+                .iter()
+                .flatten()
+                .for_each(|board| count(board.current_winner())),
+            GameBoard::Ultimate(Board::Solved(_)) | GameBoard::Deep(Board::Solved(_)) => {}
+        }
+        match counts[0].cmp(&counts[1]) {
+            Ordering::Greater => Some(Player::One),
+            Ordering::Less => Some(Player::Two),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// Tells whether the game ended in a draw, no space is left to play on and there is no winner.
+    /// A sub-board that is solved or full can't be played on.
+    /// Under [`DrawRule::MostBoards`] this can still have a [`Game::draw_winner`].
+    pub fn is_draw(&self) -> bool {
+        match &self.board {
+            GameBoard::Classic(board) => board.is_full(),
+            GameBoard::Ultimate(board) => board.is_full(),
+            GameBoard::Deep(board) => board.is_full(),
+        }
+    }
+
+    /// The result of the game if it has ended.
+    pub fn result(&self) -> Option<GameResult> {
+        if self.is_draw() {
+            Some(
+                self.draw_winner()
+                    .map_or(GameResult::Drawn, GameResult::Won),
+            )
+        } else {
+            self.winner().map(GameResult::Won)
+        }
+    }
+
+    /// Tells whether a settlement is waiting out its dispute window. No moves can be made until it's finalized or challenged.
+    pub fn is_settling(&self) -> bool {
+        self.settlement.posted_at > 0
+    }
+
+    /// Finishes a game that ended with `result`, leaving it for [`ArchiveGame`](crate::instructions::ArchiveGame).
+    /// The players are burned so nothing else can act on the game.
+    /// Returns what is archived, its [`ArchivedGame::leaf`] is kept in [`Game::finished`].
+    pub fn finish(
+        &mut self,
+        game: &Pubkey,
+        creator_profile: &Pubkey,
+        result: GameResult,
+        now: UnixTimestamp,
+    ) -> CruiserResult<ArchivedGame> {
+        if self.finished.is_finished() {
+            return Err(custom_error!("The game is already finished").into());
+        }
+        let archived = ArchivedGame {
+            game: *game,
+            player1: self.player1,
+            player2: self.player2,
+            creator: self.creator,
+            result,
+            wager: self.wager,
+            joiner_wager: self.joiner_wager,
+            ended_at: now,
+            board_hash: hash(&self.board.try_to_vec()?).to_bytes(),
+        };
+        self.finished = FinishedGame {
+            leaf: archived.leaf()?,
+            creator_profile: *creator_profile,
+        };
+        self.player1 = SystemProgram::<()>::KEY;
+        self.player2 = SystemProgram::<()>::KEY;
+        Ok(archived)
+    }
+
+    /// Replays `moves` from the game's current position, see [`ChallengeSettlement`](crate::instructions::ChallengeSettlement).
+    /// [`None`] unless every move is legal and the last one ends the game.
+    pub fn replay(&self, moves: &[MakeMoveData]) -> Option<GameResult> {
+        let mut game = self.clone();
+        game.settlement = PendingSettlement::none();
+        for (index, mov) in moves.iter().enumerate() {
+            if !game.is_valid_move(mov) {
+                return None;
+            }
+            game.make_move(
+                game.next_play,
+                mov.big_board,
+                mov.middle_board,
+                mov.small_board,
+            )
+            .ok()?;
+            if let Some(result) = game.result() {
+                return (index == moves.len() - 1).then(|| result);
+            }
+            game.next_play = game.next_play.other();
+            game.last_move = mov.small_board;
+            game.last_move_middle = mov.middle_board;
+        }
+        None
+    }
+
+    /// Tells whether `mov` is a legal next move.
+    pub fn is_valid_move(&self, mov: &MakeMoveData) -> bool {
+        // Indexes the variant doesn't use must be zero
+        let unused_ok = match self.variant() {
+            GameVariant::Classic => mov.big_board == [0, 0] && mov.middle_board == [0, 0],
+            GameVariant::Ultimate => mov.middle_board == [0, 0],
+            GameVariant::Deep => true,
+        };
+        // Verify valid with last move
+        let forced_board_ok = self
+            .forced_board()
+            .map_or(true, |(big_board, middle_board)| {
+                mov.big_board == big_board && mov.middle_board == middle_board
+            });
+        !self.is_first_player_pending()
+            && !self.is_settling()
+            && unused_ok
+            && forced_board_ok
+            && self
+                .board
+                .get_space(mov.big_board, mov.middle_board, mov.small_board)
+                .map_or(false, |space| space == &Space::Empty)
+    }
+
+    /// Every legal next move in reading order, see [`Game::is_valid_move`].
+    /// Empty once the game is over, matching [`Game::winner`] and [`Game::is_draw`].
+    pub fn valid_moves(&self) -> Vec<MakeMoveData> {
+        let all = || (0..9).map(position_to_index);
+        let (big_boards, middle_boards): (Vec<_>, Vec<_>) =
+            match (self.forced_board(), self.variant()) {
+                (Some((big_board, middle_board)), _) => (vec![big_board], vec![middle_board]),
+                (None, GameVariant::Classic) => (vec![[0, 0]], vec![[0, 0]]),
+                (None, GameVariant::Ultimate) => (all().collect(), vec![[0, 0]]),
+                (None, GameVariant::Deep) => (all().collect(), all().collect()),
+            };
+        let mut moves = Vec::new();
+        for big_board in &big_boards {
+            for middle_board in &middle_boards {
+                for small_board in all() {
+                    let mov = MakeMoveData {
+                        big_board: *big_board,
+                        middle_board: *middle_board,
+                        small_board,
+                    };
+                    if self.is_valid_move(&mov) {
+                        moves.push(mov);
+                    }
+                }
             }
         }
+        moves
+    }
+
+    /// Tells whether the game is waiting on a VRF to decide who moves first.
+    /// No moves can be made until it is fulfilled.
+    pub fn is_first_player_pending(&self) -> bool {
+        self.first_player_vrf != Pubkey::new_from_array([0; 32])
+    }
+
+    /// Tells whether a program is notified when the game settles.
+    pub fn has_settlement_hook(&self) -> bool {
+        self.settlement_hook != Pubkey::new_from_array([0; 32])
+    }
+
+    /// Tells whether the game has a turn time limit.
+    pub fn is_timed(&self) -> bool {
+        self.turn_length > 0
+    }
+
+    /// Tells whether the player to move has run out of time for their turn at `now`.
+    /// Always false for untimed games and games waiting on their first player.
+    pub fn is_turn_expired(&self, now: UnixTimestamp) -> bool {
+        self.is_timed()
+            && !self.is_first_player_pending()
+            && !self.is_settling()
+            && self.last_turn.saturating_add(self.turn_length) < now
+    }
+
+    /// Tells whether nobody joined the game within its [`Game::join_ttl`] as of `now`.
+    pub fn is_stale(&self, now: UnixTimestamp) -> bool {
+        !self.is_started()
+            && self.join_ttl > 0
+            && self.created_at.saturating_add(self.join_ttl) < now
+    }
+
+    /// Tells whether the locked other player missed their [`Game::lock_expires_at`] as of `now`.
+    pub fn is_lock_expired(&self, now: UnixTimestamp) -> bool {
+        !self.is_started() && self.lock_expires_at > 0 && self.lock_expires_at < now
+    }
+
+    /// The wagers held for the game: the creator's until someone joins, then both.
+    /// Friendly and finished games hold none.
+    pub fn pot(&self) -> u64 {
+        if self.friendly || self.finished.is_finished() {
+            0
+        } else if self.is_started() {
+            self.wager.saturating_add(self.joiner_wager)
+        } else {
+            self.wager
+        }
+    }
+
+    /// When the player to move runs out of time, see [`Game::is_turn_expired`].
+    /// [`None`] if the game is untimed, hasn't started or is finished.
+    pub fn turn_deadline(&self) -> Option<UnixTimestamp> {
+        (self.is_timed() && self.is_started() && !self.finished.is_finished())
+            .then(|| self.last_turn.saturating_add(self.turn_length))
+    }
+
+    /// When the game was last created or moved in.
+    pub fn last_activity(&self) -> UnixTimestamp {
+        self.created_at.max(self.last_turn)
+    }
+
+    /// Tells whether the game has gone `period` seconds without activity as of `now`.
+    pub fn is_inert(&self, now: UnixTimestamp, period: UnixTimestamp) -> bool {
+        self.last_activity().saturating_add(period) < now
+    }
+
+    /// Hashes a join code for [`Game::join_code_hash`].
+    pub fn hash_join_code(join_code: &[u8]) -> [u8; 32] {
+        hash(join_code).to_bytes()
+    }
+
+    /// Hashes a move with a secret salt for [`Game::move_commitment`].
+    pub fn hash_move(mov: &MakeMoveData, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&mov.big_board, &mov.middle_board, &mov.small_board, salt]).to_bytes()
+    }
+
+    /// Tells whether `mov` and `salt` reveal the committed move.
+    pub fn is_committed_move(&self, mov: &MakeMoveData, salt: &[u8; 32]) -> bool {
+        self.move_commitment != [0; 32] && Self::hash_move(mov, salt) == self.move_commitment
+    }
+
+    /// Tells whether `join_code` lets someone join the game.
+    pub fn is_valid_join_code(&self, join_code: &[u8]) -> bool {
+        self.join_code_hash == [0; 32] || Self::hash_join_code(join_code) == self.join_code_hash
+    }
+
+    /// Turns a list of profiles into the fixed size form of [`Game::invited`],
+    /// filling unused slots with the zero key. Returns [`None`] if there are too many profiles.
+    pub fn invited_array(profiles: &[Pubkey]) -> Option<[Pubkey; Self::MAX_INVITED]> {
+        if profiles.len() > Self::MAX_INVITED {
+            return None;
+        }
+        let mut out = [Pubkey::new_from_array([0; 32]); Self::MAX_INVITED];
+        out[..profiles.len()].copy_from_slice(profiles);
+        Some(out)
+    }
+
+    /// Tells whether `profile` is invited, always true if nobody in particular is.
+    pub fn is_invited(&self, profile: &Pubkey) -> bool {
+        let zero = Pubkey::new_from_array([0; 32]);
+        self.invited.iter().all(|invited| invited == &zero)
+            || (profile != &zero && self.invited.contains(profile))
+    }
+
+    /// Tells whether the other player is valid to join the game.
+    /// The creator can't play themselves, both players' stats would go to the same profile.
+    pub fn is_valid_other_player(&self, other_player: &Pubkey) -> bool {
+        let (slot, creator) = match self.creator {
+            Player::One => (&self.player2, &self.player1),
+            Player::Two => (&self.player1, &self.player2),
+        };
+        if other_player == creator {
+            return false;
+        }
+        slot == other_player
+            || (slot == &Pubkey::new_from_array([0; 32]) && self.is_invited(other_player))
+    }
+}
+
+/// The rules a game is played with.
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum GameVariant {
+    /// Plain tic-tac-toe on a single 3x3 board.
+    Classic,
+    /// Ultimate tic-tac-toe. A 3x3 board of 3x3 boards where the last move decides the next board.
+    Ultimate,
+    /// Ultimate tic-tac-toe one level deeper. A 3x3 board of ultimate boards
+    /// where the last move's middle and small indexes decide the next middle board.
+    Deep,
+}
+
+/// Optional rules for a game, set when it is created. The default is standard ultimate tic-tac-toe.
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct RuleSet {
+    /// Where a player goes when sent to a sub-board with no empty spaces left.
+    pub full_board: ClosedBoardRule,
+    /// Where a player goes when sent to a sub-board that is already won.
+    /// Solved boards don't keep their spaces so they can't be played on themselves.
+    pub solved_board: ClosedBoardRule,
+    /// How a game with no moves left is settled.
+    pub draw: DrawRule,
+    /// Whether moves are committed to with [`CommitMove`](crate::instructions::CommitMove) and played with
+    /// [`RevealMove`](crate::instructions::RevealMove), so they can't be seen before they land.
+    /// Needs a turn length so a player can't stall by never revealing.
+    pub commit_reveal: bool,
+}
+impl RuleSet {
+    /// Finds the forced board position out of `count` in reading order, [`None`] if the move is free.
+    /// `closed` tells whether a position can't be played on.
+    fn forced_index(
+        &self,
+        target: usize,
+        count: usize,
+        closed: impl Fn(usize) -> Option<ClosedBoard>,
+    ) -> Option<usize> {
+        let rule = match closed(target) {
+            None => return Some(target),
+            Some(ClosedBoard::Full) => self.full_board,
+            Some(ClosedBoard::Solved) => self.solved_board,
+        };
+        match rule {
+            ClosedBoardRule::AnyBoard => None,
+            ClosedBoardRule::NextOpenBoard => (1..count)
+                .map(|offset| (target + offset) % count)
+                .find(|position| closed(*position).is_none()),
+        }
+    }
+}
+
+/// A settlement posted by [`SettleSignedGame`](crate::instructions::SettleSignedGame),
+/// finalized by [`FinalizeSettlement`](crate::instructions::FinalizeSettlement) once
+/// [`ProgramConfig::settlement_dispute_window`](crate::accounts::ProgramConfig::settlement_dispute_window) passes.
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct PendingSettlement {
+    /// The result the settler claimed from the signed final board.
+    pub result: GameResult,
+    /// The hash of the moves both players signed, see [`settlement_moves_hash`](crate::instructions::settlement_moves_hash).
+    pub moves_hash: [u8; 32],
+    /// When the settlement was posted. 0 if none is pending.
+    pub posted_at: UnixTimestamp,
+    /// The player authority that posted the settlement and its bond.
+    pub settler: Pubkey,
+    /// The settler's bond in lamports, held by the game account.
+    /// Returned when the settlement is finalized, paid to the challenger if it's shown to be wrong.
+    pub bond: u64,
+}
+impl PendingSettlement {
+    /// No pending settlement.
+    pub fn none() -> Self {
+        Self {
+            result: GameResult::Drawn,
+            moves_hash: [0; 32],
+            posted_at: 0,
+            settler: Pubkey::new_from_array([0; 32]),
+            bond: 0,
+        }
+    }
+
+    /// Tells whether the dispute window of `window` seconds has passed as of `now`.
+    pub fn is_final(&self, now: UnixTimestamp, window: UnixTimestamp) -> bool {
+        self.posted_at > 0 && self.posted_at.saturating_add(window) <= now
+    }
+}
+
+/// A finished game waiting to be archived, see [`Game::finish`].
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct FinishedGame {
+    /// The [`ArchivedGame::leaf`] to append to the archive tree. All zeros if the game isn't finished.
+    pub leaf: [u8; 32],
+    /// The creator's profile, which gets the game's rent once it's archived.
+    pub creator_profile: Pubkey,
+}
+impl FinishedGame {
+    /// Tells whether the game is finished.
+    pub fn is_finished(&self) -> bool {
+        self.leaf != [0; 32]
+    }
+}
+
+/// The record of a game kept in the archive tree, logged when the game finishes.
+/// Its [`leaf`](ArchivedGame::leaf) is what the tree holds.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq)]
+pub struct ArchivedGame {
+    /// The game's account.
+    pub game: Pubkey,
+    /// The first player's profile.
+    pub player1: Pubkey,
+    /// The second player's profile.
+    pub player2: Pubkey,
+    /// Which player created the game.
+    pub creator: Player,
+    /// How the game ended.
+    pub result: GameResult,
+    /// The creator's wager.
+    pub wager: u64,
+    /// The joiner's wager.
+    pub joiner_wager: u64,
+    /// When the game finished.
+    pub ended_at: UnixTimestamp,
+    /// The hash of the final [`GameBoard`].
+    pub board_hash: [u8; 32],
+}
+impl ArchivedGame {
+    /// The leaf for the archive tree, the hash of the record.
+    pub fn leaf(&self) -> CruiserResult<[u8; 32]> {
+        Ok(hash(&self.try_to_vec()?).to_bytes())
+    }
+}
+
+/// What [`Game::play_premove`] did with the queued premove.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PremoveOutcome {
+    /// No premove was queued.
+    NotQueued,
+    /// The premove was played.
+    Played(MakeMoveData),
+    /// The premove was dropped as it's no longer legal.
+    Illegal(MakeMoveData),
+    /// The premove was dropped as it would end the game.
+    EndsGame(MakeMoveData),
+}
+
+/// How a game ended.
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum GameResult {
+    /// The player won, by three in a row or on boards.
+    Won(Player),
+    /// Nobody won.
+    Drawn,
+}
+
+/// Why a sub-board can't be played on.
+enum ClosedBoard {
+    Full,
+    Solved,
+}
+
+/// Where a player sent to a sub-board that can't be played on goes.
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum ClosedBoardRule {
+    /// The player can play on any open board.
+    AnyBoard,
+    /// The player must play on the next open board after it in reading order.
+    NextOpenBoard,
+}
+impl Default for ClosedBoardRule {
+    fn default() -> Self {
+        ClosedBoardRule::AnyBoard
+    }
+}
+
+/// How a game with no moves left is settled.
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum DrawRule {
+    /// The game is a draw and each player gets their wager back.
+    Split,
+    /// The player that won the most sub-boards wins, a tie is a draw.
+    MostBoards,
+}
+impl Default for DrawRule {
+    fn default() -> Self {
+        DrawRule::Split
+    }
+}
+
+/// Turns a `[row, column]` index into its position in reading order.
+fn index_to_position(index: [u8; 2]) -> usize {
+    index[0] as usize * 3 + index[1] as usize
+}
+
+/// Turns a position in reading order into a `[row, column]` index.
+fn position_to_index(position: usize) -> [u8; 2] {
+    [(position / 3) as u8, (position % 3) as u8]
+}
+
+/// The board of a game for each [`GameVariant`].
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum GameBoard {
+    /// The board for [`GameVariant::Classic`].
+    Classic(Board<Space>),
+    /// The board for [`GameVariant::Ultimate`].
+    Ultimate(Board<Board<Space>>),
+    /// The board for [`GameVariant::Deep`].
+    Deep(Board<Board<Board<Space>>>),
+}
+impl GameBoard {
+    /// Creates an empty board for `variant`.
+    pub fn new(variant: GameVariant) -> Self {
+        match variant {
+            GameVariant::Classic => GameBoard::Classic(Default::default()),
+            GameVariant::Ultimate => GameBoard::Ultimate(Default::default()),
+            GameVariant::Deep => GameBoard::Deep(Default::default()),
+        }
+    }
+
+    /// The variant this board is for.
+    pub fn variant(&self) -> GameVariant {
+        match self {
+            GameBoard::Classic(_) => GameVariant::Classic,
+            GameBoard::Ultimate(_) => GameVariant::Ultimate,
+            GameBoard::Deep(_) => GameVariant::Deep,
+        }
+    }
+
+    /// Gets a space, [`None`] if it is out of range or on a solved board.
+    /// Classic boards only use `small_board` and ultimate boards don't use `middle_board`.
+    pub fn get_space(
+        &self,
+        big_board: [u8; 2],
+        middle_board: [u8; 2],
+        small_board: [u8; 2],
+    ) -> Option<&Space> {
+        match self {
+            GameBoard::Classic(board) => board.get(small_board),
+            GameBoard::Ultimate(board) => board.get(big_board)?.get(small_board),
+            GameBoard::Deep(board) => board.get(big_board)?.get(middle_board)?.get(small_board),
+        }
+    }
+
+    /// Gets a space mutably, see [`GameBoard::get_space`].
+    pub fn get_space_mut(
+        &mut self,
+        big_board: [u8; 2],
+        middle_board: [u8; 2],
+        small_board: [u8; 2],
+    ) -> Option<&mut Space> {
+        match self {
+            GameBoard::Classic(board) => board.get_mut(small_board),
+            GameBoard::Ultimate(board) => board.get_mut(big_board)?.get_mut(small_board),
+            GameBoard::Deep(board) => board
+                .get_mut(big_board)?
+                .get_mut(middle_board)?
+                .get_mut(small_board),
+        }
     }
 }
 
@@ -89,6 +847,16 @@ pub enum Player {
     Two,
 }
 
+impl Player {
+    /// The opposing player.
+    pub fn other(self) -> Self {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+}
+
 /// A space on the game board.
 #[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
 pub enum Space {
@@ -148,6 +916,40 @@ impl<S> Board<S> {
     }
 }
 
+impl Board<Space> {
+    /// Tells whether an unsolved board has no empty spaces left.
+    pub fn is_full(&self) -> bool {
+        match self {
+            Board::Unsolved(board) => board.iter().flatten().all(|space| space != &Space::Empty),
+            Board::Solved(_) => false,
+        }
+    }
+}
+impl Board<Board<Space>> {
+    /// Tells whether an unsolved board has no sub-board left to play on.
+    pub fn is_full(&self) -> bool {
+        match self {
+            Board::Unsolved(boards) => boards
+                .iter()
+                .flatten()
+                .all(|board| board.current_winner().is_some() || board.is_full()),
+            Board::Solved(_) => false,
+        }
+    }
+}
+impl Board<Board<Board<Space>>> {
+    /// Tells whether an unsolved board has no sub-board left to play on.
+    pub fn is_full(&self) -> bool {
+        match self {
+            Board::Unsolved(boards) => boards
+                .iter()
+                .flatten()
+                .all(|board| board.current_winner().is_some() || board.is_full()),
+            Board::Solved(_) => false,
+        }
+    }
+}
+
 /// This trait lets us use the same logic for checking winners on the sub-boards and main board.
 pub trait CurrentWinner {
     /// The index used to make a move.
@@ -206,10 +1008,7 @@ where
                 // Cannot make a move on a solved board.
                 // We call `into` here to turn a generic error into the even more general `CruiserError`.
                 // You would do the same with a custom error type.
-                Err(GenericError::Custom {
-                    error: "Cannot make move on solved board".to_string(),
-                }
-                .into())
+                Err(custom_error!("Cannot make move on solved board").into())
             }
         }
     }
@@ -290,4 +1089,601 @@ mod test {
         assert!(!is_winner(&board, Player::One));
         assert!(!is_winner(&board, Player::Two));
     }
+
+    #[test]
+    fn test_draw() {
+        use Space::{PlayerOne as X, PlayerTwo as O};
+        let full = Board::Unsolved([[X, O, X], [X, O, O], [O, X, X]]);
+        assert!(full.is_full());
+        assert!(full.current_winner().is_none());
+
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert!(!game.is_draw());
+        game.board = GameBoard::Ultimate(Board::Unsolved([
+            [full, Board::Solved(Player::One), full],
+            [Board::Solved(Player::Two), full, Board::Solved(Player::One)],
+            [full, Board::Solved(Player::Two), full],
+        ]));
+        assert!(game.is_draw());
+        if let GameBoard::Ultimate(Board::Unsolved(boards)) = &mut game.board {
+            boards[0][0] = Board::default();
+        }
+        assert!(!game.is_draw());
+
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        assert!(!game.is_draw());
+        game.board = GameBoard::Classic(full);
+        assert!(game.is_draw());
+    }
+
+    #[test]
+    fn test_rules() {
+        use Space::{PlayerOne as X, PlayerTwo as O};
+        let full = Board::Unsolved([[X, O, X], [X, O, O], [O, X, X]]);
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet {
+                full_board: ClosedBoardRule::AnyBoard,
+                solved_board: ClosedBoardRule::NextOpenBoard,
+                draw: DrawRule::MostBoards,
+                commit_reveal: false,
+            },
+        );
+        assert_eq!(game.forced_board(), None);
+        game.last_move = [0, 2];
+        assert_eq!(game.forced_board(), Some(([0, 2], [0, 0])));
+
+        // Sent to a solved board, wrapping around to the first open one
+        if let GameBoard::Ultimate(Board::Unsolved(boards)) = &mut game.board {
+            boards[0][2] = Board::Solved(Player::One);
+            boards[1][0] = Board::Solved(Player::Two);
+        }
+        assert_eq!(game.forced_board(), Some(([1, 1], [0, 0])));
+        // Sent to a full board, free to play anywhere
+        if let GameBoard::Ultimate(Board::Unsolved(boards)) = &mut game.board {
+            boards[0][2] = full;
+        }
+        assert_eq!(game.forced_board(), None);
+
+        game.board = GameBoard::Ultimate(Board::Unsolved([
+            [full, Board::Solved(Player::One), full],
+            [Board::Solved(Player::Two), full, Board::Solved(Player::One)],
+            [full, Board::Solved(Player::Two), full],
+        ]));
+        assert!(game.is_draw());
+        assert_eq!(game.draw_winner(), None);
+        if let GameBoard::Ultimate(Board::Unsolved(boards)) = &mut game.board {
+            boards[0][0] = Board::Solved(Player::Two);
+        }
+        assert_eq!(game.draw_winner(), Some(Player::Two));
+
+        game.rules.draw = DrawRule::Split;
+        assert_eq!(game.draw_winner(), None);
+    }
+
+    #[test]
+    fn test_valid_moves() {
+        use Space::{PlayerOne as X, PlayerTwo as O};
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert_eq!(game.valid_moves().len(), 81);
+
+        game.make_move(Player::One, [0, 0], [0, 0], [1, 1]).unwrap();
+        game.last_move = [1, 1];
+        let moves = game.valid_moves();
+        assert_eq!(moves.len(), 9);
+        assert!(moves.iter().all(|mov| mov.big_board == [1, 1]));
+
+        // Sent back to the board with the played space
+        game.last_move = [0, 0];
+        let moves = game.valid_moves();
+        assert_eq!(moves.len(), 8);
+        assert!(moves.iter().all(|mov| game.is_valid_move(mov)));
+        assert!(!moves.contains(&MakeMoveData {
+            big_board: [0, 0],
+            middle_board: [0, 0],
+            small_board: [1, 1],
+        }));
+
+        // Moves can't be made while waiting on the first player
+        game.first_player_vrf = Pubkey::new_unique();
+        assert!(game.valid_moves().is_empty());
+        game.first_player_vrf = Pubkey::new_from_array([0; 32]);
+
+        // Agrees with the draw check
+        let full = Board::Unsolved([[X, O, X], [X, O, O], [O, X, X]]);
+        game.board = GameBoard::Ultimate(Board::Unsolved([
+            [full, Board::Solved(Player::One), full],
+            [Board::Solved(Player::Two), full, Board::Solved(Player::One)],
+            [full, Board::Solved(Player::Two), full],
+        ]));
+        assert!(game.is_draw());
+        assert!(game.valid_moves().is_empty());
+
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        assert_eq!(game.valid_moves().len(), 9);
+        game.make_move(Player::One, [0, 0], [0, 0], [2, 2]).unwrap();
+        game.last_move = [2, 2];
+        assert_eq!(game.valid_moves().len(), 8);
+
+        let game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Deep,
+            RuleSet::default(),
+        );
+        assert_eq!(game.valid_moves().len(), 729);
+    }
+
+    #[test]
+    fn test_classic() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        assert_eq!(game.variant(), GameVariant::Classic);
+        for (player, space) in [
+            (Player::One, [0, 0]),
+            (Player::Two, [1, 0]),
+            (Player::One, [0, 1]),
+            (Player::Two, [1, 1]),
+        ] {
+            game.make_move(player, [2, 2], [2, 2], space).unwrap();
+            assert_eq!(game.winner(), None);
+        }
+        assert_eq!(
+            game.board.get_space([2, 2], [2, 2], [1, 1]),
+            Some(&Space::PlayerTwo)
+        );
+        game.make_move(Player::One, [0, 0], [0, 0], [0, 2]).unwrap();
+        assert_eq!(game.winner(), Some(Player::One));
+        assert!(!game.is_draw());
+    }
+
+    #[test]
+    fn test_premove() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        let mov = |small_board| MakeMoveData {
+            big_board: [0, 0],
+            middle_board: [0, 0],
+            small_board,
+        };
+        assert_eq!(game.play_premove().unwrap(), PremoveOutcome::NotQueued);
+
+        game.make_move(Player::One, [0, 0], [0, 0], [0, 0]).unwrap();
+        game.next_play = Player::Two;
+        game.premove = mov([1, 0]);
+        assert_eq!(
+            game.play_premove().unwrap(),
+            PremoveOutcome::Played(mov([1, 0]))
+        );
+        assert_eq!(game.premove, Game::NO_PREMOVE);
+        assert_eq!(
+            game.board.get_space([0, 0], [0, 0], [1, 0]),
+            Some(&Space::PlayerTwo)
+        );
+
+        // Taken spaces are dropped
+        game.premove = mov([0, 0]);
+        assert_eq!(
+            game.play_premove().unwrap(),
+            PremoveOutcome::Illegal(mov([0, 0]))
+        );
+        assert_eq!(game.premove, Game::NO_PREMOVE);
+
+        // So are moves that end the game
+        game.next_play = Player::One;
+        game.make_move(Player::One, [0, 0], [0, 0], [0, 1]).unwrap();
+        game.premove = mov([0, 2]);
+        assert_eq!(
+            game.play_premove().unwrap(),
+            PremoveOutcome::EndsGame(mov([0, 2]))
+        );
+        assert_eq!(game.premove, Game::NO_PREMOVE);
+        assert_eq!(
+            game.board.get_space([0, 0], [0, 0], [0, 2]),
+            Some(&Space::Empty)
+        );
+    }
+
+    #[test]
+    fn test_turn_expired() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        game.last_turn = 100;
+        assert!(!game.is_timed());
+        assert!(!game.is_turn_expired(UnixTimestamp::MAX));
+
+        game.turn_length = 10;
+        assert!(game.is_timed());
+        assert!(!game.is_turn_expired(110));
+        assert!(game.is_turn_expired(111));
+
+        game.first_player_vrf = Pubkey::new_unique();
+        assert!(game.is_first_player_pending());
+        assert!(!game.is_turn_expired(111));
+    }
+
+    #[test]
+    fn test_committed_move() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        let mov = MakeMoveData {
+            big_board: [0, 0],
+            middle_board: [0, 0],
+            small_board: [1, 2],
+        };
+        let salt = [7; 32];
+        assert!(!game.is_committed_move(&mov, &salt));
+        game.move_commitment = Game::hash_move(&mov, &salt);
+        assert!(game.is_committed_move(&mov, &salt));
+        assert!(!game.is_committed_move(&mov, &[8; 32]));
+        assert!(!game.is_committed_move(
+            &MakeMoveData {
+                small_board: [2, 1],
+                ..mov
+            },
+            &salt
+        ));
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        let moves = [[0, 0], [1, 0], [0, 1], [1, 1], [0, 2]]
+            .into_iter()
+            .map(|small_board| MakeMoveData {
+                big_board: [0, 0],
+                middle_board: [0, 0],
+                small_board,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(game.replay(&moves), Some(GameResult::Won(Player::One)));
+        // Unfinished, or moves past the end
+        assert_eq!(game.replay(&moves[..4]), None);
+        let mut extra = moves.clone();
+        extra.push(moves[0].clone());
+        assert_eq!(game.replay(&extra), None);
+        // Replays ignore the pending settlement but keep the position
+        game.settlement.posted_at = 1;
+        assert!(!game.is_valid_move(&moves[0]));
+        assert_eq!(game.replay(&moves), Some(GameResult::Won(Player::One)));
+        game.make_move(Player::One, [0, 0], [0, 0], [0, 0]).unwrap();
+        assert_eq!(game.replay(&moves), None);
+    }
+
+    #[test]
+    fn test_finish() {
+        let profile = Pubkey::new_unique();
+        let game_key = Pubkey::new_unique();
+        let mut game = Game::new(
+            &profile,
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        for (player, small_board) in [
+            (Player::One, [0, 0]),
+            (Player::Two, [1, 0]),
+            (Player::One, [0, 1]),
+            (Player::Two, [1, 1]),
+            (Player::One, [0, 2]),
+        ] {
+            game.make_move(player, [0, 0], [0, 0], small_board).unwrap();
+        }
+        let result = game.result().unwrap();
+        let archived = game.finish(&game_key, &profile, result, 10).unwrap();
+        assert_eq!(archived.player1, profile);
+        assert_eq!(archived.result, GameResult::Won(Player::One));
+        assert!(game.finished.is_finished());
+        assert_eq!(game.finished.leaf, archived.leaf().unwrap());
+        assert_eq!(game.finished.creator_profile, profile);
+        assert_eq!(game.player1, SystemProgram::<()>::KEY);
+        assert!(game.finish(&game_key, &profile, result, 10).is_err());
+    }
+
+    #[test]
+    fn test_join_code() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert!(game.is_valid_join_code(&[]));
+        assert!(game.is_valid_join_code(b"anything"));
+
+        game.join_code_hash = Game::hash_join_code(b"secret");
+        assert!(game.is_valid_join_code(b"secret"));
+        assert!(!game.is_valid_join_code(b"Secret"));
+        assert!(!game.is_valid_join_code(&[]));
+    }
+
+    #[test]
+    fn test_invited() {
+        let creator = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let mut game = Game::new(
+            &creator,
+            Player::Two,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert!(game.is_valid_other_player(&stranger));
+        assert!(!game.is_valid_other_player(&creator));
+
+        game.invited = Game::invited_array(&[Pubkey::new_unique(), friend]).unwrap();
+        assert!(game.is_valid_other_player(&friend));
+        assert!(!game.is_valid_other_player(&stranger));
+        assert!(!game.is_valid_other_player(&Pubkey::new_from_array([0; 32])));
+
+        // A locked opponent overrides the invites
+        game.player1 = stranger;
+        assert!(game.is_valid_other_player(&stranger));
+        assert!(!game.is_valid_other_player(&friend));
+
+        assert!(Game::invited_array(&[Pubkey::new_unique(); Game::MAX_INVITED + 1]).is_none());
+    }
+
+    #[test]
+    fn test_stake() {
+        let game = Game::new(
+            &Pubkey::new_unique(),
+            Player::Two,
+            255,
+            200,
+            100,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert_eq!(game.stake(Player::Two), 200);
+        assert_eq!(game.stake(Player::One), 100);
+    }
+
+    #[test]
+    fn test_stale() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        game.created_at = 100;
+        assert!(!game.is_stale(UnixTimestamp::MAX));
+
+        game.join_ttl = 10;
+        assert!(!game.is_stale(110));
+        assert!(game.is_stale(111));
+
+        game.last_turn = 105;
+        assert!(!game.is_stale(111));
+    }
+
+    #[test]
+    fn test_lock_expired() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert!(!game.is_lock_expired(UnixTimestamp::MAX));
+
+        game.lock_expires_at = 110;
+        assert!(!game.is_lock_expired(110));
+        assert!(game.is_lock_expired(111));
+
+        game.last_turn = 105;
+        assert!(!game.is_lock_expired(111));
+    }
+
+    #[test]
+    fn test_pot_and_deadline() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            100,
+            50,
+            60,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        assert_eq!(game.pot(), 100);
+        assert_eq!(game.turn_deadline(), None);
+
+        game.last_turn = 1_000;
+        assert_eq!(game.pot(), 150);
+        assert_eq!(game.turn_deadline(), Some(1_060));
+
+        game.turn_length = 0;
+        assert_eq!(game.turn_deadline(), None);
+
+        game.friendly = true;
+        assert_eq!(game.pot(), 0);
+    }
+
+    #[test]
+    fn test_inert() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        game.created_at = 100;
+        assert!(!game.is_inert(150, 50));
+        assert!(game.is_inert(151, 50));
+
+        game.last_turn = 200;
+        assert!(!game.is_inert(250, 50));
+        assert!(game.is_inert(251, 50));
+    }
+
+    #[test]
+    fn test_deep() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Deep,
+            RuleSet::default(),
+        );
+        assert_eq!(game.variant(), GameVariant::Deep);
+        // Win the middle board at [1, 1] of the big board at [0, 0]
+        for small_board in [[0, 0], [0, 1], [0, 2]] {
+            game.make_move(Player::Two, [0, 0], [1, 1], small_board)
+                .unwrap();
+        }
+        assert_eq!(game.board.get_space([0, 0], [1, 1], [2, 2]), None);
+        assert_eq!(
+            game.board.get_space([0, 0], [0, 0], [2, 2]),
+            Some(&Space::Empty)
+        );
+        if let GameBoard::Deep(Board::Unsolved(boards)) = &game.board {
+            assert_eq!(
+                boards[0][0].get([1, 1]).unwrap().current_winner(),
+                Some(Player::Two)
+            );
+            assert_eq!(boards[0][0].current_winner(), None);
+        } else {
+            panic!("Board should be deep and unsolved");
+        }
+        assert_eq!(game.winner(), None);
+        assert!(!game.is_draw());
+    }
 }
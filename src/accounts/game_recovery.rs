@@ -0,0 +1,56 @@
+use cruiser::prelude::*;
+
+/// A proposed [`RecoverStuckGame`](crate::instructions::RecoverStuckGame),
+/// at the [`GameRecoverySeeder`](crate::pda::GameRecoverySeeder) PDA for the game.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct GameRecovery {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The game to recover.
+    pub game: Pubkey,
+    /// Where the game's escrow goes.
+    pub funds_to: Pubkey,
+    /// The game's [`Game::last_activity`](crate::accounts::Game::last_activity) when proposed.
+    /// Any activity since cancels the recovery.
+    pub last_activity: UnixTimestamp,
+    /// When the recovery was proposed.
+    pub proposed_at: UnixTimestamp,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+}
+impl GameRecovery {
+    /// Creates a new recovery proposed at `now`.
+    pub fn new(
+        game: &Pubkey,
+        funds_to: &Pubkey,
+        last_activity: UnixTimestamp,
+        now: UnixTimestamp,
+        bump: u8,
+    ) -> Self {
+        Self {
+            version: 0,
+            game: *game,
+            funds_to: *funds_to,
+            last_activity,
+            proposed_at: now,
+            bump,
+        }
+    }
+
+    /// Tells whether the recovery has waited out `delay` as of `now`.
+    pub fn is_due(&self, now: UnixTimestamp, delay: UnixTimestamp) -> bool {
+        self.proposed_at.saturating_add(delay) <= now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let recovery = GameRecovery::new(&Pubkey::new_unique(), &Pubkey::new_unique(), 0, 100, 255);
+        assert!(!recovery.is_due(149, 50));
+        assert!(recovery.is_due(150, 50));
+    }
+}
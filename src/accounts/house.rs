@@ -0,0 +1,33 @@
+use cruiser::prelude::*;
+
+/// The house players can play against with [`CreateHouseGame`](crate::instructions::CreateHouseGame).
+/// There is only one, at the [`HouseSeeder`](crate::pda::HouseSeeder) PDA.
+///
+/// The house's wagers come out of the [`HouseVaultSeeder`](crate::pda::HouseVaultSeeder) PDA
+/// and its winnings are paid back into it.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct House {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The key allowed to submit the house's moves with [`HouseMove`](crate::instructions::HouseMove).
+    pub operator: Pubkey,
+    /// The house's profile, at the [`HouseProfileSeeder`](crate::pda::HouseProfileSeeder) PDA.
+    /// Its authority is the vault.
+    pub profile: Pubkey,
+    /// The bump seed of the vault's PDA.
+    pub vault_bump: u8,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+}
+impl House {
+    /// Creates a new house.
+    pub fn new(operator: &Pubkey, profile: &Pubkey, vault_bump: u8, bump: u8) -> Self {
+        Self {
+            version: 0,
+            operator: *operator,
+            profile: *profile,
+            vault_bump,
+            bump,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use cruiser::prelude::*;
+
+/// Usage counters for each [`TutorialInstructions`](crate::TutorialInstructions) variant.
+/// There is only one, at the [`MetricsSeeder`](crate::pda::MetricsSeeder) PDA.
+///
+/// Instructions are only counted when the client appends this account after the instruction's own accounts.
+/// Every counted transaction write locks it, so busy clients may want to leave it off.
+///
+/// Only successful invocations are counted, failures aren't. A failed instruction rolls back its whole
+/// transaction, this account included, so a failure count can't be kept in chain state.
+/// Failures are logged as `instruction_failed` events instead.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct InstructionMetrics {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+    /// The successful invocations of each instruction, indexed by discriminant.
+    pub invocations: [u64; InstructionMetrics::MAX_INSTRUCTIONS],
+}
+impl InstructionMetrics {
+    /// The most instructions that can be counted, one past the highest discriminant.
+    pub const MAX_INSTRUCTIONS: usize = 128;
+
+    /// Creates new metrics with nothing counted.
+    pub fn new(bump: u8) -> Self {
+        Self {
+            version: 0,
+            bump,
+            invocations: [0; Self::MAX_INSTRUCTIONS],
+        }
+    }
+
+    /// Counts an invocation of the instruction with `discriminant`.
+    /// Discriminants past [`InstructionMetrics::MAX_INSTRUCTIONS`] aren't counted.
+    pub fn record(&mut self, discriminant: u8) {
+        if let Some(count) = self.invocations.get_mut(discriminant as usize) {
+            count.saturating_add_assign(1);
+        }
+    }
+
+    /// The invocations counted for the instruction with `discriminant`.
+    pub fn invocations_of(&self, discriminant: u8) -> u64 {
+        self.invocations
+            .get(discriminant as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut metrics = InstructionMetrics::new(255);
+        metrics.record(3);
+        metrics.record(3);
+        metrics.record(0);
+        metrics.record(u8::MAX);
+        assert_eq!(metrics.invocations_of(3), 2);
+        assert_eq!(metrics.invocations_of(0), 1);
+        assert_eq!(metrics.invocations_of(1), 0);
+        assert_eq!(metrics.invocations_of(u8::MAX), 0);
+
+        metrics.invocations[3] = u64::MAX;
+        metrics.record(3);
+        assert_eq!(metrics.invocations_of(3), u64::MAX);
+    }
+}
@@ -0,0 +1,182 @@
+use cruiser::prelude::*;
+
+/// One page of the leaderboard for an elo band, at the [`LeaderboardShardSeeder`](crate::pda::LeaderboardShardSeeder)
+/// PDA for its band and page.
+///
+/// Each page keeps the highest rated profiles that were listed on it.
+/// Pages are kept up to date by [`UpdateLeaderboard`](crate::instructions::UpdateLeaderboard),
+/// clients stitch them back together with `client::stitch_leaderboard`.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct LeaderboardShard {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The elo band, see [`LeaderboardShard::band_for_elo`].
+    pub band: u16,
+    /// The page within the band.
+    pub page: u16,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+    /// The listed profiles. Unused slots have the zero key.
+    pub entries: [LeaderboardEntry; LeaderboardShard::PAGE_SIZE],
+}
+impl LeaderboardShard {
+    /// The number of profiles on a page.
+    pub const PAGE_SIZE: usize = 32;
+    /// The width in elo of a band.
+    pub const BAND_WIDTH: u64 = 100;
+
+    /// Creates a new empty page.
+    pub fn new(band: u16, page: u16, bump: u8) -> Self {
+        Self {
+            version: 0,
+            band,
+            page,
+            bump,
+            entries: [LeaderboardEntry::default(); Self::PAGE_SIZE],
+        }
+    }
+
+    /// The band a profile with `elo` is listed in.
+    pub fn band_for_elo(elo: u64) -> u16 {
+        (elo / Self::BAND_WIDTH).min(u16::MAX as u64) as u16
+    }
+
+    /// The listed entries.
+    pub fn listed(&self) -> impl Iterator<Item = &LeaderboardEntry> {
+        self.entries.iter().filter(|entry| !entry.is_empty())
+    }
+
+    /// Tells whether `profile` is listed on this page.
+    pub fn is_listed(&self, profile: &Pubkey) -> bool {
+        profile != &Pubkey::new_from_array([0; 32])
+            && self.entries.iter().any(|entry| &entry.profile == profile)
+    }
+
+    /// Lists `entry` on this page, updating its elo if it is already listed.
+    /// A full page evicts its lowest rated entry if `entry` is rated higher.
+    pub fn insert(&mut self, entry: LeaderboardEntry) -> ShardInsert {
+        if entry.is_empty() {
+            return ShardInsert::Full;
+        }
+        if let Some(slot) = self
+            .entries
+            .iter_mut()
+            .find(|slot| slot.profile == entry.profile)
+        {
+            slot.elo = entry.elo;
+            return ShardInsert::Inserted;
+        }
+        if let Some(slot) = self.entries.iter_mut().find(|slot| slot.is_empty()) {
+            *slot = entry;
+            return ShardInsert::Inserted;
+        }
+        let lowest = self
+            .entries
+            .iter_mut()
+            .min_by_key(|slot| slot.elo)
+            .expect("PAGE_SIZE is not 0");
+        if lowest.elo < entry.elo {
+            ShardInsert::Evicted(std::mem::replace(lowest, entry))
+        } else {
+            ShardInsert::Full
+        }
+    }
+
+    /// Removes `profile` from this page. Returns false if it wasn't listed.
+    pub fn remove(&mut self, profile: &Pubkey) -> bool {
+        if !self.is_listed(profile) {
+            return false;
+        }
+        for slot in self
+            .entries
+            .iter_mut()
+            .filter(|slot| &slot.profile == profile)
+        {
+            *slot = LeaderboardEntry::default();
+        }
+        true
+    }
+}
+
+/// A profile listed on a [`LeaderboardShard`].
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct LeaderboardEntry {
+    /// The profile's key. The zero key for an unused slot.
+    pub profile: Pubkey,
+    /// The profile's elo when it was last listed.
+    pub elo: u64,
+}
+impl LeaderboardEntry {
+    /// Tells whether this is an unused slot.
+    pub fn is_empty(&self) -> bool {
+        self.profile == Pubkey::new_from_array([0; 32])
+    }
+}
+
+/// The result of [`LeaderboardShard::insert`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ShardInsert {
+    /// The entry was listed or updated.
+    Inserted,
+    /// The entry was listed in place of the returned entry.
+    Evicted(LeaderboardEntry),
+    /// The page is full of entries rated at least as high.
+    Full,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(elo: u64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            profile: Pubkey::new_unique(),
+            elo,
+        }
+    }
+
+    #[test]
+    fn test_band_for_elo() {
+        assert_eq!(LeaderboardShard::band_for_elo(0), 0);
+        assert_eq!(LeaderboardShard::band_for_elo(1199), 11);
+        assert_eq!(LeaderboardShard::band_for_elo(1200), 12);
+        assert_eq!(LeaderboardShard::band_for_elo(u64::MAX), u16::MAX);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut shard = LeaderboardShard::new(12, 0, 255);
+        let first = entry(1210);
+        assert_eq!(shard.insert(first), ShardInsert::Inserted);
+        assert!(shard.is_listed(&first.profile));
+        assert_eq!(
+            shard.insert(LeaderboardEntry { elo: 1250, ..first }),
+            ShardInsert::Inserted
+        );
+        assert_eq!(shard.listed().collect::<Vec<_>>().len(), 1);
+        assert_eq!(shard.listed().next().unwrap().elo, 1250);
+        assert_eq!(shard.insert(LeaderboardEntry::default()), ShardInsert::Full);
+
+        assert!(shard.remove(&first.profile));
+        assert!(!shard.remove(&first.profile));
+        assert_eq!(shard.listed().count(), 0);
+    }
+
+    #[test]
+    fn test_insert_full() {
+        let mut shard = LeaderboardShard::new(12, 0, 255);
+        let lowest = entry(1200);
+        assert_eq!(shard.insert(lowest), ShardInsert::Inserted);
+        for _ in 1..LeaderboardShard::PAGE_SIZE {
+            assert_eq!(shard.insert(entry(1250)), ShardInsert::Inserted);
+        }
+
+        assert_eq!(shard.insert(entry(1200)), ShardInsert::Full);
+        let higher = entry(1201);
+        assert_eq!(shard.insert(higher), ShardInsert::Evicted(lowest));
+        assert!(shard.is_listed(&higher.profile));
+        assert!(!shard.is_listed(&lowest.profile));
+    }
+}
@@ -1,7 +1,27 @@
 //! Accounts for the program.
 
+mod distribution;
 mod game;
+mod game_recovery;
+mod house;
+mod instruction_metrics;
+mod leaderboard_shard;
+mod payout_allowlist;
 mod player_profile;
+mod program_config;
+mod proposed_action;
+mod social_graph;
+mod wager_vault;
 
+pub use distribution::*;
 pub use game::*;
+pub use game_recovery::*;
+pub use house::*;
+pub use instruction_metrics::*;
+pub use leaderboard_shard::*;
+pub use payout_allowlist::*;
 pub use player_profile::*;
+pub use program_config::*;
+pub use proposed_action::*;
+pub use social_graph::*;
+pub use wager_vault::*;
@@ -0,0 +1,97 @@
+use cruiser::prelude::*;
+
+/// The addresses a profile's winnings are allowed to be paid out to.
+///
+/// Changes to the list only take effect after [`PayoutAllowlist::UPDATE_DELAY`],
+/// so a compromised authority can't immediately redirect a settlement to itself.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct PayoutAllowlist {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The profile this allowlist belongs to.
+    pub profile: Pubkey,
+    /// The currently allowed payout addresses. Unused slots are the zero key.
+    pub addresses: [Pubkey; PayoutAllowlist::MAX_ADDRESSES],
+    /// The addresses that will replace [`PayoutAllowlist::addresses`] once `pending_effective` passes.
+    pub pending_addresses: [Pubkey; PayoutAllowlist::MAX_ADDRESSES],
+    /// When the pending addresses become active. If 0 there is no pending update.
+    pub pending_effective: UnixTimestamp,
+}
+impl PayoutAllowlist {
+    /// The maximum number of addresses on an allowlist.
+    pub const MAX_ADDRESSES: usize = 4;
+    /// How long in seconds an update waits before taking effect. 2 days.
+    pub const UPDATE_DELAY: UnixTimestamp = 60 * 60 * 24 * 2;
+
+    /// Creates a new allowlist that is active immediately.
+    pub fn new(profile: &Pubkey, addresses: [Pubkey; Self::MAX_ADDRESSES]) -> Self {
+        Self {
+            version: 0,
+            profile: *profile,
+            addresses,
+            pending_addresses: [Pubkey::new_from_array([0; 32]); Self::MAX_ADDRESSES],
+            pending_effective: 0,
+        }
+    }
+
+    /// Turns a list of addresses into the fixed size form, filling unused slots with the zero key.
+    /// Returns [`None`] if there are too many addresses.
+    pub fn address_array(addresses: &[Pubkey]) -> Option<[Pubkey; Self::MAX_ADDRESSES]> {
+        if addresses.len() > Self::MAX_ADDRESSES {
+            return None;
+        }
+        let mut out = [Pubkey::new_from_array([0; 32]); Self::MAX_ADDRESSES];
+        out[..addresses.len()].copy_from_slice(addresses);
+        Some(out)
+    }
+
+    /// Gets the addresses active at `now`.
+    pub fn active_addresses(&self, now: UnixTimestamp) -> &[Pubkey; Self::MAX_ADDRESSES] {
+        if self.pending_effective > 0 && self.pending_effective <= now {
+            &self.pending_addresses
+        } else {
+            &self.addresses
+        }
+    }
+
+    /// Tells whether `address` can receive a payout at `now`.
+    pub fn is_allowed(&self, address: &Pubkey, now: UnixTimestamp) -> bool {
+        *address != Pubkey::new_from_array([0; 32]) && self.active_addresses(now).contains(address)
+    }
+
+    /// Queues `addresses` to replace the current list after [`PayoutAllowlist::UPDATE_DELAY`].
+    /// Any already active pending update is applied first.
+    pub fn propose(&mut self, addresses: [Pubkey; Self::MAX_ADDRESSES], now: UnixTimestamp) {
+        self.addresses = *self.active_addresses(now);
+        self.pending_addresses = addresses;
+        self.pending_effective = now.saturating_add(Self::UPDATE_DELAY);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timelocked_update() {
+        let allowed = Pubkey::new_unique();
+        let new_allowed = Pubkey::new_unique();
+        let mut allowlist = PayoutAllowlist::new(
+            &Pubkey::new_unique(),
+            PayoutAllowlist::address_array(&[allowed]).unwrap(),
+        );
+        assert!(allowlist.is_allowed(&allowed, 0));
+        assert!(!allowlist.is_allowed(&new_allowed, 0));
+        assert!(!allowlist.is_allowed(&Pubkey::new_from_array([0; 32]), 0));
+
+        allowlist.propose(PayoutAllowlist::address_array(&[new_allowed]).unwrap(), 100);
+        assert!(allowlist.is_allowed(&allowed, 100));
+        assert!(!allowlist.is_allowed(&new_allowed, 100));
+
+        let effective = 100 + PayoutAllowlist::UPDATE_DELAY;
+        assert!(!allowlist.is_allowed(&allowed, effective));
+        assert!(allowlist.is_allowed(&new_allowed, effective));
+
+        assert!(PayoutAllowlist::address_array(&[allowed; 5]).is_none());
+    }
+}
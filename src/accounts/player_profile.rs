@@ -1,9 +1,13 @@
+use crate::accounts::PayoutAllowlist;
 use cruiser::prelude::*;
+use std::ops::Deref;
 
 /// A player's profile.
 #[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq, OnChainSize)]
 pub struct PlayerProfile {
     /// The key allowed to act for this profile.
+    /// May be a PDA of another program, such as a DAO or escrow, which acts by signing CPIs with its seeds,
+    /// see [`sign_as_authority`](crate::instructions::sign_as_authority).
     pub authority: Pubkey,
     /// The number of wins this player has.
     pub wins: u64,
@@ -11,16 +15,79 @@ pub struct PlayerProfile {
     pub losses: u64,
     /// The number of draws this player has.
     pub draws: u64,
-    /// The amount of lamports this player has won.
+    /// The amount of lamports this player has won, including their own wagers returned from won pots.
     pub lamports_won: u64,
-    /// The amount of lamports this player has lost.
+    /// The amount of lamports this player has lost from their own wagers.
     pub lamports_lost: u64,
+    /// The amount of lamports this player has put into games as wagers.
+    pub lamports_wagered: u64,
+    /// The amount of lamports this player has gotten back from their wagers on draws and unjoined games.
+    pub lamports_drawn_back: u64,
+    /// The amount of lamports this player has paid in fees.
+    pub fees_paid: u64,
     /// The elo rating of the player.
     pub elo: u64,
+    /// Where winnings may be paid out to besides the authority. The zero key if there is none.
+    pub payout_address: Pubkey,
+    /// The [`PayoutAllowlist`] restricting where winnings can go. The zero key if there is none.
+    pub payout_allowlist: Pubkey,
+    /// Who can claim this profile once it has been abandoned.
+    pub beneficiary: Beneficiary,
+    /// The beneficiary that will replace `beneficiary` once `pending_beneficiary_effective` passes.
+    pub pending_beneficiary: Beneficiary,
+    /// When the pending beneficiary becomes active. If 0 there is no pending beneficiary.
+    pub pending_beneficiary_effective: UnixTimestamp,
+    /// The last time this profile created, joined, or played a game.
+    pub last_active: UnixTimestamp,
+    /// The last time a game this profile played in ended. 0 if it never finished one.
+    pub last_game_timestamp: UnixTimestamp,
+    /// The point up to which inactivity has already been counted by [`DecayElo`](crate::instructions::DecayElo).
+    pub last_elo_decay: UnixTimestamp,
+    /// The nonce the next move relayed with [`RelayMove`](crate::instructions::RelayMove) must be signed with.
+    pub relay_nonce: u64,
+    /// A session key that can make moves for this profile but never move its funds. The zero key if there is none.
+    pub delegate: Pubkey,
+    /// When [`PlayerProfile::delegate`] stops being accepted.
+    pub delegate_expiry: UnixTimestamp,
+    /// Who can move this profile to a new authority if the current one is lost.
+    pub guardians: GuardianSet,
+    /// The recovery the guardians are approving, if any.
+    pub recovery: PendingRecovery,
+    /// A second key that must also sign to create or join games wagering more than [`PlayerProfile::co_sign_threshold`],
+    /// and to change either. The zero key if there is none.
+    pub co_signer: Pubkey,
+    /// The largest wager that doesn't need [`PlayerProfile::co_signer`].
+    pub co_sign_threshold: u64,
+    /// The [`Achievement`]s claimed with [`ClaimAchievement`](crate::instructions::ClaimAchievement), as a bitmask.
+    pub achievements: u16,
+    /// When the profile was created. 0 for profiles created before this was tracked.
+    pub created_at: UnixTimestamp,
+    /// The last time this profile created a game with [`CreateGame`](crate::instructions::CreateGame). 0 if it never has.
+    pub last_game_created: UnixTimestamp,
+    /// How many games this profile is seated in that haven't ended. A profile in a game can't be claimed
+    /// with [`ClaimAbandonedProfile`](crate::instructions::ClaimAbandonedProfile), closing it would leave the game unable to end.
+    pub games_in_progress: u16,
+    /// Where the profile's off-chain metadata (avatar, bio, etc.) is hosted. Empty if there is none.
+    /// Last as the profile is reallocated to fit it, so every other field stays at the same offset.
+    pub metadata_uri: ProfileMetadataUri,
 }
 impl PlayerProfile {
     /// The initial elo for a new profile.
     pub const INITIAL_ELO: u64 = 1200;
+    /// How long in seconds a beneficiary change waits before taking effect. 7 days.
+    pub const BENEFICIARY_DELAY: UnixTimestamp = 60 * 60 * 24 * 7;
+    /// The shortest inactivity period a beneficiary can be set with. 30 days.
+    pub const MIN_INACTIVITY_PERIOD: UnixTimestamp = 60 * 60 * 24 * 30;
+    /// The elo K factor for games that are played out.
+    pub const ELO_K: f64 = 32.0;
+    /// The elo K factor for forfeits. Higher than [`PlayerProfile::ELO_K`] to discourage them.
+    pub const FORFEIT_ELO_K: f64 = 50.0;
+    /// The longest [`PlayerProfile::metadata_uri`] in bytes.
+    pub const MAX_METADATA_URI_LEN: usize = 200;
+    /// The most guardians a profile can have.
+    pub const MAX_GUARDIANS: usize = 3;
+    /// How long in seconds a recovery waits after being initiated before it can be finalized. 3 days.
+    pub const RECOVERY_DELAY: UnixTimestamp = 60 * 60 * 24 * 3;
 
     /// Creates a new player profile.
     /// `authority` is a ref to a pubkey because it's more efficient to use a ref on-chain.
@@ -32,7 +99,351 @@ impl PlayerProfile {
             draws: 0,
             lamports_won: 0,
             lamports_lost: 0,
+            lamports_wagered: 0,
+            lamports_drawn_back: 0,
+            fees_paid: 0,
             elo: Self::INITIAL_ELO,
+            payout_address: Pubkey::new_from_array([0; 32]),
+            payout_allowlist: Pubkey::new_from_array([0; 32]),
+            beneficiary: Beneficiary::default(),
+            pending_beneficiary: Beneficiary::default(),
+            pending_beneficiary_effective: 0,
+            last_active: 0,
+            last_game_timestamp: 0,
+            last_elo_decay: 0,
+            relay_nonce: 0,
+            delegate: Pubkey::new_from_array([0; 32]),
+            delegate_expiry: 0,
+            guardians: GuardianSet::default(),
+            recovery: PendingRecovery::default(),
+            co_signer: Pubkey::new_from_array([0; 32]),
+            co_sign_threshold: 0,
+            achievements: 0,
+            created_at: 0,
+            last_game_created: 0,
+            games_in_progress: 0,
+            metadata_uri: ProfileMetadataUri::default(),
+        }
+    }
+
+    /// The lamports this player has made or lost over all their games.
+    pub fn net_profit(&self) -> i128 {
+        self.lamports_won as i128 + self.lamports_drawn_back as i128
+            - self.lamports_wagered as i128
+            - self.fees_paid as i128
+    }
+
+    /// Records that the profile was active at `now`.
+    pub fn mark_active(&mut self, now: UnixTimestamp) {
+        self.last_active = now;
+    }
+
+    /// Records that the profile took a seat in a game at `now`.
+    pub fn mark_game_joined(&mut self, now: UnixTimestamp) {
+        self.mark_active(now);
+        self.games_in_progress.saturating_add_assign(1);
+    }
+
+    /// Records that a game this profile played in ended at `now`.
+    pub fn mark_game_ended(&mut self, now: UnixTimestamp) {
+        self.last_active = now;
+        self.leave_game(now);
+    }
+
+    /// Records that a game this profile was seated in ended at `now` without marking the profile active,
+    /// for games ended by someone else like a forfeit.
+    pub fn leave_game(&mut self, now: UnixTimestamp) {
+        self.last_game_timestamp = now;
+        self.games_in_progress = self.games_in_progress.saturating_sub(1);
+    }
+
+    /// Moves the elo toward [`PlayerProfile::INITIAL_ELO`] by `step` for every full `period`
+    /// without a finished game, counting only inactivity not already decayed.
+    /// Returns whether any decay periods had passed.
+    pub fn decay_elo(&mut self, period: UnixTimestamp, step: u64, now: UnixTimestamp) -> bool {
+        if period <= 0 || step == 0 {
+            return false;
+        }
+        let inactive_since = self.last_game_timestamp.max(self.last_elo_decay);
+        let periods = now.saturating_sub(inactive_since) / period;
+        if periods <= 0 {
+            return false;
+        }
+        // Keep the remainder so cranking often doesn't lose partial periods.
+        self.last_elo_decay = inactive_since.saturating_add(periods.saturating_mul(period));
+
+        let decay = step.saturating_mul(periods as u64);
+        self.elo = if self.elo > Self::INITIAL_ELO {
+            self.elo.saturating_sub(decay).max(Self::INITIAL_ELO)
+        } else {
+            self.elo.saturating_add(decay).min(Self::INITIAL_ELO)
+        };
+        true
+    }
+
+    /// Gets the beneficiary active at `now`.
+    pub fn active_beneficiary(&self, now: UnixTimestamp) -> &Beneficiary {
+        if self.pending_beneficiary_effective > 0 && self.pending_beneficiary_effective <= now {
+            &self.pending_beneficiary
+        } else {
+            &self.beneficiary
+        }
+    }
+
+    /// Queues `beneficiary` to replace the current one after [`PlayerProfile::BENEFICIARY_DELAY`].
+    /// Any already active pending beneficiary is applied first.
+    pub fn propose_beneficiary(&mut self, beneficiary: Beneficiary, now: UnixTimestamp) {
+        self.beneficiary = *self.active_beneficiary(now);
+        self.pending_beneficiary = beneficiary;
+        self.pending_beneficiary_effective = now.saturating_add(Self::BENEFICIARY_DELAY);
+    }
+
+    /// Tells whether `claimer` can claim this profile as abandoned at `now`.
+    /// Never while the profile is seated in a game, see [`PlayerProfile::games_in_progress`].
+    pub fn is_claimable_by(&self, claimer: &Pubkey, now: UnixTimestamp) -> bool {
+        let beneficiary = self.active_beneficiary(now);
+        beneficiary.key != Pubkey::new_from_array([0; 32])
+            && &beneficiary.key == claimer
+            && self.games_in_progress == 0
+            && self
+                .last_active
+                .saturating_add(beneficiary.inactivity_period)
+                < now
+    }
+
+    /// Tells whether `key` can make moves for this profile at `now`, either as the authority or an unexpired [`PlayerProfile::delegate`].
+    pub fn can_move(&self, key: &Pubkey, now: UnixTimestamp) -> bool {
+        key == &self.authority
+            || (self.delegate != Pubkey::new_from_array([0; 32])
+                && key == &self.delegate
+                && now < self.delegate_expiry)
+    }
+
+    /// Tells whether the pending recovery has enough approvals and has waited out [`PlayerProfile::RECOVERY_DELAY`] at `now`.
+    pub fn is_recoverable(&self, now: UnixTimestamp) -> bool {
+        self.recovery.is_pending()
+            && self.guardians.threshold > 0
+            && self.recovery.approvals.count_ones() >= self.guardians.threshold as u32
+            && self
+                .recovery
+                .initiated_at
+                .saturating_add(Self::RECOVERY_DELAY)
+                <= now
+    }
+
+    /// Tells whether wagering `wager` needs [`PlayerProfile::co_signer`] to sign.
+    pub fn needs_co_signer(&self, wager: u64) -> bool {
+        self.co_signer != Pubkey::new_from_array([0; 32]) && wager > self.co_sign_threshold
+    }
+
+    /// Tells whether this profile restricts its payouts to an allowlist.
+    pub fn has_payout_allowlist(&self) -> bool {
+        self.payout_allowlist != Pubkey::new_from_array([0; 32])
+    }
+
+    /// Tells whether `funds_to` is allowed to receive this profile's winnings.
+    /// Without a payout allowlist that is the authority or the payout address,
+    /// with one it is any address on the allowlist.
+    /// `payout_allowlist` is the key and data of the allowlist passed to the instruction, if any.
+    pub fn is_valid_payout(
+        &self,
+        payout_allowlist: Option<(&Pubkey, &PayoutAllowlist)>,
+        funds_to: &Pubkey,
+        now: UnixTimestamp,
+    ) -> bool {
+        if !self.has_payout_allowlist() {
+            return funds_to == &self.authority
+                || (self.payout_address != Pubkey::new_from_array([0; 32])
+                    && funds_to == &self.payout_address);
+        }
+        match payout_allowlist {
+            Some((key, allowlist)) => {
+                key == &self.payout_allowlist && allowlist.is_allowed(funds_to, now)
+            }
+            None => false,
+        }
+    }
+}
+
+/// A [`ProgramConfig::badge_uri`](crate::accounts::ProgramConfig::badge_uri), at most [`PlayerProfile::MAX_METADATA_URI_LEN`] bytes.
+///
+/// Serializes the same as a [`String`] but its on-chain size is always the longest URI's.
+#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq)]
+pub struct MetadataUri(String);
+impl MetadataUri {
+    /// Creates a new URI, [`None`] if it is too long.
+    pub fn new(uri: String) -> Option<Self> {
+        if uri.len() > PlayerProfile::MAX_METADATA_URI_LEN {
+            None
+        } else {
+            Some(Self(uri))
+        }
+    }
+}
+impl Deref for MetadataUri {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+impl OnChainSize for MetadataUri {
+    const ON_CHAIN_SIZE: usize = u32::ON_CHAIN_SIZE + PlayerProfile::MAX_METADATA_URI_LEN;
+}
+
+/// A [`PlayerProfile::metadata_uri`], at most [`PlayerProfile::MAX_METADATA_URI_LEN`] bytes.
+///
+/// Serializes the same as a [`String`] but its on-chain size is an empty URI's.
+/// [`UpdateProfile`](crate::instructions::UpdateProfile) reallocates the profile to fit the URI it sets,
+/// so profiles only pay rent for the URI they have.
+#[derive(Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq)]
+pub struct ProfileMetadataUri(MetadataUri);
+impl ProfileMetadataUri {
+    /// Creates a new URI, [`None`] if it is too long.
+    pub fn new(uri: String) -> Option<Self> {
+        MetadataUri::new(uri).map(Self)
+    }
+}
+impl Deref for ProfileMetadataUri {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+impl OnChainSize for ProfileMetadataUri {
+    const ON_CHAIN_SIZE: usize = u32::ON_CHAIN_SIZE;
+}
+
+/// A key that can claim a profile after it has been inactive for a while.
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct Beneficiary {
+    /// The beneficiary's key. The zero key if there is none.
+    pub key: Pubkey,
+    /// How long in seconds the profile must be inactive before `key` can claim it.
+    pub inactivity_period: UnixTimestamp,
+}
+
+/// Guardians who together can move a profile to a new authority,
+/// see [`InitiateRecovery`](crate::instructions::InitiateRecovery).
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct GuardianSet {
+    /// The guardians. Unused slots are the zero key.
+    pub guardians: [Pubkey; PlayerProfile::MAX_GUARDIANS],
+    /// How many guardians must approve a recovery. 0 if recovery is off.
+    pub threshold: u8,
+}
+impl GuardianSet {
+    /// The slot `key` is in, if it's a guardian.
+    pub fn index_of(&self, key: &Pubkey) -> Option<usize> {
+        if key == &Pubkey::new_from_array([0; 32]) {
+            return None;
+        }
+        self.guardians.iter().position(|guardian| guardian == key)
+    }
+
+    /// Tells whether the guardians are distinct and there are at least `threshold` of them.
+    pub fn is_valid(&self) -> bool {
+        let guardians = self
+            .guardians
+            .iter()
+            .filter(|guardian| **guardian != Pubkey::new_from_array([0; 32]));
+        let count = guardians.clone().count();
+        guardians
+            .enumerate()
+            .all(|(index, guardian)| self.index_of(guardian) == Some(index))
+            && self.threshold as usize <= count
+    }
+}
+
+/// A recovery of a profile to a new authority, waiting on guardian approvals and [`PlayerProfile::RECOVERY_DELAY`].
+#[derive(
+    Copy, Clone, Debug, Default, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize,
+)]
+pub struct PendingRecovery {
+    /// The authority the profile will move to. The zero key if no recovery is in progress.
+    pub new_authority: Pubkey,
+    /// Which slots of [`GuardianSet::guardians`] have approved, as a bitmask.
+    pub approvals: u8,
+    /// When the recovery was initiated.
+    pub initiated_at: UnixTimestamp,
+}
+impl PendingRecovery {
+    /// Tells whether a recovery is in progress.
+    pub fn is_pending(&self) -> bool {
+        self.new_authority != Pubkey::new_from_array([0; 32])
+    }
+}
+
+/// A milestone a profile can claim a badge for, see [`ClaimAchievement`](crate::instructions::ClaimAchievement).
+#[derive(Copy, Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq)]
+pub enum Achievement {
+    /// Won a game.
+    FirstWin,
+    /// Won 10 games.
+    TenWins,
+    /// Won 100 games.
+    HundredWins,
+    /// Drew a game.
+    FirstDraw,
+    /// Reached 1500 elo.
+    Elo1500,
+    /// Reached 2000 elo.
+    Elo2000,
+}
+impl Achievement {
+    /// Every achievement, in order.
+    pub const ALL: [Achievement; 6] = [
+        Achievement::FirstWin,
+        Achievement::TenWins,
+        Achievement::HundredWins,
+        Achievement::FirstDraw,
+        Achievement::Elo1500,
+        Achievement::Elo2000,
+    ];
+
+    /// The achievement's bit in [`PlayerProfile::achievements`].
+    pub fn bit(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// Tells whether `profile` has reached the achievement.
+    pub fn is_earned(self, profile: &PlayerProfile) -> bool {
+        match self {
+            Achievement::FirstWin => profile.wins >= 1,
+            Achievement::TenWins => profile.wins >= 10,
+            Achievement::HundredWins => profile.wins >= 100,
+            Achievement::FirstDraw => profile.draws >= 1,
+            Achievement::Elo1500 => profile.elo >= 1500,
+            Achievement::Elo2000 => profile.elo >= 2000,
+        }
+    }
+
+    /// The badge's name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "First Win",
+            Achievement::TenWins => "Ten Wins",
+            Achievement::HundredWins => "Hundred Wins",
+            Achievement::FirstDraw => "First Draw",
+            Achievement::Elo1500 => "Elo 1500",
+            Achievement::Elo2000 => "Elo 2000",
+        }
+    }
+
+    /// The badge's file under [`ProgramConfig::badge_uri`](crate::accounts::ProgramConfig::badge_uri).
+    pub fn slug(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "first_win.json",
+            Achievement::TenWins => "ten_wins.json",
+            Achievement::HundredWins => "hundred_wins.json",
+            Achievement::FirstDraw => "first_draw.json",
+            Achievement::Elo1500 => "elo_1500.json",
+            Achievement::Elo2000 => "elo_2000.json",
         }
     }
 }
@@ -44,19 +455,301 @@ fn win_probability(elo_a: f64, elo_b: f64) -> f64 {
 
 /// Calculates the new elo of players after a game.
 pub fn update_elo(elo_a: &mut u64, elo_b: &mut u64, k: f64, a_won: bool) {
+    update_elo_with_score(elo_a, elo_b, k, if a_won { 1.0 } else { 0.0 });
+}
+
+/// Calculates the new elo of players after a game where `a_score` is 1 for a win, 0.5 for a draw, and 0 for a loss.
+pub fn update_elo_with_score(elo_a: &mut u64, elo_b: &mut u64, k: f64, a_score: f64) {
     let mut elo_a_float = *elo_a as f64;
     let mut elo_b_float = *elo_b as f64;
     let a_prob = win_probability(elo_a_float, elo_b_float);
     let b_prob = win_probability(elo_b_float, elo_a_float);
 
-    if a_won {
-        elo_a_float += k * (1.0 - a_prob);
-        elo_b_float += k * (0.0 - b_prob);
-    } else {
-        elo_a_float += k * (0.0 - a_prob);
-        elo_b_float += k * (1.0 - b_prob);
-    }
+    elo_a_float += k * (a_score - a_prob);
+    elo_b_float += k * ((1.0 - a_score) - b_prob);
 
     *elo_a = elo_a_float as u64;
     *elo_b = elo_b_float as u64;
 }
+
+/// How a game ended for a player, for [`predict_elo_change`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameOutcome {
+    /// The player won.
+    Win,
+    /// The game was drawn.
+    Draw,
+    /// The player lost.
+    Loss,
+}
+impl GameOutcome {
+    /// The score [`update_elo_with_score`] takes for this outcome.
+    pub fn score(self) -> f64 {
+        match self {
+            GameOutcome::Win => 1.0,
+            GameOutcome::Draw => 0.5,
+            GameOutcome::Loss => 0.0,
+        }
+    }
+}
+
+/// How much `my_elo` would change after a game against `their_elo` ending in `outcome`.
+/// Rounds the same as [`update_elo_with_score`] so it matches what the program will record.
+pub fn predict_elo_change(my_elo: u64, their_elo: u64, k: f64, outcome: GameOutcome) -> i64 {
+    let (mut mine, mut theirs) = (my_elo, their_elo);
+    update_elo_with_score(&mut mine, &mut theirs, k, outcome.score());
+    mine as i64 - my_elo as i64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_achievements() {
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        assert!(Achievement::ALL
+            .iter()
+            .all(|achievement| !achievement.is_earned(&profile)));
+
+        profile.wins = 10;
+        profile.elo = 1500;
+        assert!(Achievement::FirstWin.is_earned(&profile));
+        assert!(Achievement::TenWins.is_earned(&profile));
+        assert!(!Achievement::HundredWins.is_earned(&profile));
+        assert!(!Achievement::FirstDraw.is_earned(&profile));
+        assert!(Achievement::Elo1500.is_earned(&profile));
+        assert!(!Achievement::Elo2000.is_earned(&profile));
+
+        let bits = Achievement::ALL
+            .iter()
+            .fold(0, |bits, achievement| bits | achievement.bit());
+        assert_eq!(bits.count_ones() as usize, Achievement::ALL.len());
+    }
+
+    #[test]
+    fn test_beneficiary_claim() {
+        let beneficiary = Pubkey::new_unique();
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.mark_active(100);
+        profile.propose_beneficiary(
+            Beneficiary {
+                key: beneficiary,
+                inactivity_period: PlayerProfile::MIN_INACTIVITY_PERIOD,
+            },
+            100,
+        );
+
+        // Not active until the delay passes, which is shorter than the inactivity period.
+        let effective = 100 + PlayerProfile::BENEFICIARY_DELAY;
+        assert_eq!(profile.active_beneficiary(100).key, Pubkey::default());
+        assert_eq!(profile.active_beneficiary(effective).key, beneficiary);
+
+        let abandoned = 101 + PlayerProfile::MIN_INACTIVITY_PERIOD;
+        assert!(!profile.is_claimable_by(&beneficiary, abandoned - 1));
+        assert!(profile.is_claimable_by(&beneficiary, abandoned));
+        assert!(!profile.is_claimable_by(&Pubkey::new_unique(), abandoned));
+
+        profile.mark_active(abandoned);
+        assert!(!profile.is_claimable_by(&beneficiary, abandoned));
+    }
+
+    #[test]
+    fn test_claim_in_game() {
+        let beneficiary = Pubkey::new_unique();
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.mark_game_joined(100);
+        profile.propose_beneficiary(
+            Beneficiary {
+                key: beneficiary,
+                inactivity_period: PlayerProfile::MIN_INACTIVITY_PERIOD,
+            },
+            100,
+        );
+
+        // Seated in a game that never ended, so it can't be claimed however long it's inactive.
+        let abandoned = 101 + PlayerProfile::MIN_INACTIVITY_PERIOD;
+        assert_eq!(profile.games_in_progress, 1);
+        assert!(!profile.is_claimable_by(&beneficiary, abandoned));
+
+        // Ended by someone else, which doesn't count as activity.
+        profile.leave_game(abandoned);
+        assert_eq!(profile.games_in_progress, 0);
+        assert!(profile.is_claimable_by(&beneficiary, abandoned));
+    }
+
+    #[test]
+    fn test_net_profit() {
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        // Won a 2 lamport pot, lost 3 lamports, and drew 4 lamports back
+        profile.lamports_wagered = 1 + 3 + 4;
+        profile.lamports_won = 2;
+        profile.lamports_lost = 3;
+        profile.lamports_drawn_back = 4;
+        profile.fees_paid = 1;
+        assert_eq!(profile.net_profit(), -3);
+    }
+
+    #[test]
+    fn test_elo() {
+        let (mut a, mut b) = (1200, 1200);
+        update_elo_with_score(&mut a, &mut b, PlayerProfile::ELO_K, 0.5);
+        assert_eq!((a, b), (1200, 1200));
+
+        update_elo(&mut a, &mut b, PlayerProfile::ELO_K, true);
+        assert_eq!((a, b), (1216, 1184));
+
+        // The higher rated player loses elo on a draw
+        update_elo_with_score(&mut a, &mut b, PlayerProfile::ELO_K, 0.5);
+        assert!(a < 1216 && b > 1184);
+    }
+
+    #[test]
+    fn test_predict_elo_change() {
+        let k = PlayerProfile::ELO_K;
+        assert_eq!(predict_elo_change(1200, 1200, k, GameOutcome::Win), 16);
+        assert_eq!(predict_elo_change(1200, 1200, k, GameOutcome::Draw), 0);
+        assert_eq!(predict_elo_change(1200, 1200, k, GameOutcome::Loss), -16);
+
+        let (mut a, mut b) = (1500, 1300);
+        let change = predict_elo_change(a, b, k, GameOutcome::Draw);
+        update_elo_with_score(&mut a, &mut b, k, 0.5);
+        assert!(change < 0);
+        assert_eq!(a as i64, 1500 + change);
+    }
+
+    #[test]
+    fn test_payout_address() {
+        let authority = Pubkey::new_unique();
+        let payout_address = Pubkey::new_unique();
+        let mut profile = PlayerProfile::new(&authority);
+        assert!(profile.is_valid_payout(None, &authority, 0));
+        assert!(!profile.is_valid_payout(None, &payout_address, 0));
+        assert!(!profile.is_valid_payout(None, &Pubkey::new_from_array([0; 32]), 0));
+
+        profile.payout_address = payout_address;
+        assert!(profile.is_valid_payout(None, &authority, 0));
+        assert!(profile.is_valid_payout(None, &payout_address, 0));
+        assert!(!profile.is_valid_payout(None, &Pubkey::new_unique(), 0));
+    }
+
+    #[test]
+    fn test_recovery() {
+        let guardians = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_from_array([0; 32]),
+        ];
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.guardians = GuardianSet {
+            guardians,
+            threshold: 2,
+        };
+        assert!(profile.guardians.is_valid());
+        assert_eq!(profile.guardians.index_of(&guardians[1]), Some(1));
+        assert_eq!(profile.guardians.index_of(&guardians[2]), None);
+        assert!(!GuardianSet {
+            guardians,
+            threshold: 3,
+        }
+        .is_valid());
+        assert!(!GuardianSet {
+            guardians: [guardians[0], guardians[0], guardians[1]],
+            threshold: 1,
+        }
+        .is_valid());
+
+        profile.recovery = PendingRecovery {
+            new_authority: Pubkey::new_unique(),
+            approvals: 0b01,
+            initiated_at: 100,
+        };
+        let after_delay = 100 + PlayerProfile::RECOVERY_DELAY;
+        assert!(!profile.is_recoverable(after_delay));
+        profile.recovery.approvals |= 0b10;
+        assert!(!profile.is_recoverable(after_delay - 1));
+        assert!(profile.is_recoverable(after_delay));
+    }
+
+    #[test]
+    fn test_needs_co_signer() {
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.co_sign_threshold = 100;
+        assert!(!profile.needs_co_signer(u64::MAX));
+
+        profile.co_signer = Pubkey::new_unique();
+        assert!(!profile.needs_co_signer(100));
+        assert!(profile.needs_co_signer(101));
+    }
+
+    #[test]
+    fn test_can_move() {
+        let authority = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mut profile = PlayerProfile::new(&authority);
+        assert!(profile.can_move(&authority, 0));
+        assert!(!profile.can_move(&delegate, 0));
+        assert!(!profile.can_move(&Pubkey::new_from_array([0; 32]), 0));
+
+        profile.delegate = delegate;
+        profile.delegate_expiry = 100;
+        assert!(profile.can_move(&authority, 100));
+        assert!(profile.can_move(&delegate, 99));
+        assert!(!profile.can_move(&delegate, 100));
+        // The delegate is never a payout address
+        assert!(!profile.is_valid_payout(None, &delegate, 0));
+    }
+
+    #[test]
+    fn test_decay_elo() {
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.elo = 1500;
+        profile.mark_game_ended(1_000);
+
+        assert!(!profile.decay_elo(100, 10, 1_099));
+        assert_eq!(profile.elo, 1500);
+
+        assert!(profile.decay_elo(100, 10, 1_250));
+        assert_eq!(profile.elo, 1480);
+        assert_eq!(profile.last_elo_decay, 1_200);
+        // The leftover 50 seconds still count toward the next period.
+        assert!(profile.decay_elo(100, 10, 1_300));
+        assert_eq!(profile.elo, 1470);
+        assert!(!profile.decay_elo(100, 10, 1_300));
+
+        // Never overshoots the baseline from either side.
+        assert!(profile.decay_elo(100, 10, 1_000_000));
+        assert_eq!(profile.elo, PlayerProfile::INITIAL_ELO);
+        profile.elo = 1100;
+        assert!(profile.decay_elo(100, 1_000, 1_000_100));
+        assert_eq!(profile.elo, PlayerProfile::INITIAL_ELO);
+
+        // A new game resets the clock.
+        profile.elo = 1500;
+        profile.mark_game_ended(2_000_000);
+        assert!(!profile.decay_elo(100, 10, 2_000_050));
+        assert!(!profile.decay_elo(0, 10, 3_000_000));
+        assert_eq!(profile.elo, 1500);
+    }
+
+    #[test]
+    fn test_metadata_uri() {
+        let max = "a".repeat(PlayerProfile::MAX_METADATA_URI_LEN);
+        let uri = MetadataUri::new(max.clone()).unwrap();
+        assert_eq!(&*uri, max);
+        assert_eq!(uri.try_to_vec().unwrap().len(), MetadataUri::ON_CHAIN_SIZE);
+        assert_eq!(MetadataUri::new(max.clone() + "a"), None);
+
+        // A profile grows with its URI
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        assert_eq!(
+            profile.try_to_vec().unwrap().len(),
+            PlayerProfile::ON_CHAIN_SIZE
+        );
+        profile.metadata_uri = ProfileMetadataUri::new(max).unwrap();
+        assert_eq!(
+            profile.try_to_vec().unwrap().len(),
+            PlayerProfile::ON_CHAIN_SIZE + PlayerProfile::MAX_METADATA_URI_LEN
+        );
+    }
+}
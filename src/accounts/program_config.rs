@@ -0,0 +1,476 @@
+use crate::accounts::{MetadataUri, PlayerProfile};
+use crate::error::custom_error;
+use cruiser::prelude::*;
+
+/// The program wide settings. There is only one, at the [`ConfigSeeder`](crate::pda::ConfigSeeder) PDA.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct ProgramConfig {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The key allowed to change the config. Unused while [`ProgramConfig::admin_threshold`] is set.
+    pub admin: Pubkey,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+    /// The share of the pot paid to a third party that cranks a [`ForfeitGame`](crate::instructions::ForfeitGame),
+    /// in basis points.
+    pub keeper_tip_bps: u16,
+    /// The most the house will match in one [`CreateHouseGame`](crate::instructions::CreateHouseGame).
+    /// 0 stops new house games.
+    pub max_house_wager: u64,
+    /// The most of the house vault's balance the house will match in one game, in basis points.
+    pub max_house_exposure_bps: u16,
+    /// How long in seconds a profile can go without finishing a game before [`DecayElo`](crate::instructions::DecayElo)
+    /// moves its elo. 0 turns decay off.
+    pub elo_decay_period: UnixTimestamp,
+    /// How much elo [`DecayElo`](crate::instructions::DecayElo) moves a rating toward the baseline per decay period.
+    pub elo_decay_step: u64,
+    /// The least each player can wager in a new game.
+    pub min_wager: u64,
+    /// The most each player can wager in a new game. 0 means no limit.
+    pub max_wager: u64,
+    /// The shortest turn length a new timed game can have.
+    pub min_turn_length: UnixTimestamp,
+    /// The longest turn length a new timed game can have. 0 means no limit.
+    pub max_turn_length: UnixTimestamp,
+    /// Stops new games from being created while set.
+    pub paused: bool,
+    /// The keys of the admin multisig. Zeroed slots are empty.
+    pub admin_multisig: [Pubkey; Self::MAX_ADMIN_SIGNERS],
+    /// How many of [`ProgramConfig::admin_multisig`] have to sign admin instructions.
+    /// 0 leaves the config to the single [`ProgramConfig::admin`].
+    pub admin_threshold: u8,
+    /// Where [`WithdrawFees`](crate::instructions::WithdrawFees) sends protocol fees. The zero key if there is none.
+    pub treasury: Pubkey,
+    /// The bump seed of the [`FeeVaultSeeder`](crate::pda::FeeVaultSeeder) PDA.
+    pub fee_vault_bump: u8,
+    /// How long in seconds a game has to go without activity before [`ProposeGameRecovery`](crate::instructions::ProposeGameRecovery)
+    /// can target it. 0 turns recovery off.
+    pub recovery_inert_period: UnixTimestamp,
+    /// How long in seconds a game recovery has to wait between being proposed and [`RecoverStuckGame`](crate::instructions::RecoverStuckGame).
+    pub recovery_delay: UnixTimestamp,
+    /// How long in seconds a [`ProposeAdminAction`](crate::instructions::ProposeAdminAction) waits before it can execute.
+    /// 0 lets the admin instructions take effect directly, otherwise they have to be proposed.
+    pub admin_action_delay: UnixTimestamp,
+    /// The id of the next [`ProposedAction`](crate::accounts::ProposedAction).
+    pub next_admin_action: u64,
+    /// The bond in lamports a player puts up with a [`SettleSignedGame`](crate::instructions::SettleSignedGame),
+    /// lost to whoever shows the settlement is wrong.
+    pub settlement_bond: u64,
+    /// How long in seconds a signed settlement can be challenged before it's final.
+    pub settlement_dispute_window: UnixTimestamp,
+    /// The Bubblegum tree [`ClaimAchievement`](crate::instructions::ClaimAchievement) mints badges into.
+    /// The zero key turns badges off.
+    pub badge_tree: Pubkey,
+    /// The prefix of each badge's metadata URI, the [`Achievement::slug`](crate::accounts::Achievement::slug) is appended to it.
+    pub badge_uri: MetadataUri,
+    /// The games a profile has to have finished before it can create or join wagered games.
+    pub min_ranked_games: u64,
+    /// How old in seconds a profile has to be before it can create or join wagered games.
+    /// Profiles created before [`PlayerProfile::created_at`] was tracked count as old enough.
+    pub min_profile_age: UnixTimestamp,
+    /// How long in seconds a profile has to wait between [`CreateGame`](crate::instructions::CreateGame)s.
+    /// 0 turns the cooldown off.
+    pub game_creation_cooldown: UnixTimestamp,
+    /// Makes games created while set reject instructions superseded by a newer version, see [`TutorialInstructions`](crate::TutorialInstructions).
+    /// Games created before keep taking them so they can be finished by the clients that started them.
+    pub reject_deprecated_instructions: bool,
+    /// The program notified when games created while set settle, see [`settlement_hook`](crate::settlement_hook).
+    /// The zero key if there is none.
+    pub settlement_hook: Pubkey,
+}
+impl ProgramConfig {
+    /// The most [`ProgramConfig::keeper_tip_bps`] can be set to. 5%.
+    pub const MAX_KEEPER_TIP_BPS: u16 = 500;
+    /// The most [`ProgramConfig::max_house_exposure_bps`] can be set to, the whole vault.
+    pub const MAX_HOUSE_EXPOSURE_BPS: u16 = 10_000;
+    /// The most keys [`ProgramConfig::admin_multisig`] can hold.
+    pub const MAX_ADMIN_SIGNERS: usize = 5;
+    /// The least [`ProgramConfig::recovery_inert_period`] can be set to when recovery is on. 90 days.
+    pub const MIN_RECOVERY_INERT_PERIOD: UnixTimestamp = 60 * 60 * 24 * 90;
+    /// The least [`ProgramConfig::recovery_delay`] can be set to. 2 days.
+    pub const MIN_RECOVERY_DELAY: UnixTimestamp = 60 * 60 * 24 * 2;
+    /// The least [`ProgramConfig::settlement_dispute_window`] can be set to. 1 hour.
+    pub const MIN_SETTLEMENT_DISPUTE_WINDOW: UnixTimestamp = 60 * 60;
+
+    /// Creates a new config.
+    pub fn new(
+        admin: &Pubkey,
+        bump: u8,
+        keeper_tip_bps: u16,
+        max_house_wager: u64,
+        max_house_exposure_bps: u16,
+        elo_decay_period: UnixTimestamp,
+        elo_decay_step: u64,
+    ) -> Self {
+        Self {
+            version: 0,
+            admin: *admin,
+            bump,
+            keeper_tip_bps,
+            max_house_wager,
+            max_house_exposure_bps,
+            elo_decay_period,
+            elo_decay_step,
+            min_wager: 0,
+            max_wager: 0,
+            min_turn_length: 0,
+            max_turn_length: 0,
+            paused: false,
+            admin_multisig: [Pubkey::new_from_array([0; 32]); Self::MAX_ADMIN_SIGNERS],
+            admin_threshold: 0,
+            treasury: Pubkey::new_from_array([0; 32]),
+            fee_vault_bump: 0,
+            recovery_inert_period: 0,
+            recovery_delay: Self::MIN_RECOVERY_DELAY,
+            admin_action_delay: 0,
+            next_admin_action: 0,
+            settlement_bond: 0,
+            settlement_dispute_window: Self::MIN_SETTLEMENT_DISPUTE_WINDOW,
+            badge_tree: Pubkey::new_from_array([0; 32]),
+            badge_uri: MetadataUri::default(),
+            min_ranked_games: 0,
+            min_profile_age: 0,
+            game_creation_cooldown: 0,
+            reject_deprecated_instructions: false,
+            settlement_hook: Pubkey::new_from_array([0; 32]),
+        }
+    }
+
+    /// Checks that the settings are in range and the bounds aren't crossed.
+    pub fn check_settings(&self) -> CruiserResult<()> {
+        let error = if self.keeper_tip_bps > Self::MAX_KEEPER_TIP_BPS {
+            custom_error!("keeper_tip_bps can be at most {}", Self::MAX_KEEPER_TIP_BPS)
+        } else if self.max_house_exposure_bps > Self::MAX_HOUSE_EXPOSURE_BPS {
+            custom_error!(
+                "max_house_exposure_bps can be at most {}",
+                Self::MAX_HOUSE_EXPOSURE_BPS
+            )
+        } else if self.elo_decay_period < 0
+            || self.min_turn_length < 0
+            || self.max_turn_length < 0
+            || self.admin_action_delay < 0
+            || self.min_profile_age < 0
+            || self.game_creation_cooldown < 0
+        {
+            custom_error!("Durations can't be negative")
+        } else if self.max_wager > 0 && self.min_wager > self.max_wager {
+            custom_error!("min_wager is over max_wager")
+        } else if self.max_turn_length > 0 && self.min_turn_length > self.max_turn_length {
+            custom_error!("min_turn_length is over max_turn_length")
+        } else if self.recovery_inert_period != 0
+            && self.recovery_inert_period < Self::MIN_RECOVERY_INERT_PERIOD
+        {
+            custom_error!(
+                "recovery_inert_period must be 0 or at least {}s",
+                Self::MIN_RECOVERY_INERT_PERIOD
+            )
+        } else if self.recovery_delay < Self::MIN_RECOVERY_DELAY {
+            custom_error!(
+                "recovery_delay must be at least {}s",
+                Self::MIN_RECOVERY_DELAY
+            )
+        } else if self.settlement_dispute_window < Self::MIN_SETTLEMENT_DISPUTE_WINDOW {
+            custom_error!(
+                "settlement_dispute_window must be at least {}s",
+                Self::MIN_SETTLEMENT_DISPUTE_WINDOW
+            )
+        } else if self.admin_threshold as usize > self.admin_signer_count() {
+            custom_error!("admin_threshold is over the number of admin_multisig keys")
+        } else {
+            return Ok(());
+        };
+        Err(error.into())
+    }
+
+    /// The number of keys in [`ProgramConfig::admin_multisig`], or 0 if a key is repeated.
+    fn admin_signer_count(&self) -> usize {
+        let keys = self
+            .admin_multisig
+            .iter()
+            .filter(|key| **key != Pubkey::new_from_array([0; 32]))
+            .collect::<Vec<_>>();
+        let repeated = keys
+            .iter()
+            .enumerate()
+            .any(|(index, key)| keys[..index].contains(key));
+        if repeated {
+            0
+        } else {
+            keys.len()
+        }
+    }
+
+    /// Tells whether `signers` carry the admin's authority,
+    /// either the single admin or enough of the multisig.
+    pub fn is_admin(&self, signers: impl IntoIterator<Item = Pubkey>) -> bool {
+        if self.admin_threshold == 0 {
+            return signers.into_iter().any(|signer| signer == self.admin);
+        }
+        let mut signed = [false; Self::MAX_ADMIN_SIGNERS];
+        for signer in signers {
+            if signer == Pubkey::new_from_array([0; 32]) {
+                continue;
+            }
+            if let Some(index) = self.admin_multisig.iter().position(|key| *key == signer) {
+                signed[index] = true;
+            }
+        }
+        signed.iter().filter(|signed| **signed).count() >= self.admin_threshold as usize
+    }
+
+    /// [`ProgramConfig::is_admin`] over the accounts that signed the transaction.
+    pub fn admin_signed<'b, AI>(&self, accounts: impl IntoIterator<Item = &'b AI>) -> bool
+    where
+        AI: AccountInfo + 'b,
+    {
+        self.is_admin(
+            accounts
+                .into_iter()
+                .filter(|account| account.is_signer())
+                .map(|account| *account.key()),
+        )
+    }
+
+    /// Checks that a new game with these settings is allowed.
+    /// Untimed games, with a `turn_length` of 0, skip the turn length bounds.
+    pub fn check_new_game(
+        &self,
+        wager: u64,
+        joiner_wager: u64,
+        turn_length: UnixTimestamp,
+    ) -> CruiserResult<()> {
+        let error = if self.paused {
+            custom_error!("New games are paused")
+        } else if wager.min(joiner_wager) < self.min_wager {
+            custom_error!("Wagers must be at least {} lamports", self.min_wager)
+        } else if self.max_wager > 0 && wager.max(joiner_wager) > self.max_wager {
+            custom_error!("Wagers can be at most {} lamports", self.max_wager)
+        } else if turn_length > 0 && turn_length < self.min_turn_length {
+            custom_error!("Turn length must be at least {}s", self.min_turn_length)
+        } else if turn_length > 0 && self.max_turn_length > 0 && turn_length > self.max_turn_length
+        {
+            custom_error!("Turn length can be at most {}s", self.max_turn_length)
+        } else {
+            return Ok(());
+        };
+        Err(error.into())
+    }
+
+    /// Checks that `profile` has played enough and is old enough at `now` to create or join wagered games.
+    /// Keeps fresh profiles from farming elo off each other.
+    pub fn check_ranked_profile(
+        &self,
+        profile: &PlayerProfile,
+        now: UnixTimestamp,
+    ) -> CruiserResult<()> {
+        let games = profile
+            .wins
+            .saturating_add(profile.losses)
+            .saturating_add(profile.draws);
+        let error = if games < self.min_ranked_games {
+            custom_error!(
+                "Profile needs {} finished games to play wagered games",
+                self.min_ranked_games
+            )
+        } else if now.saturating_sub(profile.created_at) < self.min_profile_age {
+            custom_error!(
+                "Profile must be {}s old to play wagered games",
+                self.min_profile_age
+            )
+        } else {
+            return Ok(());
+        };
+        Err(error.into())
+    }
+
+    /// Checks that `profile` has waited out [`ProgramConfig::game_creation_cooldown`] since it last created a game.
+    pub fn check_game_cooldown(
+        &self,
+        profile: &PlayerProfile,
+        now: UnixTimestamp,
+    ) -> CruiserResult<()> {
+        let ready_at = profile
+            .last_game_created
+            .saturating_add(self.game_creation_cooldown);
+        if profile.last_game_created == 0 || now >= ready_at {
+            Ok(())
+        } else {
+            Err(custom_error!("Profile can create another game in {}s", ready_at - now).into())
+        }
+    }
+
+    /// Checks that a new friendly game, played without wagers, is allowed.
+    /// The same as [`ProgramConfig::check_new_game`] without the wager bounds.
+    pub fn check_new_friendly_game(&self, turn_length: UnixTimestamp) -> CruiserResult<()> {
+        // `check_settings` keeps the min wager within the max wager.
+        self.check_new_game(self.min_wager, self.min_wager, turn_length)
+    }
+
+    /// The tip a keeper gets for forfeiting a game with `pot` lamports in it.
+    pub fn keeper_tip(&self, pot: u64) -> u64 {
+        // Can't overflow as `keeper_tip_bps` is at most 10,000.
+        (pot as u128 * self.keeper_tip_bps as u128 / 10_000) as u64
+    }
+
+    /// The most the house can match in a new game with `vault_balance` lamports in its vault.
+    pub fn house_wager_limit(&self, vault_balance: u64) -> u64 {
+        // Can't overflow as `max_house_exposure_bps` is at most 10,000.
+        let exposure =
+            (vault_balance as u128 * self.max_house_exposure_bps as u128 / 10_000) as u64;
+        exposure.min(self.max_house_wager)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keeper_tip() {
+        let config = ProgramConfig::new(&Pubkey::new_unique(), 255, 100, 0, 0, 0, 0);
+        assert_eq!(config.keeper_tip(2_000_000_000), 20_000_000);
+        assert_eq!(config.keeper_tip(99), 0);
+        assert_eq!(config.keeper_tip(u64::MAX), u64::MAX / 100);
+
+        let config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 0, 0, 0, 0);
+        assert_eq!(config.keeper_tip(2_000_000_000), 0);
+    }
+
+    #[test]
+    fn test_house_wager_limit() {
+        let config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 1_000_000_000, 2_500, 0, 0);
+        assert_eq!(config.house_wager_limit(2_000_000_000), 500_000_000);
+        assert_eq!(config.house_wager_limit(10_000_000_000), 1_000_000_000);
+        assert_eq!(config.house_wager_limit(u64::MAX), 1_000_000_000);
+        assert_eq!(config.house_wager_limit(0), 0);
+
+        let config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 0, 10_000, 0, 0);
+        assert_eq!(config.house_wager_limit(2_000_000_000), 0);
+    }
+
+    #[test]
+    fn test_check_new_game() {
+        let mut config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 0, 0, 0, 0);
+        assert!(config.check_new_game(0, 0, 0).is_ok());
+
+        config.min_wager = 100;
+        config.max_wager = 1_000;
+        assert!(config.check_new_game(100, 1_000, 0).is_ok());
+        assert!(config.check_new_game(99, 500, 0).is_err());
+        assert!(config.check_new_friendly_game(0).is_ok());
+        assert!(config.check_new_game(500, 1_001, 0).is_err());
+
+        config.min_turn_length = 30;
+        config.max_turn_length = 600;
+        assert!(config.check_new_game(100, 100, 0).is_ok());
+        assert!(config.check_new_game(100, 100, 30).is_ok());
+        assert!(config.check_new_game(100, 100, 29).is_err());
+        assert!(config.check_new_game(100, 100, 601).is_err());
+
+        config.paused = true;
+        assert!(config.check_new_game(100, 100, 30).is_err());
+    }
+
+    #[test]
+    fn test_check_ranked_profile() {
+        let mut config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 0, 0, 0, 0);
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        profile.created_at = 1_000;
+        assert!(config.check_ranked_profile(&profile, 1_000).is_ok());
+
+        config.min_ranked_games = 3;
+        config.min_profile_age = 100;
+        assert!(config.check_ranked_profile(&profile, 2_000).is_err());
+        profile.wins = 1;
+        profile.losses = 1;
+        profile.draws = 1;
+        assert!(config.check_ranked_profile(&profile, 1_099).is_err());
+        assert!(config.check_ranked_profile(&profile, 1_100).is_ok());
+
+        // Profiles from before `created_at` was set are old enough.
+        profile.created_at = 0;
+        assert!(config.check_ranked_profile(&profile, 50).is_err());
+        assert!(config.check_ranked_profile(&profile, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_game_cooldown() {
+        let mut config = ProgramConfig::new(&Pubkey::new_unique(), 255, 0, 0, 0, 0, 0);
+        let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+        config.game_creation_cooldown = 60;
+        assert!(config.check_game_cooldown(&profile, 10).is_ok());
+
+        profile.last_game_created = 1_000;
+        assert!(config.check_game_cooldown(&profile, 1_059).is_err());
+        assert!(config.check_game_cooldown(&profile, 1_060).is_ok());
+
+        config.game_creation_cooldown = 0;
+        assert!(config.check_game_cooldown(&profile, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_settings() {
+        let mut config = ProgramConfig::new(&Pubkey::new_unique(), 255, 500, 0, 10_000, 0, 0);
+        assert!(config.check_settings().is_ok());
+
+        config.keeper_tip_bps = 501;
+        assert!(config.check_settings().is_err());
+        config.keeper_tip_bps = 0;
+
+        config.min_wager = 10;
+        assert!(config.check_settings().is_ok());
+        config.max_wager = 9;
+        assert!(config.check_settings().is_err());
+        config.max_wager = 10;
+        assert!(config.check_settings().is_ok());
+
+        config.max_turn_length = 60;
+        config.min_turn_length = 61;
+        assert!(config.check_settings().is_err());
+        config.min_turn_length = -1;
+        assert!(config.check_settings().is_err());
+        config.min_turn_length = 0;
+
+        let key = Pubkey::new_unique();
+        config.admin_threshold = 1;
+        assert!(config.check_settings().is_err());
+        config.admin_multisig[0] = key;
+        assert!(config.check_settings().is_ok());
+        config.admin_multisig[3] = key;
+        assert!(config.check_settings().is_err());
+        config.admin_threshold = 0;
+
+        config.recovery_inert_period = ProgramConfig::MIN_RECOVERY_INERT_PERIOD - 1;
+        assert!(config.check_settings().is_err());
+        config.recovery_inert_period = ProgramConfig::MIN_RECOVERY_INERT_PERIOD;
+        assert!(config.check_settings().is_ok());
+        config.recovery_delay = ProgramConfig::MIN_RECOVERY_DELAY - 1;
+        assert!(config.check_settings().is_err());
+        config.recovery_delay = ProgramConfig::MIN_RECOVERY_DELAY;
+
+        config.settlement_dispute_window = ProgramConfig::MIN_SETTLEMENT_DISPUTE_WINDOW - 1;
+        assert!(config.check_settings().is_err());
+    }
+
+    #[test]
+    fn test_is_admin() {
+        let admin = Pubkey::new_unique();
+        let mut config = ProgramConfig::new(&admin, 255, 0, 0, 0, 0, 0);
+        assert!(config.is_admin([Pubkey::new_unique(), admin]));
+        assert!(!config.is_admin([Pubkey::new_unique()]));
+
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        config.admin_multisig[..3].copy_from_slice(&keys);
+        config.admin_threshold = 2;
+        assert!(config.is_admin([keys[0], keys[2]]));
+        assert!(!config.is_admin([keys[1]]));
+        // A repeated signer only counts once
+        assert!(!config.is_admin([keys[1], keys[1]]));
+        // The single admin is unused while the multisig is set
+        assert!(!config.is_admin([admin]));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::instructions::UpdateConfigData;
+use cruiser::prelude::*;
+
+/// An admin action waiting out [`ProgramConfig::admin_action_delay`](crate::accounts::ProgramConfig::admin_action_delay),
+/// at the [`ProposedActionSeeder`](crate::pda::ProposedActionSeeder) PDA for its id.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct ProposedAction {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The id the action was proposed with, from [`ProgramConfig::next_admin_action`](crate::accounts::ProgramConfig::next_admin_action).
+    pub id: u64,
+    /// Who paid the rent, refunded when the action executes or is cancelled.
+    pub funder: Pubkey,
+    /// When the action can execute.
+    pub executable_at: UnixTimestamp,
+    /// What the action does.
+    pub action: AdminAction,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+}
+impl ProposedAction {
+    /// Creates a new action executable at `executable_at`.
+    pub fn new(
+        id: u64,
+        funder: &Pubkey,
+        executable_at: UnixTimestamp,
+        action: AdminAction,
+        bump: u8,
+    ) -> Self {
+        Self {
+            version: 0,
+            id,
+            funder: *funder,
+            executable_at,
+            action,
+            bump,
+        }
+    }
+
+    /// Tells whether the action's delay has passed as of `now`.
+    pub fn is_executable(&self, now: UnixTimestamp) -> bool {
+        self.executable_at <= now
+    }
+}
+
+/// An admin instruction that can be timelocked.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub enum AdminAction {
+    /// An [`UpdateConfig`](crate::instructions::UpdateConfig).
+    UpdateConfig(UpdateConfigData),
+    /// A [`WithdrawFees`](crate::instructions::WithdrawFees).
+    WithdrawFees {
+        /// The lamports to send to the treasury.
+        amount: u64,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_executable() {
+        let action = ProposedAction::new(
+            0,
+            &Pubkey::new_unique(),
+            100,
+            AdminAction::WithdrawFees { amount: 1 },
+            255,
+        );
+        assert!(!action.is_executable(99));
+        assert!(action.is_executable(100));
+    }
+}
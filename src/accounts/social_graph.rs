@@ -0,0 +1,106 @@
+use cruiser::prelude::*;
+
+/// The profiles a profile follows, at the [`SocialGraphSeeder`](crate::pda::SocialGraphSeeder) PDA for the profile.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct SocialGraph {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The profile doing the following.
+    pub profile: Pubkey,
+    /// The bump seed of this account's PDA.
+    pub bump: u8,
+    /// The followed profiles. Unused slots are the zero key.
+    pub following: [Pubkey; SocialGraph::MAX_FOLLOWING],
+}
+impl SocialGraph {
+    /// The most profiles a profile can follow.
+    pub const MAX_FOLLOWING: usize = 32;
+
+    /// Creates a new social graph following nobody.
+    pub fn new(profile: &Pubkey, bump: u8) -> Self {
+        Self {
+            version: 0,
+            profile: *profile,
+            bump,
+            following: [Pubkey::new_from_array([0; 32]); Self::MAX_FOLLOWING],
+        }
+    }
+
+    /// Tells whether `profile` is followed.
+    pub fn is_following(&self, profile: &Pubkey) -> bool {
+        profile != &Pubkey::new_from_array([0; 32]) && self.following.contains(profile)
+    }
+
+    /// The followed profiles.
+    pub fn followed(&self) -> impl Iterator<Item = &Pubkey> {
+        let zero = Pubkey::new_from_array([0; 32]);
+        self.following
+            .iter()
+            .filter(move |profile| **profile != zero)
+    }
+
+    /// Follows `profile`. Returns false if it is already followed, is this graph's profile, or the list is full.
+    pub fn follow(&mut self, profile: &Pubkey) -> bool {
+        let zero = Pubkey::new_from_array([0; 32]);
+        if profile == &zero || profile == &self.profile || self.is_following(profile) {
+            return false;
+        }
+        match self.following.iter_mut().find(|slot| **slot == zero) {
+            Some(slot) => {
+                *slot = *profile;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unfollows `profile`. Returns false if it wasn't followed.
+    pub fn unfollow(&mut self, profile: &Pubkey) -> bool {
+        if !self.is_following(profile) {
+            return false;
+        }
+        for slot in self.following.iter_mut().filter(|slot| *slot == profile) {
+            *slot = Pubkey::new_from_array([0; 32]);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_follow() {
+        let owner = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let mut graph = SocialGraph::new(&owner, 255);
+        assert!(!graph.is_following(&Pubkey::new_from_array([0; 32])));
+        assert!(!graph.follow(&Pubkey::new_from_array([0; 32])));
+        assert!(!graph.follow(&owner));
+
+        assert!(graph.follow(&friend));
+        assert!(!graph.follow(&friend));
+        assert!(graph.is_following(&friend));
+        assert_eq!(graph.followed().collect::<Vec<_>>(), vec![&friend]);
+
+        assert!(graph.unfollow(&friend));
+        assert!(!graph.unfollow(&friend));
+        assert!(!graph.is_following(&friend));
+        assert_eq!(graph.followed().count(), 0);
+    }
+
+    #[test]
+    fn test_follow_full() {
+        let mut graph = SocialGraph::new(&Pubkey::new_unique(), 255);
+        for _ in 0..SocialGraph::MAX_FOLLOWING {
+            assert!(graph.follow(&Pubkey::new_unique()));
+        }
+        assert!(!graph.follow(&Pubkey::new_unique()));
+
+        // Unfollowing frees a slot
+        let followed = graph.following[3];
+        assert!(graph.unfollow(&followed));
+        assert!(graph.follow(&Pubkey::new_unique()));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::custom_error;
+use cruiser::prelude::*;
+use cruiser::solana_program::rent::Rent;
+
+/// The escrow holding a game's wagers, at the [`GameSignerSeeder`](crate::pda::GameSignerSeeder) PDA for the game.
+///
+/// Being owned by the program, escrows can be found with a discriminant filter
+/// and wagers are paid straight out of them without a system program CPI.
+/// The vault's rent is paid with the creator's wager and goes back to the creator's profile when the game is
+/// played out or forfeited, or to the creator with their refund when an unjoined game is swept or reclaimed.
+///
+/// This replaced the system owned game signer that used to hold the wagers, and with it the `unchecked-cpi`
+/// feature and `PAYOUT_CPI`, as payouts no longer CPI into the system program there are no borrow checks left to skip.
+/// Games created before then still hold their wagers on a system owned signer the program can't debit,
+/// they have to be finished, forfeited or swept with the previous program before upgrading.
+/// [`WagerVault::escrow`] rejects their signers rather than paying out of them.
+#[derive(Debug, BorshDeserialize, BorshSerialize, Eq, PartialEq, OnChainSize)]
+pub struct WagerVault {
+    /// The version of this account. Should be 0 until a new version is added.
+    pub version: u8,
+    /// The game whose wagers this holds.
+    pub game: Pubkey,
+    /// All the wagers put into the vault.
+    pub total_deposited: u64,
+    /// The mint of the wagers. The zero key for lamports.
+    pub mint: Pubkey,
+}
+impl WagerVault {
+    /// Creates a new empty lamport vault for `game`.
+    pub fn new(game: &Pubkey) -> Self {
+        Self {
+            version: 0,
+            game: *game,
+            total_deposited: 0,
+            mint: Pubkey::new_from_array([0; 32]),
+        }
+    }
+
+    /// Records a wager of `amount` put into the vault.
+    pub fn deposit(&mut self, amount: u64) {
+        self.total_deposited.saturating_add_assign(amount);
+    }
+
+    /// The wagers held by the vault account `vault`, its lamports past its rent.
+    /// Fails for the system owned signers of games created before vaults.
+    pub fn escrow<AI>(vault: &AI) -> CruiserResult<u64>
+    where
+        AI: AccountInfo,
+    {
+        if vault.data().is_empty() {
+            return Err(custom_error!(
+                "Game signer predates wager vaults, finish the game with the previous program"
+            )
+            .into());
+        }
+        let rent = Rent::get()?.minimum_balance(vault.data().len());
+        Ok(vault.lamports().saturating_sub(rent))
+    }
+
+    /// Moves `amount` lamports from the vault account `vault` to `to`.
+    pub fn pay_out<AI>(vault: &AI, to: &AI, amount: u64) -> CruiserResult<()>
+    where
+        AI: AccountInfo,
+    {
+        let mut vault_lamports = vault.lamports_mut();
+        *vault_lamports = vault_lamports
+            .checked_sub(amount)
+            .ok_or(custom_error!("Wager vault is short"))?;
+        *to.lamports_mut() += amount;
+        Ok(())
+    }
+
+    /// Moves everything left in the vault account `vault`, its rent included, to `to`, closing it.
+    pub fn close<AI>(vault: &AI, to: &AI) -> CruiserResult<()>
+    where
+        AI: AccountInfo,
+    {
+        let remaining = *vault.lamports();
+        Self::pay_out(vault, to, remaining)
+    }
+}
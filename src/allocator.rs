@@ -0,0 +1,119 @@
+//! The bump allocator used with the `custom-heap` feature.
+//!
+//! Solana's default allocator never frees anything. This one frees the most recent allocation,
+//! so the buffers an instruction builds and drops one after another reuse the same memory,
+//! and it records the most heap used so an instruction's usage can be logged.
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::mem::size_of;
+use std::ptr::null_mut;
+
+/// Where the program's heap starts.
+pub const HEAP_START: usize = 0x300000000;
+/// The size of the program's heap, the runtime's default of 32KB.
+pub const HEAP_LENGTH: usize = 32 * 1024;
+
+/// The program's allocator.
+#[cfg(all(feature = "custom-heap", target_os = "solana"))]
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator {
+    start: HEAP_START,
+    len: HEAP_LENGTH,
+};
+
+/// The most heap the program has used so far in this instruction, in bytes.
+#[cfg(all(feature = "custom-heap", target_os = "solana"))]
+pub fn heap_used() -> usize {
+    ALLOCATOR.high_water_mark()
+}
+
+/// A bump allocator over `len` bytes from `start`.
+/// Its state is kept in the first words of the heap, which the runtime zeroes for each instruction.
+#[derive(Debug)]
+pub struct BumpAllocator {
+    /// The start of the heap.
+    pub start: usize,
+    /// The length of the heap in bytes.
+    pub len: usize,
+}
+impl BumpAllocator {
+    /// The bytes at the start of the heap holding the position and the high water mark.
+    const STATE_SIZE: usize = 2 * size_of::<usize>();
+
+    /// The end of the used heap, as an offset from the start.
+    fn position(&self) -> *mut usize {
+        self.start as *mut usize
+    }
+
+    /// The largest the position has been.
+    fn high_water(&self) -> *mut usize {
+        (self.start + size_of::<usize>()) as *mut usize
+    }
+
+    /// The most of the heap used so far, in bytes.
+    pub fn high_water_mark(&self) -> usize {
+        // Safe as `start` points to the heap, which is valid for the whole instruction.
+        unsafe { *self.high_water() }
+    }
+}
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // A zeroed position means nothing has been allocated yet.
+        let position = (*self.position()).max(Self::STATE_SIZE);
+        let align = layout.align() - 1;
+        let start = match (self.start + position).checked_add(align) {
+            Some(start) => start & !align,
+            None => return null_mut(),
+        };
+        match start.checked_add(layout.size()) {
+            Some(end) if end <= self.start + self.len => {
+                *self.position() = end - self.start;
+                *self.high_water() = (*self.high_water()).max(end - self.start);
+                start as *mut u8
+            }
+            _ => null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // Only the most recent allocation can be given back.
+        if ptr as usize + layout.size() == self.start + *self.position() {
+            *self.position() = ptr as usize - self.start;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bump_allocator() {
+        let mut heap = vec![0u64; 64];
+        let allocator = BumpAllocator {
+            start: heap.as_mut_ptr() as usize,
+            len: heap.len() * size_of::<u64>(),
+        };
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        unsafe {
+            let first = allocator.alloc(layout);
+            assert_eq!(first as usize, allocator.start + BumpAllocator::STATE_SIZE);
+            let second = allocator.alloc(layout);
+            assert_eq!(second as usize, first as usize + 16);
+
+            // Freeing the latest allocation reuses its memory, freeing an older one doesn't.
+            allocator.dealloc(second, layout);
+            assert_eq!(allocator.alloc(layout), second);
+            allocator.dealloc(first, layout);
+            assert_eq!(allocator.alloc(layout) as usize, second as usize + 16);
+            assert_eq!(
+                allocator.high_water_mark(),
+                BumpAllocator::STATE_SIZE + 3 * 16
+            );
+
+            assert!(allocator
+                .alloc(Layout::from_size_align(allocator.len, 8).unwrap())
+                .is_null());
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Glue for [Bubblegum](https://github.com/metaplex-foundation/mpl-bubblegum) compressed NFTs,
+//! used to mint achievement badges.
+//!
+//! Only the parts of Bubblegum's interface this program uses are mirrored here,
+//! so there is no dependency on the Metaplex crates. Badge trees are created with the Bubblegum tooling
+//! and their tree delegate set to the [`BadgeAuthoritySeeder`](crate::pda::BadgeAuthoritySeeder) PDA.
+
+use cruiser::prelude::*;
+
+/// The Bubblegum program.
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    152, 139, 128, 235, 121, 53, 40, 105, 178, 36, 116, 95, 89, 221, 191, 138, 38, 88, 202, 19,
+    220, 104, 129, 33, 38, 53, 28, 174, 7, 193, 165, 165,
+]);
+
+/// The Anchor discriminant of Bubblegum's `mint_v1` instruction.
+const MINT_V1_DISCRIMINANT: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+/// The seeder for a tree's config, derived under [`BUBBLEGUM_PROGRAM_ID`].
+#[derive(Debug, Clone)]
+pub struct TreeConfigSeeder {
+    /// The tree the config is for.
+    pub merkle_tree: Pubkey,
+}
+impl PDASeeder for TreeConfigSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&self.merkle_tree as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The metadata of a compressed NFT.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct MetadataArgs {
+    /// The name of the asset.
+    pub name: String,
+    /// The symbol of the asset.
+    pub symbol: String,
+    /// The URI of the asset's off-chain JSON metadata.
+    pub uri: String,
+    /// Royalties in basis points.
+    pub seller_fee_basis_points: u16,
+    /// Whether the asset has been sold before.
+    pub primary_sale_happened: bool,
+    /// Whether the metadata can be changed.
+    pub is_mutable: bool,
+    /// The edition nonce, unused for compressed NFTs.
+    pub edition_nonce: Option<u8>,
+    /// The token standard.
+    pub token_standard: Option<TokenStandard>,
+    /// The collection the asset belongs to.
+    pub collection: Option<Collection>,
+    /// How the asset can be used.
+    pub uses: Option<Uses>,
+    /// The token program the asset would decompress to.
+    pub token_program_version: TokenProgramVersion,
+    /// The asset's creators. Verified creators must sign the mint.
+    pub creators: Vec<Creator>,
+}
+
+/// A token standard. Compressed NFTs are always [`TokenStandard::NonFungible`].
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum TokenStandard {
+    /// A non-fungible token.
+    NonFungible,
+    /// A fungible token with metadata.
+    FungibleAsset,
+    /// A fungible token.
+    Fungible,
+    /// A non-fungible edition.
+    NonFungibleEdition,
+}
+
+/// The collection an asset belongs to.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct Collection {
+    /// Whether the collection's authority verified the asset.
+    pub verified: bool,
+    /// The collection's mint.
+    pub key: Pubkey,
+}
+
+/// How an asset can be used.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct Uses {
+    /// What a use does.
+    pub use_method: UseMethod,
+    /// The uses left.
+    pub remaining: u64,
+    /// The uses the asset started with.
+    pub total: u64,
+}
+
+/// What using an asset does.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum UseMethod {
+    /// The asset is burned once used.
+    Burn,
+    /// The asset can be used many times.
+    Multiple,
+    /// The asset can be used once.
+    Single,
+}
+
+/// The token program an asset would decompress to.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum TokenProgramVersion {
+    /// The original token program.
+    Original,
+    /// Token-2022.
+    Token2022,
+}
+
+/// A creator of an asset.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct Creator {
+    /// The creator's key.
+    pub address: Pubkey,
+    /// Whether the creator signed the mint.
+    pub verified: bool,
+    /// The creator's share of royalties, in percent.
+    pub share: u8,
+}
+
+/// CPI for Bubblegum's `mint_v1`.
+#[derive(Debug)]
+pub struct MintV1CPI<'a, AI> {
+    accounts: [MaybeOwned<'a, AI>; 9],
+    data: Vec<u8>,
+}
+impl<'a, AI> MintV1CPI<'a, AI> {
+    /// Mints a compressed NFT with `metadata` to `leaf_owner`.
+    /// `tree_delegate` must be the tree's creator or delegate.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        tree_config: impl Into<MaybeOwned<'a, AI>>,
+        leaf_owner: impl Into<MaybeOwned<'a, AI>>,
+        leaf_delegate: impl Into<MaybeOwned<'a, AI>>,
+        merkle_tree: impl Into<MaybeOwned<'a, AI>>,
+        payer: impl Into<MaybeOwned<'a, AI>>,
+        tree_delegate: impl Into<MaybeOwned<'a, AI>>,
+        noop_program: impl Into<MaybeOwned<'a, AI>>,
+        compression_program: impl Into<MaybeOwned<'a, AI>>,
+        system_program: impl Into<MaybeOwned<'a, AI>>,
+        metadata: &MetadataArgs,
+    ) -> CruiserResult<Self> {
+        let mut data = MINT_V1_DISCRIMINANT.to_vec();
+        metadata.serialize(&mut data)?;
+        Ok(Self {
+            accounts: [
+                tree_config.into(),
+                leaf_owner.into(),
+                leaf_delegate.into(),
+                merkle_tree.into(),
+                payer.into(),
+                tree_delegate.into(),
+                noop_program.into(),
+                compression_program.into(),
+                system_program.into(),
+            ],
+            data,
+        })
+    }
+}
+impl<'a, AI> MintV1CPI<'a, AI>
+where
+    AI: ToSolanaAccountMeta,
+{
+    /// Gets the instruction and accounts to call Bubblegum with.
+    pub fn instruction(
+        self,
+        bubblegum_program: impl Into<MaybeOwned<'a, AI>>,
+    ) -> InstructionAndAccounts<[MaybeOwned<'a, AI>; 10]> {
+        let bubblegum_program = bubblegum_program.into();
+        let instruction = SolanaInstruction {
+            program_id: *bubblegum_program.meta_key(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(MaybeOwned::as_ref)
+                .map(AI::to_solana_account_meta)
+                .collect(),
+            data: self.data,
+        };
+        let mut accounts = self.accounts.into_iter();
+        InstructionAndAccounts {
+            instruction,
+            accounts: [
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                bubblegum_program,
+            ],
+        }
+    }
+}
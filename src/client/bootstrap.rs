@@ -0,0 +1,57 @@
+use crate::client::{send_instruction_set, RetryConfig};
+use crate::instructions::create_profile;
+use cruiser::prelude::*;
+use std::error::Error;
+
+/// The genesis hash of devnet, the only cluster [`bootstrap_player`] airdrops on.
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+/// The balance [`bootstrap_player`] tops `wallet` up to on devnet, enough for a profile and a few games.
+pub const BOOTSTRAP_AIRDROP: u64 = LAMPORTS_PER_SOL;
+
+/// Gets `wallet` ready to play: airdrops to it if on devnet and it's below [`BOOTSTRAP_AIRDROP`],
+/// then creates a profile with it as the authority and funder.
+/// Waits for both to be confirmed and returns the new profile's key.
+///
+/// Off devnet `wallet` has to be funded already.
+pub async fn bootstrap_player(
+    rpc: &RpcClient,
+    program_id: Pubkey,
+    wallet: &Keypair,
+) -> Result<Pubkey, Box<dyn Error>> {
+    if rpc.get_genesis_hash().await?.to_string() == DEVNET_GENESIS_HASH {
+        let balance = rpc
+            .get_balance_with_commitment(&wallet.pubkey(), CommitmentConfig::confirmed())
+            .await?
+            .value;
+        if balance < BOOTSTRAP_AIRDROP {
+            let blockhash = rpc.get_latest_blockhash().await?;
+            let sig = rpc
+                .request_airdrop_with_blockhash(
+                    &wallet.pubkey(),
+                    BOOTSTRAP_AIRDROP - balance,
+                    &blockhash,
+                )
+                .await?;
+            rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
+                .await?;
+        }
+    }
+
+    let profile = Keypair::new();
+    send_instruction_set(
+        rpc,
+        wallet,
+        || create_profile(program_id, wallet, &profile, wallet),
+        RpcSendTransactionConfig {
+            skip_preflight: false,
+            preflight_commitment: Some(CommitmentLevel::Confirmed),
+            encoding: None,
+            max_retries: None,
+        },
+        CommitmentConfig::confirmed(),
+        RetryConfig::default(),
+    )
+    .await?
+    .into_result()?;
+    Ok(profile.pubkey())
+}
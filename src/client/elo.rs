@@ -0,0 +1,40 @@
+use crate::accounts::{predict_elo_change, GameOutcome, PlayerProfile};
+
+/// How a player's elo would move after a game, for showing before they commit to it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EloPreview {
+    /// The change if they win.
+    pub win: i64,
+    /// The change on a draw.
+    pub draw: i64,
+    /// The change if they lose.
+    pub loss: i64,
+}
+
+/// Previews how `me`'s elo would move in a game against `them` that's played out,
+/// using [`PlayerProfile::ELO_K`]. Forfeits move elo further, see [`PlayerProfile::FORFEIT_ELO_K`].
+pub fn preview_elo(me: &PlayerProfile, them: &PlayerProfile) -> EloPreview {
+    let change = |outcome| predict_elo_change(me.elo, them.elo, PlayerProfile::ELO_K, outcome);
+    EloPreview {
+        win: change(GameOutcome::Win),
+        draw: change(GameOutcome::Draw),
+        loss: change(GameOutcome::Loss),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cruiser::prelude::Pubkey;
+
+    #[test]
+    fn test_preview_elo() {
+        let mut me = PlayerProfile::new(&Pubkey::new_unique());
+        let mut them = PlayerProfile::new(&Pubkey::new_unique());
+        me.elo = 1300;
+        them.elo = 1500;
+        let preview = preview_elo(&me, &them);
+        assert!(preview.win > -preview.loss);
+        assert!(preview.draw > 0);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::event::ProgramEvent;
+use crate::log::LogEvent;
+use cruiser::prelude::Pubkey;
+
+/// The prefix the runtime logs `sol_log_data` with, followed by each slice in base64.
+const DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Something the program logged, decoded from its [`LogEvent`]s by [`decode_events`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TutorialEvent {
+    /// A game was created by [`CreateGame`](crate::instructions::CreateGame).
+    GameCreated,
+    /// A game against the house was created by [`CreateHouseGame`](crate::instructions::CreateHouseGame).
+    HouseGameCreated,
+    /// A profile's elo decayed to `elo`.
+    EloDecayed {
+        /// The profile's new elo.
+        elo: u64,
+    },
+    /// A profile was pushed off a leaderboard page.
+    LeaderboardEvicted {
+        /// The evicted profile.
+        profile: Pubkey,
+    },
+    /// Lamports were withdrawn from a profile.
+    ProfileWithdrawal {
+        /// The lamports withdrawn.
+        amount: u64,
+    },
+    /// A config field was changed.
+    ConfigChanged {
+        /// The name of the field.
+        field: String,
+        /// The old value, debug formatted.
+        from: String,
+        /// The new value, debug formatted.
+        to: String,
+    },
+    /// The config admin was changed.
+    AdminChanged {
+        /// The old admin.
+        from: Pubkey,
+        /// The new admin.
+        to: Pubkey,
+    },
+    /// Fees were withdrawn from the fee vault.
+    FeesWithdrawn {
+        /// The lamports withdrawn.
+        amount: u64,
+        /// The treasury they went to.
+        treasury: Pubkey,
+    },
+    /// An admin action was proposed.
+    AdminActionProposed {
+        /// The action's id.
+        id: u64,
+        /// When it can be executed.
+        executable_at: i64,
+    },
+    /// An admin action was executed.
+    AdminActionExecuted {
+        /// The action's id.
+        id: u64,
+    },
+    /// An admin action was cancelled.
+    AdminActionCancelled {
+        /// The action's id.
+        id: u64,
+    },
+    /// Recovery of a stuck game was proposed.
+    GameRecoveryProposed {
+        /// The stuck game.
+        game: Pubkey,
+        /// Where the escrow will go.
+        funds_to: Pubkey,
+    },
+    /// A stuck game was recovered, refunding its players.
+    GameRecovered {
+        /// The stakes refunded to the players' profiles.
+        amount: u64,
+        /// The stuck game.
+        game: Pubkey,
+        /// Where the rent of the game and its vault went.
+        funds_to: Pubkey,
+    },
+    /// A game recovery was cancelled because the game moved.
+    GameRecoveryCancelled {
+        /// The game that moved.
+        game: Pubkey,
+    },
+}
+
+/// Decodes the program's events out of a transaction's log messages, skipping lines that aren't one.
+pub fn decode_events(logs: &[String]) -> Vec<TutorialEvent> {
+    logs.iter()
+        .filter_map(|log| LogEvent::parse(log))
+        .filter_map(|event| decode_event(&event))
+        .collect()
+}
+
+/// Decodes the [`ProgramEvent`]s `program_id` emitted from a transaction's log messages, including from CPIs into it.
+/// Events that don't decode, like ones from a newer version of the program, are skipped.
+pub fn decode_program_events(program_id: &Pubkey, logs: &[String]) -> Vec<ProgramEvent> {
+    let program_id = program_id.to_string();
+    // The programs being invoked, innermost last
+    let mut invoked = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        if let Some(data) = log.strip_prefix(DATA_LOG_PREFIX) {
+            if invoked.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            if let Some(event) = base64::decode(data.trim())
+                .ok()
+                .and_then(|data| ProgramEvent::decode(&data).ok())
+            {
+                events.push(event);
+            }
+        } else if let Some((program, status)) = log
+            .strip_prefix("Program ")
+            .and_then(|log| log.split_once(' '))
+        {
+            if status.starts_with("invoke [") {
+                invoked.push(program);
+            } else if (status == "success" || status.starts_with("failed"))
+                && invoked.last() == Some(&program)
+            {
+                invoked.pop();
+            }
+        }
+    }
+    events
+}
+
+fn decode_event(event: &LogEvent) -> Option<TutorialEvent> {
+    Some(match event.event.as_str() {
+        "game_created" => TutorialEvent::GameCreated,
+        "house_game_created" => TutorialEvent::HouseGameCreated,
+        "elo_decayed" => TutorialEvent::EloDecayed {
+            elo: event.parse_field("elo")?,
+        },
+        "leaderboard_evicted" => TutorialEvent::LeaderboardEvicted {
+            profile: event.parse_field("profile")?,
+        },
+        "profile_withdrawal" => TutorialEvent::ProfileWithdrawal {
+            amount: event.parse_field("amount")?,
+        },
+        "config_changed" => TutorialEvent::ConfigChanged {
+            field: event.str_field("field")?,
+            from: event.field("from")?.to_string(),
+            to: event.field("to")?.to_string(),
+        },
+        "admin_changed" => TutorialEvent::AdminChanged {
+            from: event.parse_field("from")?,
+            to: event.parse_field("to")?,
+        },
+        "fees_withdrawn" => TutorialEvent::FeesWithdrawn {
+            amount: event.parse_field("amount")?,
+            treasury: event.parse_field("treasury")?,
+        },
+        "admin_action_proposed" => TutorialEvent::AdminActionProposed {
+            id: event.parse_field("id")?,
+            executable_at: event.parse_field("executable_at")?,
+        },
+        "admin_action_executed" => TutorialEvent::AdminActionExecuted {
+            id: event.parse_field("id")?,
+        },
+        "admin_action_cancelled" => TutorialEvent::AdminActionCancelled {
+            id: event.parse_field("id")?,
+        },
+        "game_recovery_proposed" => TutorialEvent::GameRecoveryProposed {
+            game: event.parse_field("game")?,
+            funds_to: event.parse_field("funds_to")?,
+        },
+        "game_recovered" => TutorialEvent::GameRecovered {
+            amount: event.parse_field("amount")?,
+            game: event.parse_field("game")?,
+            funds_to: event.parse_field("funds_to")?,
+        },
+        "game_recovery_cancelled" => TutorialEvent::GameRecoveryCancelled {
+            game: event.parse_field("game")?,
+        },
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::Player;
+
+    #[test]
+    fn test_decode_program_events() {
+        let program_id = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let joined = ProgramEvent::GameJoined {
+            game: Pubkey::new_unique(),
+            profile: Pubkey::new_unique(),
+            first_player: Player::Two,
+            started_at: 100,
+        };
+        let data = |event: &ProgramEvent| {
+            format!(
+                "{}{}",
+                DATA_LOG_PREFIX,
+                base64::encode(event.encode().unwrap())
+            )
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: INFO event=game_created".to_string(),
+            data(&joined),
+            format!("Program {} invoke [2]", other_program),
+            // Another program's data isn't an event
+            data(&joined),
+            format!("Program {} success", other_program),
+            // An event from a newer program
+            format!("{}{}", DATA_LOG_PREFIX, base64::encode([1, 200])),
+            data(&ProgramEvent::AdminActionExecuted { id: 3 }),
+            format!(
+                "Program {} consumed 5000 of 200000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+            data(&joined),
+        ];
+        assert_eq!(
+            decode_program_events(&program_id, &logs),
+            vec![joined, ProgramEvent::AdminActionExecuted { id: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_decode_events() {
+        let game = Pubkey::new_unique();
+        let funds_to = Pubkey::new_unique();
+        let logs = vec![
+            "Program 11111111111111111111111111111111 invoke [1]".to_string(),
+            "Program log: INFO event=game_created".to_string(),
+            format!(
+                "Program log: INFO event=game_recovered amount=1500 game={} funds_to={}",
+                game, funds_to
+            ),
+            "Program log: INFO event=config_changed field=\"paused\" from=false to=true".to_string(),
+            "Program log: INFO event=admin_action_proposed id=3 executable_at=100 action=WithdrawFees { amount: 5 }"
+                .to_string(),
+            "Program log: DEBUG event=transferring".to_string(),
+        ];
+        assert_eq!(
+            decode_events(&logs),
+            vec![
+                TutorialEvent::GameCreated,
+                TutorialEvent::GameRecovered {
+                    amount: 1500,
+                    game,
+                    funds_to,
+                },
+                TutorialEvent::ConfigChanged {
+                    field: "paused".to_string(),
+                    from: "false".to_string(),
+                    to: "true".to_string(),
+                },
+                TutorialEvent::AdminActionProposed {
+                    id: 3,
+                    executable_at: 100,
+                },
+            ]
+        );
+    }
+}
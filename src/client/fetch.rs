@@ -0,0 +1,112 @@
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_account_decoder::UiAccountEncoding;
+use cruiser::solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use cruiser::solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use std::error::Error;
+
+/// Fetches every account of type `T` the program owns, found by its discriminant, as `(key, account)` pairs.
+/// The node filters on the discriminant so only `T`s are sent, but it still scans every account the program owns,
+/// so this is meant for indexers and tools more than for every page view.
+/// Accounts that don't decode as a `T`, like ones left by an older version of the program, are skipped.
+/// Use [`fetch_static_program_accounts`] for accounts that are always the same size.
+pub async fn fetch_program_accounts<T>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Vec<(Pubkey, T)>, Box<dyn Error>>
+where
+    TutorialAccounts: AccountListItem<T>,
+    T: BorshDeserialize,
+{
+    fetch_filtered_program_accounts(rpc, program_id, None).await
+}
+
+/// [`fetch_program_accounts`] for accounts that are always [`OnChainSize::ON_CHAIN_SIZE`] past their discriminant,
+/// also filtering on the size so the node sends less.
+pub async fn fetch_static_program_accounts<T>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Vec<(Pubkey, T)>, Box<dyn Error>>
+where
+    TutorialAccounts: AccountListItem<T>,
+    T: BorshDeserialize + OnChainSize,
+{
+    fetch_filtered_program_accounts(rpc, program_id, Some(T::ON_CHAIN_SIZE)).await
+}
+
+/// Fetches the program's `T`s filtered by discriminant and, if set, by `size` past the discriminant.
+async fn fetch_filtered_program_accounts<T>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    size: Option<usize>,
+) -> Result<Vec<(Pubkey, T)>, Box<dyn Error>>
+where
+    TutorialAccounts: AccountListItem<T>,
+    T: BorshDeserialize,
+{
+    let mut discriminant = Vec::new();
+    <TutorialAccounts as AccountListItem<T>>::compressed_discriminant()
+        .serialize(&mut discriminant)?;
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp {
+        offset: 0,
+        bytes: MemcmpEncodedBytes::Base64(base64::encode(&discriminant)),
+        encoding: None,
+    })];
+    if let Some(size) = size {
+        filters.push(RpcFilterType::DataSize((discriminant.len() + size) as u64));
+    }
+    let accounts = rpc
+        .get_program_accounts_with_config(
+            program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..Default::default()
+                },
+                with_context: None,
+            },
+        )
+        .await?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(key, account)| {
+            decode_account(&key, &account.data)
+                .ok()
+                .map(|account| (key, account))
+        })
+        .collect())
+}
+
+/// Fetches the account of type `T` at `key`, [`None`] if there is none.
+/// Errors if the account is some other type.
+pub async fn fetch_account<T>(rpc: &RpcClient, key: &Pubkey) -> Result<Option<T>, Box<dyn Error>>
+where
+    TutorialAccounts: AccountListItem<T>,
+    T: BorshDeserialize,
+{
+    let account = match rpc
+        .get_account_with_commitment(key, CommitmentConfig::confirmed())
+        .await?
+        .value
+    {
+        Some(account) => account,
+        None => return Ok(None),
+    };
+    decode_account(key, &account.data).map(Some)
+}
+
+/// Decodes the account data `data` of `key` as a `T`, erroring if it is some other type.
+pub fn decode_account<T>(key: &Pubkey, mut data: &[u8]) -> Result<T, Box<dyn Error>>
+where
+    TutorialAccounts: AccountListItem<T>,
+    T: BorshDeserialize,
+{
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    if discriminant != <TutorialAccounts as AccountListItem<T>>::compressed_discriminant() {
+        return Err(format!("account {} is not of the expected type", key).into());
+    }
+    Ok(T::deserialize(&mut data)?)
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "client-rpc")]
+pub use cruiser::prelude::{HashedSigner, InstructionSet};
+pub use solana_sdk::signature::{Keypair, Signature};
+pub use solana_sdk::signer::{Signer, SignerError};
+
+#[cfg(not(feature = "client-rpc"))]
+use cruiser::solana_program::instruction::Instruction as SolanaInstruction;
+#[cfg(not(feature = "client-rpc"))]
+use cruiser::solana_program::pubkey::Pubkey;
+#[cfg(not(feature = "client-rpc"))]
+use std::collections::HashSet;
+#[cfg(not(feature = "client-rpc"))]
+use std::fmt::{Debug, Formatter};
+#[cfg(not(feature = "client-rpc"))]
+use std::hash::{Hash, Hasher};
+
+/// Instructions and the signers they need, returned by every instruction builder.
+/// Without `client-rpc` this stands in for cruiser's own, which needs its RPC client.
+#[cfg(not(feature = "client-rpc"))]
+#[derive(Debug, Default)]
+pub struct InstructionSet<'a> {
+    /// The instructions, in the order they're sent.
+    pub instructions: Vec<SolanaInstruction>,
+    /// Everyone who has to sign for the instructions.
+    pub signers: HashSet<HashedSigner<'a>>,
+}
+
+/// A signer that hashes and compares by its pubkey, so a key signing for several instructions is only asked once.
+#[cfg(not(feature = "client-rpc"))]
+#[derive(Copy, Clone)]
+pub struct HashedSigner<'a>(pub &'a dyn Signer);
+#[cfg(not(feature = "client-rpc"))]
+impl HashedSigner<'_> {
+    /// The signer's pubkey.
+    pub fn pubkey(&self) -> Pubkey {
+        self.0.pubkey()
+    }
+}
+#[cfg(not(feature = "client-rpc"))]
+impl<'a, T> From<&'a T> for HashedSigner<'a>
+where
+    T: Signer,
+{
+    fn from(from: &'a T) -> Self {
+        Self(from)
+    }
+}
+#[cfg(not(feature = "client-rpc"))]
+impl Debug for HashedSigner<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HashedSigner").field(&self.pubkey()).finish()
+    }
+}
+#[cfg(not(feature = "client-rpc"))]
+impl PartialEq for HashedSigner<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pubkey() == other.pubkey()
+    }
+}
+#[cfg(not(feature = "client-rpc"))]
+impl Eq for HashedSigner<'_> {}
+#[cfg(not(feature = "client-rpc"))]
+impl Hash for HashedSigner<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pubkey().hash(state)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::accounts::{LeaderboardEntry, LeaderboardShard};
+use cruiser::prelude::*;
+use std::collections::HashSet;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+use std::error::Error;
+
+/// Stitches leaderboard pages into one leaderboard, highest elo first.
+///
+/// A profile whose elo changed may still be listed on a page of its old band until someone
+/// updates it, so only its highest listing is kept.
+pub fn stitch_leaderboard<'a>(
+    shards: impl IntoIterator<Item = &'a LeaderboardShard>,
+) -> Vec<LeaderboardEntry> {
+    sort_entries(
+        shards
+            .into_iter()
+            .flat_map(LeaderboardShard::listed)
+            .copied(),
+    )
+}
+
+/// Sorts `entries` highest elo first, keeping only the highest listing of each profile.
+fn sort_entries(entries: impl IntoIterator<Item = LeaderboardEntry>) -> Vec<LeaderboardEntry> {
+    let mut entries = entries.into_iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.elo.cmp(&a.elo).then_with(|| a.profile.cmp(&b.profile)));
+    let mut seen = HashSet::new();
+    entries.retain(|entry| seen.insert(entry.profile));
+    entries
+}
+
+/// A profile's place on a leaderboard.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RankedEntry {
+    /// The profile's rank, from 1. Profiles with the same elo share a rank and the next rank is skipped.
+    pub rank: usize,
+    /// The profile's key.
+    pub profile: Pubkey,
+    /// The profile's elo.
+    pub elo: u64,
+}
+
+/// The part of a leaderboard to return, see [`rank_leaderboard`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardPage {
+    /// The number of entries to skip.
+    pub offset: usize,
+    /// The most entries to return.
+    pub limit: usize,
+}
+impl Default for LeaderboardPage {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: 50,
+        }
+    }
+}
+
+/// A page of a ranked leaderboard.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Leaderboard {
+    /// The number of profiles on the whole leaderboard.
+    pub total: usize,
+    /// The entries on the requested page, highest elo first.
+    pub entries: Vec<RankedEntry>,
+}
+
+/// Ranks `entries` highest elo first and returns `page` of them.
+/// A profile listed more than once keeps only its highest listing, as with [`stitch_leaderboard`].
+pub fn rank_leaderboard(
+    entries: impl IntoIterator<Item = LeaderboardEntry>,
+    page: LeaderboardPage,
+) -> Leaderboard {
+    let entries = sort_entries(entries);
+    let mut rank = 0;
+    let mut last_elo = None;
+    let ranked = entries.iter().enumerate().map(|(index, entry)| {
+        if last_elo != Some(entry.elo) {
+            rank = index + 1;
+            last_elo = Some(entry.elo);
+        }
+        RankedEntry {
+            rank,
+            profile: entry.profile,
+            elo: entry.elo,
+        }
+    });
+    Leaderboard {
+        total: entries.len(),
+        // Ranks depend on the entries before the page so they're all walked
+        entries: ranked.skip(page.offset).take(page.limit).collect(),
+    }
+}
+
+/// Where [`fetch_leaderboard`] reads the leaderboard from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LeaderboardSource {
+    /// Every [`PlayerProfile`](crate::accounts::PlayerProfile) at its current elo.
+    Profiles,
+    /// The [`LeaderboardShard`] pages, the leaderboard as published on-chain. Only has profiles that have been listed with
+    /// [`UpdateLeaderboard`](crate::instructions::UpdateLeaderboard), at the elo they were last listed at.
+    Shards,
+}
+
+/// Fetches the program's accounts and returns `page` of the leaderboard ranked from `source`, see [`rank_leaderboard`].
+/// Profiles are read with [`fetch_program_accounts`](crate::client::fetch_program_accounts) and shards with
+/// [`fetch_static_program_accounts`](crate::client::fetch_static_program_accounts), both skip accounts that don't decode.
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub async fn fetch_leaderboard(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    source: LeaderboardSource,
+    page: LeaderboardPage,
+) -> Result<Leaderboard, Box<dyn Error>> {
+    use crate::accounts::PlayerProfile;
+    use crate::client::{fetch_program_accounts, fetch_static_program_accounts};
+
+    let entries = match source {
+        LeaderboardSource::Profiles => fetch_program_accounts::<PlayerProfile>(rpc, program_id)
+            .await?
+            .into_iter()
+            .map(|(profile, player_profile)| LeaderboardEntry {
+                profile,
+                elo: player_profile.elo,
+            })
+            .collect::<Vec<_>>(),
+        LeaderboardSource::Shards => {
+            fetch_static_program_accounts::<LeaderboardShard>(rpc, program_id)
+                .await?
+                .iter()
+                .flat_map(|(_, shard)| shard.listed().copied())
+                .collect()
+        }
+    };
+    Ok(rank_leaderboard(entries, page))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stitch_leaderboard() {
+        let top = Pubkey::new_unique();
+        let moved = Pubkey::new_unique();
+        let bottom = Pubkey::new_unique();
+
+        let mut high = LeaderboardShard::new(13, 0, 255);
+        high.insert(LeaderboardEntry {
+            profile: top,
+            elo: 1390,
+        });
+        let mut low = LeaderboardShard::new(12, 0, 255);
+        low.insert(LeaderboardEntry {
+            profile: bottom,
+            elo: 1200,
+        });
+        low.insert(LeaderboardEntry {
+            profile: moved,
+            elo: 1210,
+        });
+        let mut low_page = LeaderboardShard::new(12, 1, 255);
+        // Not yet removed from its old band
+        low_page.insert(LeaderboardEntry {
+            profile: top,
+            elo: 1290,
+        });
+
+        assert_eq!(
+            stitch_leaderboard([&low, &low_page, &high]),
+            vec![
+                LeaderboardEntry {
+                    profile: top,
+                    elo: 1390
+                },
+                LeaderboardEntry {
+                    profile: moved,
+                    elo: 1210
+                },
+                LeaderboardEntry {
+                    profile: bottom,
+                    elo: 1200
+                },
+            ]
+        );
+        assert!(stitch_leaderboard([]).is_empty());
+    }
+
+    #[test]
+    fn test_rank_leaderboard() {
+        let entries = [1200, 1300, 1200, 1100, 1300]
+            .into_iter()
+            .map(|elo| LeaderboardEntry {
+                profile: Pubkey::new_unique(),
+                elo,
+            })
+            .collect::<Vec<_>>();
+        let all = rank_leaderboard(
+            entries.clone(),
+            LeaderboardPage {
+                offset: 0,
+                limit: 10,
+            },
+        );
+        assert_eq!(all.total, 5);
+        assert_eq!(
+            all.entries
+                .iter()
+                .map(|entry| (entry.rank, entry.elo))
+                .collect::<Vec<_>>(),
+            vec![(1, 1300), (1, 1300), (3, 1200), (3, 1200), (5, 1100)]
+        );
+
+        let page = rank_leaderboard(
+            entries,
+            LeaderboardPage {
+                offset: 3,
+                limit: 1,
+            },
+        );
+        assert_eq!(page.total, 5);
+        assert_eq!(page.entries, all.entries[3..4]);
+    }
+}
@@ -0,0 +1,25 @@
+use solana_remote_wallet::locator::Locator;
+use solana_remote_wallet::remote_keypair::{generate_remote_keypair, RemoteKeypair};
+use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+use solana_sdk::derivation_path::DerivationPath;
+use std::error::Error;
+
+/// Connects to the first Ledger plugged in, signing with the Solana app's key at `m/44'/501'/account'/change'`.
+/// Pass a reference to the returned keypair anywhere the client helpers take a signer,
+/// each transaction will need approving on the device.
+///
+/// With `confirm_key` the device shows the key to check before anything is signed.
+pub fn ledger_signer(
+    account: Option<u32>,
+    change: Option<u32>,
+    confirm_key: bool,
+) -> Result<RemoteKeypair, Box<dyn Error>> {
+    let wallet_manager = maybe_wallet_manager()?.ok_or("No hardware wallet found")?;
+    Ok(generate_remote_keypair(
+        Locator::new_from_path("usb://ledger")?,
+        DerivationPath::new_bip44(account, change),
+        &wallet_manager,
+        confirm_key,
+        "ledger",
+    )?)
+}
@@ -0,0 +1,221 @@
+use crate::accounts::{Game, GameVariant, Player, RuleSet};
+use crate::client::{
+    fetch_account, fetch_static_program_accounts, send_instruction_set, RetryConfig,
+};
+use crate::instructions::{create_game, join_game_self_funded, CreateGameClientData};
+use cruiser::prelude::*;
+use std::error::Error;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+
+/// The games [`find_match`] joins or creates.
+#[derive(Clone, Debug)]
+pub struct MatchOptions {
+    /// The board to play on.
+    pub variant: GameVariant,
+    /// The rules of a created game. Games to join can have any rules.
+    pub rules: RuleSet,
+    /// The turn length of a created game.
+    pub turn_length: UnixTimestamp,
+    /// How long a created game waits for a joiner before it can be swept.
+    pub join_ttl: UnixTimestamp,
+    /// How often to check whether a created game has been joined.
+    pub poll_interval: Duration,
+    /// How long to wait for a created game to be joined before giving up on it.
+    pub timeout: Duration,
+}
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            variant: GameVariant::Ultimate,
+            rules: RuleSet::default(),
+            turn_length: 60 * 5,
+            join_ttl: 60 * 10,
+            poll_interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(60 * 10),
+        }
+    }
+}
+
+/// Keeps the games in `games` that `profile` could join as of `now`, with a joiner's wager in `wager_range`
+/// and played on `variant`, longest waiting first.
+/// Private games, games for other invited or locked players and [stale](Game::is_stale) games are left out.
+pub fn joinable_games(
+    profile: &Pubkey,
+    games: impl IntoIterator<Item = (Pubkey, Game)>,
+    wager_range: &RangeInclusive<u64>,
+    variant: GameVariant,
+    now: UnixTimestamp,
+) -> Vec<(Pubkey, Game)> {
+    let mut games = games
+        .into_iter()
+        .filter(|(_, game)| {
+            !game.is_started()
+                && game.join_code_hash == [0; 32]
+                && game.variant == variant
+                && wager_range.contains(&game.joiner_wager)
+                && !game.is_stale(now)
+                && game.is_valid_other_player(profile)
+        })
+        .collect::<Vec<_>>();
+    games.sort_by_key(|(key, game)| (game.created_at, *key));
+    games
+}
+
+/// Finds a game for `profile` with a joiner's wager in `wager_range`, resolving with the game's key once it has started.
+///
+/// Joins the longest waiting open game that fits, see [`joinable_games`], moving on to the next if another player gets there first.
+/// With none to join it creates a public game wagering the bottom of `wager_range` on both sides and waits for someone to join it.
+/// A range starting at 0 creates friendly games.
+/// `wallet` is the profile's authority and pays for everything.
+///
+/// A created game left unjoined past [`MatchOptions::timeout`] errors and is left open,
+/// it can be swept once its [`MatchOptions::join_ttl`] passes.
+pub async fn find_match(
+    rpc: &RpcClient,
+    program_id: Pubkey,
+    wallet: &Keypair,
+    profile: Pubkey,
+    wager_range: RangeInclusive<u64>,
+    options: MatchOptions,
+) -> Result<Pubkey, Box<dyn Error>> {
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as UnixTimestamp;
+    let games = fetch_static_program_accounts::<Game>(rpc, &program_id).await?;
+    for (game, game_data) in joinable_games(&profile, games, &wager_range, options.variant, now) {
+        let joined = send_instruction_set(
+            rpc,
+            wallet,
+            || {
+                join_game_self_funded(
+                    program_id,
+                    wallet,
+                    profile,
+                    game,
+                    game_data.signer_bump,
+                    vec![],
+                )
+            },
+            send_config,
+            CommitmentConfig::confirmed(),
+            RetryConfig::default(),
+        )
+        .await?;
+        // Anything but a join means someone else got there first or the game changed, try the next
+        if joined.into_result().is_ok() {
+            return Ok(game);
+        }
+    }
+
+    let game = Keypair::new();
+    let wager = *wager_range.start();
+    send_instruction_set(
+        rpc,
+        wallet,
+        || {
+            create_game(
+                program_id,
+                wallet,
+                profile,
+                &game,
+                wallet,
+                wallet,
+                None,
+                CreateGameClientData {
+                    creator_player: Player::One,
+                    wager,
+                    joiner_wager: wager,
+                    turn_length: options.turn_length,
+                    join_ttl: options.join_ttl,
+                    variant: options.variant,
+                    rules: options.rules,
+                    random_first_player: true,
+                    join_code_hash: [0; 32],
+                    invited: [Pubkey::new_from_array([0; 32]); Game::MAX_INVITED],
+                    friendly: wager == 0,
+                    lock_ttl: 0,
+                },
+            )
+        },
+        send_config,
+        CommitmentConfig::confirmed(),
+        RetryConfig::default(),
+    )
+    .await?
+    .into_result()?;
+
+    let started = Instant::now();
+    loop {
+        if let Some(game_data) = fetch_account::<Game>(rpc, &game.pubkey()).await? {
+            if game_data.is_started() {
+                return Ok(game.pubkey());
+            }
+        }
+        if started.elapsed() >= options.timeout {
+            return Err(format!("no one joined game {} in time", game.pubkey()).into());
+        }
+        sleep(options.poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_game(creator: &Pubkey, wager: u64, created_at: UnixTimestamp) -> (Pubkey, Game) {
+        let mut game = Game::new(
+            creator,
+            Player::One,
+            255,
+            wager,
+            wager,
+            0,
+            600,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        game.created_at = created_at;
+        (Pubkey::new_unique(), game)
+    }
+
+    #[test]
+    fn test_joinable_games() {
+        let me = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let newer = new_game(&other, 100, 20);
+        let older = new_game(&other, 200, 10);
+        let too_rich = new_game(&other, 1_000, 0);
+        let mine = new_game(&me, 100, 0);
+        let stale = new_game(&other, 100, -1_000);
+        let mut private = new_game(&other, 100, 0);
+        private.1.join_code_hash = Game::hash_join_code(b"code");
+        let mut classic = new_game(&other, 100, 0);
+        classic.1.variant = GameVariant::Classic;
+        let mut started = new_game(&other, 100, 0);
+        started.1.player2 = Pubkey::new_unique();
+        started.1.last_turn = 30;
+
+        let games = vec![
+            newer.clone(),
+            too_rich,
+            mine,
+            stale,
+            private,
+            classic,
+            started,
+            older.clone(),
+        ];
+        assert_eq!(
+            joinable_games(&me, games, &(50..=500), GameVariant::Ultimate, 100),
+            vec![older, newer]
+        );
+    }
+}
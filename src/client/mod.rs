@@ -0,0 +1,58 @@
+//! Off-chain helpers for tools built on the program.
+//! Instruction builders live next to their instructions.
+//!
+//! `client-core` has the instruction builders, PDAs and account types without cruiser's RPC client,
+//! the signer types it needs are re-exported here. The RPC, websocket and Ledger helpers are left out on wasm
+//! even when their features are on. Building `client-core` for `wasm32-unknown-unknown` isn't checked yet.
+
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+mod bootstrap;
+mod elo;
+mod events;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+mod fetch;
+mod instruction_set;
+mod leaderboard;
+#[cfg(all(feature = "ledger", not(target_arch = "wasm32")))]
+mod ledger;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+mod matchmaking;
+mod nonce;
+mod pda;
+mod render;
+mod return_data;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+mod send;
+mod signer;
+mod social;
+mod suggest;
+#[cfg(all(feature = "client-ws", not(target_arch = "wasm32")))]
+mod turn;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+mod zeroed;
+
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub use bootstrap::*;
+pub use elo::*;
+pub use events::*;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub use fetch::*;
+pub use instruction_set::*;
+pub use leaderboard::*;
+#[cfg(all(feature = "ledger", not(target_arch = "wasm32")))]
+pub use ledger::*;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub use matchmaking::*;
+pub use nonce::*;
+pub use pda::*;
+pub use render::*;
+pub use return_data::*;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub use send::*;
+pub use signer::*;
+pub use social::*;
+pub use suggest::*;
+#[cfg(all(feature = "client-ws", not(target_arch = "wasm32")))]
+pub use turn::*;
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub use zeroed::*;
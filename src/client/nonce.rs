@@ -0,0 +1,77 @@
+use crate::client::{HashedSigner, InstructionSet};
+use cruiser::prelude::*;
+use cruiser::solana_program::hash::Hash;
+use cruiser::solana_program::message::Message;
+use cruiser::solana_program::system_instruction::advance_nonce_account;
+
+/// Makes `set` use the durable nonce in `nonce_account` so it can be signed ahead of time without expiring,
+/// like a pre-signed move. Prepends the advance nonce instruction and adds `nonce_authority` to the signers.
+///
+/// Build the message with [`durable_nonce_message`] using the nonce's current value.
+/// Only one transaction can use each nonce value, sending it advances the nonce.
+pub fn with_durable_nonce<'a>(
+    mut set: InstructionSet<'a>,
+    nonce_account: Pubkey,
+    nonce_authority: impl Into<HashedSigner<'a>>,
+) -> InstructionSet<'a> {
+    let nonce_authority = nonce_authority.into();
+    set.instructions.insert(
+        0,
+        advance_nonce_account(&nonce_account, &nonce_authority.pubkey()),
+    );
+    set.signers.extend([nonce_authority]);
+    set
+}
+
+/// The message for a `set` built with [`with_durable_nonce`], using `nonce` in place of a recent blockhash.
+/// It has to be signed by `payer` and everyone in `set`'s signers.
+pub fn durable_nonce_message(set: &InstructionSet, payer: &Pubkey, nonce: &Hash) -> Message {
+    Message::new_with_blockhash(&set.instructions, Some(payer), nonce)
+}
+
+/// Reads the authority and current nonce value out of a nonce account's data.
+/// [`None`] if the account isn't an initialized nonce account.
+pub fn parse_nonce_account(data: &[u8]) -> Option<(Pubkey, Hash)> {
+    // Versions tag (u32), state tag (u32), authority, nonce, fee calculator
+    if data.len() < 80 || data[4..8] != 1u32.to_le_bytes() {
+        return None;
+    }
+    let authority = Pubkey::new_from_array(data[8..40].try_into().unwrap());
+    let nonce = Hash::new_from_array(data[40..72].try_into().unwrap());
+    Some((authority, nonce))
+}
+
+/// Fetches the current value of the durable nonce in `nonce_account`.
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub async fn get_durable_nonce(
+    rpc: &RpcClient,
+    nonce_account: &Pubkey,
+) -> Result<Hash, Box<dyn std::error::Error>> {
+    let data = rpc.get_account_data(nonce_account).await?;
+    parse_nonce_account(&data)
+        .map(|(_, nonce)| nonce)
+        .ok_or_else(|| format!("{} is not an initialized nonce account", nonce_account).into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_nonce_account() {
+        let authority = Pubkey::new_unique();
+        let nonce = Hash::new_from_array([7; 32]);
+        let mut data = Vec::new();
+        data.extend(1u32.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(authority.to_bytes());
+        data.extend(nonce.to_bytes());
+        data.extend(5000u64.to_le_bytes());
+        assert_eq!(parse_nonce_account(&data), Some((authority, nonce)));
+
+        // Uninitialized
+        data[4..8].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(parse_nonce_account(&data), None);
+        assert_eq!(parse_nonce_account(&[]), None);
+    }
+}
@@ -0,0 +1,84 @@
+//! Derives the program's PDAs as `(key, bump)` so front-ends don't need to know the seeds.
+//! Each wraps the matching seeder in [`pda`](crate::pda) at its canonical bump.
+
+use crate::pda::{
+    ArchiveAuthoritySeeder, BadgeAuthoritySeeder, ClaimReceiptSeeder, ConfigSeeder, FeeVaultSeeder,
+    GameRecoverySeeder, GameSignerSeeder, HouseProfileSeeder, HouseSeeder, HouseVaultSeeder,
+    LeaderboardShardSeeder, MetricsSeeder, ProfileSeeder, ProposedActionSeeder, SocialGraphSeeder,
+};
+use cruiser::prelude::*;
+
+/// Derives the signer holding `game`'s wagers, its [`WagerVault`](crate::accounts::WagerVault).
+pub fn derive_game_signer(program_id: &Pubkey, game: Pubkey) -> (Pubkey, u8) {
+    GameSignerSeeder { game }.find_address(program_id)
+}
+
+/// Derives the profile created at a PDA of `authority`, see [`create_profile_pda`](crate::instructions::create_profile_pda).
+pub fn derive_profile(program_id: &Pubkey, authority: Pubkey) -> (Pubkey, u8) {
+    ProfileSeeder { authority }.find_address(program_id)
+}
+
+/// Derives the [`ProgramConfig`](crate::accounts::ProgramConfig).
+pub fn derive_config(program_id: &Pubkey) -> (Pubkey, u8) {
+    ConfigSeeder.find_address(program_id)
+}
+
+/// Derives the [`House`](crate::accounts::House).
+pub fn derive_house(program_id: &Pubkey) -> (Pubkey, u8) {
+    HouseSeeder.find_address(program_id)
+}
+
+/// Derives the system account holding the house's bankroll.
+pub fn derive_house_vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    HouseVaultSeeder.find_address(program_id)
+}
+
+/// Derives the house's profile.
+pub fn derive_house_profile(program_id: &Pubkey) -> (Pubkey, u8) {
+    HouseProfileSeeder.find_address(program_id)
+}
+
+/// Derives the system account collecting protocol fees.
+pub fn derive_fee_vault(program_id: &Pubkey) -> (Pubkey, u8) {
+    FeeVaultSeeder.find_address(program_id)
+}
+
+/// Derives `profile`'s [`SocialGraph`](crate::accounts::SocialGraph).
+pub fn derive_social_graph(program_id: &Pubkey, profile: Pubkey) -> (Pubkey, u8) {
+    SocialGraphSeeder { profile }.find_address(program_id)
+}
+
+/// Derives `page` of the leaderboard for elo `band`.
+pub fn derive_leaderboard_shard(program_id: &Pubkey, band: u16, page: u16) -> (Pubkey, u8) {
+    LeaderboardShardSeeder::new(band, page).find_address(program_id)
+}
+
+/// Derives the [`InstructionMetrics`](crate::accounts::InstructionMetrics).
+pub fn derive_metrics(program_id: &Pubkey) -> (Pubkey, u8) {
+    MetricsSeeder.find_address(program_id)
+}
+
+/// Derives `game`'s [`GameRecovery`](crate::accounts::GameRecovery).
+pub fn derive_game_recovery(program_id: &Pubkey, game: Pubkey) -> (Pubkey, u8) {
+    GameRecoverySeeder { game }.find_address(program_id)
+}
+
+/// Derives the [`ProposedAction`](crate::accounts::ProposedAction) with `id`.
+pub fn derive_proposed_action(program_id: &Pubkey, id: u64) -> (Pubkey, u8) {
+    ProposedActionSeeder::new(id).find_address(program_id)
+}
+
+/// Derives the authority of the trees finished games are archived into.
+pub fn derive_archive_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    ArchiveAuthoritySeeder.find_address(program_id)
+}
+
+/// Derives the authority achievement badges are minted by.
+pub fn derive_badge_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    BadgeAuthoritySeeder.find_address(program_id)
+}
+
+/// Derives the [`ClaimReceipt`](crate::accounts::ClaimReceipt) of the claim at `index` of `distribution`.
+pub fn derive_claim_receipt(program_id: &Pubkey, distribution: Pubkey, index: u64) -> (Pubkey, u8) {
+    ClaimReceiptSeeder::new(distribution, index).find_address(program_id)
+}
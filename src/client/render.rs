@@ -0,0 +1,205 @@
+use crate::accounts::{Board, Game, GameBoard, Player, Space};
+use std::fmt;
+
+/// Renders a game's board as text, one line per row of spaces followed by a status line.
+///
+/// Empty spaces are `.` and played spaces are `x` for [`Player::One`] and `o` for [`Player::Two`].
+/// Solved sub-boards don't keep their spaces, so they are filled with their winner in uppercase.
+/// Sub-boards are split by `|` and `-`, and the middle boards of depth-3 games by `#` and `=`.
+pub fn render_board(game: &Game) -> String {
+    let size = match &game.board {
+        GameBoard::Classic(_) => 3,
+        GameBoard::Ultimate(_) => 9,
+        GameBoard::Deep(_) => 27,
+    };
+    let mut grid = vec![vec!['.'; size]; size];
+    match &game.board {
+        GameBoard::Classic(board) => fill_small(&mut grid, 0, 0, board),
+        GameBoard::Ultimate(board) => fill_ultimate(&mut grid, 0, 0, board),
+        GameBoard::Deep(board) => match board {
+            Board::Unsolved(boards) => {
+                for (row, boards) in boards.iter().enumerate() {
+                    for (col, board) in boards.iter().enumerate() {
+                        fill_ultimate(&mut grid, row * 9, col * 9, board);
+                    }
+                }
+            }
+            Board::Solved(player) => fill_solved(&mut grid, 0, 0, 27, *player),
+        },
+    }
+
+    let rows = grid
+        .iter()
+        .map(|row| {
+            let mut line = String::new();
+            for (col, space) in row.iter().enumerate() {
+                if col > 0 {
+                    line.push_str(if col % 9 == 0 {
+                        " # "
+                    } else if col % 3 == 0 {
+                        " | "
+                    } else {
+                        " "
+                    });
+                }
+                line.push(*space);
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        if index > 0 && index % 3 == 0 {
+            let (line, cross) = if index % 9 == 0 {
+                ('=', '#')
+            } else {
+                ('-', '+')
+            };
+            out.extend(row.chars().map(|c| match c {
+                '|' | '#' => cross,
+                _ => line,
+            }));
+            out.push('\n');
+        }
+        out.push_str(row);
+        out.push('\n');
+    }
+
+    if let Some(winner) = game.winner() {
+        out.push_str(&format!("{} won", player_name(winner)));
+    } else if game.is_draw() {
+        out.push_str("No moves left");
+    } else {
+        out.push_str(&format!("{} to move", player_name(game.next_play)));
+        if let Some((big_board, middle_board)) = game.forced_board() {
+            match game.board {
+                GameBoard::Deep(_) => out.push_str(&format!(
+                    " on big board {:?}, middle board {:?}",
+                    big_board, middle_board
+                )),
+                _ => out.push_str(&format!(" on board {:?}", big_board)),
+            }
+        }
+    }
+    out
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::One => "Player one (x)",
+        Player::Two => "Player two (o)",
+    }
+}
+
+fn fill_solved(grid: &mut [Vec<char>], row: usize, col: usize, size: usize, player: Player) {
+    let mark = match player {
+        Player::One => 'X',
+        Player::Two => 'O',
+    };
+    for line in &mut grid[row..row + size] {
+        for space in &mut line[col..col + size] {
+            *space = mark;
+        }
+    }
+}
+
+fn fill_small(grid: &mut [Vec<char>], row: usize, col: usize, board: &Board<Space>) {
+    match board {
+        Board::Unsolved(spaces) => {
+            for (row_offset, spaces) in spaces.iter().enumerate() {
+                for (col_offset, space) in spaces.iter().enumerate() {
+                    grid[row + row_offset][col + col_offset] = match space {
+                        Space::PlayerOne => 'x',
+                        Space::PlayerTwo => 'o',
+                        Space::Empty => '.',
+                    };
+                }
+            }
+        }
+        Board::Solved(player) => fill_solved(grid, row, col, 3, *player),
+    }
+}
+
+fn fill_ultimate(grid: &mut [Vec<char>], row: usize, col: usize, board: &Board<Board<Space>>) {
+    match board {
+        Board::Unsolved(boards) => {
+            for (row_offset, boards) in boards.iter().enumerate() {
+                for (col_offset, board) in boards.iter().enumerate() {
+                    fill_small(grid, row + row_offset * 3, col + col_offset * 3, board);
+                }
+            }
+        }
+        Board::Solved(player) => fill_solved(grid, row, col, 9, *player),
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_board(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameVariant, RuleSet};
+    use cruiser::prelude::Pubkey;
+
+    fn new_game(variant: GameVariant) -> Game {
+        Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            variant,
+            RuleSet::default(),
+        )
+    }
+
+    #[test]
+    fn test_render_classic() {
+        let mut game = new_game(GameVariant::Classic);
+        game.make_move(Player::One, [0, 0], [0, 0], [1, 1]).unwrap();
+        game.make_move(Player::Two, [0, 0], [0, 0], [0, 2]).unwrap();
+        game.next_play = Player::One;
+        assert_eq!(
+            render_board(&game),
+            ". . o\n. x .\n. . .\nPlayer one (x) to move"
+        );
+    }
+
+    #[test]
+    fn test_render_ultimate() {
+        let mut game = new_game(GameVariant::Ultimate);
+        for small_board in [[0, 0], [1, 1], [2, 2]] {
+            game.make_move(Player::Two, [1, 2], [0, 0], small_board)
+                .unwrap();
+        }
+        game.make_move(Player::One, [0, 0], [0, 0], [1, 2]).unwrap();
+        game.last_move = [0, 1];
+        game.next_play = Player::Two;
+        let rendered = render_board(&game);
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[1], ". . x | . . . | . . .");
+        assert_eq!(lines[3], "------+-------+------");
+        assert_eq!(lines[4], ". . . | . . . | O O O");
+        assert_eq!(lines[11], "Player two (o) to move on board [0, 1]");
+        assert_eq!(rendered, game.to_string());
+    }
+
+    #[test]
+    fn test_render_deep() {
+        let game = new_game(GameVariant::Deep);
+        let rendered = render_board(&game);
+        let lines = rendered.lines().collect::<Vec<_>>();
+        // 27 rows, 6 small separators, 2 middle separators, and the status
+        assert_eq!(lines.len(), 36);
+        assert!(lines[11].starts_with("======"));
+        assert_eq!(lines[35], "Player one (x) to move");
+    }
+}
@@ -0,0 +1,101 @@
+use cruiser::prelude::*;
+use std::error::Error;
+
+/// The prefix the runtime logs return data with, followed by the program and the base64 data.
+const RETURN_LOG_PREFIX: &str = "Program return: ";
+
+/// Decodes the return data `program_id` set from a transaction's log messages,
+/// either from simulating it or from the confirmed transaction.
+/// Each instruction's return type is its [`Instruction::ReturnType`], such as a [`MoveOutcome`](crate::instructions::MoveOutcome).
+///
+/// Only the last return data is kept by the runtime, so with several instructions this is the last one's.
+/// [`None`] if the program set none.
+pub fn decode_return_data<T>(
+    program_id: &Pubkey,
+    logs: &[String],
+) -> Result<Option<T>, Box<dyn Error>>
+where
+    T: BorshDeserialize,
+{
+    let program_id = program_id.to_string();
+    let data = logs
+        .iter()
+        .filter_map(|log| log.strip_prefix(RETURN_LOG_PREFIX)?.split_once(' '))
+        .filter(|(program, _)| *program == program_id)
+        .map(|(_, data)| data)
+        .last();
+    match data {
+        Some(data) => Ok(Some(T::try_from_slice(&base64::decode(data.trim())?)?)),
+        None => Ok(None),
+    }
+}
+
+/// Fetches the confirmed transaction `signature` and decodes the return data `program_id` set, see [`decode_return_data`].
+#[cfg(all(feature = "client-rpc", not(target_arch = "wasm32")))]
+pub async fn fetch_return_data<T>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Option<T>, Box<dyn Error>>
+where
+    T: BorshDeserialize,
+{
+    let logs = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None,
+            },
+        )
+        .await?
+        .transaction
+        .meta
+        .and_then(|meta| meta.log_messages)
+        .unwrap_or_default();
+    decode_return_data(program_id, &logs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameResult, Player};
+    use crate::instructions::{EloChange, MoveOutcome};
+
+    #[test]
+    fn test_decode_return_data() {
+        let program_id = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let outcome = MoveOutcome {
+            result: Some(GameResult::Won(Player::Two)),
+            elo: EloChange {
+                player: 16,
+                other: -16,
+            },
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!(
+                "Program return: {} {}",
+                other_program,
+                base64::encode([1, 2, 3])
+            ),
+            format!(
+                "Program return: {} {}",
+                program_id,
+                base64::encode(outcome.try_to_vec().unwrap())
+            ),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(
+            decode_return_data::<MoveOutcome>(&program_id, &logs).unwrap(),
+            Some(outcome)
+        );
+        assert_eq!(
+            decode_return_data::<MoveOutcome>(&Pubkey::new_unique(), &logs).unwrap(),
+            None
+        );
+        assert!(decode_return_data::<MoveOutcome>(&other_program, &logs).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::error::TutorialError;
+use cruiser::prelude::*;
+use cruiser::solana_client::client_error::{ClientError, ClientErrorKind};
+use cruiser::solana_program::instruction::InstructionError;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// How [`send_instruction_set`] retries.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryConfig {
+    /// The most times the transaction is sent, counting the first.
+    pub max_attempts: usize,
+    /// How long to wait after the first failed attempt, doubling after each one after.
+    pub initial_backoff: Duration,
+    /// The longest to wait between attempts.
+    pub max_backoff: Duration,
+    /// How often to poll for confirmation.
+    pub poll_interval: Duration,
+}
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// How a transaction sent with [`send_instruction_set`] ended up.
+#[derive(Debug)]
+pub enum SendOutcome {
+    /// The transaction landed and succeeded.
+    Success(Signature),
+    /// The transaction failed, retrying won't help.
+    /// The signature is [`None`] if the transaction failed its preflight simulation and never landed.
+    Failed(Option<Signature>, Box<dyn Error>),
+    /// The instruction at the index failed with a [`TutorialError`].
+    /// The signature is [`None`] if the transaction failed its preflight simulation and never landed.
+    ProgramFailed(Option<Signature>, u8, TutorialError),
+    /// Every attempt expired before landing.
+    Dropped,
+}
+impl SendOutcome {
+    /// Turns anything but [`SendOutcome::Success`] into an error.
+    pub fn into_result(self) -> Result<Signature, Box<dyn Error>> {
+        match self {
+            SendOutcome::Success(sig) => Ok(sig),
+            SendOutcome::Failed(_, error) => Err(error),
+            SendOutcome::ProgramFailed(_, index, error) => Err(format!(
+                "instruction {} failed with {:?} ({})",
+                index,
+                error,
+                error.code()
+            )
+            .into()),
+            SendOutcome::Dropped => Err("Transaction dropped".into()),
+        }
+    }
+}
+
+/// The [`TutorialError`] a transaction failed with and the index of the instruction that failed,
+/// [`None`] if it failed some other way.
+pub fn program_error(error: &TransactionError) -> Option<(u8, TutorialError)> {
+    match error {
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+            TutorialError::from_code(*code).map(|error| (*index, error))
+        }
+        _ => None,
+    }
+}
+
+/// The [`TutorialError`] a transaction failed its preflight simulation with and the index of the instruction
+/// that failed, [`None`] if it was rejected some other way.
+pub fn preflight_program_error(error: &ClientError) -> Option<(u8, TutorialError)> {
+    error
+        .get_transaction_error()
+        .as_ref()
+        .and_then(program_error)
+}
+
+/// Sends the instructions from `instructions` paid for by `payer`, retrying per `retry`.
+///
+/// `instructions` is called again for each attempt, each of which is sent with a fresh blockhash,
+/// so a transaction whose blockhash expired is re-sent rather than reported as dropped.
+/// Transport errors, timeouts and blockhashes the node hasn't seen are retried with backoff, any other error
+/// from the node is returned right away. A transaction that lands or fails its preflight simulation is
+/// returned as is, as a [`SendOutcome::ProgramFailed`] if it failed with a [`TutorialError`].
+pub async fn send_instruction_set<'a>(
+    rpc: &RpcClient,
+    payer: &'a Keypair,
+    mut instructions: impl FnMut() -> InstructionSet<'a>,
+    send_config: RpcSendTransactionConfig,
+    commitment: CommitmentConfig,
+    retry: RetryConfig,
+) -> Result<SendOutcome, Box<dyn Error>> {
+    let mut backoff = retry.initial_backoff;
+    for attempt in 1..=retry.max_attempts {
+        let sent = TransactionBuilder::new(payer)
+            .signed_instructions(instructions())
+            .send_and_confirm_transaction(rpc, send_config, commitment, retry.poll_interval)
+            .await;
+        match sent {
+            Ok((sig, ConfirmationResult::Success)) => return Ok(SendOutcome::Success(sig)),
+            Ok((sig, ConfirmationResult::Failure(error))) => {
+                return Ok(match program_error(&error) {
+                    Some((index, program_error)) => {
+                        SendOutcome::ProgramFailed(Some(sig), index, program_error)
+                    }
+                    None => SendOutcome::Failed(Some(sig), error.into()),
+                })
+            }
+            // Expired blockhash, go again right away with a new one.
+            Ok((_, ConfirmationResult::Dropped)) => continue,
+            Err(error) => {
+                if let Some((index, program_error)) = preflight_program_error(&error) {
+                    return Ok(SendOutcome::ProgramFailed(None, index, program_error));
+                }
+                if !is_retryable(&error) {
+                    return match error.get_transaction_error() {
+                        Some(_) => Ok(SendOutcome::Failed(None, error.into())),
+                        None => Err(error.into()),
+                    };
+                }
+                if attempt == retry.max_attempts {
+                    return Err(error.into());
+                }
+                sleep(backoff).await;
+                backoff = (backoff * 2).min(retry.max_backoff);
+            }
+        }
+    }
+    Ok(SendOutcome::Dropped)
+}
+
+/// Whether sending again might get past `error`, a transport error, a timeout or a blockhash the node hasn't seen yet.
+fn is_retryable(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        _ => matches!(
+            error.get_transaction_error(),
+            Some(TransactionError::BlockhashNotFound)
+        ),
+    }
+}
@@ -0,0 +1,96 @@
+use crate::client::{Signature, Signer, SignerError};
+use cruiser::prelude::*;
+use std::fmt::{Debug, Formatter};
+
+/// Signs messages for a key whose secret isn't in memory, like a custodial or MPC signing service.
+pub trait SignMessage {
+    /// Signs `message` for the key, returning the signature.
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError>;
+}
+impl<F> SignMessage for F
+where
+    F: Fn(&[u8]) -> Result<Signature, SignerError>,
+{
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self(message)
+    }
+}
+
+/// A [`Signer`] for `pubkey` that hands messages off to a [`SignMessage`].
+/// Pass a reference to it anywhere the client helpers take a signer, like [`create_game`](crate::instructions::create_game)
+/// or [`make_move`](crate::instructions::make_move).
+pub struct RemoteSigner<'a> {
+    pubkey: Pubkey,
+    signer: Box<dyn SignMessage + 'a>,
+    interactive: bool,
+}
+impl<'a> RemoteSigner<'a> {
+    /// A remote signer for `pubkey`.
+    pub fn new(pubkey: Pubkey, signer: impl SignMessage + 'a) -> Self {
+        Self {
+            pubkey,
+            signer: Box::new(signer),
+            interactive: false,
+        }
+    }
+
+    /// Marks the signer as needing a person to approve each signature, like a threshold of custodians.
+    pub fn interactive(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+}
+impl Debug for RemoteSigner<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("pubkey", &self.pubkey)
+            .field("interactive", &self.interactive)
+            .finish()
+    }
+}
+impl Signer for RemoteSigner<'_> {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let signature = self.signer.sign_message(message)?;
+        if signature.verify(self.pubkey.as_ref(), message) {
+            Ok(signature)
+        } else {
+            Err(SignerError::Custom(format!(
+                "Remote signer returned a bad signature for {}",
+                self.pubkey
+            )))
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::Keypair;
+
+    #[test]
+    fn test_remote_signer() {
+        let keypair = Keypair::new();
+        let remote = RemoteSigner::new(keypair.pubkey(), |message: &[u8]| {
+            keypair.try_sign_message(message)
+        });
+        assert_eq!(remote.pubkey(), keypair.pubkey());
+        assert_eq!(
+            remote.try_sign_message(b"move"),
+            keypair.try_sign_message(b"move")
+        );
+
+        let other = Keypair::new();
+        let wrong = RemoteSigner::new(keypair.pubkey(), |message: &[u8]| {
+            other.try_sign_message(message)
+        });
+        assert!(wrong.try_sign_message(b"move").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::accounts::{Game, SocialGraph};
+use cruiser::prelude::Pubkey;
+
+/// Keeps the games created by profiles `graph` follows, for a lobby of friends' games.
+/// `games` are `(key, game)` pairs as fetched from the program's accounts.
+pub fn followed_games<'a>(
+    graph: &'a SocialGraph,
+    games: impl IntoIterator<Item = (Pubkey, Game)> + 'a,
+) -> impl Iterator<Item = (Pubkey, Game)> + 'a {
+    games
+        .into_iter()
+        .filter(move |(_, game)| graph.is_following(game.creator_profile()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameVariant, Player, RuleSet};
+
+    fn new_game(creator: &Pubkey, player: Player) -> (Pubkey, Game) {
+        let game = Game::new(
+            creator,
+            player,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        (Pubkey::new_unique(), game)
+    }
+
+    #[test]
+    fn test_followed_games() {
+        let friend = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let mut graph = SocialGraph::new(&Pubkey::new_unique(), 255);
+        assert!(graph.follow(&friend));
+
+        let friend_one = new_game(&friend, Player::One);
+        let friend_two = new_game(&friend, Player::Two);
+        let mut stranger_game = new_game(&stranger, Player::One);
+        // Joining a stranger's game doesn't make it the friend's
+        stranger_game.1.player2 = friend;
+
+        let games = vec![friend_one.clone(), stranger_game, friend_two.clone()];
+        let followed = followed_games(&graph, games).collect::<Vec<_>>();
+        assert_eq!(followed, vec![friend_one, friend_two]);
+    }
+}
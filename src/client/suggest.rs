@@ -0,0 +1,185 @@
+use crate::accounts::{Board, Game, GameBoard, Player, Space};
+use crate::instructions::MakeMoveData;
+
+/// The score of a won game, ahead of any position the heuristic can give.
+const WIN: i32 = 1_000_000;
+
+/// How many winning lines go through each position in reading order.
+const POSITION_WEIGHTS: [i32; 9] = [3, 2, 3, 2, 4, 2, 3, 2, 3];
+
+/// What a solved board is worth to the board it is in.
+/// Above the most an unsolved board can score.
+const SOLVED_WEIGHT: i32 = 30;
+
+/// Suggests a move for [`Game::next_play`] by searching `depth` moves ahead with minimax.
+/// A `depth` of 0 is treated as 1.
+///
+/// Ties go to the first move in reading order, so the same position always gets the same suggestion.
+/// Returns [`None`] if there are no valid moves.
+pub fn suggest_move(game: &Game, depth: usize) -> Option<MakeMoveData> {
+    let depth = depth.max(1);
+    let mut best = None;
+    let mut alpha = -WIN * 2;
+    for mov in game.valid_moves() {
+        let score = -negamax(&play(game, &mov), depth - 1, -WIN * 2, -alpha);
+        if best.is_none() || score > alpha {
+            alpha = score;
+            best = Some(mov);
+        }
+    }
+    best
+}
+
+/// Plays `mov` for [`Game::next_play`] the same way [`MakeMove`](crate::instructions::MakeMove) does.
+fn play(game: &Game, mov: &MakeMoveData) -> Game {
+    let mut next = game.clone();
+    next.make_move(
+        game.next_play,
+        mov.big_board,
+        mov.middle_board,
+        mov.small_board,
+    )
+    .expect("valid moves can be made");
+    next.last_move = mov.small_board;
+    next.last_move_middle = mov.middle_board;
+    next.next_play = game.next_play.other();
+    next
+}
+
+/// Scores `game` for [`Game::next_play`], higher is better.
+fn negamax(game: &Game, depth: usize, mut alpha: i32, beta: i32) -> i32 {
+    let player = game.next_play;
+    // Sooner wins score higher, they have more depth left
+    let outcome = |winner: Player| {
+        let score = WIN + depth as i32;
+        if winner == player {
+            score
+        } else {
+            -score
+        }
+    };
+    if let Some(winner) = game.winner() {
+        return outcome(winner);
+    }
+    if game.is_draw() {
+        return game.draw_winner().map_or(0, outcome);
+    }
+    let moves = game.valid_moves();
+    if depth == 0 || moves.is_empty() {
+        return heuristic(game, player);
+    }
+
+    let mut best = -WIN * 2;
+    for mov in moves {
+        let score = -negamax(&play(game, &mov), depth - 1, -beta, -alpha);
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+/// Scores an unfinished game for `player` by who holds the spaces and boards
+/// that are on the most winning lines.
+fn heuristic(game: &Game, player: Player) -> i32 {
+    let sign = |owner: Player| if owner == player { 1 } else { -1 };
+    let small = |board: &Board<Space>| match board {
+        Board::Unsolved(spaces) => weighted(spaces, |space| match space {
+            Space::PlayerOne => sign(Player::One),
+            Space::PlayerTwo => sign(Player::Two),
+            Space::Empty => 0,
+        }),
+        Board::Solved(owner) => sign(*owner) * SOLVED_WEIGHT,
+    };
+    let ultimate = |board: &Board<Board<Space>>| match board {
+        Board::Unsolved(boards) => weighted(boards, small),
+        Board::Solved(owner) => sign(*owner) * SOLVED_WEIGHT * SOLVED_WEIGHT,
+    };
+    match &game.board {
+        GameBoard::Classic(board) => small(board),
+        GameBoard::Ultimate(board) => ultimate(board),
+        GameBoard::Deep(Board::Unsolved(boards)) => weighted(boards, ultimate),
+        GameBoard::Deep(Board::Solved(owner)) => {
+            sign(*owner) * SOLVED_WEIGHT * SOLVED_WEIGHT * SOLVED_WEIGHT
+        }
+    }
+}
+
+/// Sums `score` over a 3x3 board weighted by [`POSITION_WEIGHTS`].
+fn weighted<S>(board: &[[S; 3]; 3], score: impl Fn(&S) -> i32) -> i32 {
+    board
+        .iter()
+        .flatten()
+        .zip(POSITION_WEIGHTS)
+        .map(|(item, weight)| score(item) * weight)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameVariant, RuleSet};
+    use cruiser::prelude::Pubkey;
+
+    fn new_game(variant: GameVariant) -> Game {
+        Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            variant,
+            RuleSet::default(),
+        )
+    }
+
+    fn play_classic(game: &mut Game, moves: &[[u8; 2]]) {
+        for small_board in moves {
+            *game = play(
+                game,
+                &MakeMoveData {
+                    big_board: [0, 0],
+                    middle_board: [0, 0],
+                    small_board: *small_board,
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_takes_win() {
+        let mut game = new_game(GameVariant::Classic);
+        play_classic(&mut game, &[[0, 0], [1, 0], [0, 1], [1, 1]]);
+        assert_eq!(game.next_play, Player::One);
+        assert_eq!(suggest_move(&game, 3).unwrap().small_board, [0, 2]);
+    }
+
+    #[test]
+    fn test_blocks_loss() {
+        let mut game = new_game(GameVariant::Classic);
+        play_classic(&mut game, &[[0, 0], [1, 1], [0, 1]]);
+        assert_eq!(game.next_play, Player::Two);
+        assert_eq!(suggest_move(&game, 2).unwrap().small_board, [0, 2]);
+    }
+
+    #[test]
+    fn test_self_play() {
+        let mut game = new_game(GameVariant::Ultimate);
+        let mut moves = 0;
+        while let Some(mov) = suggest_move(&game, 2) {
+            assert!(game.is_valid_move(&mov));
+            game = play(&game, &mov);
+            moves += 1;
+            if game.winner().is_some() || game.is_draw() {
+                break;
+            }
+        }
+        assert!(game.winner().is_some() || game.is_draw());
+        assert!(moves <= 81);
+        assert_eq!(suggest_move(&game, 2), None);
+    }
+}
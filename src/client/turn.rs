@@ -0,0 +1,182 @@
+use crate::accounts::{Game, Player};
+use crate::client::{decode_account, fetch_account};
+use cruiser::prelude::*;
+use cruiser::solana_account_decoder::UiAccountEncoding;
+use cruiser::solana_client::nonblocking::pubsub_client::PubsubClient;
+use cruiser::solana_client::rpc_config::RpcAccountInfoConfig;
+use cruiser::solana_sdk::account::Account;
+use futures::StreamExt;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+
+/// What [`wait_for_my_turn`] stopped waiting on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TurnWait {
+    /// It's the profile's turn in the game as it is now.
+    MyTurn(Game),
+    /// The game ended, see [`Game::result`].
+    Ended(Game),
+}
+
+/// Tells whether `game` is waiting on `player` or has ended, [`None`] while it's waiting on the other player or a joiner.
+pub fn turn_state(game: &Game, player: Player) -> Option<TurnWait> {
+    if game.finished.is_finished() || game.result().is_some() {
+        Some(TurnWait::Ended(game.clone()))
+    } else if game.is_started() && game.next_play == player {
+        Some(TurnWait::MyTurn(game.clone()))
+    } else {
+        None
+    }
+}
+
+/// Waits until it's `my_profile`'s turn in `game` or the game ends.
+///
+/// Changes to the game come from an account subscription on `pubsub`, and `rpc` is polled every `poll_interval`
+/// the subscription is quiet so a dropped notification or subscription only delays the result.
+/// Without a subscription it falls back to polling alone.
+/// Errors if the game is closed or `my_profile` isn't playing in it once started.
+pub async fn wait_for_my_turn(
+    pubsub: &PubsubClient,
+    rpc: &RpcClient,
+    game: Pubkey,
+    my_profile: Pubkey,
+    poll_interval: Duration,
+) -> Result<TurnWait, Box<dyn Error>> {
+    // Subscribe before the first read so no change lands between the two
+    let subscription = pubsub
+        .account_subscribe(
+            &game,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            }),
+        )
+        .await;
+    let (mut updates, unsubscribe) = match subscription {
+        Ok((updates, unsubscribe)) => (Some(updates), Some(unsubscribe)),
+        Err(_) => (None, None),
+    };
+
+    // The players are cleared when the game finishes so the side is kept from when it was known
+    let mut player = None;
+    let result = loop {
+        let game_data = match fetch_account::<Game>(rpc, &game).await? {
+            Some(game_data) => game_data,
+            None => break Err(format!("game {} was closed", game).into()),
+        };
+        if let Some(state) = check_game(&game_data, &my_profile, &mut player) {
+            break state;
+        }
+
+        // Wait for an update, polling again if none comes in time
+        loop {
+            let stream = match &mut updates {
+                Some(stream) => stream,
+                None => {
+                    sleep(poll_interval).await;
+                    break;
+                }
+            };
+            match timeout(poll_interval, stream.next()).await {
+                Ok(Some(response)) => {
+                    if let Some(account) = response.value.decode::<Account>() {
+                        let game_data = decode_account::<Game>(&game, &account.data)?;
+                        if let Some(state) = check_game(&game_data, &my_profile, &mut player) {
+                            return finish(unsubscribe, state).await;
+                        }
+                    }
+                }
+                // The subscription ended, poll from here on
+                Ok(None) => {
+                    updates = None;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+    };
+    finish(unsubscribe, result).await
+}
+
+/// Checks `game` for [`turn_state`], finding `my_profile`'s side once the game has started.
+fn check_game(
+    game: &Game,
+    my_profile: &Pubkey,
+    player: &mut Option<Player>,
+) -> Option<Result<TurnWait, Box<dyn Error>>> {
+    if player.is_none() && game.is_started() && !game.finished.is_finished() {
+        *player = if &game.player1 == my_profile {
+            Some(Player::One)
+        } else if &game.player2 == my_profile {
+            Some(Player::Two)
+        } else {
+            return Some(Err(format!("{} isn't playing this game", my_profile).into()));
+        };
+    }
+    match player {
+        Some(player) => turn_state(game, *player).map(Ok),
+        None if game.finished.is_finished() => Some(Ok(TurnWait::Ended(game.clone()))),
+        None => None,
+    }
+}
+
+/// Unsubscribes if subscribed and returns `result`.
+async fn finish<U, F>(
+    unsubscribe: Option<U>,
+    result: Result<TurnWait, Box<dyn Error>>,
+) -> Result<TurnWait, Box<dyn Error>>
+where
+    U: FnOnce() -> F,
+    F: std::future::Future<Output = ()>,
+{
+    if let Some(unsubscribe) = unsubscribe {
+        unsubscribe().await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameResult, GameVariant, RuleSet};
+
+    #[test]
+    fn test_turn_state() {
+        let mut game = Game::new(
+            &Pubkey::new_unique(),
+            Player::One,
+            255,
+            0,
+            0,
+            0,
+            0,
+            GameVariant::Classic,
+            RuleSet::default(),
+        );
+        // Waiting on a joiner
+        assert_eq!(turn_state(&game, Player::One), None);
+
+        game.player2 = Pubkey::new_unique();
+        game.last_turn = 10;
+        game.next_play = Player::Two;
+        assert_eq!(turn_state(&game, Player::One), None);
+        assert_eq!(
+            turn_state(&game, Player::Two),
+            Some(TurnWait::MyTurn(game.clone()))
+        );
+
+        game.finish(
+            &Pubkey::new_unique(),
+            &game.player1.clone(),
+            GameResult::Drawn,
+            20,
+        )
+        .unwrap();
+        assert_eq!(
+            turn_state(&game, Player::One),
+            Some(TurnWait::Ended(game.clone()))
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use cruiser::prelude::*;
+use std::error::Error;
+
+/// Creates `account` with `size` zeroed bytes owned by `program_id`, funded by `funder` with the rent exempt minimum from `rpc`.
+/// For instructions taking an [`InitOrZeroedAccount`] such as [`create_game_from_zeroed`](crate::instructions::create_game_from_zeroed),
+/// which must come after it in the same transaction.
+pub async fn create_zeroed_account<'a>(
+    rpc: &RpcClient,
+    program_id: Pubkey,
+    funder: impl Into<HashedSigner<'a>>,
+    account: impl Into<HashedSigner<'a>>,
+    size: usize,
+) -> Result<InstructionSet<'a>, Box<dyn Error>> {
+    let rent = rpc.get_minimum_balance_for_rent_exemption(size).await?;
+    Ok(system_program::create_account(
+        funder,
+        account,
+        rent,
+        size as u64,
+        program_id,
+    ))
+}
@@ -0,0 +1,185 @@
+//! Glue for [Clockwork](https://github.com/clockwork-xyz/clockwork) automation threads.
+//!
+//! Only the parts of the thread program's interface this program uses are mirrored here,
+//! so there is no dependency on the Clockwork crates.
+
+use cruiser::prelude::*;
+
+/// The Clockwork thread program.
+pub const THREAD_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    168, 130, 211, 34, 180, 16, 11, 91, 210, 107, 238, 73, 114, 36, 220, 100, 86, 253, 186, 23, 29,
+    123, 105, 187, 8, 124, 106, 153, 176, 24, 159, 112,
+]);
+
+/// The static seed for [`ThreadSeeder`].
+pub const THREAD_SEED: &str = "thread";
+
+/// The Anchor discriminant of the thread program's `thread_create` instruction.
+const THREAD_CREATE_DISCRIMINANT: [u8; 8] = [54, 1, 238, 224, 71, 244, 252, 173];
+
+/// The seeder for a thread, derived under [`THREAD_PROGRAM_ID`].
+#[derive(Debug, Clone)]
+pub struct ThreadSeeder {
+    /// The authority of the thread.
+    pub authority: Pubkey,
+    /// The id of the thread, unique per authority. At most 32 bytes.
+    pub id: Vec<u8>,
+}
+impl PDASeeder for ThreadSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&THREAD_SEED as &dyn PDASeed, &self.authority, &self.id].into_iter())
+    }
+}
+
+/// When a thread runs.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum Trigger {
+    /// Runs when data in an account changes.
+    Account {
+        /// The account to watch.
+        address: Pubkey,
+        /// The offset of the watched data.
+        offset: u64,
+        /// The size of the watched data.
+        size: u64,
+    },
+    /// Runs on a cron schedule. The schedule has a leading seconds field.
+    Cron {
+        /// The cron schedule.
+        schedule: String,
+        /// Whether missed runs are skipped rather than caught up.
+        skippable: bool,
+    },
+}
+
+/// An instruction for a thread to run.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct SerializableInstruction {
+    /// The program to call.
+    pub program_id: Pubkey,
+    /// The accounts to pass.
+    pub accounts: Vec<SerializableAccount>,
+    /// The instruction data.
+    pub data: Vec<u8>,
+}
+impl From<SolanaInstruction> for SerializableInstruction {
+    fn from(from: SolanaInstruction) -> Self {
+        Self {
+            program_id: from.program_id,
+            accounts: from
+                .accounts
+                .into_iter()
+                .map(|meta| SerializableAccount {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: from.data,
+        }
+    }
+}
+
+/// An account passed to a [`SerializableInstruction`].
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct SerializableAccount {
+    /// The account's key.
+    pub pubkey: Pubkey,
+    /// Whether the account signs. Only the thread itself can be a signer.
+    pub is_signer: bool,
+    /// Whether the account is writable.
+    pub is_writable: bool,
+}
+
+/// The cron schedule a forfeit thread checks a game on.
+/// Checks every `turn_length` seconds for short turns and every minute otherwise,
+/// so a forfeit lands at most one period after the turn expires.
+pub fn forfeit_schedule(turn_length: UnixTimestamp) -> String {
+    if (1..60).contains(&turn_length) {
+        format!("*/{} * * * * * *", turn_length)
+    } else {
+        "0 * * * * * *".to_string()
+    }
+}
+
+/// CPI for the thread program's `thread_create`.
+#[derive(Debug)]
+pub struct ThreadCreateCPI<'a, AI> {
+    accounts: [MaybeOwned<'a, AI>; 4],
+    data: Vec<u8>,
+}
+impl<'a, AI> ThreadCreateCPI<'a, AI> {
+    /// Creates a thread, funding it with `amount` lamports for its fees on top of its rent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        authority: impl Into<MaybeOwned<'a, AI>>,
+        payer: impl Into<MaybeOwned<'a, AI>>,
+        system_program: impl Into<MaybeOwned<'a, AI>>,
+        thread: impl Into<MaybeOwned<'a, AI>>,
+        amount: u64,
+        id: &[u8],
+        instructions: &[SerializableInstruction],
+        trigger: &Trigger,
+    ) -> CruiserResult<Self> {
+        let mut data = THREAD_CREATE_DISCRIMINANT.to_vec();
+        amount.serialize(&mut data)?;
+        id.serialize(&mut data)?;
+        instructions.serialize(&mut data)?;
+        trigger.serialize(&mut data)?;
+        Ok(Self {
+            accounts: [
+                authority.into(),
+                payer.into(),
+                system_program.into(),
+                thread.into(),
+            ],
+            data,
+        })
+    }
+}
+impl<'a, AI> ThreadCreateCPI<'a, AI>
+where
+    AI: ToSolanaAccountMeta,
+{
+    /// Gets the instruction and accounts to call the thread program with.
+    pub fn instruction(
+        self,
+        thread_program: impl Into<MaybeOwned<'a, AI>>,
+    ) -> InstructionAndAccounts<[MaybeOwned<'a, AI>; 5]> {
+        let thread_program = thread_program.into();
+        let instruction = SolanaInstruction {
+            program_id: *thread_program.meta_key(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(MaybeOwned::as_ref)
+                .map(AI::to_solana_account_meta)
+                .collect(),
+            data: self.data,
+        };
+        let mut accounts = self.accounts.into_iter();
+        InstructionAndAccounts {
+            instruction,
+            accounts: [
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                thread_program,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_forfeit_schedule() {
+        assert_eq!(forfeit_schedule(10), "*/10 * * * * * *");
+        assert_eq!(forfeit_schedule(59), "*/59 * * * * * *");
+        assert_eq!(forfeit_schedule(60), "0 * * * * * *");
+        assert_eq!(forfeit_schedule(60 * 60 * 24), "0 * * * * * *");
+    }
+}
@@ -0,0 +1,136 @@
+//! Reading signatures checked by the native ed25519 program out of the instructions sysvar.
+//!
+//! The runtime fails the whole transaction if an ed25519 instruction's signatures don't verify,
+//! so any ed25519 instruction found in the sysvar is proof its keys signed its messages.
+
+#[cfg(feature = "client-core")]
+use crate::client::Signature;
+use cruiser::prelude::*;
+use cruiser::solana_program::ed25519_program;
+
+/// The size of an ed25519 instruction's offsets for one signature.
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+/// Where the offsets start, after the signature count and a padding byte.
+const SIGNATURE_OFFSETS_START: usize = 2;
+/// The instruction index meaning the data is in the ed25519 instruction itself.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Every `(key, message)` verified by an ed25519 instruction in the transaction, given the instructions sysvar's data.
+/// Only signatures whose key and message are inside the ed25519 instruction itself are returned.
+/// [`None`] if the data isn't a valid instructions sysvar.
+pub fn ed25519_signed_messages(instructions_sysvar: &[u8]) -> Option<Vec<(Pubkey, &[u8])>> {
+    let mut out = Vec::new();
+    let count = read_u16(instructions_sysvar, 0)?;
+    for index in 0..count {
+        let offset = read_u16(instructions_sysvar, 2 + index as usize * 2)? as usize;
+        let accounts = read_u16(instructions_sysvar, offset)? as usize;
+        let program_id_start = offset + 2 + accounts * 33;
+        let program_id = instructions_sysvar.get(program_id_start..program_id_start + 32)?;
+        if program_id != ed25519_program::ID.as_ref() {
+            continue;
+        }
+        let data_len = read_u16(instructions_sysvar, program_id_start + 32)? as usize;
+        let data_start = program_id_start + 34;
+        let data = instructions_sysvar.get(data_start..data_start + data_len)?;
+        for signature in 0..*data.first()? as usize {
+            let offsets = SIGNATURE_OFFSETS_START + signature * SIGNATURE_OFFSETS_SIZE;
+            let public_key_offset = read_u16(data, offsets + 4)? as usize;
+            let public_key_index = read_u16(data, offsets + 6)?;
+            let message_offset = read_u16(data, offsets + 8)? as usize;
+            let message_size = read_u16(data, offsets + 10)? as usize;
+            let message_index = read_u16(data, offsets + 12)?;
+            if public_key_index != THIS_INSTRUCTION || message_index != THIS_INSTRUCTION {
+                continue;
+            }
+            let key = data.get(public_key_offset..public_key_offset + 32)?;
+            let message = data.get(message_offset..message_offset + message_size)?;
+            out.push((Pubkey::new_from_array(key.try_into().unwrap()), message));
+        }
+    }
+    Some(out)
+}
+
+/// An ed25519 program instruction verifying `signature` of `message` by `key`,
+/// for signatures made elsewhere like a player's wallet.
+#[cfg(feature = "client-core")]
+pub fn ed25519_instruction(
+    key: &Pubkey,
+    message: &[u8],
+    signature: &Signature,
+) -> SolanaInstruction {
+    let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset as u16,
+        THIS_INSTRUCTION,
+        public_key_offset as u16,
+        THIS_INSTRUCTION,
+        message_offset as u16,
+        message.len() as u16,
+        THIS_INSTRUCTION,
+    ] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(key.as_ref());
+    data.extend(signature.as_ref());
+    data.extend(message);
+    SolanaInstruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().unwrap(),
+    ))
+}
+
+#[cfg(all(test, feature = "client-core"))]
+mod test {
+    use super::*;
+
+    /// Lays out `instructions` the way the runtime fills the instructions sysvar.
+    fn instructions_sysvar(instructions: &[SolanaInstruction]) -> Vec<u8> {
+        let mut data = (instructions.len() as u16).to_le_bytes().to_vec();
+        let mut body = Vec::new();
+        let header_len = 2 + instructions.len() * 2;
+        for instruction in instructions {
+            data.extend(((header_len + body.len()) as u16).to_le_bytes());
+            body.extend((instruction.accounts.len() as u16).to_le_bytes());
+            for meta in &instruction.accounts {
+                body.push(meta.is_signer as u8 | (meta.is_writable as u8) << 1);
+                body.extend(meta.pubkey.as_ref());
+            }
+            body.extend(instruction.program_id.as_ref());
+            body.extend((instruction.data.len() as u16).to_le_bytes());
+            body.extend(&instruction.data);
+        }
+        data.extend(body);
+        // Current instruction index
+        data.extend(0u16.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_ed25519_signed_messages() {
+        let key = Pubkey::new_unique();
+        let signature = Signature::new(&[3; 64]);
+        let sysvar = instructions_sysvar(&[
+            SolanaInstruction {
+                program_id: Pubkey::new_unique(),
+                accounts: vec![SolanaAccountMeta::new(Pubkey::new_unique(), true)],
+                data: vec![1, 2, 3],
+            },
+            ed25519_instruction(&key, b"settle", &signature),
+        ]);
+        assert_eq!(
+            ed25519_signed_messages(&sysvar),
+            Some(vec![(key, b"settle".as_ref())])
+        );
+        assert_eq!(ed25519_signed_messages(&sysvar[..20]), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Errors for the program.
+//!
+//! Failures callers are expected to handle are [`TutorialError`]s,
+//! anything else is a [`GenericError::Custom`] built with [`custom_error!`].
+//!
+//! Each [`TutorialError`] fails the transaction with a fixed [`TutorialError::code`].
+//! Variants are only ever appended so the codes clients match on never change.
+
+use cruiser::prelude::*;
+
+/// Builds a [`GenericError::Custom`] from a message, formatted like [`format!`].
+///
+/// With the `small` feature the message is left out,
+/// so none of the formatting code or message strings make it into the program.
+macro_rules! custom_error {
+    ($message:literal $(,)?) => {
+        $crate::error::custom_error(|| ::std::string::ToString::to_string($message))
+    };
+    ($format:literal, $($arg:tt)+) => {
+        $crate::error::custom_error(|| ::std::format!($format, $($arg)+))
+    };
+}
+pub(crate) use custom_error;
+
+/// The function behind [`custom_error!`]. `message` is only called if messages are kept.
+#[inline(always)]
+pub(crate) fn custom_error(message: impl FnOnce() -> String) -> GenericError {
+    #[cfg(not(feature = "small"))]
+    let error = message();
+    #[cfg(feature = "small")]
+    let error = {
+        drop(message);
+        String::new()
+    };
+    GenericError::Custom { error }
+}
+
+/// Errors the program can fail with.
+#[derive(Debug, Error, Copy, Clone, Eq, PartialEq)]
+#[error(start = 1000)]
+pub enum TutorialError {
+    /// `funds_to` is the game's signer, which is emptied and closed as it pays out.
+    #[error_msg("funds_to can't be the game's signer")]
+    FundsToIsGameSigner,
+    /// `funds_to` is the game account itself.
+    #[error_msg("funds_to can't be the game")]
+    FundsToIsGame,
+    /// `funds_to` is owned by the program, the lamports would be stuck in it.
+    #[error_msg("funds_to can't be owned by the program")]
+    FundsToIsProgramOwned,
+    /// A PDA's bump isn't the canonical one [`PDASeeder::find_address`] gives, which clients couldn't find again.
+    #[error_msg("bump isn't the canonical bump for the PDA")]
+    NonCanonicalBump,
+    /// The instruction was superseded by a newer version and the game was created while
+    /// [`ProgramConfig::reject_deprecated_instructions`](crate::accounts::ProgramConfig::reject_deprecated_instructions) was set.
+    #[error_msg("instruction is deprecated, use its newer version")]
+    DeprecatedInstruction,
+    /// The game was already joined, usually by another joiner whose transaction landed first.
+    #[error_msg("game was already joined")]
+    GameAlreadyJoined,
+    /// The profile is playing the game but it's the other player's turn.
+    #[error_msg("it's the other player's turn")]
+    NotPlayersTurn,
+    /// The authority is neither the profile's authority nor its unexpired delegate.
+    #[error_msg("authority can't move for this profile")]
+    AuthorityCantMove,
+    /// The move isn't legal on the game's board, see [`Game::is_valid_move`](crate::accounts::Game::is_valid_move).
+    #[error_msg("move isn't legal")]
+    InvalidMove,
+}
+
+impl TutorialError {
+    /// Every variant, in code order.
+    pub const ALL: [Self; 9] = [
+        Self::FundsToIsGameSigner,
+        Self::FundsToIsGame,
+        Self::FundsToIsProgramOwned,
+        Self::NonCanonicalBump,
+        Self::DeprecatedInstruction,
+        Self::GameAlreadyJoined,
+        Self::NotPlayersTurn,
+        Self::AuthorityCantMove,
+        Self::InvalidMove,
+    ];
+
+    /// The custom program error code the transaction fails with.
+    pub const fn code(self) -> u32 {
+        match self {
+            Self::FundsToIsGameSigner => 1000,
+            Self::FundsToIsGame => 1001,
+            Self::FundsToIsProgramOwned => 1002,
+            Self::NonCanonicalBump => 1003,
+            Self::DeprecatedInstruction => 1004,
+            Self::GameAlreadyJoined => 1005,
+            Self::NotPlayersTurn => 1006,
+            Self::AuthorityCantMove => 1007,
+            Self::InvalidMove => 1008,
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+impl TutorialError {
+    /// Finds the error a transaction failed with from its custom program error code.
+    /// [`None`] if the code isn't a [`TutorialError`], such as for a [`custom_error!`].
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_codes() {
+        for (index, error) in TutorialError::ALL.into_iter().enumerate() {
+            // Pins the codes the derive hands out to the table clients match on.
+            assert_eq!(
+                error.to_program_error(),
+                ProgramError::Custom(error.code()),
+                "{:?}",
+                error
+            );
+            assert_eq!(error.code(), 1000 + index as u32);
+        }
+    }
+
+    #[cfg(feature = "client-core")]
+    #[test]
+    fn test_from_code() {
+        for error in TutorialError::ALL {
+            assert_eq!(TutorialError::from_code(error.code()), Some(error));
+        }
+        assert_eq!(TutorialError::from_code(999), None);
+        assert_eq!(
+            TutorialError::from_code(1000 + TutorialError::ALL.len() as u32),
+            None
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Binary events for indexers.
+//!
+//! Every processor emits a [`ProgramEvent`] with `sol_log_data`, which the runtime logs as
+//! `Program data: <base64>` under the program's invocation. Geyser plugins and indexers read the raw bytes
+//! out of the transaction's log messages and decode them with [`ProgramEvent::decode`],
+//! clients with [`decode_program_events`](crate::client::decode_program_events).
+//! The program logs no other data, so every `Program data:` line under it is an event.
+//!
+//! An event is encoded as:
+//!
+//! | Bytes | Field                                                      |
+//! |-------|------------------------------------------------------------|
+//! | 1     | [`EVENT_VERSION`]                                          |
+//! | 1     | The discriminator, the [`ProgramEvent`] variant's index    |
+//! | rest  | The variant's fields, borsh encoded                        |
+//!
+//! Variants are only ever appended, so a discriminator always means the same event.
+//! Fields are only ever appended to the end of a variant and decoders ignore bytes past the ones they know,
+//! so older decoders keep reading newer events of the same version.
+//! Any other change bumps [`EVENT_VERSION`].
+//!
+//! [`HouseMove`](crate::instructions::HouseMove) emits nothing of its own, the [`MakeMove`](crate::instructions::MakeMove)
+//! it calls emits the house's move. [`GetGameState`](crate::instructions::GetGameState) changes nothing so emits nothing.
+//! Unlike [`log_event!`](crate::log), events aren't left out by the `log-errors-only` or `log-off` features.
+
+use crate::accounts::{Achievement, AdminAction, GameResult, GameVariant, Player};
+use crate::instructions::{MakeMoveData, UnlockAction};
+use crate::settlement_hook::GameSettled;
+use cruiser::prelude::*;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The version of the event encoding, the first byte of every event.
+pub const EVENT_VERSION: u8 = 1;
+/// How many [`ProgramEvent`] variants this version of the crate knows.
+/// Discriminators at or past this are events from a newer program.
+pub const EVENT_COUNT: u8 = 51;
+
+/// Something that happened in the program, see the [module docs](self) for the encoding.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum ProgramEvent {
+    /// A profile was created.
+    ProfileCreated {
+        /// The new profile.
+        profile: Pubkey,
+        /// The profile's authority.
+        authority: Pubkey,
+    },
+    /// A game was created.
+    GameCreated {
+        /// The new game.
+        game: Pubkey,
+        /// The creator's profile.
+        profile: Pubkey,
+        /// The seat the creator took.
+        creator_player: Player,
+        /// The board being played on.
+        variant: GameVariant,
+        /// The creator's wager.
+        wager: u64,
+        /// What the joiner has to wager.
+        joiner_wager: u64,
+        /// Whether the game has no wagers.
+        friendly: bool,
+        /// The only profile that can join the game, the zero key if anyone can.
+        other_player: Pubkey,
+    },
+    /// A game was joined, starting it.
+    GameJoined {
+        /// The game.
+        game: Pubkey,
+        /// The joiner's profile.
+        profile: Pubkey,
+        /// Who moves first.
+        first_player: Player,
+        /// When the game started.
+        started_at: UnixTimestamp,
+    },
+    /// A move was played, by any of the move instructions or a queued premove.
+    MovePlayed {
+        /// The game.
+        game: Pubkey,
+        /// The profile of the player that moved.
+        profile: Pubkey,
+        /// The seat that moved.
+        player: Player,
+        /// The move.
+        mov: MakeMoveData,
+    },
+    /// A game was won, drawn or forfeited and paid out.
+    /// The same data a [settlement hook](crate::settlement_hook) is called with.
+    GameEnded(GameSettled),
+    /// A profile created its payout allowlist.
+    PayoutAllowlistCreated {
+        /// The profile.
+        profile: Pubkey,
+        /// The new allowlist.
+        payout_allowlist: Pubkey,
+    },
+    /// A timelocked update to a payout allowlist was queued.
+    PayoutAllowlistUpdateProposed {
+        /// The allowlist.
+        payout_allowlist: Pubkey,
+        /// When the update was queued.
+        proposed_at: UnixTimestamp,
+    },
+    /// A timelocked beneficiary change was queued for a profile.
+    BeneficiaryProposed {
+        /// The profile.
+        profile: Pubkey,
+        /// The proposed beneficiary.
+        beneficiary: Pubkey,
+        /// How long the profile has to be inactive before the beneficiary can claim it.
+        inactivity_period: UnixTimestamp,
+    },
+    /// A beneficiary claimed an abandoned profile, closing it.
+    AbandonedProfileClaimed {
+        /// The closed profile.
+        profile: Pubkey,
+        /// Where its lamports went.
+        funds_to: Pubkey,
+    },
+    /// A profile's settings changed.
+    ProfileUpdated {
+        /// The profile.
+        profile: Pubkey,
+    },
+    /// Lamports were withdrawn from a profile.
+    ProfileWithdrawal {
+        /// The profile.
+        profile: Pubkey,
+        /// The lamports withdrawn.
+        amount: u64,
+        /// Where they went.
+        funds_to: Pubkey,
+    },
+    /// The program config was created.
+    ConfigInitialized {
+        /// The config.
+        config: Pubkey,
+    },
+    /// An automation thread was registered to forfeit a game.
+    ForfeitThreadRegistered {
+        /// The game.
+        game: Pubkey,
+        /// The thread.
+        thread: Pubkey,
+    },
+    /// A game nobody joined in time was closed.
+    StaleGameSwept {
+        /// The closed game.
+        game: Pubkey,
+        /// The wager refunded to the creator.
+        refund: u64,
+    },
+    /// VRF randomness was requested to pick a game's first player.
+    FirstPlayerVrfRequested {
+        /// The game.
+        game: Pubkey,
+        /// The VRF account.
+        vrf: Pubkey,
+    },
+    /// A game's first player was picked from its VRF.
+    FirstPlayerPicked {
+        /// The game.
+        game: Pubkey,
+        /// Who moves first.
+        first_player: Player,
+    },
+    /// The house was created.
+    HouseInitialized {
+        /// The house.
+        house: Pubkey,
+    },
+    /// A game against the house was created and started.
+    HouseGameCreated {
+        /// The new game.
+        game: Pubkey,
+        /// The player's profile.
+        profile: Pubkey,
+        /// The house's profile.
+        house_profile: Pubkey,
+        /// The seat the player took.
+        creator_player: Player,
+        /// The board being played on.
+        variant: GameVariant,
+        /// What the player and the house each wagered.
+        wager: u64,
+    },
+    /// The house's bankroll was topped up.
+    HouseVaultFunded {
+        /// Who funded it.
+        funder: Pubkey,
+        /// The lamports added.
+        amount: u64,
+    },
+    /// Lamports were taken out of the house's bankroll.
+    HouseVaultWithdrawn {
+        /// Where they went.
+        funds_to: Pubkey,
+        /// The lamports withdrawn.
+        amount: u64,
+    },
+    /// A profile created its social graph.
+    SocialGraphCreated {
+        /// The profile.
+        profile: Pubkey,
+        /// The new social graph.
+        social_graph: Pubkey,
+    },
+    /// A profile followed another.
+    Followed {
+        /// The follower's social graph.
+        social_graph: Pubkey,
+        /// The followed profile.
+        followed: Pubkey,
+    },
+    /// A profile unfollowed another.
+    Unfollowed {
+        /// The follower's social graph.
+        social_graph: Pubkey,
+        /// The unfollowed profile.
+        unfollowed: Pubkey,
+    },
+    /// A chat message was posted to a game.
+    GameMessagePosted {
+        /// The game.
+        game: Pubkey,
+        /// The profile that posted it.
+        from: Pubkey,
+        /// The message.
+        message: String,
+    },
+    /// An inactive profile's elo decayed.
+    EloDecayed {
+        /// The profile.
+        profile: Pubkey,
+        /// The profile's new elo.
+        elo: u64,
+    },
+    /// A page of the leaderboard was created.
+    LeaderboardShardCreated {
+        /// The new page.
+        shard: Pubkey,
+    },
+    /// A profile was listed on a leaderboard page.
+    LeaderboardUpdated {
+        /// The page.
+        shard: Pubkey,
+        /// The listed profile.
+        profile: Pubkey,
+        /// The elo it was listed with.
+        elo: u64,
+        /// The profile pushed off the page to make room, if any.
+        evicted: Option<Pubkey>,
+    },
+    /// The instruction usage metrics were created.
+    MetricsInitialized {
+        /// The metrics.
+        metrics: Pubkey,
+    },
+    /// The config's settings were changed directly by the admin.
+    ConfigUpdated {
+        /// The config.
+        config: Pubkey,
+    },
+    /// The config admin was changed.
+    AdminChanged {
+        /// The old admin.
+        from: Pubkey,
+        /// The new admin.
+        to: Pubkey,
+    },
+    /// Fees were withdrawn from the fee vault, directly or by an admin action.
+    FeesWithdrawn {
+        /// The lamports withdrawn.
+        amount: u64,
+        /// The treasury they went to.
+        treasury: Pubkey,
+    },
+    /// Recovery of a stuck game was proposed.
+    GameRecoveryProposed {
+        /// The stuck game.
+        game: Pubkey,
+        /// Where the escrow will go.
+        funds_to: Pubkey,
+    },
+    /// A stuck game was recovered, refunding its players.
+    GameRecovered {
+        /// The stuck game.
+        game: Pubkey,
+        /// The stakes refunded to the players' profiles.
+        amount: u64,
+        /// Where the rent of the game and its vault went.
+        funds_to: Pubkey,
+    },
+    /// A game recovery was cancelled because the game moved.
+    GameRecoveryCancelled {
+        /// The game that moved.
+        game: Pubkey,
+    },
+    /// An admin action was proposed.
+    AdminActionProposed {
+        /// The action's id.
+        id: u64,
+        /// When it can be executed.
+        executable_at: UnixTimestamp,
+        /// The action.
+        action: AdminAction,
+    },
+    /// An admin action was executed.
+    AdminActionExecuted {
+        /// The action's id.
+        id: u64,
+    },
+    /// An admin action was cancelled.
+    AdminActionCancelled {
+        /// The action's id.
+        id: u64,
+    },
+    /// A premove was queued or cleared.
+    PremoveQueued {
+        /// The game.
+        game: Pubkey,
+        /// The queued move, [`None`] if it was cleared.
+        premove: Option<MakeMoveData>,
+    },
+    /// A move was committed to in a commit-reveal game.
+    MoveCommitted {
+        /// The game.
+        game: Pubkey,
+        /// The profile that committed.
+        profile: Pubkey,
+        /// The commitment.
+        commitment: [u8; 32],
+    },
+    /// A game played off-chain was settled, pending its dispute window.
+    SettlementPosted {
+        /// The game.
+        game: Pubkey,
+        /// The claimed result.
+        result: GameResult,
+        /// Who posted the settlement.
+        settler: Pubkey,
+        /// The bond the settler put up.
+        bond: u64,
+    },
+    /// A signed settlement was challenged with its moves and the settler's bond went to the challenger.
+    SettlementChallenged {
+        /// The game.
+        game: Pubkey,
+        /// Who challenged it.
+        challenger: Pubkey,
+        /// The result the moves reach, [`None`] if they don't finish the game.
+        replayed: Option<GameResult>,
+    },
+    /// A signed settlement's dispute window passed and its bond went back to the settler.
+    SettlementFinalized {
+        /// The game.
+        game: Pubkey,
+        /// Who posted the settlement.
+        settler: Pubkey,
+    },
+    /// A profile's session key was set, or removed if `delegate` is the zero key.
+    SessionKeySet {
+        /// The profile.
+        profile: Pubkey,
+        /// The session key.
+        delegate: Pubkey,
+        /// When the session key expires.
+        expiry: UnixTimestamp,
+    },
+    /// A guardian started recovering a profile.
+    RecoveryInitiated {
+        /// The profile.
+        profile: Pubkey,
+        /// The guardian.
+        guardian: Pubkey,
+        /// The authority the profile is being recovered to.
+        new_authority: Pubkey,
+    },
+    /// A guardian approved a profile recovery.
+    RecoveryApproved {
+        /// The profile.
+        profile: Pubkey,
+        /// The guardian.
+        guardian: Pubkey,
+    },
+    /// A profile was moved to its recovered authority.
+    ProfileRecovered {
+        /// The profile.
+        profile: Pubkey,
+        /// The old authority.
+        from: Pubkey,
+        /// The new authority.
+        to: Pubkey,
+    },
+    /// A finished game was archived into a compression tree and closed.
+    GameArchived {
+        /// The closed game.
+        game: Pubkey,
+        /// The leaf appended to the tree.
+        leaf: [u8; 32],
+    },
+    /// A profile claimed an achievement badge.
+    AchievementClaimed {
+        /// The profile.
+        profile: Pubkey,
+        /// The achievement.
+        achievement: Achievement,
+    },
+    /// A game its locked other player didn't join in time was reclaimed or opened.
+    GameUnlocked {
+        /// The game.
+        game: Pubkey,
+        /// What was done with it.
+        action: UnlockAction,
+    },
+    /// A distribution was created.
+    DistributionCreated {
+        /// The new distribution.
+        distribution: Pubkey,
+        /// The lamports it pays out.
+        total: u64,
+    },
+    /// A reward was claimed from a distribution.
+    RewardClaimed {
+        /// The distribution.
+        distribution: Pubkey,
+        /// Who claimed it.
+        claimant: Pubkey,
+        /// The claim's index.
+        index: u64,
+        /// The lamports claimed.
+        amount: u64,
+    },
+}
+impl ProgramEvent {
+    /// Encodes the event as it's logged, see the [module docs](self).
+    pub fn encode(&self) -> CruiserResult<Vec<u8>> {
+        let mut data = vec![EVENT_VERSION];
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+
+    /// Decodes an event from the bytes of a `Program data:` log line.
+    /// Bytes past the event's known fields are ignored, see the [module docs](self).
+    pub fn decode(data: &[u8]) -> Result<Self, EventDecodeError> {
+        match data {
+            [] | [_] => Err(EventDecodeError::TooShort),
+            [version, ..] if *version != EVENT_VERSION => {
+                Err(EventDecodeError::UnsupportedVersion(*version))
+            }
+            [_, discriminator, ..] if *discriminator >= EVENT_COUNT => {
+                Err(EventDecodeError::UnknownEvent(*discriminator))
+            }
+            [_, payload @ ..] => {
+                Self::deserialize(&mut &*payload).map_err(EventDecodeError::Payload)
+            }
+        }
+    }
+
+    /// Logs the event for indexers.
+    #[cfg(feature = "processor")]
+    pub(crate) fn emit(&self) -> CruiserResult<()> {
+        cruiser::solana_program::log::sol_log_data(&[&self.encode()?]);
+        Ok(())
+    }
+}
+
+/// Why [`ProgramEvent::decode`] failed.
+#[derive(Debug)]
+pub enum EventDecodeError {
+    /// There's no version and discriminator.
+    TooShort,
+    /// The event was encoded with a version this crate can't decode.
+    UnsupportedVersion(u8),
+    /// The event is newer than this crate, indexers can skip it.
+    UnknownEvent(u8),
+    /// The event's fields don't decode.
+    Payload(std::io::Error),
+}
+impl Display for EventDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventDecodeError::TooShort => write!(f, "event is too short"),
+            EventDecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported event version {}", version)
+            }
+            EventDecodeError::UnknownEvent(discriminator) => {
+                write!(f, "unknown event discriminator {}", discriminator)
+            }
+            EventDecodeError::Payload(error) => write!(f, "invalid event data: {}", error),
+        }
+    }
+}
+impl Error for EventDecodeError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let game = Pubkey::new_unique();
+        let event = ProgramEvent::MovePlayed {
+            game,
+            profile: Pubkey::new_unique(),
+            player: Player::Two,
+            mov: MakeMoveData {
+                big_board: [0, 0],
+                middle_board: [1, 2],
+                small_board: [2, 0],
+            },
+        };
+        let data = event.encode().unwrap();
+        assert_eq!(data[..2], [EVENT_VERSION, 3]);
+        assert_eq!(data[2..34], game.to_bytes());
+        assert_eq!(ProgramEvent::decode(&data).unwrap(), event);
+
+        // Fields appended by a newer program are skipped
+        let mut newer = data.clone();
+        newer.extend([1, 2, 3]);
+        assert_eq!(ProgramEvent::decode(&newer).unwrap(), event);
+
+        // The last variant is the last discriminator this crate knows
+        let last = ProgramEvent::RewardClaimed {
+            distribution: game,
+            claimant: game,
+            index: 0,
+            amount: 0,
+        };
+        assert_eq!(last.encode().unwrap()[1], EVENT_COUNT - 1);
+
+        assert!(matches!(
+            ProgramEvent::decode(&[EVENT_VERSION, EVENT_COUNT]),
+            Err(EventDecodeError::UnknownEvent(EVENT_COUNT))
+        ));
+        assert!(matches!(
+            ProgramEvent::decode(&[EVENT_VERSION + 1, 0]),
+            Err(EventDecodeError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            ProgramEvent::decode(&[EVENT_VERSION]),
+            Err(EventDecodeError::TooShort)
+        ));
+        assert!(matches!(
+            ProgramEvent::decode(&data[..20]),
+            Err(EventDecodeError::Payload(_))
+        ));
+    }
+}
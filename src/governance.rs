@@ -0,0 +1,42 @@
+//! Glue for [SPL Governance](https://github.com/solana-labs/solana-program-library/tree/master/governance),
+//! the program behind Realms DAOs.
+//!
+//! A governance account signs the instructions of its proposals when they execute,
+//! so making one the config's admin puts config changes behind a vote.
+//! Only the account layout needed to recognize a governance is mirrored here,
+//! so there is no dependency on the governance crates.
+
+use cruiser::prelude::*;
+
+/// The SPL Governance program Realms deploys. DAOs on their own deployment aren't recognized.
+pub const GOVERNANCE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    234, 228, 53, 189, 238, 117, 183, 52, 205, 89, 62, 207, 154, 48, 75, 128, 36, 186, 40, 152,
+    103, 183, 105, 177, 249, 60, 167, 187, 184, 142, 70, 254,
+]);
+
+/// The `GovernanceAccountType`s, the first byte of the account, of the governance kinds that can sign proposals.
+/// The V1 and V2 layouts of the generic, program, mint, and token governances.
+const GOVERNANCE_ACCOUNT_TYPES: [u8; 8] = [3, 4, 9, 10, 18, 19, 20, 21];
+
+/// Tells whether an account with `owner` and `data` is a governance that can execute proposals.
+pub fn is_governance(owner: &Pubkey, data: &[u8]) -> bool {
+    owner == &GOVERNANCE_PROGRAM_ID
+        && data.first().map_or(false, |account_type| {
+            GOVERNANCE_ACCOUNT_TYPES.contains(account_type)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_governance() {
+        assert!(is_governance(&GOVERNANCE_PROGRAM_ID, &[18, 1, 2, 3]));
+        assert!(is_governance(&GOVERNANCE_PROGRAM_ID, &[3]));
+        // A realm isn't a governance
+        assert!(!is_governance(&GOVERNANCE_PROGRAM_ID, &[16]));
+        assert!(!is_governance(&GOVERNANCE_PROGRAM_ID, &[]));
+        assert!(!is_governance(&Pubkey::new_unique(), &[18]));
+    }
+}
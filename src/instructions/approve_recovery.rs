@@ -0,0 +1,188 @@
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Approves the recovery started with [`InitiateRecovery`](crate::instructions::InitiateRecovery) as another guardian.
+#[derive(Debug)]
+pub enum ApproveRecovery {}
+
+impl<AI> Instruction<AI> for ApproveRecovery {
+    type Accounts = ApproveRecoveryAccounts<AI>;
+    type Data = ApproveRecoveryData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ApproveRecovery`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct ApproveRecoveryAccounts<AI> {
+    /// One of the profile's guardians.
+    #[validate(signer)]
+    pub guardian: AI,
+    /// The profile to recover.
+    #[validate(
+        writable,
+        custom = self.player_profile.recovery.is_pending(),
+        custom = self.player_profile.guardians.index_of(self.guardian.key()).is_some(),
+    )]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`ApproveRecovery`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ApproveRecoveryData {
+    /// The authority the recovery moves the profile to.
+    /// Must match the recovery in progress so a guardian can't approve one that replaced what they meant to approve.
+    pub new_authority: Pubkey,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, ApproveRecovery> for ApproveRecovery
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ApproveRecoveryData;
+
+        fn data_to_instruction_arg(
+            data: <ApproveRecovery as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <ApproveRecovery as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ApproveRecovery as Instruction<AI>>::ReturnType> {
+            if data.new_authority != accounts.player_profile.recovery.new_authority {
+                return Err(
+                    custom_error!("new_authority doesn't match the recovery in progress").into(),
+                );
+            }
+            let index = accounts
+                .player_profile
+                .guardians
+                .index_of(accounts.guardian.key())
+                .unwrap();
+            accounts.player_profile.recovery.approvals |= 1 << index;
+            ProgramEvent::RecoveryApproved {
+                profile: *accounts.player_profile.info().key(),
+                guardian: *accounts.guardian.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ApproveRecovery`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Approves recovering a profile to a new authority.
+    #[derive(Debug)]
+    pub struct ApproveRecoveryCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ApproveRecoveryCPI<'a, AI> {
+        /// Approves recovering a profile to a new authority.
+        pub fn new(
+            guardian: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            recovery_data: &ApproveRecoveryData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ApproveRecovery>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            recovery_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [guardian.into(), player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for ApproveRecoveryCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ApproveRecovery;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ApproveRecovery`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Approves recovering `player_profile` to `new_authority` as `guardian`.
+    pub fn approve_recovery<'a>(
+        program_id: Pubkey,
+        guardian: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        new_authority: Pubkey,
+    ) -> InstructionSet<'a> {
+        let guardian = guardian.into();
+        InstructionSet {
+            instructions: vec![
+                ApproveRecoveryCPI::new(
+                    SolanaAccountMeta::new_readonly(guardian.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    &ApproveRecoveryData { new_authority },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [guardian].into_iter().collect(),
+        }
+    }
+}
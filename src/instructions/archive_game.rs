@@ -0,0 +1,231 @@
+use crate::account_compression::{ACCOUNT_COMPRESSION_PROGRAM_ID, NOOP_PROGRAM_ID};
+use crate::log::log_event;
+use crate::pda::ArchiveAuthoritySeeder;
+use crate::{Game, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Archives a finished game, played out or forfeited, into an account compression tree and closes it.
+/// Anyone can archive a game, the rent always goes back to the creator's profile.
+///
+/// The leaf appended is [`FinishedGame::leaf`](crate::accounts::FinishedGame::leaf),
+/// the [`ArchivedGame`](crate::accounts::ArchivedGame) it hashes is logged when the game finishes.
+/// The tree can be any tree whose authority is the [`ArchiveAuthoritySeeder`] PDA.
+#[derive(Debug)]
+pub enum ArchiveGame {}
+
+impl<AI> Instruction<AI> for ArchiveGame {
+    type Accounts = ArchiveGameAccounts<AI>;
+    type Data = ArchiveGameData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ArchiveGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(data = (authority_bump: u8))]
+pub struct ArchiveGameAccounts<AI> {
+    /// The finished game to archive.
+    #[validate(writable, custom = self.game.finished.is_finished())]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The profile of the game's creator, gets the game's rent.
+    #[validate(writable, custom = self.creator_profile.key() == &self.game.finished.creator_profile)]
+    pub creator_profile: AI,
+    /// The tree's authority.
+    #[validate(data = (ArchiveAuthoritySeeder, authority_bump))]
+    pub archive_authority: Seeds<AI, ArchiveAuthoritySeeder>,
+    /// The tree to archive into.
+    #[validate(writable)]
+    pub merkle_tree: AI,
+    /// The noop program the compression program logs through.
+    #[validate(custom = self.noop_program.key() == &NOOP_PROGRAM_ID)]
+    pub noop_program: AI,
+    /// The account compression program.
+    #[validate(custom = self.compression_program.key() == &ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AI,
+}
+
+/// Data for [`ArchiveGame`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ArchiveGameData {
+    /// The bump for the [`ArchiveAuthoritySeeder`] PDA.
+    pub authority_bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::account_compression::AppendCPI;
+    use crate::event::ProgramEvent;
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, ArchiveGame> for ArchiveGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = u8;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <ArchiveGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), data.authority_bump, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <ArchiveGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ArchiveGame as Instruction<AI>>::ReturnType> {
+            let authority_seeds = accounts.archive_authority.take_seed_set().unwrap();
+
+            log_event!(info, "game_archived", game = accounts.game.info().key());
+            let InstructionAndAccounts {
+                instruction,
+                accounts: cpi_accounts,
+            } = AppendCPI::new(
+                &accounts.merkle_tree,
+                accounts.archive_authority.info(),
+                &accounts.noop_program,
+                accounts.game.finished.leaf,
+            )
+            .instruction(&accounts.compression_program);
+            CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&authority_seeds))?;
+
+            accounts.game.set_fundee(accounts.creator_profile.clone());
+
+            ProgramEvent::GameArchived {
+                game: *accounts.game.info().key(),
+                leaf: accounts.game.finished.leaf,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ArchiveGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Archives a finished game.
+    #[derive(Debug)]
+    pub struct ArchiveGameCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ArchiveGameCPI<'a, AI> {
+        /// Archives a finished game.
+        pub fn new(
+            game: impl Into<MaybeOwned<'a, AI>>,
+            creator_profile: impl Into<MaybeOwned<'a, AI>>,
+            archive_authority: impl Into<MaybeOwned<'a, AI>>,
+            merkle_tree: impl Into<MaybeOwned<'a, AI>>,
+            noop_program: impl Into<MaybeOwned<'a, AI>>,
+            compression_program: impl Into<MaybeOwned<'a, AI>>,
+            archive_data: &ArchiveGameData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ArchiveGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            archive_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    game.into(),
+                    creator_profile.into(),
+                    archive_authority.into(),
+                    merkle_tree.into(),
+                    noop_program.into(),
+                    compression_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for ArchiveGameCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ArchiveGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [game, creator_profile, archive_authority, merkle_tree, noop_program, compression_program] =
+                self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    game,
+                    creator_profile,
+                    archive_authority,
+                    merkle_tree,
+                    noop_program,
+                    compression_program,
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ArchiveGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_archive_authority, InstructionSet};
+
+    /// Archives a finished game into `merkle_tree`. Nothing needs to sign but the fee payer.
+    pub fn archive_game<'a>(
+        program_id: Pubkey,
+        game: Pubkey,
+        creator_profile: Pubkey,
+        merkle_tree: Pubkey,
+    ) -> InstructionSet<'a> {
+        let (archive_authority, authority_bump) = derive_archive_authority(&program_id);
+        InstructionSet {
+            instructions: vec![
+                ArchiveGameCPI::new(
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(creator_profile, false),
+                    SolanaAccountMeta::new_readonly(archive_authority, false),
+                    SolanaAccountMeta::new(merkle_tree, false),
+                    SolanaAccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+                    SolanaAccountMeta::new_readonly(ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+                    &ArchiveGameData { authority_bump },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
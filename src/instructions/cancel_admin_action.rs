@@ -0,0 +1,182 @@
+use crate::accounts::{ProgramConfig, ProposedAction};
+use crate::log::log_event;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Drops a [`ProposeAdminAction`](crate::instructions::ProposeAdminAction) before it executes,
+/// refunding its rent. Only the config's admin can.
+#[derive(Debug)]
+pub enum CancelAdminAction {}
+
+impl<AI> Instruction<AI> for CancelAdminAction {
+    type Accounts = CancelAdminActionAccounts<AI>;
+    type Data = ();
+    type ReturnType = ();
+}
+
+/// Accounts for [`CancelAdminAction`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct CancelAdminActionAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The proposal to cancel.
+    #[validate(writable)]
+    pub proposal: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, ProposedAction>>>,
+    /// The proposal's funder, which gets the rent back.
+    #[validate(writable, custom = self.funder.key() == &self.proposal.funder)]
+    pub funder: AI,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, CancelAdminAction> for CancelAdminAction
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <CancelAdminAction as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <CancelAdminAction as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CancelAdminAction as Instruction<AI>>::ReturnType> {
+            log_event!(info, "admin_action_cancelled", id = accounts.proposal.id);
+            accounts.proposal.set_fundee(accounts.funder.clone());
+            ProgramEvent::AdminActionCancelled {
+                id: accounts.proposal.id,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CancelAdminAction`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Cancels a proposed admin action.
+    #[derive(Debug)]
+    pub struct CancelAdminActionCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 4],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CancelAdminActionCPI<'a, AI> {
+        /// Cancels a proposed admin action.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            proposal: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CancelAdminAction>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            Ok(Self {
+                accounts: [admin.into(), config.into(), proposal.into(), funder.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 5> for CancelAdminActionCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CancelAdminAction;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CancelAdminAction`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_proposed_action, HashedSigner, InstructionSet};
+
+    /// Cancels the proposed admin action with `id`, refunding its `funder`.
+    pub fn cancel_admin_action<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        id: u64,
+        funder: Pubkey,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        let (proposal, _) = derive_proposed_action(&program_id, id);
+        InstructionSet {
+            instructions: vec![
+                CancelAdminActionCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(proposal, false),
+                    SolanaAccountMeta::new(funder, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+}
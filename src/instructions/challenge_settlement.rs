@@ -0,0 +1,263 @@
+use crate::accounts::ProgramConfig;
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Challenges a [`SettleSignedGame`](crate::instructions::SettleSignedGame) settlement within its dispute window
+/// by posting the signed moves. The moves are replayed from the game's position on-chain, see [`Game::replay`].
+///
+/// If they don't reach the settled result the settler's bond goes to the challenger,
+/// and the game is settled with the replayed result or goes back to being played on-chain if the moves don't finish it.
+/// Deep games can have more moves than fit in a transaction.
+#[derive(Debug)]
+pub enum ChallengeSettlement {}
+
+impl<AI> Instruction<AI> for ChallengeSettlement {
+    type Accounts = ChallengeSettlementAccounts<AI>;
+    type Data = ChallengeSettlementData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ChallengeSettlement`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct ChallengeSettlementAccounts<AI> {
+    /// The challenger, who gets the settler's bond.
+    #[validate(signer, writable)]
+    pub challenger: AI,
+    /// The program config, for the dispute window.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The settled game.
+    /// Left for [`ArchiveGame`](crate::instructions::ArchiveGame) if the moves finish it.
+    #[validate(
+        writable,
+        custom = self.game.is_settling(),
+        custom = !self.game.settlement.is_final(
+            Clock::get()?.unix_timestamp,
+            self.config.settlement_dispute_window,
+        ),
+    )]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The first player's profile.
+    #[validate(writable, custom = &self.game.player1 == self.player1_profile.info().key())]
+    pub player1_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The second player's profile.
+    #[validate(writable, custom = &self.game.player2 == self.player2_profile.info().key())]
+    pub player2_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`ChallengeSettlement`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ChallengeSettlementData {
+    /// The moves both players signed, see [`settlement_moves_hash`](crate::instructions::settlement_moves_hash).
+    pub moves: Vec<MakeMoveData>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::{PendingSettlement, WagerVault};
+    use crate::event::ProgramEvent;
+    use crate::instructions::{pay_out_settlement, settlement_moves_hash};
+
+    impl<'a, AI> InstructionProcessor<AI, ChallengeSettlement> for ChallengeSettlement
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = Vec<MakeMoveData>;
+
+        fn data_to_instruction_arg(
+            data: <ChallengeSettlement as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.moves))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            moves: Self::InstructionData,
+            accounts: &mut <ChallengeSettlement as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ChallengeSettlement as Instruction<AI>>::ReturnType> {
+            if settlement_moves_hash(&moves) != accounts.game.settlement.moves_hash {
+                return Err(custom_error!("Moves don't match the signed moves").into());
+            }
+            let replayed = accounts.game.replay(&moves);
+            if replayed == Some(accounts.game.settlement.result) {
+                return Err(custom_error!("The settlement matches the moves").into());
+            }
+
+            // The settler posted a board the moves don't reach, their bond goes to the challenger.
+            log_event!(info, "settlement_challenged", replayed = replayed);
+            ProgramEvent::SettlementChallenged {
+                game: *accounts.game.info().key(),
+                challenger: *accounts.challenger.key(),
+                replayed,
+            }
+            .emit()?;
+            WagerVault::pay_out(
+                accounts.game.info(),
+                &accounts.challenger,
+                accounts.game.settlement.bond,
+            )?;
+            accounts.game.settlement = PendingSettlement::none();
+
+            let now = Clock::get()?.unix_timestamp;
+            match replayed {
+                Some(result) => pay_out_settlement(
+                    &mut accounts.game,
+                    accounts.game_signer.info(),
+                    &mut accounts.player1_profile,
+                    &mut accounts.player2_profile,
+                    result,
+                    now,
+                ),
+                None => {
+                    // The moves don't finish the game so play goes on from where it was on-chain.
+                    accounts.game.last_turn = now;
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ChallengeSettlement`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Challenges a signed settlement.
+    #[derive(Debug)]
+    pub struct ChallengeSettlementCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ChallengeSettlementCPI<'a, AI> {
+        /// Challenges a signed settlement.
+        pub fn new(
+            challenger: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            player1_profile: impl Into<MaybeOwned<'a, AI>>,
+            player2_profile: impl Into<MaybeOwned<'a, AI>>,
+            challenge_data: &ChallengeSettlementData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ChallengeSettlement>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            challenge_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    challenger.into(),
+                    config.into(),
+                    game.into(),
+                    game_signer.into(),
+                    player1_profile.into(),
+                    player2_profile.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for ChallengeSettlementCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ChallengeSettlement;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ChallengeSettlement`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
+
+    /// Challenges the settlement of `game` with the signed `moves`.
+    pub fn challenge_settlement<'a>(
+        program_id: Pubkey,
+        challenger: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        game_signer_bump: u8,
+        player1_profile: Pubkey,
+        player2_profile: Pubkey,
+        moves: Vec<MakeMoveData>,
+    ) -> InstructionSet<'a> {
+        let challenger = challenger.into();
+        let (config, _) = derive_config(&program_id);
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        InstructionSet {
+            instructions: vec![
+                ChallengeSettlementCPI::new(
+                    SolanaAccountMeta::new(challenger.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(player1_profile, false),
+                    SolanaAccountMeta::new(player2_profile, false),
+                    &ChallengeSettlementData { moves },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [challenger].into_iter().collect(),
+        }
+    }
+}
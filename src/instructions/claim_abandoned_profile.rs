@@ -0,0 +1,184 @@
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Lets a profile's beneficiary close it once it has been inactive for long enough,
+/// taking all the lamports it holds.
+/// Rejected while the profile is still seated in a game, see [`PlayerProfile::games_in_progress`].
+#[derive(Debug)]
+pub enum ClaimAbandonedProfile {}
+
+impl<AI> Instruction<AI> for ClaimAbandonedProfile {
+    type Accounts = ClaimAbandonedProfileAccounts<AI>;
+    type Data = ClaimAbandonedProfileData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ClaimAbandonedProfile`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct ClaimAbandonedProfileAccounts<AI> {
+    /// The profile's beneficiary.
+    #[validate(signer)]
+    pub beneficiary: AI,
+    /// The abandoned profile. Will be closed.
+    #[validate(
+        writable,
+        custom = self.player_profile.is_claimable_by(self.beneficiary.key(), Clock::get()?.unix_timestamp),
+    )]
+    pub player_profile: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, PlayerProfile>>>,
+    /// Where the profile's lamports should go to.
+    #[validate(writable)]
+    pub funds_to: AI,
+}
+
+/// Data for [`ClaimAbandonedProfile`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ClaimAbandonedProfileData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, ClaimAbandonedProfile> for ClaimAbandonedProfile
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <ClaimAbandonedProfile as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <ClaimAbandonedProfile as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ClaimAbandonedProfile as Instruction<AI>>::ReturnType> {
+            log_event!(info, "abandoned_profile_claimed");
+            // Closing sends the profile's rent and any winnings it holds to `funds_to`.
+            accounts
+                .player_profile
+                .set_fundee(accounts.funds_to.clone());
+            ProgramEvent::AbandonedProfileClaimed {
+                profile: *accounts.player_profile.info().key(),
+                funds_to: *accounts.funds_to.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ClaimAbandonedProfile`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Claims an abandoned profile.
+    #[derive(Debug)]
+    pub struct ClaimAbandonedProfileCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ClaimAbandonedProfileCPI<'a, AI> {
+        /// Claims an abandoned profile.
+        pub fn new(
+            beneficiary: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ClaimAbandonedProfile>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            ClaimAbandonedProfileData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [beneficiary.into(), player_profile.into(), funds_to.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for ClaimAbandonedProfileCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ClaimAbandonedProfile;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ClaimAbandonedProfile`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Claims an abandoned profile, sending its lamports to `funds_to`.
+    pub fn claim_abandoned_profile<'a>(
+        program_id: Pubkey,
+        beneficiary: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        funds_to: Pubkey,
+    ) -> InstructionSet<'a> {
+        let beneficiary = beneficiary.into();
+        InstructionSet {
+            instructions: vec![
+                ClaimAbandonedProfileCPI::new(
+                    SolanaAccountMeta::new_readonly(beneficiary.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [beneficiary].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::account_compression::{ACCOUNT_COMPRESSION_PROGRAM_ID, NOOP_PROGRAM_ID};
+use crate::accounts::{Achievement, ProgramConfig};
+use crate::bubblegum::BUBBLEGUM_PROGRAM_ID;
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::pda::BadgeAuthoritySeeder;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Mints a badge for an [`Achievement`] the profile has reached, as a Bubblegum compressed NFT
+/// owned by the profile's authority. Each achievement can be claimed once per profile.
+///
+/// Badges go into [`ProgramConfig::badge_tree`], whose tree delegate must be the [`BadgeAuthoritySeeder`] PDA.
+/// The PDA is also the badge's only verified creator, which is what tells real badges apart.
+#[derive(Debug)]
+pub enum ClaimAchievement {}
+
+impl<AI> Instruction<AI> for ClaimAchievement {
+    type Accounts = ClaimAchievementAccounts<AI>;
+    type Data = ClaimAchievementData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ClaimAchievement`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(data = (badge_authority_bump: u8))]
+pub struct ClaimAchievementAccounts<AI> {
+    /// The authority of the profile, gets the badge.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile claiming the achievement.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The program config, for the badge tree.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    #[validate(custom = self.config.badge_tree != Pubkey::new_from_array([0; 32]))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The badge tree's Bubblegum config, see [`TreeConfigSeeder`](crate::bubblegum::TreeConfigSeeder).
+    #[validate(writable)]
+    pub tree_config: AI,
+    /// The badge tree.
+    #[validate(writable, custom = self.merkle_tree.key() == &self.config.badge_tree)]
+    pub merkle_tree: AI,
+    /// The badge tree's delegate.
+    #[validate(data = (BadgeAuthoritySeeder, badge_authority_bump))]
+    pub badge_authority: Seeds<AI, BadgeAuthoritySeeder>,
+    /// Pays the mint's fees.
+    #[validate(signer, writable)]
+    pub payer: AI,
+    /// The noop program the compression program logs through.
+    #[validate(custom = self.noop_program.key() == &NOOP_PROGRAM_ID)]
+    pub noop_program: AI,
+    /// The account compression program.
+    #[validate(custom = self.compression_program.key() == &ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AI,
+    /// The Bubblegum program.
+    #[validate(custom = self.bubblegum_program.key() == &BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AI,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`ClaimAchievement`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ClaimAchievementData {
+    /// The achievement to claim.
+    pub achievement: Achievement,
+    /// The bump for the [`BadgeAuthoritySeeder`] PDA.
+    pub badge_authority_bump: u8,
+}
+
+/// The symbol of achievement badges.
+pub const BADGE_SYMBOL: &str = "UTTT";
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::bubblegum::{Creator, MetadataArgs, MintV1CPI, TokenProgramVersion, TokenStandard};
+    use crate::event::ProgramEvent;
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, ClaimAchievement> for ClaimAchievement
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = u8;
+        type InstructionData = Achievement;
+
+        fn data_to_instruction_arg(
+            data: <ClaimAchievement as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), data.badge_authority_bump, data.achievement))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            achievement: Self::InstructionData,
+            accounts: &mut <ClaimAchievement as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ClaimAchievement as Instruction<AI>>::ReturnType> {
+            if accounts.player_profile.achievements & achievement.bit() != 0 {
+                return Err(custom_error!("{:?} was already claimed", achievement).into());
+            }
+            if !achievement.is_earned(&accounts.player_profile) {
+                return Err(custom_error!("{:?} hasn't been earned", achievement).into());
+            }
+            accounts.player_profile.achievements |= achievement.bit();
+
+            let badge_seeds = accounts.badge_authority.take_seed_set().unwrap();
+            let metadata = MetadataArgs {
+                name: achievement.name().to_string(),
+                symbol: BADGE_SYMBOL.to_string(),
+                uri: format!("{}{}", &*accounts.config.badge_uri, achievement.slug()),
+                seller_fee_basis_points: 0,
+                primary_sale_happened: false,
+                is_mutable: false,
+                edition_nonce: None,
+                token_standard: Some(TokenStandard::NonFungible),
+                collection: None,
+                uses: None,
+                token_program_version: TokenProgramVersion::Original,
+                creators: vec![Creator {
+                    address: *accounts.badge_authority.info().key(),
+                    verified: true,
+                    share: 100,
+                }],
+            };
+
+            log_event!(info, "achievement_claimed", achievement = achievement);
+            let InstructionAndAccounts {
+                instruction,
+                accounts: cpi_accounts,
+            } = MintV1CPI::new(
+                &accounts.tree_config,
+                &accounts.authority,
+                &accounts.authority,
+                &accounts.merkle_tree,
+                &accounts.payer,
+                accounts.badge_authority.info(),
+                &accounts.noop_program,
+                &accounts.compression_program,
+                accounts.system_program.info(),
+                &metadata,
+            )?
+            .instruction(&accounts.bubblegum_program);
+            CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&badge_seeds))?;
+
+            ProgramEvent::AchievementClaimed {
+                profile: *accounts.player_profile.info().key(),
+                achievement,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ClaimAchievement`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Claims an achievement badge.
+    #[derive(Debug)]
+    pub struct ClaimAchievementCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 11],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ClaimAchievementCPI<'a, AI> {
+        /// Claims an achievement badge.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            tree_config: impl Into<MaybeOwned<'a, AI>>,
+            merkle_tree: impl Into<MaybeOwned<'a, AI>>,
+            badge_authority: impl Into<MaybeOwned<'a, AI>>,
+            payer: impl Into<MaybeOwned<'a, AI>>,
+            noop_program: impl Into<MaybeOwned<'a, AI>>,
+            compression_program: impl Into<MaybeOwned<'a, AI>>,
+            bubblegum_program: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            claim_data: &ClaimAchievementData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ClaimAchievement>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            claim_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    config.into(),
+                    tree_config.into(),
+                    merkle_tree.into(),
+                    badge_authority.into(),
+                    payer.into(),
+                    noop_program.into(),
+                    compression_program.into(),
+                    bubblegum_program.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 12> for ClaimAchievementCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ClaimAchievement;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 12]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ClaimAchievement`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::bubblegum::TreeConfigSeeder;
+    use crate::client::{derive_badge_authority, derive_config, HashedSigner, InstructionSet};
+
+    /// Claims `achievement` for a profile, minting its badge from `badge_tree` to `authority`.
+    pub fn claim_achievement<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        badge_tree: Pubkey,
+        payer: impl Into<HashedSigner<'a>>,
+        achievement: Achievement,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let payer = payer.into();
+        let (config, _) = derive_config(&program_id);
+        let (tree_config, _) = TreeConfigSeeder {
+            merkle_tree: badge_tree,
+        }
+        .find_address(&BUBBLEGUM_PROGRAM_ID);
+        let (badge_authority, badge_authority_bump) = derive_badge_authority(&program_id);
+        InstructionSet {
+            instructions: vec![
+                ClaimAchievementCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(tree_config, false),
+                    SolanaAccountMeta::new(badge_tree, false),
+                    SolanaAccountMeta::new_readonly(badge_authority, false),
+                    SolanaAccountMeta::new(payer.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+                    SolanaAccountMeta::new_readonly(ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+                    SolanaAccountMeta::new_readonly(BUBBLEGUM_PROGRAM_ID, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &ClaimAchievementData {
+                        achievement,
+                        badge_authority_bump,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, payer].into_iter().collect(),
+        }
+    }
+}
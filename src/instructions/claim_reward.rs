@@ -0,0 +1,265 @@
+use crate::accounts::{ClaimReceipt, Distribution};
+use crate::pda::ClaimReceiptSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Pays out a claim from a [`Distribution`], proven with a Merkle proof of its leaf, see [`merkle`](crate::merkle).
+/// Anyone can make a claim as the lamports only ever go to the claimant.
+/// The claim's [`ClaimReceipt`] is created so it can't be made again.
+#[derive(Debug)]
+pub enum ClaimReward {}
+
+impl<AI> Instruction<AI> for ClaimReward {
+    type Accounts = ClaimRewardAccounts<AI>;
+    type Data = ClaimRewardData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ClaimReward`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (index: u64, amount: u64))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>], data = (index: u64, receipt_bump: u8))]
+pub struct ClaimRewardAccounts<AI> {
+    /// The distribution to claim from.
+    #[validate(writable)]
+    pub distribution: Box<DataAccount<AI, TutorialAccounts, Distribution>>,
+    /// Who the claim pays. Must already hold the rent exempt minimum if the claim is smaller than it.
+    #[validate(writable)]
+    pub claimant: AI,
+    /// The claim's receipt.
+    #[from(data = ClaimReceipt::new(distribution.info().key(), index, claimant.key(), amount))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            ClaimReceiptSeeder::new(*self.distribution.info().key(), index),
+            receipt_bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub claim_receipt: Box<InitAccount<AI, TutorialAccounts, ClaimReceipt>>,
+    /// The funder for the receipt's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`ClaimReward`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ClaimRewardData {
+    /// The claim's index in the tree.
+    pub index: u64,
+    /// The lamports claimed.
+    pub amount: u64,
+    /// The proof of the claim's leaf.
+    pub proof: Vec<[u8; 32]>,
+    /// The bump of the receipt's PDA, must be the canonical bump.
+    pub receipt_bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::error::custom_error;
+    use crate::event::ProgramEvent;
+    use crate::log::log_event;
+    use crate::merkle::{leaf, verify};
+    use crate::pda::check_canonical_bump;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, ClaimReward> for ClaimReward
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = (u64, u64);
+        type ValidateData = (u64, u8);
+        type InstructionData = ClaimRewardData;
+
+        fn data_to_instruction_arg(
+            data: <ClaimReward as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((
+                (data.index, data.amount),
+                (data.index, data.receipt_bump),
+                data,
+            ))
+        }
+
+        fn process(
+            program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <ClaimReward as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ClaimReward as Instruction<AI>>::ReturnType> {
+            // Any other bump would be a second receipt for the same claim
+            check_canonical_bump(
+                &ClaimReceiptSeeder::new(*accounts.distribution.info().key(), data.index),
+                data.receipt_bump,
+                program_id,
+            )?;
+            if !verify(
+                &data.proof,
+                &accounts.distribution.root,
+                leaf(data.index, accounts.claimant.key(), data.amount),
+            ) {
+                return Err(custom_error!("proof doesn't match the distribution's root").into());
+            }
+            accounts.distribution.claim(data.index, data.amount)?;
+
+            log_event!(
+                info,
+                "reward_claimed",
+                index = data.index,
+                amount = data.amount
+            );
+            *accounts.distribution.info().lamports_mut() -= data.amount;
+            *accounts.claimant.lamports_mut() += data.amount;
+            accounts.claim_receipt.claimed_at = Clock::get()?.unix_timestamp;
+            ProgramEvent::RewardClaimed {
+                distribution: *accounts.distribution.info().key(),
+                claimant: *accounts.claimant.key(),
+                index: data.index,
+                amount: data.amount,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ClaimReward`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Claims from a distribution.
+    #[derive(Debug)]
+    pub struct ClaimRewardCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ClaimRewardCPI<'a, AI> {
+        /// Claims from a distribution.
+        pub fn new(
+            distribution: impl Into<MaybeOwned<'a, AI>>,
+            claimant: impl Into<MaybeOwned<'a, AI>>,
+            claim_receipt: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            claim_data: &ClaimRewardData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ClaimReward>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            claim_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    distribution.into(),
+                    claimant.into(),
+                    claim_receipt.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for ClaimRewardCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ClaimReward;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [distribution, claimant, claim_receipt, funder, system_program] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    distribution,
+                    claimant,
+                    claim_receipt,
+                    funder,
+                    system_program,
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ClaimReward`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_claim_receipt, HashedSigner, InstructionSet};
+    use crate::merkle::MerkleTree;
+
+    /// Claims `amount` for `claimant`, the claim at `index` of `tree` which `distribution` was created with.
+    /// [`None`] if `tree` has no claim at `index`.
+    pub fn claim_reward<'a>(
+        program_id: Pubkey,
+        distribution: Pubkey,
+        tree: &MerkleTree,
+        index: u64,
+        claimant: Pubkey,
+        amount: u64,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> Option<InstructionSet<'a>> {
+        let funder = funder.into();
+        let proof = tree.proof(usize::try_from(index).ok()?)?;
+        let (claim_receipt, receipt_bump) = derive_claim_receipt(&program_id, distribution, index);
+        Some(InstructionSet {
+            instructions: vec![
+                ClaimRewardCPI::new(
+                    SolanaAccountMeta::new(distribution, false),
+                    SolanaAccountMeta::new(claimant, false),
+                    SolanaAccountMeta::new(claim_receipt, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &ClaimRewardData {
+                        index,
+                        amount,
+                        proof,
+                        receipt_bump,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [funder].into_iter().collect(),
+        })
+    }
+}
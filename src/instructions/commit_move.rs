@@ -0,0 +1,194 @@
+use crate::accounts::Player;
+use crate::log::log_event;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Commits to a move in a [`RuleSet::commit_reveal`](crate::accounts::RuleSet::commit_reveal) game, replacing any earlier commitment.
+/// The move is played with [`RevealMove`](crate::instructions::RevealMove),
+/// which has to land before the turn runs out or the player can be forfeited like any other.
+#[derive(Debug)]
+pub enum CommitMove {}
+
+impl<AI> Instruction<AI> for CommitMove {
+    type Accounts = CommitMoveAccounts<AI>;
+    type Data = CommitMoveData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CommitMove`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct CommitMoveAccounts<AI> {
+    /// The authority for the player.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The player to move.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to commit the move in.
+    #[validate(
+        writable,
+        custom = self.game.is_started(),
+        custom = self.game.rules.commit_reveal,
+        custom = match self.game.next_play {
+            Player::One => &self.game.player1 == self.player_profile.info().key(),
+            Player::Two => &self.game.player2 == self.player_profile.info().key(),
+        },
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+}
+
+/// Data for [`CommitMove`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CommitMoveData {
+    /// The move's hash, see [`Game::hash_move`].
+    pub commitment: [u8; 32],
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, CommitMove> for CommitMove
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = CommitMoveData;
+
+        fn data_to_instruction_arg(
+            data: <CommitMove as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <CommitMove as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CommitMove as Instruction<AI>>::ReturnType> {
+            accounts.game.move_commitment = data.commitment;
+            log_event!(info, "move_committed");
+            ProgramEvent::MoveCommitted {
+                game: *accounts.game.info().key(),
+                profile: *accounts.player_profile.info().key(),
+                commitment: data.commitment,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CommitMove`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Commits to a move.
+    #[derive(Debug)]
+    pub struct CommitMoveCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CommitMoveCPI<'a, AI> {
+        /// Commits to a move.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            commitment: [u8; 32],
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CommitMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            CommitMoveData { commitment }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for CommitMoveCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CommitMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CommitMove`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Commits to `commitment` in `game` for `player_profile`.
+    /// Make it with [`Game::hash_move`] and keep the salt secret until [`reveal_move`](crate::instructions::reveal_move).
+    pub fn commit_move<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        commitment: [u8; 32],
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                CommitMoveCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    commitment,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::accounts::{Player, ProgramConfig};
+use crate::clockwork::{SerializableAccount, SerializableInstruction};
+use crate::instructions::EloChange;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts, TutorialInstructions};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Forfeits the player whose turn expired without needing to know who won.
+///
+/// Unlike [`ForfeitGame`](crate::instructions::ForfeitGame) the accounts are the same no matter who is winning,
+/// so an automation thread registered with [`RegisterForfeitThread`](crate::instructions::RegisterForfeitThread)
+/// can keep calling it with one fixed instruction.
+/// The winnings go to the winner's profile account, to be taken out with
+/// [`WithdrawProfileBalance`](crate::instructions::WithdrawProfileBalance).
+/// The caller is paid [`ProgramConfig::keeper_tip`] unless it is the winner's authority.
+/// The game is left for [`ArchiveGame`](crate::instructions::ArchiveGame) like one that was played out.
+/// Returns the [`EloChange`] with the winner as the player.
+#[derive(Debug)]
+pub enum CrankForfeit {}
+
+impl<AI> Instruction<AI> for CrankForfeit {
+    type Accounts = CrankForfeitAccounts<AI>;
+    type Data = CrankForfeitData;
+    type ReturnType = EloChange;
+}
+
+impl CrankForfeit {
+    /// The instruction an automation thread should run for `game`.
+    /// `keeper` is the thread, which signs and receives the keeper tip.
+    pub fn thread_instruction(
+        program_id: Pubkey,
+        keeper: Pubkey,
+        game: Pubkey,
+        game_signer: Pubkey,
+        player1_profile: Pubkey,
+        player2_profile: Pubkey,
+        config: Pubkey,
+    ) -> CruiserResult<SerializableInstruction> {
+        let mut data = Vec::new();
+        <TutorialInstructions as InstructionListItem<CrankForfeit>>::discriminant_compressed()
+            .serialize(&mut data)?;
+        CrankForfeitData {}.serialize(&mut data)?;
+        let account = |pubkey, is_signer, is_writable| SerializableAccount {
+            pubkey,
+            is_signer,
+            is_writable,
+        };
+        Ok(SerializableInstruction {
+            program_id,
+            accounts: vec![
+                account(keeper, true, true),
+                account(game, false, true),
+                account(game_signer, false, true),
+                account(player1_profile, false, true),
+                account(player2_profile, false, true),
+                account(config, false, false),
+                account(SystemProgram::<()>::KEY, false, false),
+            ],
+            data,
+        })
+    }
+}
+
+/// Accounts for [`CrankForfeit`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct CrankForfeitAccounts<AI> {
+    /// Whoever is calling the forfeit, receives the keeper tip if not the winner's authority.
+    #[validate(signer, writable)]
+    pub keeper: AI,
+    /// The game whose turn expired.
+    #[validate(writable, custom = self.game.is_turn_expired(Clock::get()?.unix_timestamp))]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The first player's profile.
+    #[validate(writable, custom = self.player1_profile.info().key() == &self.game.player1)]
+    pub player1_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The second player's profile.
+    #[validate(writable, custom = self.player2_profile.info().key() == &self.game.player2)]
+    pub player2_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The program config, for the keeper tip.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+    /// The game's [`settlement_hook`](crate::settlement_hook) program followed by the accounts it needs.
+    /// Only needed if the game has a hook.
+    pub settlement_hook: Rest<AI>,
+}
+
+/// Data for [`CrankForfeit`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CrankForfeitData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::update_elo;
+    use crate::accounts::GameResult;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+    use crate::instructions::set_return;
+    use crate::settlement_hook::{notify_settlement, GameSettled};
+
+    impl<'a, AI> InstructionProcessor<AI, CrankForfeit> for CrankForfeit
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <CrankForfeit as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <CrankForfeit as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CrankForfeit as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+
+            // The player to move is the one who ran out of time.
+            let forfeited_stake = accounts.game.stake(accounts.game.next_play);
+            let (winner, loser) = match accounts.game.next_play {
+                Player::One => (&mut accounts.player2_profile, &mut accounts.player1_profile),
+                Player::Two => (&mut accounts.player1_profile, &mut accounts.player2_profile),
+            };
+            // The vault's and the game's rent go back to the creator's profile.
+            let creator_won = accounts.game.creator == accounts.game.next_play.other();
+
+            let (pot, transfer_amount) = if accounts.game.friendly {
+                (0, 0)
+            } else {
+                log_event!(debug, "transferring");
+                let pot = WagerVault::escrow(accounts.game_signer.info())?;
+                let keeper_tip = if accounts.keeper.key() == &winner.authority {
+                    0
+                } else {
+                    accounts.config.keeper_tip(pot)
+                };
+                if keeper_tip > 0 {
+                    WagerVault::pay_out(accounts.game_signer.info(), &accounts.keeper, keeper_tip)?;
+                }
+                let transfer_amount = pot - keeper_tip;
+                WagerVault::pay_out(accounts.game_signer.info(), winner.info(), transfer_amount)?;
+                let creator_profile = if creator_won {
+                    winner.info()
+                } else {
+                    loser.info()
+                };
+                WagerVault::close(accounts.game_signer.info(), creator_profile)?;
+                (pot, transfer_amount)
+            };
+
+            log_event!(debug, "setting_fields");
+            let result = GameResult::Won(accounts.game.next_play.other());
+            let game_key = *accounts.game.info().key();
+            let settled = GameSettled {
+                game: game_key,
+                signer_bump: accounts.game.signer_bump,
+                player1: accounts.game.player1,
+                player2: accounts.game.player2,
+                result,
+                forfeited: true,
+                pot,
+                ended_at: now,
+            };
+            // Same as `ForfeitGame`, leave the game for `ArchiveGame`.
+            let creator_profile = *if creator_won {
+                winner.info().key()
+            } else {
+                loser.info().key()
+            };
+            let archived = accounts
+                .game
+                .finish(&game_key, &creator_profile, result, now)?;
+            log_event!(
+                info,
+                "game_finished",
+                game = game_key,
+                leaf = archived.leaf()?
+            );
+
+            winner.lamports_won.saturating_add_assign(transfer_amount);
+            winner.wins.saturating_add_assign(1);
+            loser.lamports_lost.saturating_add_assign(forfeited_stake);
+            loser.losses.saturating_add_assign(1);
+            // Neither player is necessarily around, so only the game end is recorded.
+            winner.leave_game(now);
+            loser.leave_game(now);
+
+            let elo_before = (winner.elo, loser.elo);
+            update_elo(
+                &mut winner.elo,
+                &mut loser.elo,
+                PlayerProfile::FORFEIT_ELO_K,
+                true,
+            );
+
+            ProgramEvent::GameEnded(settled.clone()).emit()?;
+            notify_settlement(
+                &accounts.game,
+                accounts.game_signer.info(),
+                accounts.settlement_hook.iter(),
+                &settled,
+            )?;
+
+            set_return(EloChange::new(
+                elo_before.0,
+                winner.elo,
+                elo_before.1,
+                loser.elo,
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CrankForfeit`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+
+    /// Forfeits the player whose turn expired.
+    #[derive(Debug)]
+    pub struct CrankForfeitCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 7],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CrankForfeitCPI<'a, AI> {
+        /// Forfeits the player whose turn expired.
+        pub fn new(
+            keeper: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            player1_profile: impl Into<MaybeOwned<'a, AI>>,
+            player2_profile: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CrankForfeit>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            CrankForfeitData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    keeper.into(),
+                    game.into(),
+                    game_signer.into(),
+                    player1_profile.into(),
+                    player2_profile.into(),
+                    config.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 8> for CrankForfeitCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CrankForfeit;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 8]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CrankForfeit`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
+
+    /// Forfeits the player whose turn expired, paying `keeper` the keeper tip.
+    /// Only succeeds once [`Game::is_turn_expired`], so check that before sending.
+    pub fn crank_forfeit<'a>(
+        program_id: Pubkey,
+        keeper: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        game_signer_bump: u8,
+        player1_profile: Pubkey,
+        player2_profile: Pubkey,
+    ) -> InstructionSet<'a> {
+        let keeper = keeper.into();
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                CrankForfeitCPI::new(
+                    SolanaAccountMeta::new(keeper.pubkey(), true),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new(player1_profile, false),
+                    SolanaAccountMeta::new(player2_profile, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [keeper].into_iter().collect(),
+        }
+    }
+}
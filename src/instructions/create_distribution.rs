@@ -0,0 +1,232 @@
+use crate::accounts::Distribution;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Creates a [`Distribution`] paying `total` lamports out to the claims under a Merkle root,
+/// claimed one at a time with [`ClaimReward`](crate::instructions::ClaimReward).
+/// The funder puts the whole pool in up front.
+///
+/// Build the tree with [`MerkleTree`](crate::merkle::MerkleTree), `total` should be its [`total`](crate::merkle::MerkleTree::total).
+#[derive(Debug)]
+pub enum CreateDistribution {}
+
+impl<AI> Instruction<AI> for CreateDistribution {
+    type Accounts = CreateDistributionAccounts<AI>;
+    type Data = CreateDistributionData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CreateDistribution`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(
+    data = (create_data: CreateDistributionData),
+    custom = create_data.total > 0,
+    custom = create_data.claims > 0,
+)]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct CreateDistributionAccounts<AI> {
+    /// The creator of the distribution.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The distribution to create, a new keypair.
+    #[from(data = Distribution::new(authority.key(), create_data.root, create_data.total, create_data.claims))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: None,
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub distribution: Box<InitAccount<AI, TutorialAccounts, Distribution>>,
+    /// The funder of the pool and the distribution's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`CreateDistribution`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CreateDistributionData {
+    /// The root of the tree of claims.
+    pub root: [u8; 32],
+    /// The lamports to put into the pool, the sum of all the claims.
+    pub total: u64,
+    /// The number of claims in the tree.
+    pub claims: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use crate::log::log_event;
+    use cruiser::solana_program::clock::Clock;
+    use std::iter::empty;
+
+    impl<'a, AI> InstructionProcessor<AI, CreateDistribution> for CreateDistribution
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = CreateDistributionData;
+        type ValidateData = ();
+        type InstructionData = CreateDistributionData;
+
+        fn data_to_instruction_arg(
+            data: <CreateDistribution as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <CreateDistribution as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CreateDistribution as Instruction<AI>>::ReturnType> {
+            accounts.distribution.created_at = Clock::get()?.unix_timestamp;
+
+            log_event!(info, "distribution_created", total = data.total);
+            accounts.system_program.transfer(
+                CPIChecked,
+                &accounts.funder,
+                accounts.distribution.info(),
+                data.total,
+                empty(),
+            )?;
+            ProgramEvent::DistributionCreated {
+                distribution: *accounts.distribution.info().key(),
+                total: data.total,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CreateDistribution`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates a distribution.
+    #[derive(Debug)]
+    pub struct CreateDistributionCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 4],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreateDistributionCPI<'a, AI> {
+        /// Creates a distribution.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            distribution: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            create_data: &CreateDistributionData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreateDistribution>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            create_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    distribution.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 5> for CreateDistributionCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreateDistribution;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [authority, distribution, funder, system_program] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    authority,
+                    distribution,
+                    funder,
+                    system_program,
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CreateDistribution`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+    use crate::merkle::MerkleTree;
+
+    /// Creates `distribution` paying out the claims in `tree`, funded with their total by `funder`.
+    pub fn create_distribution<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        distribution: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        tree: &MerkleTree,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let distribution = distribution.into();
+        let funder = funder.into();
+        InstructionSet {
+            instructions: vec![
+                CreateDistributionCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(distribution.pubkey(), true),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &CreateDistributionData {
+                        root: tree.root(),
+                        total: tree.total(),
+                        claims: tree.len() as u64,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, distribution, funder].into_iter().collect(),
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use crate::accounts::Player;
-use crate::pda::GameSignerSeeder;
+use crate::accounts::{GameVariant, Player, ProgramConfig, RuleSet, WagerVault};
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::pda::{GameSignerSeeder, KnownPda};
 use crate::{Game, PlayerProfile, TutorialAccounts};
 use cruiser::prelude::*;
 
@@ -18,24 +20,34 @@ impl<AI> Instruction<AI> for CreateGame {
 #[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
 #[from(
     data = (create_data: CreateGameData),
-    custom = create_data.wager.checked_mul(2).is_some(),
-    custom = create_data.turn_length > 0,
+    custom = create_data.wager.checked_add(create_data.joiner_wager).is_some(),
+    custom = create_data.turn_length >= 0,
+    custom = !create_data.rules.commit_reveal || create_data.turn_length > 0,
+    custom = create_data.join_ttl >= 0,
+    custom = create_data.lock_ttl >= 0,
 )]
 #[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
 pub struct CreateGameAccounts<AI> {
     /// The authority for the creator's profile.
+    /// A program's PDA signs by calling this with `invoke_signed`, see [`sign_as_authority`](crate::instructions::sign_as_authority).
     #[validate(signer)]
     pub authority: AI,
     /// The creator's profile.
-    #[validate(custom = &self.player_profile.authority == self.authority.key())]
-    pub player_profile: ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>,
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The program config, for the limits on new games.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
     /// The game to be created.
     #[from(data = Game::new(
         player_profile.info().key(),
         create_data.creator_player,
         create_data.signer_bump,
         create_data.wager,
+        create_data.joiner_wager,
         create_data.turn_length,
+        create_data.join_ttl,
+        create_data.variant,
+        create_data.rules,
     ))]
     #[validate(data = InitArgs{
         system_program: Some(&self.system_program),
@@ -47,10 +59,12 @@ pub struct CreateGameAccounts<AI> {
         cpi: CPIChecked,
     })]
     pub game: Box<InitOrZeroedAccount<AI, TutorialAccounts, Game>>,
-    /// The game signer that will hold the wager.
+    /// The game signer, created as the [`WagerVault`] that will hold the wagers.
+    /// Left empty for friendly games.
     #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
     pub game_signer: Seeds<AI, GameSignerSeeder>,
-    /// The funder that will put the creator's wager into the game.
+    /// The funder that will put the creator's wager into the game and pay the wager vault's rent.
+    /// Unused for friendly games.
     #[validate(signer, writable)]
     pub wager_funder: AI,
     /// The system program for transferring the wager and initializing the game if needed.
@@ -59,8 +73,16 @@ pub struct CreateGameAccounts<AI> {
     #[from(data = game.is_init())]
     #[validate(signer(IfSome), writable(IfSome))]
     pub funder: Option<AI>,
+    /// The creator's [`PlayerProfile::co_signer`].
+    /// Only needed if they have one and `wager` is above their [`PlayerProfile::co_sign_threshold`].
+    #[from(data = player_profile.needs_co_signer(create_data.wager))]
+    #[validate(
+        signer(IfSome),
+        custom = self.co_signer.as_ref().map_or(true, |co_signer| co_signer.key() == &self.player_profile.co_signer),
+    )]
+    pub co_signer: Option<AI>,
     /// If [`Some`] locks other player to a given profile.
-    pub other_player_profile: Option<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    pub other_player_profile: Option<Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>>,
 }
 
 /// Data for [`CreateGame`]
@@ -68,18 +90,42 @@ pub struct CreateGameAccounts<AI> {
 pub struct CreateGameData {
     /// Which position the creator wants to play in.
     pub creator_player: Player,
-    /// The bump for the game signer.
+    /// The bump for the game signer, must be the canonical bump.
     pub signer_bump: u8,
-    /// The wager each player will place. Winner gets double this amount.
+    /// The wager the creator will place.
     pub wager: u64,
+    /// The wager the joiner will place. Set it different from `wager` to give the game odds.
+    /// Winner gets both wagers.
+    pub joiner_wager: u64,
     /// The length of time each player gets to play their turn. Starts once other player joins.
+    /// 0 makes the game untimed.
     pub turn_length: UnixTimestamp,
+    /// How long the game waits for a joiner before anyone can sweep it with
+    /// [`SweepStaleGame`](crate::instructions::SweepStaleGame). 0 waits forever.
+    pub join_ttl: UnixTimestamp,
+    /// The board to play on.
+    pub variant: GameVariant,
+    /// How closed boards and full boards are handled.
+    pub rules: RuleSet,
+    /// Randomizes who moves first when the opponent joins instead of always [`Player::One`].
+    pub random_first_player: bool,
+    /// Makes the game private, see [`Game::join_code_hash`]. All zeros for a public game.
+    pub join_code_hash: [u8; 32],
+    /// The profiles that can join, see [`Game::invited`].
+    pub invited: [Pubkey; Game::MAX_INVITED],
+    /// Plays the game for nothing. Both wagers must be 0, which is rejected otherwise.
+    pub friendly: bool,
+    /// How long a locked other player has to join before the creator can
+    /// [`UnlockGame`](crate::instructions::UnlockGame). 0 waits forever, ignored unless the other player is locked.
+    pub lock_ttl: UnixTimestamp,
 }
 
 #[cfg(feature = "processor")]
 mod processor {
     use super::*;
-    use std::iter::empty;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+    use std::iter::{empty, once};
 
     impl<'a, AI> InstructionProcessor<AI, CreateGame> for CreateGame
     where
@@ -100,32 +146,121 @@ mod processor {
         }
 
         fn process(
-            _program_id: &Pubkey,
+            program_id: &Pubkey,
             data: Self::InstructionData,
             accounts: &mut <CreateGame as Instruction<AI>>::Accounts,
         ) -> CruiserResult<<CreateGame as Instruction<AI>>::ReturnType> {
-            msg!("Transferring wager");
-
-            // Transfer the wager from the wager_funder to the game signer.
-            accounts.system_program.transfer(
-                CPIChecked,
-                &accounts.wager_funder,
-                accounts.game_signer.info(),
-                data.wager,
-                empty(),
+            let game_signer = KnownPda::with_bump(
+                GameSignerSeeder {
+                    game: *accounts.game.info().key(),
+                },
+                data.signer_bump,
+                program_id,
             )?;
+            game_signer.verify(program_id)?;
+            let now = Clock::get()?.unix_timestamp;
+            accounts
+                .config
+                .check_game_cooldown(&accounts.player_profile, now)?;
 
-            msg!("Setting other player");
+            if data.friendly {
+                if data.wager != 0 || data.joiner_wager != 0 {
+                    return Err(custom_error!("Friendly games can't have wagers").into());
+                }
+                accounts.config.check_new_friendly_game(data.turn_length)?;
+            } else {
+                if data.wager == 0 {
+                    return Err(custom_error!(
+                        "Wager is 0, create a friendly game to play without one"
+                    )
+                    .into());
+                }
+                accounts
+                    .config
+                    .check_new_game(data.wager, data.joiner_wager, data.turn_length)?;
+                accounts
+                    .config
+                    .check_ranked_profile(&accounts.player_profile, now)?;
+            }
+
+            if data.friendly {
+                accounts.game.friendly = true;
+            } else {
+                log_event!(debug, "transferring_wager");
+
+                // Create the vault at the game signer and transfer the wager from the wager_funder into it.
+                let mut vault = InitAccount::<AI, TutorialAccounts, WagerVault>::from_accounts(
+                    program_id,
+                    &mut once(accounts.game_signer.info().clone()),
+                    WagerVault::new(accounts.game.info().key()),
+                )?;
+                vault.validate(
+                    program_id,
+                    InitArgs {
+                        system_program: &accounts.system_program,
+                        space: InitStaticSized,
+                        funder: &accounts.wager_funder,
+                        funder_seeds: None,
+                        account_seeds: Some(&game_signer.signer_seeds()),
+                        rent: None,
+                        cpi: CPIChecked,
+                    },
+                )?;
+                accounts.system_program.transfer(
+                    CPIChecked,
+                    &accounts.wager_funder,
+                    vault.info(),
+                    data.wager,
+                    empty(),
+                )?;
+                vault.deposit(data.wager);
+                vault.write_back(program_id)?;
+                accounts
+                    .player_profile
+                    .lamports_wagered
+                    .saturating_add_assign(data.wager);
+            }
+
+            log_event!(debug, "setting_other_player");
 
             // Set the other player's profile if locked game.
             if let Some(other_player_profile) = &accounts.other_player_profile {
                 *match data.creator_player {
                     Player::One => &mut accounts.game.player2,
                     Player::Two => &mut accounts.game.player1,
-                } = *other_player_profile.info().key()
+                } = *other_player_profile.info().key();
+                if data.lock_ttl > 0 {
+                    accounts.game.lock_expires_at = now.saturating_add(data.lock_ttl);
+                }
             }
 
-            msg!("Created game");
+            accounts.game.random_first_player = data.random_first_player;
+            accounts.game.join_code_hash = data.join_code_hash;
+            accounts.game.invited = data.invited;
+
+            accounts.game.rejects_deprecated = accounts.config.reject_deprecated_instructions;
+            accounts.game.settlement_hook = accounts.config.settlement_hook;
+            accounts.game.created_at = now;
+            accounts.player_profile.last_game_created = now;
+            accounts.player_profile.mark_game_joined(now);
+
+            log_event!(info, "game_created");
+            ProgramEvent::GameCreated {
+                game: *accounts.game.info().key(),
+                profile: *accounts.player_profile.info().key(),
+                creator_player: data.creator_player,
+                variant: data.variant,
+                wager: data.wager,
+                joiner_wager: data.joiner_wager,
+                friendly: data.friendly,
+                other_player: accounts
+                    .other_player_profile
+                    .as_ref()
+                    .map_or(Pubkey::new_from_array([0; 32]), |profile| {
+                        *profile.info().key()
+                    }),
+            }
+            .emit()?;
             Ok(())
         }
     }
@@ -137,6 +272,7 @@ pub use cpi::*;
 #[cfg(feature = "cpi")]
 mod cpi {
     use super::*;
+    use crate::instructions::required;
     use crate::TutorialInstructions;
 
     /// Creates a new game.
@@ -145,130 +281,192 @@ mod cpi {
         accounts: [MaybeOwned<'a, AI>; N],
         data: Vec<u8>,
     }
-    impl<'a, AI> CreateGameCPI<'a, AI, 6> {
-        /// Creates a new game from a zeroed account.
-        pub fn new_zeroed(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
-            game_signer: impl Into<MaybeOwned<'a, AI>>,
-            wager_funder: impl Into<MaybeOwned<'a, AI>>,
-            system_program: impl Into<MaybeOwned<'a, AI>>,
-            create_game_data: &CreateGameData,
-        ) -> CruiserResult<Self> {
-            let mut data = Vec::new();
-            <TutorialInstructions as InstructionListItem<CreateGame>>::discriminant_compressed()
-                .serialize(&mut data)?;
-            create_game_data.serialize(&mut data)?;
-            Ok(Self {
-                accounts: [
-                    authority.into(),
-                    player_profile.into(),
-                    game.into(),
-                    game_signer.into(),
-                    wager_funder.into(),
-                    system_program.into(),
-                ],
-                data,
-            })
+    impl<'a, AI> CreateGameCPI<'a, AI, 7> {
+        /// Starts building a [`CreateGameCPI`].
+        /// The optional accounts that are set decide how many accounts it takes.
+        /// Use [`CreateGameCPIDynamic`] if the creator needs their co-signer.
+        pub fn builder() -> CreateGameCPIBuilder<'a, AI, (), ()> {
+            CreateGameCPIBuilder {
+                authority: None,
+                player_profile: None,
+                config: None,
+                game: None,
+                game_signer: None,
+                wager_funder: None,
+                system_program: None,
+                data: None,
+                funder: (),
+                locked_opponent: (),
+            }
         }
     }
-    impl<'a, AI> CreateGameCPI<'a, AI, 7> {
-        /// Creates a new game
-        #[allow(clippy::too_many_arguments)]
-        pub fn new(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
-            game_signer: impl Into<MaybeOwned<'a, AI>>,
-            wager_funder: impl Into<MaybeOwned<'a, AI>>,
-            system_program: impl Into<MaybeOwned<'a, AI>>,
+
+    /// Builder for [`CreateGameCPI`].
+    /// `F` and `O` are `()` until [`funder`](Self::funder) or [`locked_opponent`](Self::locked_opponent) is set.
+    #[derive(Debug)]
+    pub struct CreateGameCPIBuilder<'a, AI, F, O> {
+        authority: Option<MaybeOwned<'a, AI>>,
+        player_profile: Option<MaybeOwned<'a, AI>>,
+        config: Option<MaybeOwned<'a, AI>>,
+        game: Option<MaybeOwned<'a, AI>>,
+        game_signer: Option<MaybeOwned<'a, AI>>,
+        wager_funder: Option<MaybeOwned<'a, AI>>,
+        system_program: Option<MaybeOwned<'a, AI>>,
+        data: Option<CreateGameData>,
+        funder: F,
+        locked_opponent: O,
+    }
+    impl<'a, AI, F, O> CreateGameCPIBuilder<'a, AI, F, O> {
+        /// Sets the authority for the creator's profile.
+        pub fn authority(mut self, authority: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.authority = Some(authority.into());
+            self
+        }
+
+        /// Sets the creator's profile.
+        pub fn player_profile(mut self, player_profile: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.player_profile = Some(player_profile.into());
+            self
+        }
+
+        /// Sets the program config.
+        pub fn config(mut self, config: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.config = Some(config.into());
+            self
+        }
+
+        /// Sets the game to be created.
+        pub fn game(mut self, game: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.game = Some(game.into());
+            self
+        }
+
+        /// Sets the game signer that will hold the wager.
+        pub fn game_signer(mut self, game_signer: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.game_signer = Some(game_signer.into());
+            self
+        }
+
+        /// Sets the funder that will put the creator's wager into the game.
+        pub fn wager_funder(mut self, wager_funder: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.wager_funder = Some(wager_funder.into());
+            self
+        }
+
+        /// Sets the system program.
+        pub fn system_program(mut self, system_program: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.system_program = Some(system_program.into());
+            self
+        }
+
+        /// Sets the instruction data.
+        pub fn data(mut self, data: CreateGameData) -> Self {
+            self.data = Some(data);
+            self
+        }
+
+        /// Sets the funder for the game's rent. Leave unset if the game is zeroed.
+        pub fn funder(
+            self,
             funder: impl Into<MaybeOwned<'a, AI>>,
-            create_game_data: &CreateGameData,
-        ) -> CruiserResult<Self> {
-            let mut data = Vec::new();
-            <TutorialInstructions as InstructionListItem<CreateGame>>::discriminant_compressed()
-                .serialize(&mut data)?;
-            create_game_data.serialize(&mut data)?;
-            Ok(Self {
-                accounts: [
-                    authority.into(),
-                    player_profile.into(),
-                    game.into(),
-                    game_signer.into(),
-                    wager_funder.into(),
-                    system_program.into(),
-                    funder.into(),
-                ],
-                data,
-            })
+        ) -> CreateGameCPIBuilder<'a, AI, MaybeOwned<'a, AI>, O> {
+            CreateGameCPIBuilder {
+                authority: self.authority,
+                player_profile: self.player_profile,
+                config: self.config,
+                game: self.game,
+                game_signer: self.game_signer,
+                wager_funder: self.wager_funder,
+                system_program: self.system_program,
+                data: self.data,
+                funder: funder.into(),
+                locked_opponent: self.locked_opponent,
+            }
         }
 
-        /// Creates a new game from a zeroed account and locked other player.
-        #[allow(clippy::too_many_arguments)]
-        pub fn new_zeroed_with_locked_player(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
-            game_signer: impl Into<MaybeOwned<'a, AI>>,
-            wager_funder: impl Into<MaybeOwned<'a, AI>>,
-            system_program: impl Into<MaybeOwned<'a, AI>>,
+        /// Locks the other player to a given profile.
+        pub fn locked_opponent(
+            self,
             other_player_profile: impl Into<MaybeOwned<'a, AI>>,
-            create_game_data: &CreateGameData,
-        ) -> CruiserResult<Self> {
+        ) -> CreateGameCPIBuilder<'a, AI, F, MaybeOwned<'a, AI>> {
+            CreateGameCPIBuilder {
+                authority: self.authority,
+                player_profile: self.player_profile,
+                config: self.config,
+                game: self.game,
+                game_signer: self.game_signer,
+                wager_funder: self.wager_funder,
+                system_program: self.system_program,
+                data: self.data,
+                funder: self.funder,
+                locked_opponent: other_player_profile.into(),
+            }
+        }
+
+        /// Splits into the required accounts, the serialized data, and the optional accounts.
+        #[allow(clippy::type_complexity)]
+        fn into_parts(self) -> CruiserResult<([MaybeOwned<'a, AI>; 7], Vec<u8>, F, O)> {
             let mut data = Vec::new();
             <TutorialInstructions as InstructionListItem<CreateGame>>::discriminant_compressed()
                 .serialize(&mut data)?;
-            create_game_data.serialize(&mut data)?;
-            Ok(Self {
-                accounts: [
-                    authority.into(),
-                    player_profile.into(),
-                    game.into(),
-                    game_signer.into(),
-                    wager_funder.into(),
-                    system_program.into(),
-                    other_player_profile.into(),
+            required(self.data, "data")?.serialize(&mut data)?;
+            Ok((
+                [
+                    required(self.authority, "authority")?,
+                    required(self.player_profile, "player_profile")?,
+                    required(self.config, "config")?,
+                    required(self.game, "game")?,
+                    required(self.game_signer, "game_signer")?,
+                    required(self.wager_funder, "wager_funder")?,
+                    required(self.system_program, "system_program")?,
                 ],
                 data,
+                self.funder,
+                self.locked_opponent,
+            ))
+        }
+    }
+    impl<'a, AI> CreateGameCPIBuilder<'a, AI, (), ()> {
+        /// Builds a CPI creating a game from a zeroed account.
+        pub fn build(self) -> CruiserResult<CreateGameCPI<'a, AI, 7>> {
+            let (accounts, data, (), ()) = self.into_parts()?;
+            Ok(CreateGameCPI { accounts, data })
+        }
+    }
+    impl<'a, AI> CreateGameCPIBuilder<'a, AI, MaybeOwned<'a, AI>, ()> {
+        /// Builds a CPI creating a game.
+        pub fn build(self) -> CruiserResult<CreateGameCPI<'a, AI, 8>> {
+            let ([a0, a1, a2, a3, a4, a5, a6], data, funder, ()) = self.into_parts()?;
+            Ok(CreateGameCPI {
+                accounts: [a0, a1, a2, a3, a4, a5, a6, funder],
+                data,
             })
         }
     }
-    impl<'a, AI> CreateGameCPI<'a, AI, 8> {
-        /// Creates a new game with a locked other player.
-        #[allow(clippy::too_many_arguments)]
-        pub fn new_with_locked_player(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
-            game_signer: impl Into<MaybeOwned<'a, AI>>,
-            wager_funder: impl Into<MaybeOwned<'a, AI>>,
-            system_program: impl Into<MaybeOwned<'a, AI>>,
-            funder: impl Into<MaybeOwned<'a, AI>>,
-            other_player_profile: impl Into<MaybeOwned<'a, AI>>,
-            create_game_data: &CreateGameData,
-        ) -> CruiserResult<Self> {
-            let mut data = Vec::new();
-            <TutorialInstructions as InstructionListItem<CreateGame>>::discriminant_compressed()
-                .serialize(&mut data)?;
-            create_game_data.serialize(&mut data)?;
-            Ok(Self {
-                accounts: [
-                    authority.into(),
-                    player_profile.into(),
-                    game.into(),
-                    game_signer.into(),
-                    wager_funder.into(),
-                    system_program.into(),
-                    funder.into(),
-                    other_player_profile.into(),
-                ],
+    impl<'a, AI> CreateGameCPIBuilder<'a, AI, (), MaybeOwned<'a, AI>> {
+        /// Builds a CPI creating a game from a zeroed account with a locked other player.
+        pub fn build(self) -> CruiserResult<CreateGameCPI<'a, AI, 8>> {
+            let ([a0, a1, a2, a3, a4, a5, a6], data, (), other_player_profile) =
+                self.into_parts()?;
+            Ok(CreateGameCPI {
+                accounts: [a0, a1, a2, a3, a4, a5, a6, other_player_profile],
+                data,
+            })
+        }
+    }
+    impl<'a, AI> CreateGameCPIBuilder<'a, AI, MaybeOwned<'a, AI>, MaybeOwned<'a, AI>> {
+        /// Builds a CPI creating a game with a locked other player.
+        pub fn build(self) -> CruiserResult<CreateGameCPI<'a, AI, 9>> {
+            let ([a0, a1, a2, a3, a4, a5, a6], data, funder, other_player_profile) =
+                self.into_parts()?;
+            Ok(CreateGameCPI {
+                accounts: [a0, a1, a2, a3, a4, a5, a6, funder, other_player_profile],
                 data,
             })
         }
     }
 
-    impl<'a, AI> CPIClientStatic<'a, 7> for CreateGameCPI<'a, AI, 6>
+    impl<'a, AI> CPIClientStatic<'a, 8> for CreateGameCPI<'a, AI, 7>
     where
         AI: ToSolanaAccountMeta,
     {
@@ -279,7 +477,7 @@ mod cpi {
         fn instruction(
             self,
             program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
-        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 8]> {
             let program_account = program_account.into();
             let instruction = SolanaInstruction {
                 program_id: *program_account.meta_key(),
@@ -301,12 +499,13 @@ mod cpi {
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
+                    accounts.next().unwrap(),
                     program_account,
                 ],
             }
         }
     }
-    impl<'a, AI> CPIClientStatic<'a, 8> for CreateGameCPI<'a, AI, 7>
+    impl<'a, AI> CPIClientStatic<'a, 9> for CreateGameCPI<'a, AI, 8>
     where
         AI: ToSolanaAccountMeta,
     {
@@ -317,7 +516,7 @@ mod cpi {
         fn instruction(
             self,
             program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
-        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 8]> {
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
             let program_account = program_account.into();
             let instruction = SolanaInstruction {
                 program_id: *program_account.meta_key(),
@@ -340,12 +539,13 @@ mod cpi {
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
+                    accounts.next().unwrap(),
                     program_account,
                 ],
             }
         }
     }
-    impl<'a, AI> CPIClientStatic<'a, 9> for CreateGameCPI<'a, AI, 8>
+    impl<'a, AI> CPIClientStatic<'a, 10> for CreateGameCPI<'a, AI, 9>
     where
         AI: ToSolanaAccountMeta,
     {
@@ -356,7 +556,7 @@ mod cpi {
         fn instruction(
             self,
             program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
-        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 10]> {
             let program_account = program_account.into();
             let instruction = SolanaInstruction {
                 program_id: *program_account.meta_key(),
@@ -380,19 +580,98 @@ mod cpi {
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
+                    accounts.next().unwrap(),
                     program_account,
                 ],
             }
         }
     }
+
+    /// Creates a new game with the optional accounts picked at runtime.
+    #[derive(Debug)]
+    pub struct CreateGameCPIDynamic<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreateGameCPIDynamic<'a, AI> {
+        /// Creates a new game.
+        /// `funder` is needed unless `game` is zeroed,
+        /// `co_signer` if the creator's profile needs it for the wager,
+        /// `other_player_profile` locks the other player to a given profile.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            wager_funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            funder: Option<MaybeOwned<'a, AI>>,
+            co_signer: Option<MaybeOwned<'a, AI>>,
+            other_player_profile: Option<MaybeOwned<'a, AI>>,
+            create_game_data: &CreateGameData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreateGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            create_game_data.serialize(&mut data)?;
+            let mut accounts = vec![
+                authority.into(),
+                player_profile.into(),
+                config.into(),
+                game.into(),
+                game_signer.into(),
+                wager_funder.into(),
+                system_program.into(),
+            ];
+            accounts.extend(funder);
+            accounts.extend(co_signer);
+            accounts.extend(other_player_profile);
+            Ok(Self { accounts, data })
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for CreateGameCPIDynamic<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreateGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use client::*;
 /// Client for [`CreateGame`]
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod client {
     use super::*;
+    use crate::client::{derive_config, derive_game_signer, HashedSigner, InstructionSet};
     use std::future::Future;
 
     /// Data for [`create_game`]
@@ -400,10 +679,32 @@ mod client {
     pub struct CreateGameClientData {
         /// Which position the creator wants to play in.
         pub creator_player: Player,
-        /// The wager each player will place. Winner gets double this amount.
+        /// The wager the creator will place.
         pub wager: u64,
+        /// The wager the joiner will place. Set it different from `wager` to give the game odds.
+        /// Winner gets both wagers.
+        pub joiner_wager: u64,
         /// The length of time each player gets to play their turn. Starts once other player joins.
+        /// 0 makes the game untimed.
         pub turn_length: UnixTimestamp,
+        /// How long the game waits for a joiner before anyone can sweep it. 0 waits forever.
+        pub join_ttl: UnixTimestamp,
+        /// The board to play on.
+        pub variant: GameVariant,
+        /// How closed boards and full boards are handled.
+        pub rules: RuleSet,
+        /// Randomizes who moves first when the opponent joins instead of always [`Player::One`].
+        pub random_first_player: bool,
+        /// Makes the game private, hash the code to share with [`Game::hash_join_code`].
+        /// All zeros for a public game.
+        pub join_code_hash: [u8; 32],
+        /// The profiles that can join, build it with [`Game::invited_array`].
+        /// All zero keys lets anyone join.
+        pub invited: [Pubkey; Game::MAX_INVITED],
+        /// Plays the game for nothing, both wagers must be 0.
+        pub friendly: bool,
+        /// How long a locked other player has to join before the creator can unlock the game. 0 waits forever.
+        pub lock_ttl: UnixTimestamp,
     }
     impl CreateGameClientData {
         /// Turns this into [`CreateGameData`]
@@ -411,7 +712,16 @@ mod client {
             CreateGameData {
                 creator_player: self.creator_player,
                 wager: self.wager,
+                joiner_wager: self.joiner_wager,
                 turn_length: self.turn_length,
+                join_ttl: self.join_ttl,
+                variant: self.variant,
+                rules: self.rules,
+                random_first_player: self.random_first_player,
+                join_code_hash: self.join_code_hash,
+                invited: self.invited,
+                friendly: self.friendly,
+                lock_ttl: self.lock_ttl,
                 signer_bump,
             }
         }
@@ -434,28 +744,33 @@ mod client {
         let wager_funder = wager_funder.into();
         let funder = funder.into();
 
-        let (game_signer, signer_bump) = GameSignerSeeder {
-            game: game.pubkey(),
-        }
-        .find_address(&program_id);
+        let (config, _) = derive_config(&program_id);
+        let (game_signer, signer_bump) = derive_game_signer(&program_id, game.pubkey());
 
         match other_player_profile {
             Some(other_player_profile) => InstructionSet {
                 instructions: vec![
-                    CreateGameCPI::new_with_locked_player(
-                        SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                        SolanaAccountMeta::new(player_profile, false),
-                        SolanaAccountMeta::new(game.pubkey(), true),
-                        SolanaAccountMeta::new(game_signer, false),
-                        SolanaAccountMeta::new(wager_funder.pubkey(), true),
-                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
-                        SolanaAccountMeta::new(funder.pubkey(), true),
-                        SolanaAccountMeta::new_readonly(other_player_profile, false),
-                        &data.into_data(signer_bump),
-                    )
-                    .unwrap()
-                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
-                    .instruction,
+                    CreateGameCPI::builder()
+                        .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                        .player_profile(SolanaAccountMeta::new(player_profile, false))
+                        .config(SolanaAccountMeta::new_readonly(config, false))
+                        .game(SolanaAccountMeta::new(game.pubkey(), true))
+                        .game_signer(SolanaAccountMeta::new(game_signer, false))
+                        .wager_funder(SolanaAccountMeta::new(wager_funder.pubkey(), true))
+                        .system_program(SolanaAccountMeta::new_readonly(
+                            SystemProgram::<()>::KEY,
+                            false,
+                        ))
+                        .funder(SolanaAccountMeta::new(funder.pubkey(), true))
+                        .locked_opponent(SolanaAccountMeta::new_readonly(
+                            other_player_profile,
+                            false,
+                        ))
+                        .data(data.into_data(signer_bump))
+                        .build()
+                        .unwrap()
+                        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                        .instruction,
                 ],
                 signers: [authority, game, wager_funder, funder]
                     .into_iter()
@@ -463,19 +778,23 @@ mod client {
             },
             None => InstructionSet {
                 instructions: vec![
-                    CreateGameCPI::new(
-                        SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                        SolanaAccountMeta::new(player_profile, false),
-                        SolanaAccountMeta::new(game.pubkey(), true),
-                        SolanaAccountMeta::new(game_signer, false),
-                        SolanaAccountMeta::new(wager_funder.pubkey(), true),
-                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
-                        SolanaAccountMeta::new(funder.pubkey(), true),
-                        &data.into_data(signer_bump),
-                    )
-                    .unwrap()
-                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
-                    .instruction,
+                    CreateGameCPI::builder()
+                        .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                        .player_profile(SolanaAccountMeta::new(player_profile, false))
+                        .config(SolanaAccountMeta::new_readonly(config, false))
+                        .game(SolanaAccountMeta::new(game.pubkey(), true))
+                        .game_signer(SolanaAccountMeta::new(game_signer, false))
+                        .wager_funder(SolanaAccountMeta::new(wager_funder.pubkey(), true))
+                        .system_program(SolanaAccountMeta::new_readonly(
+                            SystemProgram::<()>::KEY,
+                            false,
+                        ))
+                        .funder(SolanaAccountMeta::new(funder.pubkey(), true))
+                        .data(data.into_data(signer_bump))
+                        .build()
+                        .unwrap()
+                        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                        .instruction,
                 ],
                 signers: [authority, game, wager_funder, funder]
                     .into_iter()
@@ -484,7 +803,58 @@ mod client {
         }
     }
 
+    /// Creates a new game with the creator's [`PlayerProfile::co_signer`] also signing,
+    /// needed when `data.wager` is above their [`PlayerProfile::co_sign_threshold`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_game_co_signed<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        co_signer: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: impl Into<HashedSigner<'a>>,
+        wager_funder: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        other_player_profile: Option<Pubkey>,
+        data: CreateGameClientData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let co_signer = co_signer.into();
+        let game = game.into();
+        let wager_funder = wager_funder.into();
+        let funder = funder.into();
+
+        let (config, _) = derive_config(&program_id);
+        let (game_signer, signer_bump) = derive_game_signer(&program_id, game.pubkey());
+
+        InstructionSet {
+            instructions: vec![
+                CreateGameCPIDynamic::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(game.pubkey(), true),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(wager_funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    Some(SolanaAccountMeta::new(funder.pubkey(), true).into()),
+                    Some(SolanaAccountMeta::new_readonly(co_signer.pubkey(), true).into()),
+                    other_player_profile.map(|other_player_profile| {
+                        SolanaAccountMeta::new_readonly(other_player_profile, false).into()
+                    }),
+                    &data.into_data(signer_bump),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, co_signer, game, wager_funder, funder]
+                .into_iter()
+                .collect(),
+        }
+    }
+
     /// Creates a new game from a zeroed account.
+    /// `rent` gives the rent exempt minimum for a size, such as `|size| rpc.get_minimum_balance_for_rent_exemption(size)`.
     #[allow(clippy::too_many_arguments)]
     pub async fn create_game_zeroed<'a, F, E>(
         program_id: Pubkey,
@@ -500,14 +870,8 @@ mod client {
     where
         F: Future<Output = Result<u64, E>>,
     {
-        let authority = authority.into();
         let game = game.into();
         let game_key = game.pubkey();
-        let wager_funder = wager_funder.into();
-        let funder = funder.into();
-
-        let (game_signer, signer_bump) =
-            GameSignerSeeder { game: game_key }.find_address(&program_id);
 
         let mut out = system_program::create_account(
             funder,
@@ -516,43 +880,82 @@ mod client {
             Game::ON_CHAIN_SIZE as u64,
             program_id,
         );
-        out.add_set(match other_player_profile {
+        out.add_set(create_game_from_zeroed(
+            program_id,
+            authority,
+            player_profile,
+            game_key,
+            wager_funder,
+            other_player_profile,
+            data,
+        ));
+        Ok(out)
+    }
+
+    /// Creates a new game in `game`, an account of [`Game::ON_CHAIN_SIZE`] zeroed bytes owned by the program.
+    /// The account must be created earlier in the same transaction, otherwise anyone could create the game in it first.
+    pub fn create_game_from_zeroed<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        wager_funder: impl Into<HashedSigner<'a>>,
+        other_player_profile: Option<Pubkey>,
+        data: CreateGameClientData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let wager_funder = wager_funder.into();
+
+        let (config, _) = derive_config(&program_id);
+        let (game_signer, signer_bump) = derive_game_signer(&program_id, game);
+
+        match other_player_profile {
             Some(other_player_profile) => InstructionSet {
                 instructions: vec![
-                    CreateGameCPI::new_zeroed_with_locked_player(
-                        SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                        SolanaAccountMeta::new(player_profile, false),
-                        SolanaAccountMeta::new(game_key, false),
-                        SolanaAccountMeta::new(game_signer, false),
-                        SolanaAccountMeta::new(wager_funder.pubkey(), true),
-                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
-                        SolanaAccountMeta::new_readonly(other_player_profile, false),
-                        &data.into_data(signer_bump),
-                    )
-                    .unwrap()
-                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
-                    .instruction,
+                    CreateGameCPI::builder()
+                        .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                        .player_profile(SolanaAccountMeta::new(player_profile, false))
+                        .config(SolanaAccountMeta::new_readonly(config, false))
+                        .game(SolanaAccountMeta::new(game, false))
+                        .game_signer(SolanaAccountMeta::new(game_signer, false))
+                        .wager_funder(SolanaAccountMeta::new(wager_funder.pubkey(), true))
+                        .system_program(SolanaAccountMeta::new_readonly(
+                            SystemProgram::<()>::KEY,
+                            false,
+                        ))
+                        .locked_opponent(SolanaAccountMeta::new_readonly(
+                            other_player_profile,
+                            false,
+                        ))
+                        .data(data.into_data(signer_bump))
+                        .build()
+                        .unwrap()
+                        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                        .instruction,
                 ],
                 signers: [authority, wager_funder].into_iter().collect(),
             },
             None => InstructionSet {
                 instructions: vec![
-                    CreateGameCPI::new_zeroed(
-                        SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                        SolanaAccountMeta::new(player_profile, false),
-                        SolanaAccountMeta::new(game_key, false),
-                        SolanaAccountMeta::new(game_signer, false),
-                        SolanaAccountMeta::new(wager_funder.pubkey(), true),
-                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
-                        &data.into_data(signer_bump),
-                    )
-                    .unwrap()
-                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
-                    .instruction,
+                    CreateGameCPI::builder()
+                        .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                        .player_profile(SolanaAccountMeta::new(player_profile, false))
+                        .config(SolanaAccountMeta::new_readonly(config, false))
+                        .game(SolanaAccountMeta::new(game, false))
+                        .game_signer(SolanaAccountMeta::new(game_signer, false))
+                        .wager_funder(SolanaAccountMeta::new(wager_funder.pubkey(), true))
+                        .system_program(SolanaAccountMeta::new_readonly(
+                            SystemProgram::<()>::KEY,
+                            false,
+                        ))
+                        .data(data.into_data(signer_bump))
+                        .build()
+                        .unwrap()
+                        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                        .instruction,
                 ],
                 signers: [authority, wager_funder].into_iter().collect(),
             },
-        });
-        Ok(out)
+        }
     }
 }
@@ -0,0 +1,379 @@
+use crate::accounts::{GameVariant, House, Player, ProgramConfig, RuleSet, WagerVault};
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::pda::{GameSignerSeeder, HouseVaultSeeder, KnownPda};
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Creates a game against the house, which starts right away.
+///
+/// The house matches the player's wager from its vault, up to the config's house wager limit.
+/// The house's moves are submitted by its operator with [`HouseMove`](crate::instructions::HouseMove).
+#[derive(Debug)]
+pub enum CreateHouseGame {}
+
+impl<AI> Instruction<AI> for CreateHouseGame {
+    type Accounts = CreateHouseGameAccounts<AI>;
+    type Data = CreateHouseGameData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CreateHouseGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(
+    data = (create_data: CreateHouseGameData),
+    custom = create_data.wager.checked_mul(2).is_some(),
+    custom = create_data.turn_length >= 0,
+    custom = !create_data.rules.commit_reveal,
+)]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct CreateHouseGameAccounts<AI> {
+    /// The authority for the player's profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The player's profile.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The program config, for the limits on new games and the house's exposure.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The house.
+    pub house: ReadOnlyDataAccount<AI, TutorialAccounts, House>,
+    /// The house's profile.
+    #[validate(writable, custom = &self.house.profile == self.house_profile.info().key())]
+    pub house_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The vault the house's wager comes from.
+    #[validate(writable, data = (HouseVaultSeeder, self.house.vault_bump))]
+    pub house_vault: Seeds<AI, HouseVaultSeeder>,
+    /// The game to be created.
+    #[from(data = Game::new(
+        player_profile.info().key(),
+        create_data.creator_player,
+        create_data.signer_bump,
+        create_data.wager,
+        create_data.wager,
+        create_data.turn_length,
+        0,
+        create_data.variant,
+        RuleSet::default(),
+    ))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: None,
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub game: Box<InitAccount<AI, TutorialAccounts, Game>>,
+    /// The game signer, created as the [`WagerVault`] that will hold the wagers.
+    #[from(data = WagerVault::new(game.info().key()))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.wager_funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            GameSignerSeeder{ game: *self.game.info().key() },
+            self.game.signer_bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub game_signer: InitAccount<AI, TutorialAccounts, WagerVault>,
+    /// The funder that will put the player's wager into the game and pay the wager vault's rent.
+    #[validate(signer, writable)]
+    pub wager_funder: AI,
+    /// The funder for the game's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`CreateHouseGame`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CreateHouseGameData {
+    /// Which position the player wants to play in.
+    pub creator_player: Player,
+    /// The bump for the game signer, must be the canonical bump.
+    pub signer_bump: u8,
+    /// The wager the player will place. The house places the same.
+    pub wager: u64,
+    /// The length of time each player gets to play their turn. 0 makes the game untimed.
+    pub turn_length: UnixTimestamp,
+    /// The board to play on.
+    pub variant: GameVariant,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+    use std::iter::{empty, once};
+
+    impl<'a, AI> InstructionProcessor<AI, CreateHouseGame> for CreateHouseGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = CreateHouseGameData;
+        type ValidateData = ();
+        type InstructionData = CreateHouseGameData;
+
+        fn data_to_instruction_arg(
+            data: <CreateHouseGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), (), data))
+        }
+
+        fn process(
+            program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <CreateHouseGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CreateHouseGame as Instruction<AI>>::ReturnType> {
+            KnownPda::with_bump(
+                GameSignerSeeder {
+                    game: *accounts.game.info().key(),
+                },
+                data.signer_bump,
+                program_id,
+            )?
+            .verify(program_id)?;
+            let now = Clock::get()?.unix_timestamp;
+            accounts
+                .config
+                .check_new_game(data.wager, data.wager, data.turn_length)?;
+            accounts
+                .config
+                .check_ranked_profile(&accounts.player_profile, now)?;
+            let limit = accounts
+                .config
+                .house_wager_limit(*accounts.house_vault.info().lamports());
+            if data.wager > limit {
+                return Err(
+                    custom_error!("The house will match at most {} lamports", limit).into(),
+                );
+            }
+
+            log_event!(debug, "transferring_wagers");
+
+            accounts.system_program.transfer(
+                CPIChecked,
+                &accounts.wager_funder,
+                accounts.game_signer.info(),
+                data.wager,
+                empty(),
+            )?;
+            let vault_seeds = accounts.house_vault.take_seed_set().unwrap();
+            accounts.system_program.transfer(
+                CPIChecked,
+                accounts.house_vault.info(),
+                accounts.game_signer.info(),
+                data.wager,
+                once(&vault_seeds),
+            )?;
+            accounts.game_signer.deposit(data.wager.saturating_mul(2));
+
+            // The house takes the other seat, there's nothing to join
+            *match data.creator_player {
+                Player::One => &mut accounts.game.player2,
+                Player::Two => &mut accounts.game.player1,
+            } = *accounts.house_profile.info().key();
+
+            accounts.game.created_at = now;
+            accounts.game.last_turn = now;
+            for profile in [&mut accounts.player_profile, &mut accounts.house_profile] {
+                profile.lamports_wagered.saturating_add_assign(data.wager);
+                profile.mark_game_joined(now);
+            }
+
+            log_event!(info, "house_game_created");
+            ProgramEvent::HouseGameCreated {
+                game: *accounts.game.info().key(),
+                profile: *accounts.player_profile.info().key(),
+                house_profile: *accounts.house_profile.info().key(),
+                creator_player: data.creator_player,
+                variant: data.variant,
+                wager: data.wager,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CreateHouseGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates a game against the house.
+    #[derive(Debug)]
+    pub struct CreateHouseGameCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 11],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreateHouseGameCPI<'a, AI> {
+        /// Creates a game against the house.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            house_profile: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            wager_funder: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            create_data: &CreateHouseGameData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreateHouseGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            create_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    config.into(),
+                    house.into(),
+                    house_profile.into(),
+                    house_vault.into(),
+                    game.into(),
+                    game_signer.into(),
+                    wager_funder.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 12> for CreateHouseGameCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreateHouseGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 12]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CreateHouseGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_config, derive_game_signer, derive_house, derive_house_profile, derive_house_vault,
+        HashedSigner, InstructionSet,
+    };
+
+    /// Creates a game against the house.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_house_game<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: impl Into<HashedSigner<'a>>,
+        wager_funder: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        creator_player: Player,
+        wager: u64,
+        turn_length: UnixTimestamp,
+        variant: GameVariant,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let game = game.into();
+        let wager_funder = wager_funder.into();
+        let funder = funder.into();
+
+        let (config, _) = derive_config(&program_id);
+        let (house, _) = derive_house(&program_id);
+        let (house_profile, _) = derive_house_profile(&program_id);
+        let (house_vault, _) = derive_house_vault(&program_id);
+        let (game_signer, signer_bump) = derive_game_signer(&program_id, game.pubkey());
+
+        InstructionSet {
+            instructions: vec![
+                CreateHouseGameCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(house, false),
+                    SolanaAccountMeta::new(house_profile, false),
+                    SolanaAccountMeta::new(house_vault, false),
+                    SolanaAccountMeta::new(game.pubkey(), true),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(wager_funder.pubkey(), true),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &CreateHouseGameData {
+                        creator_player,
+                        signer_bump,
+                        wager,
+                        turn_length,
+                        variant,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, game, wager_funder, funder]
+                .into_iter()
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::accounts::LeaderboardShard;
+use crate::pda::LeaderboardShardSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Creates a page of the leaderboard for an elo band. Anyone can create one.
+#[derive(Debug)]
+pub enum CreateLeaderboardShard {}
+
+impl<AI> Instruction<AI> for CreateLeaderboardShard {
+    type Accounts = CreateLeaderboardShardAccounts<AI>;
+    type Data = CreateLeaderboardShardData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CreateLeaderboardShard`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (create_data: CreateLeaderboardShardData))]
+#[validate(
+    generics = [<'a> where AI: ToSolanaAccountInfo<'a>],
+    data = (create_data: CreateLeaderboardShardData),
+)]
+pub struct CreateLeaderboardShardAccounts<AI> {
+    /// The page to create.
+    #[from(data = LeaderboardShard::new(create_data.band, create_data.page, create_data.bump))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            LeaderboardShardSeeder::new(create_data.band, create_data.page),
+            create_data.bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub shard: Box<InitAccount<AI, TutorialAccounts, LeaderboardShard>>,
+    /// The funder for the page's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`CreateLeaderboardShard`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CreateLeaderboardShardData {
+    /// The elo band of the page.
+    pub band: u16,
+    /// The page within the band.
+    pub page: u16,
+    /// The bump seed of the page's PDA.
+    pub bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, CreateLeaderboardShard> for CreateLeaderboardShard
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = CreateLeaderboardShardData;
+        type ValidateData = CreateLeaderboardShardData;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <CreateLeaderboardShard as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), data, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <CreateLeaderboardShard as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CreateLeaderboardShard as Instruction<AI>>::ReturnType> {
+            // All initialization is handled in the accounts.
+            ProgramEvent::LeaderboardShardCreated {
+                shard: *accounts.shard.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CreateLeaderboardShard`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates a leaderboard page.
+    #[derive(Debug)]
+    pub struct CreateLeaderboardShardCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreateLeaderboardShardCPI<'a, AI> {
+        /// Creates a leaderboard page.
+        pub fn new(
+            shard: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            create_data: &CreateLeaderboardShardData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreateLeaderboardShard>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            create_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [shard.into(), funder.into(), system_program.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for CreateLeaderboardShardCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreateLeaderboardShard;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CreateLeaderboardShard`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_leaderboard_shard, HashedSigner, InstructionSet};
+
+    /// Creates `page` of the leaderboard for `band`.
+    pub fn create_leaderboard_shard<'a>(
+        program_id: Pubkey,
+        funder: impl Into<HashedSigner<'a>>,
+        band: u16,
+        page: u16,
+    ) -> InstructionSet<'a> {
+        let funder = funder.into();
+        let (shard, bump) = derive_leaderboard_shard(&program_id, band, page);
+        InstructionSet {
+            instructions: vec![
+                CreateLeaderboardShardCPI::new(
+                    SolanaAccountMeta::new(shard, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &CreateLeaderboardShardData { band, page, bump },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [funder].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::accounts::PayoutAllowlist;
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Creates a payout allowlist for a profile.
+/// The initial list is active immediately, later changes go through [`UpdatePayoutAllowlist`](crate::instructions::UpdatePayoutAllowlist).
+#[derive(Debug)]
+pub enum CreatePayoutAllowlist {}
+
+impl<AI> Instruction<AI> for CreatePayoutAllowlist {
+    type Accounts = CreatePayoutAllowlistAccounts<AI>;
+    type Data = CreatePayoutAllowlistData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CreatePayoutAllowlist`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (addresses: [Pubkey; PayoutAllowlist::MAX_ADDRESSES]))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct CreatePayoutAllowlistAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile to add the allowlist to. Cannot already have one.
+    #[validate(
+        writable,
+        custom = &self.player_profile.authority == self.authority.key(),
+        custom = !self.player_profile.has_payout_allowlist(),
+    )]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The new allowlist.
+    #[from(data = PayoutAllowlist::new(player_profile.info().key(), addresses))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: None,
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub payout_allowlist: InitAccount<AI, TutorialAccounts, PayoutAllowlist>,
+    /// The funder for the allowlist's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`CreatePayoutAllowlist`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CreatePayoutAllowlistData {
+    /// The allowed payout addresses. At most [`PayoutAllowlist::MAX_ADDRESSES`].
+    pub addresses: Vec<Pubkey>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, CreatePayoutAllowlist> for CreatePayoutAllowlist
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = [Pubkey; PayoutAllowlist::MAX_ADDRESSES];
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <CreatePayoutAllowlist as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            let addresses = PayoutAllowlist::address_array(&data.addresses)
+                .ok_or(custom_error!("Too many payout addresses"))?;
+            Ok((addresses, (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <CreatePayoutAllowlist as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CreatePayoutAllowlist as Instruction<AI>>::ReturnType> {
+            // Link the allowlist so settlements know to check it.
+            accounts.player_profile.payout_allowlist = *accounts.payout_allowlist.info().key();
+            ProgramEvent::PayoutAllowlistCreated {
+                profile: *accounts.player_profile.info().key(),
+                payout_allowlist: *accounts.payout_allowlist.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CreatePayoutAllowlist`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates a payout allowlist.
+    #[derive(Debug)]
+    pub struct CreatePayoutAllowlistCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreatePayoutAllowlistCPI<'a, AI> {
+        /// Creates a payout allowlist.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            create_data: &CreatePayoutAllowlistData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreatePayoutAllowlist>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            create_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    payout_allowlist.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for CreatePayoutAllowlistCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreatePayoutAllowlist;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CreatePayoutAllowlist`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Creates a payout allowlist for a profile.
+    pub fn create_payout_allowlist<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        payout_allowlist: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        addresses: Vec<Pubkey>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let payout_allowlist = payout_allowlist.into();
+        let funder = funder.into();
+        InstructionSet {
+            instructions: vec![
+                CreatePayoutAllowlistCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(payout_allowlist.pubkey(), true),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &CreatePayoutAllowlistData { addresses },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, payout_allowlist, funder].into_iter().collect(),
+        }
+    }
+}
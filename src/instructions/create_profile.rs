@@ -1,36 +1,43 @@
+use crate::pda::ProfileSeeder;
 use crate::{PlayerProfile, TutorialAccounts};
 use cruiser::prelude::*;
 
 /// Creates a new player profile.
+/// Returns the profile's key, which is only known up front for a keypair profile.
 #[derive(Debug)]
 pub enum CreateProfile {}
 
 impl<AI> Instruction<AI> for CreateProfile {
     type Accounts = CreateProfileAccounts<AI>;
     type Data = CreateProfileData;
-    type ReturnType = ();
+    type ReturnType = Pubkey;
 }
 
 /// Accounts for [`CreateProfile`]
 #[derive(AccountArgument, Debug)]
 #[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
-#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+#[validate(
+    generics = [<'a> where AI: ToSolanaAccountInfo<'a>],
+    data = (profile_bump: Option<u8>),
+)]
 pub struct CreateProfileAccounts<AI> {
-    /// The authority for the new profile.
+    /// The authority for the new profile, which may be a PDA of the calling program signing with `invoke_signed`.
     #[validate(signer)]
     pub authority: AI,
-    /// The new profile to create
+    /// The new profile to create. Either a new keypair or the authority's [`ProfileSeeder`] PDA.
     #[from(data = PlayerProfile::new(authority.key()))] // This is where we set the initial value of the profile
     #[validate(data = InitArgs{
         system_program: &self.system_program,
         space: InitStaticSized,
         funder: &self.funder,
         funder_seeds: None,
-        account_seeds: None,
+        account_seeds: profile_bump
+            .map(|bump| PDASeedSet::new(ProfileSeeder { authority: *self.authority.key() }, bump))
+            .as_ref(),
         rent: None,
         cpi: CPIChecked,
     })]
-    pub profile: InitAccount<AI, TutorialAccounts, PlayerProfile>,
+    pub profile: Box<InitAccount<AI, TutorialAccounts, PlayerProfile>>,
     /// The funder for the new account. Needed if the account is not zeroed.
     #[validate(signer, writable)]
     pub funder: AI,
@@ -40,38 +47,53 @@ pub struct CreateProfileAccounts<AI> {
 
 /// Data for [`CreateProfile`]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
-pub struct CreateProfileData {}
+pub struct CreateProfileData {
+    /// The bump of the authority's [`ProfileSeeder`] PDA to create the profile at.
+    /// [`None`] if the profile is a new keypair signing for itself.
+    pub profile_bump: Option<u8>,
+}
 
 #[cfg(feature = "processor")]
 mod processor {
     use super::*;
+    use crate::event::ProgramEvent;
+    use crate::instructions::set_return;
+    use cruiser::solana_program::clock::Clock;
 
     impl<'a, AI> InstructionProcessor<AI, CreateProfile> for CreateProfile
     where
         AI: ToSolanaAccountInfo<'a>,
     {
         type FromAccountsData = ();
-        type ValidateData = ();
+        type ValidateData = Option<u8>;
         type InstructionData = ();
 
         fn data_to_instruction_arg(
-            _data: <CreateProfile as Instruction<AI>>::Data,
+            data: <CreateProfile as Instruction<AI>>::Data,
         ) -> CruiserResult<(
             Self::FromAccountsData,
             Self::ValidateData,
             Self::InstructionData,
         )> {
-            Ok(((), (), ()))
+            Ok(((), data.profile_bump, ()))
         }
 
         fn process(
             _program_id: &Pubkey,
             _data: Self::InstructionData,
-            _accounts: &mut <CreateProfile as Instruction<AI>>::Accounts,
+            accounts: &mut <CreateProfile as Instruction<AI>>::Accounts,
         ) -> CruiserResult<<CreateProfile as Instruction<AI>>::ReturnType> {
-            // We don't need any processing here, all initialization is handled in the accounts.
-            // You could do some data setting here but we handled that with the profile init.
-            Ok(())
+            // Most initialization is handled in the accounts.
+            // The clock isn't available there so we set the times here.
+            let now = Clock::get()?.unix_timestamp;
+            accounts.profile.created_at = now;
+            accounts.profile.mark_active(now);
+            ProgramEvent::ProfileCreated {
+                profile: *accounts.profile.info().key(),
+                authority: *accounts.authority.key(),
+            }
+            .emit()?;
+            set_return(*accounts.profile.info().key())
         }
     }
 }
@@ -92,17 +114,19 @@ pub mod cpi {
     }
     impl<'a, AI> CreateProfileCPI<'a, AI> {
         /// Creates a new player profile.
+        /// `profile_bump` is the bump of the authority's [`ProfileSeeder`] PDA if `profile` is it,
+        /// [`None`] if `profile` is a new keypair.
         pub fn new(
             authority: impl Into<MaybeOwned<'a, AI>>,
             profile: impl Into<MaybeOwned<'a, AI>>,
             funder: impl Into<MaybeOwned<'a, AI>>,
             system_program: impl Into<MaybeOwned<'a, AI>>,
+            profile_bump: Option<u8>,
         ) -> CruiserResult<Self> {
             let mut data = Vec::new();
             <TutorialInstructions as InstructionListItem<CreateProfile>>::discriminant_compressed()
                 .serialize(&mut data)?;
-            // This will do nothing but throw an error if we update this to include more data.
-            CreateProfileData {}.serialize(&mut data)?;
+            CreateProfileData { profile_bump }.serialize(&mut data)?;
             Ok(Self {
                 accounts: [
                     authority.into(),
@@ -153,12 +177,13 @@ pub mod cpi {
     }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use client::*;
 /// Client functions for [`CreateProfile`]
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod client {
     use super::*;
+    use crate::client::{derive_profile, HashedSigner, InstructionSet};
 
     /// Creates a new player profile.
     pub fn create_profile<'a>(
@@ -177,6 +202,7 @@ mod client {
                     SolanaAccountMeta::new(profile.pubkey(), true),
                     SolanaAccountMeta::new(funder.pubkey(), true),
                     SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    None,
                 )
                 .unwrap()
                 .instruction(SolanaAccountMeta::new_readonly(program_id, false))
@@ -185,4 +211,30 @@ mod client {
             signers: [authority, profile, funder].into_iter().collect(),
         }
     }
+
+    /// Creates a new player profile at the authority's [`ProfileSeeder`] PDA, so it can be found from the authority alone.
+    pub fn create_profile_pda<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let funder = funder.into();
+        let (profile, profile_bump) = derive_profile(&program_id, authority.pubkey());
+        InstructionSet {
+            instructions: vec![
+                CreateProfileCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(profile, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    Some(profile_bump),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, funder].into_iter().collect(),
+        }
+    }
 }
@@ -0,0 +1,213 @@
+use crate::accounts::SocialGraph;
+use crate::pda::SocialGraphSeeder;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Creates a profile's social graph so it can [`Follow`](crate::instructions::Follow) other profiles.
+#[derive(Debug)]
+pub enum CreateSocialGraph {}
+
+impl<AI> Instruction<AI> for CreateSocialGraph {
+    type Accounts = CreateSocialGraphAccounts<AI>;
+    type Data = CreateSocialGraphData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`CreateSocialGraph`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (bump: u8))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>], data = (bump: u8))]
+pub struct CreateSocialGraphAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile the graph is for.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The social graph to create.
+    #[from(data = SocialGraph::new(player_profile.info().key(), bump))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            SocialGraphSeeder{ profile: *self.player_profile.info().key() },
+            bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub social_graph: Box<InitAccount<AI, TutorialAccounts, SocialGraph>>,
+    /// The funder for the graph's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`CreateSocialGraph`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CreateSocialGraphData {
+    /// The bump seed of the graph's PDA.
+    pub bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, CreateSocialGraph> for CreateSocialGraph
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = u8;
+        type ValidateData = u8;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <CreateSocialGraph as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.bump, data.bump, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <CreateSocialGraph as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<CreateSocialGraph as Instruction<AI>>::ReturnType> {
+            // All initialization is handled in the accounts.
+            ProgramEvent::SocialGraphCreated {
+                profile: *accounts.player_profile.info().key(),
+                social_graph: *accounts.social_graph.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`CreateSocialGraph`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates a profile's social graph.
+    #[derive(Debug)]
+    pub struct CreateSocialGraphCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> CreateSocialGraphCPI<'a, AI> {
+        /// Creates a profile's social graph.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            social_graph: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            bump: u8,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<CreateSocialGraph>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            CreateSocialGraphData { bump }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    social_graph.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for CreateSocialGraphCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = CreateSocialGraph;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`CreateSocialGraph`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_social_graph, HashedSigner, InstructionSet};
+
+    /// Creates a profile's social graph.
+    pub fn create_social_graph<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let funder = funder.into();
+        let (social_graph, bump) = derive_social_graph(&program_id, player_profile);
+        InstructionSet {
+            instructions: vec![
+                CreateSocialGraphCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(social_graph, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    bump,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, funder].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::accounts::ProgramConfig;
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Moves a long-inactive profile's elo toward [`PlayerProfile::INITIAL_ELO`] as set in the [`ProgramConfig`].
+/// Anyone can call this so stale ratings don't sit on the leaderboard.
+#[derive(Debug)]
+pub enum DecayElo {}
+
+impl<AI> Instruction<AI> for DecayElo {
+    type Accounts = DecayEloAccounts<AI>;
+    type Data = DecayEloData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`DecayElo`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct DecayEloAccounts<AI> {
+    /// The program config, for the decay settings.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The profile to decay.
+    #[validate(writable)]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`DecayElo`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct DecayEloData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, DecayElo> for DecayElo
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <DecayElo as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <DecayElo as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<DecayElo as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            let period = accounts.config.elo_decay_period;
+            let step = accounts.config.elo_decay_step;
+            if !accounts.player_profile.decay_elo(period, step, now) {
+                return Err(custom_error!("Profile is not due for elo decay").into());
+            }
+            log_event!(info, "elo_decayed", elo = accounts.player_profile.elo);
+            ProgramEvent::EloDecayed {
+                profile: *accounts.player_profile.info().key(),
+                elo: accounts.player_profile.elo,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`DecayElo`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Decays an inactive profile's elo.
+    #[derive(Debug)]
+    pub struct DecayEloCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> DecayEloCPI<'a, AI> {
+        /// Decays an inactive profile's elo.
+        pub fn new(
+            config: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<DecayElo>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            DecayEloData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [config.into(), player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for DecayEloCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = DecayElo;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`DecayElo`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, InstructionSet};
+
+    /// Decays `player_profile`'s elo. Only succeeds once a full [`ProgramConfig::elo_decay_period`]
+    /// has passed since its last game or decay.
+    pub fn decay_elo<'a>(program_id: Pubkey, player_profile: Pubkey) -> InstructionSet<'a> {
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                DecayEloCPI::new(
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(player_profile, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
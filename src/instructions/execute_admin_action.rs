@@ -0,0 +1,239 @@
+use crate::accounts::{AdminAction, ProgramConfig, ProposedAction};
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::pda::FeeVaultSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Runs a [`ProposeAdminAction`](crate::instructions::ProposeAdminAction) once its delay has passed,
+/// refunding the proposal's rent. Anyone can as the admin already approved it.
+#[derive(Debug)]
+pub enum ExecuteAdminAction {}
+
+impl<AI> Instruction<AI> for ExecuteAdminAction {
+    type Accounts = ExecuteAdminActionAccounts<AI>;
+    type Data = ();
+    type ReturnType = ();
+}
+
+/// Accounts for [`ExecuteAdminAction`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct ExecuteAdminActionAccounts<AI> {
+    /// The program config.
+    #[validate(writable)]
+    pub config: Box<DataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The proposal to execute.
+    #[validate(writable, custom = self.proposal.is_executable(Clock::get()?.unix_timestamp))]
+    pub proposal: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, ProposedAction>>>,
+    /// The proposal's funder, which gets the rent back.
+    #[validate(writable, custom = self.funder.key() == &self.proposal.funder)]
+    pub funder: AI,
+    /// The fee vault. Only needed for [`AdminAction::WithdrawFees`].
+    #[validate(
+        writable(IfSome),
+        data = IfSomeArg((FeeVaultSeeder, self.config.fee_vault_bump)),
+    )]
+    pub fee_vault: Option<Seeds<AI, FeeVaultSeeder>>,
+    /// The config's treasury. Only needed for [`AdminAction::WithdrawFees`].
+    #[validate(
+        writable(IfSome),
+        custom = self.treasury.as_ref().map_or(true, |treasury| treasury.key() == &self.config.treasury),
+    )]
+    pub treasury: Option<AI>,
+    /// Only needed for [`AdminAction::WithdrawFees`].
+    pub system_program: Option<SystemProgram<AI>>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, ExecuteAdminAction> for ExecuteAdminAction
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <ExecuteAdminAction as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <ExecuteAdminAction as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ExecuteAdminAction as Instruction<AI>>::ReturnType> {
+            accounts.proposal.set_fundee(accounts.funder.clone());
+            match accounts.proposal.action.clone() {
+                AdminAction::UpdateConfig(data) => data.apply(&mut accounts.config)?,
+                AdminAction::WithdrawFees { amount } => {
+                    let (fee_vault, treasury, system_program) = match (
+                        &mut accounts.fee_vault,
+                        &accounts.treasury,
+                        &accounts.system_program,
+                    ) {
+                        (Some(fee_vault), Some(treasury), Some(system_program)) => {
+                            (fee_vault, treasury, system_program)
+                        }
+                        _ => {
+                            return Err(custom_error!(
+                                "Withdrawing fees needs fee_vault, treasury, and system_program"
+                            )
+                            .into())
+                        }
+                    };
+                    if accounts.config.treasury == Pubkey::new_from_array([0; 32]) {
+                        return Err(custom_error!("No treasury is set").into());
+                    }
+                    let vault_seeds = fee_vault.take_seed_set().unwrap();
+                    system_program.transfer(
+                        CPIChecked,
+                        fee_vault.info(),
+                        treasury,
+                        amount,
+                        once(&vault_seeds),
+                    )?;
+                    log_event!(
+                        info,
+                        "fees_withdrawn",
+                        amount = amount,
+                        treasury = treasury.key()
+                    );
+                    ProgramEvent::FeesWithdrawn {
+                        amount,
+                        treasury: *treasury.key(),
+                    }
+                    .emit()?;
+                }
+            }
+            log_event!(info, "admin_action_executed", id = accounts.proposal.id);
+            ProgramEvent::AdminActionExecuted {
+                id: accounts.proposal.id,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ExecuteAdminAction`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Executes a proposed admin action.
+    #[derive(Debug)]
+    pub struct ExecuteAdminActionCPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ExecuteAdminActionCPI<'a, AI> {
+        /// Executes a proposed admin action.
+        /// `withdraw_fees_accounts` are the fee vault, treasury, and system program, needed to withdraw fees.
+        pub fn new(
+            config: impl Into<MaybeOwned<'a, AI>>,
+            proposal: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            withdraw_fees_accounts: Option<[MaybeOwned<'a, AI>; 3]>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ExecuteAdminAction>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            let mut accounts = vec![config.into(), proposal.into(), funder.into()];
+            accounts.extend(withdraw_fees_accounts.into_iter().flatten());
+            Ok(Self { accounts, data })
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for ExecuteAdminActionCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ExecuteAdminAction;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ExecuteAdminAction`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_fee_vault, derive_proposed_action, InstructionSet};
+
+    /// Executes the proposed admin action with `id`, refunding its `funder`.
+    /// `treasury` is needed if the action withdraws fees.
+    pub fn execute_admin_action<'a>(
+        program_id: Pubkey,
+        id: u64,
+        funder: Pubkey,
+        treasury: Option<Pubkey>,
+    ) -> InstructionSet<'a> {
+        let (config, _) = derive_config(&program_id);
+        let (proposal, _) = derive_proposed_action(&program_id, id);
+        let (fee_vault, _) = derive_fee_vault(&program_id);
+        InstructionSet {
+            instructions: vec![
+                ExecuteAdminActionCPI::new(
+                    SolanaAccountMeta::new(config, false),
+                    SolanaAccountMeta::new(proposal, false),
+                    SolanaAccountMeta::new(funder, false),
+                    treasury.map(|treasury| {
+                        [
+                            SolanaAccountMeta::new(fee_vault, false).into(),
+                            SolanaAccountMeta::new(treasury, false).into(),
+                            SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false).into(),
+                        ]
+                    }),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
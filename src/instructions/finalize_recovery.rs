@@ -0,0 +1,167 @@
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Moves a profile to the authority of its recovery once enough guardians have approved
+/// and [`PlayerProfile::RECOVERY_DELAY`] has passed since it was initiated. Anyone can finalize it.
+#[derive(Debug)]
+pub enum FinalizeRecovery {}
+
+impl<AI> Instruction<AI> for FinalizeRecovery {
+    type Accounts = FinalizeRecoveryAccounts<AI>;
+    type Data = FinalizeRecoveryData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`FinalizeRecovery`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct FinalizeRecoveryAccounts<AI> {
+    /// The profile to recover.
+    #[validate(
+        writable,
+        custom = self.player_profile.is_recoverable(Clock::get()?.unix_timestamp),
+    )]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`FinalizeRecovery`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct FinalizeRecoveryData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::PendingRecovery;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, FinalizeRecovery> for FinalizeRecovery
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <FinalizeRecovery as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <FinalizeRecovery as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<FinalizeRecovery as Instruction<AI>>::ReturnType> {
+            let profile = &mut accounts.player_profile;
+            log_event!(
+                info,
+                "profile_recovered",
+                from = profile.authority,
+                to = profile.recovery.new_authority
+            );
+            ProgramEvent::ProfileRecovered {
+                profile: *profile.info().key(),
+                from: profile.authority,
+                to: profile.recovery.new_authority,
+            }
+            .emit()?;
+            profile.authority = profile.recovery.new_authority;
+            profile.recovery = PendingRecovery::default();
+            // The lost key may have set a session key.
+            profile.delegate = Pubkey::new_from_array([0; 32]);
+            profile.delegate_expiry = 0;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`FinalizeRecovery`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Finalizes a profile's recovery.
+    #[derive(Debug)]
+    pub struct FinalizeRecoveryCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 1],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> FinalizeRecoveryCPI<'a, AI> {
+        /// Finalizes a profile's recovery.
+        pub fn new(player_profile: impl Into<MaybeOwned<'a, AI>>) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<FinalizeRecovery>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            FinalizeRecoveryData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 2> for FinalizeRecoveryCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = FinalizeRecovery;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 2]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [accounts.next().unwrap(), program_account],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`FinalizeRecovery`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::InstructionSet;
+
+    /// Finalizes the recovery of `player_profile`.
+    pub fn finalize_recovery<'a>(program_id: Pubkey, player_profile: Pubkey) -> InstructionSet<'a> {
+        InstructionSet {
+            instructions: vec![
+                FinalizeRecoveryCPI::new(SolanaAccountMeta::new(player_profile, false))
+                    .unwrap()
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
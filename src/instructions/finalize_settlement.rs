@@ -0,0 +1,230 @@
+use crate::accounts::ProgramConfig;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Pays out a [`SettleSignedGame`](crate::instructions::SettleSignedGame) settlement once its dispute window passes,
+/// returning the settler's bond. Anyone can send it.
+#[derive(Debug)]
+pub enum FinalizeSettlement {}
+
+impl<AI> Instruction<AI> for FinalizeSettlement {
+    type Accounts = FinalizeSettlementAccounts<AI>;
+    type Data = ();
+    type ReturnType = ();
+}
+
+/// Accounts for [`FinalizeSettlement`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct FinalizeSettlementAccounts<AI> {
+    /// The program config, for the dispute window.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The settled game.
+    /// Left for [`ArchiveGame`](crate::instructions::ArchiveGame).
+    #[validate(
+        writable,
+        custom = self.game.settlement.is_final(
+            Clock::get()?.unix_timestamp,
+            self.config.settlement_dispute_window,
+        ),
+    )]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The first player's profile.
+    #[validate(writable, custom = &self.game.player1 == self.player1_profile.info().key())]
+    pub player1_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The second player's profile.
+    #[validate(writable, custom = &self.game.player2 == self.player2_profile.info().key())]
+    pub player2_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The settler, who gets their bond back.
+    #[validate(writable, custom = self.settler.key() == &self.game.settlement.settler)]
+    pub settler: AI,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+    use crate::instructions::pay_out_settlement;
+
+    impl<'a, AI> InstructionProcessor<AI, FinalizeSettlement> for FinalizeSettlement
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <FinalizeSettlement as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <FinalizeSettlement as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<FinalizeSettlement as Instruction<AI>>::ReturnType> {
+            log_event!(
+                info,
+                "settlement_finalized",
+                result = accounts.game.settlement.result
+            );
+            ProgramEvent::SettlementFinalized {
+                game: *accounts.game.info().key(),
+                settler: *accounts.settler.key(),
+            }
+            .emit()?;
+            // The game account held the bond the same way a vault holds wagers.
+            WagerVault::pay_out(
+                accounts.game.info(),
+                &accounts.settler,
+                accounts.game.settlement.bond,
+            )?;
+            let result = accounts.game.settlement.result;
+            pay_out_settlement(
+                &mut accounts.game,
+                accounts.game_signer.info(),
+                &mut accounts.player1_profile,
+                &mut accounts.player2_profile,
+                result,
+                Clock::get()?.unix_timestamp,
+            )
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`FinalizeSettlement`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Finalizes a signed settlement.
+    #[derive(Debug)]
+    pub struct FinalizeSettlementCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> FinalizeSettlementCPI<'a, AI> {
+        /// Finalizes a signed settlement.
+        pub fn new(
+            config: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            player1_profile: impl Into<MaybeOwned<'a, AI>>,
+            player2_profile: impl Into<MaybeOwned<'a, AI>>,
+            settler: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<FinalizeSettlement>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    config.into(),
+                    game.into(),
+                    game_signer.into(),
+                    player1_profile.into(),
+                    player2_profile.into(),
+                    settler.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for FinalizeSettlementCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = FinalizeSettlement;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`FinalizeSettlement`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, InstructionSet};
+
+    /// Finalizes the settlement of `game` once its dispute window has passed.
+    pub fn finalize_settlement<'a>(
+        program_id: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        player1_profile: Pubkey,
+        player2_profile: Pubkey,
+        settler: Pubkey,
+    ) -> InstructionSet<'a> {
+        let (config, _) = derive_config(&program_id);
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        InstructionSet {
+            instructions: vec![
+                FinalizeSettlementCPI::new(
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(player1_profile, false),
+                    SolanaAccountMeta::new(player2_profile, false),
+                    SolanaAccountMeta::new(settler, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::accounts::SocialGraph;
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Adds a profile to a profile's social graph.
+#[derive(Debug)]
+pub enum Follow {}
+
+impl<AI> Instruction<AI> for Follow {
+    type Accounts = FollowAccounts<AI>;
+    type Data = FollowData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`Follow`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct FollowAccounts<AI> {
+    /// The authority for the follower's profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The follower's profile.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The follower's social graph.
+    #[validate(writable, custom = &self.social_graph.profile == self.player_profile.info().key())]
+    pub social_graph: Box<DataAccount<AI, TutorialAccounts, SocialGraph>>,
+    /// The profile to follow.
+    pub followed: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`Follow`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct FollowData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, Follow> for Follow
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <Follow as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <Follow as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<Follow as Instruction<AI>>::ReturnType> {
+            if !accounts.social_graph.follow(accounts.followed.info().key()) {
+                return Err(custom_error!(
+                    "Profile is already followed, is the follower, or the follow list is full"
+                )
+                .into());
+            }
+            ProgramEvent::Followed {
+                social_graph: *accounts.social_graph.info().key(),
+                followed: *accounts.followed.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`Follow`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Follows a profile.
+    #[derive(Debug)]
+    pub struct FollowCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 4],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> FollowCPI<'a, AI> {
+        /// Follows a profile.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            social_graph: impl Into<MaybeOwned<'a, AI>>,
+            followed: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<Follow>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            FollowData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    social_graph.into(),
+                    followed.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 5> for FollowCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = Follow;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`Follow`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_social_graph, HashedSigner, InstructionSet};
+
+    /// Follows `followed` from `player_profile`'s social graph.
+    pub fn follow<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        followed: Pubkey,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let (social_graph, _) = derive_social_graph(&program_id, player_profile);
+        InstructionSet {
+            instructions: vec![
+                FollowCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(social_graph, false),
+                    SolanaAccountMeta::new_readonly(followed, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
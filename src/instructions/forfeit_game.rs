@@ -1,17 +1,25 @@
-use crate::accounts::Player;
+use crate::accounts::{PayoutAllowlist, Player, ProgramConfig};
+use crate::instructions::EloChange;
+use crate::log::log_event;
 use crate::pda::GameSignerSeeder;
 use crate::{Game, PlayerProfile, TutorialAccounts};
 use cruiser::prelude::*;
 use cruiser::solana_program::clock::Clock;
 
-/// Causes another player to forfeit the game if they run out of time for their turn.
+/// Causes a player to forfeit the game if they run out of time for their turn.
+/// Untimed games can't be forfeited.
+///
+/// Anyone can call this once the turn expires so games resolve even if the winner is offline.
+/// A caller other than the winner's authority is paid [`ProgramConfig::keeper_tip`] from the pot.
+/// The game is left for [`ArchiveGame`](crate::instructions::ArchiveGame) like one that was played out.
+/// Returns the [`EloChange`] with the winner as the player.
 #[derive(Debug)]
 pub enum ForfeitGame {}
 
 impl<AI> Instruction<AI> for ForfeitGame {
     type Accounts = ForfeitGameAccounts<AI>;
     type Data = ForfeitGameData;
-    type ReturnType = ();
+    type ReturnType = EloChange;
 }
 
 /// Accounts for [`ForfeitGame`]
@@ -19,18 +27,17 @@ impl<AI> Instruction<AI> for ForfeitGame {
 #[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
 #[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
 pub struct ForfeitGameAccounts<AI> {
-    /// The authority of the player calling the forfeit.
-    #[validate(signer)]
+    /// Whoever is calling the forfeit, receives the keeper tip if not the winner's authority.
+    #[validate(signer, writable)]
     pub authority: AI,
-    /// The profile of the calling player.
-    #[validate(custom = &self.player_profile.authority == self.authority.key())]
-    pub player_profile: DataAccount<AI, TutorialAccounts, PlayerProfile>,
+    /// The profile of the winning player.
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
     /// The other player's profile.
-    pub other_profile: DataAccount<AI, TutorialAccounts, PlayerProfile>,
+    pub other_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
     /// The game the other player has forfeited.
     #[validate(
-        custom = self.game.turn_length == 0
-            || self.game.last_turn.saturating_add(self.game.turn_length) < Clock::get()?.unix_timestamp,
+        writable,
+        custom = self.game.is_turn_expired(Clock::get()?.unix_timestamp),
         custom = match self.game.next_play {
             Player::One => self.player_profile.info().key() == &self.game.player2,
             Player::Two => self.player_profile.info().key() == &self.game.player1,
@@ -40,15 +47,31 @@ pub struct ForfeitGameAccounts<AI> {
             Player::Two => self.other_profile.info().key() == &self.game.player2,
         },
     )]
-    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
     /// The game's signer.
     #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
     pub game_signer: Seeds<AI, GameSignerSeeder>,
     /// Where the funds should go to.
+    /// Must be the winning player's authority or payout address, or on their payout allowlist if they have one.
+    /// Can't be the game, its signer or any other account owned by the program, see [`TutorialError`](crate::error::TutorialError).
     #[validate(writable)]
     pub funds_to: AI,
     /// The system program
     pub system_program: SystemProgram<AI>,
+    /// The program config, for the keeper tip.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The winning player's payout allowlist. Required if their profile has one, it's only read then.
+    #[from(data = player_profile.has_payout_allowlist())]
+    #[validate(custom = self.player_profile.is_valid_payout(
+        self.payout_allowlist.as_ref().map(|allowlist| (allowlist.info().key(), &**allowlist)),
+        self.funds_to.key(),
+        Clock::get()?.unix_timestamp,
+    ))]
+    pub payout_allowlist: Option<ReadOnlyDataAccount<AI, TutorialAccounts, PayoutAllowlist>>,
+    /// The game's [`settlement_hook`](crate::settlement_hook) program followed by the accounts it needs.
+    /// Only needed if the game has a hook.
+    pub settlement_hook: Rest<AI>,
 }
 
 /// Data for [`ForfeitGame`]
@@ -59,7 +82,11 @@ pub struct ForfeitGameData {}
 mod processor {
     use super::*;
     use crate::accounts::update_elo;
-    use std::iter::once;
+    use crate::accounts::GameResult;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+    use crate::instructions::{check_funds_to, set_return};
+    use crate::settlement_hook::{notify_settlement, GameSettled};
 
     impl<'a, AI> InstructionProcessor<AI, ForfeitGame> for ForfeitGame
     where
@@ -84,51 +111,122 @@ mod processor {
             _data: Self::InstructionData,
             accounts: &mut <ForfeitGame as Instruction<AI>>::Accounts,
         ) -> CruiserResult<<ForfeitGame as Instruction<AI>>::ReturnType> {
-            // Get the seeds out of the signer account
-            let signer_seeds = accounts.game_signer.take_seed_set().unwrap();
-
-            msg!("Transferring");
-            // Need to separate this out because it will cause a borrow error if done in-line.
-            // Can also be avoided with `CPIUnchecked`
-            let transfer_amount = *accounts.game_signer.lamports();
-            // Transfer wager to forfeit-eer
-            accounts.system_program.transfer(
-                CPIChecked,
-                accounts.game_signer.info(),
+            check_funds_to(
                 &accounts.funds_to,
-                transfer_amount,
-                once(&signer_seeds),
+                accounts.game.info(),
+                accounts.game_signer.info(),
             )?;
+            let now = Clock::get()?.unix_timestamp;
+            let is_winner = accounts.authority.key() == &accounts.player_profile.authority;
+            // The vault's and the game's rent go back to the creator's profile.
+            let creator_won = accounts.game.creator == accounts.game.next_play.other();
 
-            msg!("Setting fields");
-            // Zero out the players so the game is dead.
-            // We will close the game but this prevents it from being re-opened in the same transaction and still being useful.
-            accounts.game.player1 = SystemProgram::<()>::KEY;
-            accounts.game.player2 = SystemProgram::<()>::KEY;
+            // Friendly games have no vault so there's nothing to transfer.
+            let (pot, transfer_amount) = if accounts.game.friendly {
+                (0, 0)
+            } else {
+                log_event!(debug, "transferring");
+                let pot = WagerVault::escrow(accounts.game_signer.info())?;
+                let keeper_tip = if is_winner {
+                    0
+                } else {
+                    accounts.config.keeper_tip(pot)
+                };
+                if keeper_tip > 0 {
+                    WagerVault::pay_out(
+                        accounts.game_signer.info(),
+                        &accounts.authority,
+                        keeper_tip,
+                    )?;
+                }
+                let transfer_amount = pot - keeper_tip;
+                // Transfer the rest of the pot to the winner, the vault's rent goes back to the creator's profile
+                WagerVault::pay_out(
+                    accounts.game_signer.info(),
+                    &accounts.funds_to,
+                    transfer_amount,
+                )?;
+                let creator_profile = if creator_won {
+                    accounts.player_profile.info()
+                } else {
+                    accounts.other_profile.info()
+                };
+                WagerVault::close(accounts.game_signer.info(), creator_profile)?;
+                (pot, transfer_amount)
+            };
 
-            // Set who gets the funds on close
-            accounts.game.set_fundee(accounts.funds_to.clone());
+            log_event!(debug, "setting_fields");
+            let result = GameResult::Won(accounts.game.next_play.other());
+            let game_key = *accounts.game.info().key();
+            let settled = GameSettled {
+                game: game_key,
+                signer_bump: accounts.game.signer_bump,
+                player1: accounts.game.player1,
+                player2: accounts.game.player2,
+                result,
+                forfeited: true,
+                pot,
+                ended_at: now,
+            };
+            // Leave the game for `ArchiveGame` like a played out one, finishing it clears the players.
+            let creator_profile = *if creator_won {
+                accounts.player_profile.info().key()
+            } else {
+                accounts.other_profile.info().key()
+            };
+            let archived = accounts
+                .game
+                .finish(&game_key, &creator_profile, result, now)?;
+            log_event!(
+                info,
+                "game_finished",
+                game = game_key,
+                leaf = archived.leaf()?
+            );
+
+            // The other player is the one who ran out of time.
+            let forfeited_stake = accounts.game.stake(accounts.game.next_play);
 
             accounts
                 .player_profile
                 .lamports_won
-                .saturating_add_assign(accounts.game.wager);
+                .saturating_add_assign(transfer_amount);
             accounts.player_profile.wins.saturating_add_assign(1);
+            // A keeper calling this says nothing about whether the winner is still around.
+            if is_winner {
+                accounts.player_profile.mark_active(now);
+            }
+            accounts.player_profile.leave_game(now);
 
             accounts
                 .other_profile
                 .lamports_lost
-                .saturating_add_assign(accounts.game.wager);
+                .saturating_add_assign(forfeited_stake);
             accounts.other_profile.losses.saturating_add_assign(1);
+            accounts.other_profile.leave_game(now);
 
+            let elo_before = (accounts.player_profile.elo, accounts.other_profile.elo);
             update_elo(
                 &mut accounts.player_profile.elo,
                 &mut accounts.other_profile.elo,
-                50.0, // 50 for forfeits to discourage them
+                PlayerProfile::FORFEIT_ELO_K,
                 true,
             );
 
-            Ok(())
+            ProgramEvent::GameEnded(settled.clone()).emit()?;
+            notify_settlement(
+                &accounts.game,
+                accounts.game_signer.info(),
+                accounts.settlement_hook.iter(),
+                &settled,
+            )?;
+
+            set_return(EloChange::new(
+                elo_before.0,
+                accounts.player_profile.elo,
+                elo_before.1,
+                accounts.other_profile.elo,
+            ))
         }
     }
 }
@@ -144,12 +242,13 @@ mod cpi {
 
     /// Forfiets another player from a game.
     #[derive(Debug)]
-    pub struct ForfeitGameCPI<'a, AI> {
-        accounts: [MaybeOwned<'a, AI>; 7],
+    pub struct ForfeitGameCPI<'a, AI, const N: usize> {
+        accounts: [MaybeOwned<'a, AI>; N],
         data: Vec<u8>,
     }
-    impl<'a, AI> ForfeitGameCPI<'a, AI> {
+    impl<'a, AI> ForfeitGameCPI<'a, AI, 8> {
         /// Forfiets another player from a game.
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             authority: impl Into<MaybeOwned<'a, AI>>,
             player_profile: impl Into<MaybeOwned<'a, AI>>,
@@ -158,6 +257,7 @@ mod cpi {
             game_signer: impl Into<MaybeOwned<'a, AI>>,
             funds_to: impl Into<MaybeOwned<'a, AI>>,
             system_program: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
         ) -> CruiserResult<Self> {
             let mut data = Vec::new();
             <TutorialInstructions as InstructionListItem<ForfeitGame>>::discriminant_compressed()
@@ -172,13 +272,48 @@ mod cpi {
                     game_signer.into(),
                     funds_to.into(),
                     system_program.into(),
+                    config.into(),
+                ],
+                data,
+            })
+        }
+    }
+    impl<'a, AI> ForfeitGameCPI<'a, AI, 9> {
+        /// Forfiets another player from a game, paying out to an address on the winner's payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_with_payout_allowlist(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ForfeitGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            ForfeitGameData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    other_profile.into(),
+                    game.into(),
+                    game_signer.into(),
+                    funds_to.into(),
+                    system_program.into(),
+                    config.into(),
+                    payout_allowlist.into(),
                 ],
                 data,
             })
         }
     }
 
-    impl<'a, AI> CPIClientStatic<'a, 8> for ForfeitGameCPI<'a, AI>
+    impl<'a, AI> CPIClientStatic<'a, 9> for ForfeitGameCPI<'a, AI, 8>
     where
         AI: ToSolanaAccountMeta,
     {
@@ -189,7 +324,7 @@ mod cpi {
         fn instruction(
             self,
             program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
-        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 8]> {
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
             let program_account = program_account.into();
             let instruction = SolanaInstruction {
                 program_id: *program_account.meta_key(),
@@ -212,22 +347,141 @@ mod cpi {
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
+                    accounts.next().unwrap(),
                     program_account,
                 ],
             }
         }
     }
+    impl<'a, AI> CPIClientStatic<'a, 10> for ForfeitGameCPI<'a, AI, 9>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ForfeitGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 10]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+
+    /// Forfiets another player from a game with the optional accounts picked at runtime.
+    #[derive(Debug)]
+    pub struct ForfeitGameCPIDynamic<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ForfeitGameCPIDynamic<'a, AI> {
+        /// Forfiets another player from a game.
+        /// `payout_allowlist` is needed if the winner has a payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: Option<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ForfeitGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            ForfeitGameData {}.serialize(&mut data)?;
+            let mut accounts = vec![
+                authority.into(),
+                player_profile.into(),
+                other_profile.into(),
+                game.into(),
+                game_signer.into(),
+                funds_to.into(),
+                system_program.into(),
+                config.into(),
+            ];
+            accounts.extend(payout_allowlist);
+            Ok(Self { accounts, data })
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for ForfeitGameCPIDynamic<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ForfeitGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use client::*;
 
 /// Client for [`ForfeitGame`]
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod client {
     use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
 
-    /// Forfeits another player from a game.
+    /// Forfeits a player from a game, `player_profile` being the winner's profile.
+    /// Only succeeds once [`Game::is_turn_expired`], so check that before sending.
+    /// If `authority` is not the winner's authority it is paid the keeper tip.
+    /// `payout_allowlist` must be set if the winner's profile has one.
+    #[allow(clippy::too_many_arguments)]
     pub fn forfeit_game<'a>(
         program_id: Pubkey,
         authority: impl Into<HashedSigner<'a>>,
@@ -236,28 +490,48 @@ mod client {
         game: Pubkey,
         game_signer_bump: u8,
         funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
     ) -> InstructionSet<'a> {
         let authority = authority.into();
-        InstructionSet {
-            instructions: vec![
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        let (config, _) = derive_config(&program_id);
+        let instruction = match payout_allowlist {
+            Some(payout_allowlist) => {
+                ForfeitGameCPI::new_with_payout_allowlist(
+                    SolanaAccountMeta::new(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(other_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(payout_allowlist, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction
+            }
+            None => {
                 ForfeitGameCPI::new(
-                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(authority.pubkey(), true),
                     SolanaAccountMeta::new(player_profile, false),
                     SolanaAccountMeta::new(other_profile, false),
                     SolanaAccountMeta::new(game, false),
-                    SolanaAccountMeta::new(
-                        GameSignerSeeder { game }
-                            .create_address(&program_id, game_signer_bump)
-                            .unwrap(),
-                        false,
-                    ),
+                    SolanaAccountMeta::new(game_signer, false),
                     SolanaAccountMeta::new(funds_to, false),
                     SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(config, false),
                 )
                 .unwrap()
                 .instruction(SolanaAccountMeta::new_readonly(program_id, false))
-                .instruction,
-            ],
+                .instruction
+            }
+        };
+        InstructionSet {
+            instructions: vec![instruction],
             signers: [authority].into_iter().collect(),
         }
     }
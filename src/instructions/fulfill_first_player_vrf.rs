@@ -0,0 +1,193 @@
+use crate::error::custom_error;
+use crate::switchboard::SWITCHBOARD_PROGRAM_ID;
+use crate::{Game, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// The VRF callback for [`RequestFirstPlayerVrf`](crate::instructions::RequestFirstPlayerVrf),
+/// picks who moves first from the verified randomness.
+///
+/// The oracle calls this once the randomness is verified but anyone can, it only reads the VRF account.
+/// If the game already started the first player's turn starts now.
+#[derive(Debug)]
+pub enum FulfillFirstPlayerVrf {}
+
+impl<AI> Instruction<AI> for FulfillFirstPlayerVrf {
+    type Accounts = FulfillFirstPlayerVrfAccounts<AI>;
+    type Data = FulfillFirstPlayerVrfData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`FulfillFirstPlayerVrf`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct FulfillFirstPlayerVrfAccounts<AI> {
+    /// The game waiting on `vrf`.
+    #[validate(
+        writable,
+        custom = self.game.is_first_player_pending(),
+        custom = &self.game.first_player_vrf == self.vrf.key(),
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// The VRF account holding the randomness.
+    #[validate(custom = *self.vrf.owner() == SWITCHBOARD_PROGRAM_ID)]
+    pub vrf: AI,
+}
+
+/// Data for [`FulfillFirstPlayerVrf`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct FulfillFirstPlayerVrfData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::Player;
+    use crate::event::ProgramEvent;
+    use crate::switchboard::vrf_result;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, FulfillFirstPlayerVrf> for FulfillFirstPlayerVrf
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <FulfillFirstPlayerVrf as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <FulfillFirstPlayerVrf as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<FulfillFirstPlayerVrf as Instruction<AI>>::ReturnType> {
+            let result =
+                vrf_result(&accounts.vrf.data()).ok_or(custom_error!("vrf has no result yet"))?;
+
+            accounts.game.next_play = if result[0] & 1 == 0 {
+                Player::One
+            } else {
+                Player::Two
+            };
+            accounts.game.first_player_vrf = Pubkey::new_from_array([0; 32]);
+
+            // The first turn couldn't be played while waiting, so it starts now
+            if accounts.game.is_started() {
+                accounts.game.last_turn = Clock::get()?.unix_timestamp;
+            }
+
+            ProgramEvent::FirstPlayerPicked {
+                game: *accounts.game.info().key(),
+                first_player: accounts.game.next_play,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`FulfillFirstPlayerVrf`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Settles a game's first player from its VRF.
+    #[derive(Debug)]
+    pub struct FulfillFirstPlayerVrfCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> FulfillFirstPlayerVrfCPI<'a, AI> {
+        /// Settles a game's first player from its VRF.
+        pub fn new(
+            game: impl Into<MaybeOwned<'a, AI>>,
+            vrf: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<FulfillFirstPlayerVrf>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            FulfillFirstPlayerVrfData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [game.into(), vrf.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for FulfillFirstPlayerVrfCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = FulfillFirstPlayerVrf;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`FulfillFirstPlayerVrf`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::InstructionSet;
+
+    /// Settles a game's first player from its VRF.
+    /// The instruction is also the callback to set when creating the VRF account.
+    pub fn fulfill_first_player_vrf<'a>(
+        program_id: Pubkey,
+        game: Pubkey,
+        vrf: Pubkey,
+    ) -> InstructionSet<'a> {
+        InstructionSet {
+            instructions: vec![
+                FulfillFirstPlayerVrfCPI::new(
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new_readonly(vrf, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::accounts::{House, ProgramConfig};
+use crate::pda::HouseVaultSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Tops up the house's bankroll. Only the config's admin can.
+#[derive(Debug)]
+pub enum FundHouseVault {}
+
+impl<AI> Instruction<AI> for FundHouseVault {
+    type Accounts = FundHouseVaultAccounts<AI>;
+    type Data = FundHouseVaultData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`FundHouseVault`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct FundHouseVaultAccounts<AI> {
+    /// The config's admin.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = &self.config.admin == self.admin.key())]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The house.
+    pub house: ReadOnlyDataAccount<AI, TutorialAccounts, House>,
+    /// The vault to fund.
+    #[validate(writable, data = (HouseVaultSeeder, self.house.vault_bump))]
+    pub house_vault: Seeds<AI, HouseVaultSeeder>,
+    /// Where the funds come from.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`FundHouseVault`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct FundHouseVaultData {
+    /// The lamports to add to the vault.
+    pub amount: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use std::iter::empty;
+
+    impl<'a, AI> InstructionProcessor<AI, FundHouseVault> for FundHouseVault
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = u64;
+
+        fn data_to_instruction_arg(
+            data: <FundHouseVault as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.amount))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            amount: Self::InstructionData,
+            accounts: &mut <FundHouseVault as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<FundHouseVault as Instruction<AI>>::ReturnType> {
+            accounts.system_program.transfer(
+                CPIChecked,
+                &accounts.funder,
+                accounts.house_vault.info(),
+                amount,
+                empty(),
+            )?;
+            ProgramEvent::HouseVaultFunded {
+                funder: *accounts.funder.key(),
+                amount,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`FundHouseVault`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Tops up the house's bankroll.
+    #[derive(Debug)]
+    pub struct FundHouseVaultCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> FundHouseVaultCPI<'a, AI> {
+        /// Tops up the house's bankroll.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            amount: u64,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<FundHouseVault>>::discriminant_compressed(
+            )
+            .serialize(&mut data)?;
+            FundHouseVaultData { amount }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    house.into(),
+                    house_vault.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for FundHouseVaultCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = FundHouseVault;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`FundHouseVault`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_config, derive_house, derive_house_vault, HashedSigner, InstructionSet,
+    };
+
+    /// Tops up the house's bankroll with `amount` lamports from `funder`.
+    pub fn fund_house_vault<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        amount: u64,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, _) = derive_config(&program_id);
+        let (house, _) = derive_house(&program_id);
+        let (house_vault, _) = derive_house_vault(&program_id);
+        InstructionSet {
+            instructions: vec![
+                FundHouseVaultCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(house, false),
+                    SolanaAccountMeta::new(house_vault, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    amount,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::accounts::{GameResult, Player};
+use crate::{Game, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Returns a [`GameSummary`] of a game without writing to anything,
+/// so other programs can check a game through CPI without depending on [`Game`]'s layout.
+#[derive(Debug)]
+pub enum GetGameState {}
+
+impl<AI> Instruction<AI> for GetGameState {
+    type Accounts = GetGameStateAccounts<AI>;
+    type Data = GetGameStateData;
+    type ReturnType = GameSummary;
+}
+
+/// Accounts for [`GetGameState`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct GetGameStateAccounts<AI> {
+    /// The game to summarize.
+    pub game: Box<ReadOnlyDataAccount<AI, TutorialAccounts, Game>>,
+}
+
+/// Data for [`GetGameState`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct GetGameStateData {}
+
+/// A game's state returned by [`GetGameState`].
+/// Fields are only ever appended so callers built against an older version still decode it.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct GameSummary {
+    /// Whether both players have joined.
+    pub started: bool,
+    /// The player to move.
+    pub next_play: Player,
+    /// How the game ended, [`None`] while it's being played.
+    pub result: Option<GameResult>,
+    /// When the player to move runs out of time, see [`Game::turn_deadline`].
+    pub deadline: Option<UnixTimestamp>,
+    /// The wagers held for the game, see [`Game::pot`].
+    pub pot: u64,
+}
+impl GameSummary {
+    /// Summarizes `game`.
+    pub fn new(game: &Game) -> Self {
+        Self {
+            started: game.is_started(),
+            next_play: game.next_play,
+            result: game.result(),
+            deadline: game.turn_deadline(),
+            pot: game.pot(),
+        }
+    }
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::instructions::set_return;
+
+    impl<'a, AI> InstructionProcessor<AI, GetGameState> for GetGameState
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <GetGameState as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <GetGameState as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<GetGameState as Instruction<AI>>::ReturnType> {
+            set_return(GameSummary::new(&accounts.game))
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`GetGameState`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::error::custom_error;
+    use crate::TutorialInstructions;
+    use cruiser::solana_program::program::get_return_data;
+
+    /// Gets the state of a game.
+    #[derive(Debug)]
+    pub struct GetGameStateCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 1],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> GetGameStateCPI<'a, AI> {
+        /// Gets the state of a game.
+        pub fn new(game: impl Into<MaybeOwned<'a, AI>>) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<GetGameState>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            GetGameStateData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [game.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 2> for GetGameStateCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = GetGameState;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 2]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [game] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [game, program_account],
+            }
+        }
+    }
+
+    impl GameSummary {
+        /// Reads the summary a [`GetGameState`] CPI into `program_id` just returned.
+        pub fn from_return_data(program_id: &Pubkey) -> CruiserResult<Self> {
+            match get_return_data() {
+                Some((program, data)) if &program == program_id => Ok(Self::try_from_slice(&data)?),
+                _ => Err(custom_error!("no game summary returned by {}", program_id).into()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`GetGameState`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::InstructionSet;
+
+    /// Gets the state of `game`. Meant to be simulated, decode the result with
+    /// [`decode_return_data`](crate::client::decode_return_data).
+    pub fn get_game_state<'a>(program_id: Pubkey, game: Pubkey) -> InstructionSet<'a> {
+        InstructionSet {
+            instructions: vec![
+                GetGameStateCPI::new(SolanaAccountMeta::new_readonly(game, false))
+                    .unwrap()
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
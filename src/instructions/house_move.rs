@@ -0,0 +1,325 @@
+use crate::accounts::House;
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
+use crate::log::log_event;
+use crate::pda::HouseVaultSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Makes the house's move in a game against the house, only the house's operator can.
+///
+/// Calls [`MakeMove`](crate::instructions::MakeMove) with the vault signing as the house profile's authority,
+/// so all the game's rules are checked there. Winnings are paid into the vault.
+/// The return data is left as the [`MoveOutcome`](crate::instructions::MoveOutcome) `MakeMove` set.
+#[derive(Debug)]
+pub enum HouseMove {}
+
+impl<AI> Instruction<AI> for HouseMove {
+    type Accounts = HouseMoveAccounts<AI>;
+    type Data = HouseMoveData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`HouseMove`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct HouseMoveAccounts<AI> {
+    /// The house's operator.
+    #[validate(signer)]
+    pub operator: AI,
+    /// The house.
+    #[validate(custom = &self.house.operator == self.operator.key())]
+    pub house: ReadOnlyDataAccount<AI, TutorialAccounts, House>,
+    /// The house's vault, the authority of its profile.
+    #[validate(writable, data = (HouseVaultSeeder, self.house.vault_bump))]
+    pub house_vault: Seeds<AI, HouseVaultSeeder>,
+    /// The house's profile.
+    #[validate(writable, custom = &self.house.profile == self.house_profile.key())]
+    pub house_profile: AI,
+    /// The game to make a move on. Checked by [`MakeMove`](crate::instructions::MakeMove).
+    #[validate(writable)]
+    pub game: AI,
+    /// This program, to call [`MakeMove`](crate::instructions::MakeMove).
+    pub tutorial_program: AI,
+    /// The signer for the game.
+    /// Only needed if will win or draw the game.
+    #[validate(writable(IfSome))]
+    pub game_signer: Option<AI>,
+    /// The other player's profile.
+    /// Only needed if will win or draw the game.
+    #[validate(writable(IfSome))]
+    pub other_profile: Option<AI>,
+    /// Only needed if will win or draw the game.
+    pub system_program: Option<AI>,
+}
+
+/// Data for [`HouseMove`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct HouseMoveData {
+    /// The move to make.
+    pub mov: MakeMoveData,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::instructions::{sign_as_authority, MakeMoveCPIDynamic};
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, HouseMove> for HouseMove
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = MakeMoveData;
+
+        fn data_to_instruction_arg(
+            data: <HouseMove as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.mov))
+        }
+
+        fn process(
+            program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <HouseMove as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<HouseMove as Instruction<AI>>::ReturnType> {
+            if accounts.tutorial_program.key() != program_id {
+                return Err(custom_error!("tutorial_program must be this program").into());
+            }
+
+            let vault_seeds = accounts.house_vault.take_seed_set().unwrap();
+            let vault = accounts.house_vault.info();
+            let cpi = match (
+                &accounts.game_signer,
+                &accounts.other_profile,
+                &accounts.system_program,
+            ) {
+                (None, None, None) => {
+                    MakeMoveCPIDynamic::new(vault, &accounts.house_profile, &accounts.game, data)?
+                }
+                (Some(game_signer), Some(other_profile), Some(system_program)) => {
+                    MakeMoveCPIDynamic::new_win(
+                        vault,
+                        &accounts.house_profile,
+                        &accounts.game,
+                        game_signer,
+                        other_profile,
+                        vault,
+                        system_program,
+                        None,
+                        data,
+                    )?
+                }
+                _ => {
+                    return Err(custom_error!(
+                        "game_signer, other_profile, and system_program go together"
+                    )
+                    .into())
+                }
+            };
+
+            log_event!(debug, "making_house_move");
+            // The vault can't sign the outer transaction, it signs the call with its seeds
+            let InstructionAndAccounts {
+                instruction,
+                accounts: cpi_accounts,
+            } = sign_as_authority(cpi.instruction(&accounts.tutorial_program));
+            CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&vault_seeds))?;
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`HouseMove`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Makes the house's move.
+    #[derive(Debug)]
+    pub struct HouseMoveCPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> HouseMoveCPI<'a, AI> {
+        /// Makes a house move that won't win or draw the game.
+        pub fn new(
+            operator: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            house_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            tutorial_program: impl Into<MaybeOwned<'a, AI>>,
+            house_move_data: &HouseMoveData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<HouseMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            house_move_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: vec![
+                    operator.into(),
+                    house.into(),
+                    house_vault.into(),
+                    house_profile.into(),
+                    game.into(),
+                    tutorial_program.into(),
+                ],
+                data,
+            })
+        }
+
+        /// Makes a house move that will win or draw the game.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_win(
+            operator: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            house_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            tutorial_program: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            house_move_data: &HouseMoveData,
+        ) -> CruiserResult<Self> {
+            let mut out = Self::new(
+                operator,
+                house,
+                house_vault,
+                house_profile,
+                game,
+                tutorial_program,
+                house_move_data,
+            )?;
+            out.accounts.extend([
+                game_signer.into(),
+                other_profile.into(),
+                system_program.into(),
+            ]);
+            Ok(out)
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for HouseMoveCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = HouseMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`HouseMove`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_game_signer, derive_house, derive_house_profile, derive_house_vault, HashedSigner,
+        InstructionSet,
+    };
+
+    /// Makes a house move that won't win or draw the game.
+    pub fn house_move<'a>(
+        program_id: Pubkey,
+        operator: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        data: MakeMoveData,
+    ) -> InstructionSet<'a> {
+        let operator = operator.into();
+        let (house, _) = derive_house(&program_id);
+        let (house_vault, _) = derive_house_vault(&program_id);
+        let (house_profile, _) = derive_house_profile(&program_id);
+        InstructionSet {
+            instructions: vec![
+                HouseMoveCPI::new(
+                    SolanaAccountMeta::new_readonly(operator.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(house, false),
+                    SolanaAccountMeta::new(house_vault, false),
+                    SolanaAccountMeta::new(house_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new_readonly(program_id, false),
+                    &HouseMoveData { mov: data },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [operator].into_iter().collect(),
+        }
+    }
+
+    /// Makes a house move that will win or draw the game.
+    pub fn house_move_win<'a>(
+        program_id: Pubkey,
+        operator: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        other_profile: Pubkey,
+        data: MakeMoveData,
+    ) -> InstructionSet<'a> {
+        let operator = operator.into();
+        let (house, _) = derive_house(&program_id);
+        let (house_vault, _) = derive_house_vault(&program_id);
+        let (house_profile, _) = derive_house_profile(&program_id);
+        let (game_signer, _) = derive_game_signer(&program_id, game);
+        InstructionSet {
+            instructions: vec![
+                HouseMoveCPI::new_win(
+                    SolanaAccountMeta::new_readonly(operator.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(house, false),
+                    SolanaAccountMeta::new(house_vault, false),
+                    SolanaAccountMeta::new(house_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new_readonly(program_id, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(other_profile, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &HouseMoveData { mov: data },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [operator].into_iter().collect(),
+        }
+    }
+}
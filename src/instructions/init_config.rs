@@ -0,0 +1,248 @@
+use crate::accounts::ProgramConfig;
+use crate::pda::ConfigSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+
+/// Creates the program config. Can only be done once as the config lives at a fixed PDA.
+#[derive(Debug)]
+pub enum InitConfig {}
+
+impl<AI> Instruction<AI> for InitConfig {
+    type Accounts = InitConfigAccounts<AI>;
+    type Data = InitConfigData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`InitConfig`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(
+    data = (init_data: InitConfigData),
+    custom = init_data.keeper_tip_bps <= ProgramConfig::MAX_KEEPER_TIP_BPS,
+    custom = init_data.max_house_exposure_bps <= ProgramConfig::MAX_HOUSE_EXPOSURE_BPS,
+    custom = init_data.elo_decay_period >= 0,
+)]
+#[validate(
+    generics = [<'a> where AI: ToSolanaAccountInfo<'a>],
+    data = (init_data: InitConfigData),
+)]
+pub struct InitConfigAccounts<AI> {
+    /// The admin of the new config.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The config to create.
+    /// Creating it with the PDA's seeds checks that this is the config address.
+    #[from(data = ProgramConfig {
+        fee_vault_bump: init_data.fee_vault_bump,
+        ..ProgramConfig::new(
+            admin.key(),
+            init_data.config_bump,
+            init_data.keeper_tip_bps,
+            init_data.max_house_wager,
+            init_data.max_house_exposure_bps,
+            init_data.elo_decay_period,
+            init_data.elo_decay_step,
+        )
+    })]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(ConfigSeeder, init_data.config_bump)),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub config: Box<InitAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The funder for the config's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`InitConfig`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitConfigData {
+    /// The bump seed of the config's PDA.
+    pub config_bump: u8,
+    /// The bump seed of the [`FeeVaultSeeder`](crate::pda::FeeVaultSeeder) PDA.
+    pub fee_vault_bump: u8,
+    /// The starting [`ProgramConfig::keeper_tip_bps`].
+    pub keeper_tip_bps: u16,
+    /// The starting [`ProgramConfig::max_house_wager`].
+    pub max_house_wager: u64,
+    /// The starting [`ProgramConfig::max_house_exposure_bps`].
+    pub max_house_exposure_bps: u16,
+    /// The starting [`ProgramConfig::elo_decay_period`].
+    pub elo_decay_period: UnixTimestamp,
+    /// The starting [`ProgramConfig::elo_decay_step`].
+    pub elo_decay_step: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, InitConfig> for InitConfig
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = InitConfigData;
+        type ValidateData = InitConfigData;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <InitConfig as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), data, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <InitConfig as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<InitConfig as Instruction<AI>>::ReturnType> {
+            // All initialization is handled in the accounts.
+            ProgramEvent::ConfigInitialized {
+                config: *accounts.config.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`InitConfig`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates the program config.
+    #[derive(Debug)]
+    pub struct InitConfigCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 4],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> InitConfigCPI<'a, AI> {
+        /// Creates the program config.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            init_data: &InitConfigData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<InitConfig>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            init_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 5> for InitConfigCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = InitConfig;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`InitConfig`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_fee_vault, HashedSigner, InstructionSet};
+
+    /// Creates the program config.
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_config<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+        keeper_tip_bps: u16,
+        max_house_wager: u64,
+        max_house_exposure_bps: u16,
+        elo_decay_period: UnixTimestamp,
+        elo_decay_step: u64,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, config_bump) = derive_config(&program_id);
+        let (_, fee_vault_bump) = derive_fee_vault(&program_id);
+        InstructionSet {
+            instructions: vec![
+                InitConfigCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new(config, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &InitConfigData {
+                        config_bump,
+                        fee_vault_bump,
+                        keeper_tip_bps,
+                        max_house_wager,
+                        max_house_exposure_bps,
+                        elo_decay_period,
+                        elo_decay_step,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+}
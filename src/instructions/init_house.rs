@@ -0,0 +1,267 @@
+use crate::accounts::{House, ProgramConfig};
+use crate::pda::{HouseProfileSeeder, HouseSeeder, HouseVaultSeeder};
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Creates the house and its profile, registering the operator that plays for it.
+/// Can only be done once by the config's admin as the house lives at a fixed PDA.
+///
+/// The vault starts empty, send it lamports to give the house a bankroll.
+#[derive(Debug)]
+pub enum InitHouse {}
+
+impl<AI> Instruction<AI> for InitHouse {
+    type Accounts = InitHouseAccounts<AI>;
+    type Data = InitHouseData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`InitHouse`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (init_data: InitHouseData))]
+#[validate(
+    generics = [<'a> where AI: ToSolanaAccountInfo<'a>],
+    data = (init_data: InitHouseData),
+)]
+pub struct InitHouseAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The key that will play for the house.
+    pub operator: AI,
+    /// The vault that will hold the house's bankroll.
+    #[validate(data = (HouseVaultSeeder, init_data.vault_bump))]
+    pub house_vault: Seeds<AI, HouseVaultSeeder>,
+    /// The house's profile to create. Its authority is the vault.
+    #[from(data = PlayerProfile::new(house_vault.info().key()))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(HouseProfileSeeder, init_data.profile_bump)),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub house_profile: Box<InitAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The house to create.
+    #[from(data = House::new(
+        operator.key(),
+        house_profile.info().key(),
+        init_data.vault_bump,
+        init_data.house_bump,
+    ))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(HouseSeeder, init_data.house_bump)),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub house: InitAccount<AI, TutorialAccounts, House>,
+    /// The funder for the house's and its profile's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`InitHouse`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitHouseData {
+    /// The bump seed of the house's PDA.
+    pub house_bump: u8,
+    /// The bump seed of the vault's PDA.
+    pub vault_bump: u8,
+    /// The bump seed of the house profile's PDA.
+    pub profile_bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, InitHouse> for InitHouse
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = InitHouseData;
+        type ValidateData = InitHouseData;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <InitHouse as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), data, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <InitHouse as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<InitHouse as Instruction<AI>>::ReturnType> {
+            // All initialization is handled in the accounts.
+            ProgramEvent::HouseInitialized {
+                house: *accounts.house.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`InitHouse`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates the house.
+    #[derive(Debug)]
+    pub struct InitHouseCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 8],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> InitHouseCPI<'a, AI> {
+        /// Creates the house.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            operator: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            house_profile: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            init_data: &InitHouseData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<InitHouse>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            init_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    operator.into(),
+                    house_vault.into(),
+                    house_profile.into(),
+                    house.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 9> for InitHouseCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = InitHouse;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`InitHouse`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_config, derive_house, derive_house_profile, derive_house_vault, HashedSigner,
+        InstructionSet,
+    };
+
+    /// Creates the house with `operator` playing for it.
+    pub fn init_house<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        operator: Pubkey,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, _) = derive_config(&program_id);
+        let (house_vault, vault_bump) = derive_house_vault(&program_id);
+        let (house_profile, profile_bump) = derive_house_profile(&program_id);
+        let (house, house_bump) = derive_house(&program_id);
+        InstructionSet {
+            instructions: vec![
+                InitHouseCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(operator, false),
+                    SolanaAccountMeta::new_readonly(house_vault, false),
+                    SolanaAccountMeta::new(house_profile, false),
+                    SolanaAccountMeta::new(house, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    &InitHouseData {
+                        house_bump,
+                        vault_bump,
+                        profile_bump,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+}
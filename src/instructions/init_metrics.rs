@@ -0,0 +1,227 @@
+use crate::accounts::{InstructionMetrics, ProgramConfig};
+use crate::pda::MetricsSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Creates the instruction usage metrics. Can only be done once by the config's admin
+/// as the metrics live at a fixed PDA.
+#[derive(Debug)]
+pub enum InitMetrics {}
+
+impl<AI> Instruction<AI> for InitMetrics {
+    type Accounts = InitMetricsAccounts<AI>;
+    type Data = InitMetricsData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`InitMetrics`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (bump: u8))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>], data = (bump: u8))]
+pub struct InitMetricsAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The metrics to create.
+    #[from(data = InstructionMetrics::new(bump))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(MetricsSeeder, bump)),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub metrics: Box<InitAccount<AI, TutorialAccounts, InstructionMetrics>>,
+    /// The funder for the metrics' rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`InitMetrics`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitMetricsData {
+    /// The bump seed of the metrics' PDA.
+    pub bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, InitMetrics> for InitMetrics
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = u8;
+        type ValidateData = u8;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <InitMetrics as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.bump, data.bump, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <InitMetrics as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<InitMetrics as Instruction<AI>>::ReturnType> {
+            // All initialization is handled in the accounts.
+            ProgramEvent::MetricsInitialized {
+                metrics: *accounts.metrics.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`InitMetrics`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Creates the instruction usage metrics.
+    #[derive(Debug)]
+    pub struct InitMetricsCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> InitMetricsCPI<'a, AI> {
+        /// Creates the instruction usage metrics.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            metrics: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            bump: u8,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<InitMetrics>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            InitMetricsData { bump }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    metrics.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for InitMetricsCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = InitMetrics;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`InitMetrics`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_metrics, HashedSigner, InstructionSet};
+
+    /// Creates the instruction usage metrics.
+    pub fn init_metrics<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, _) = derive_config(&program_id);
+        let (metrics, bump) = derive_metrics(&program_id);
+        InstructionSet {
+            instructions: vec![
+                InitMetricsCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(metrics, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    bump,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+
+    /// Appends the metrics account to each of `set`'s instructions for `program_id`
+    /// so they're counted in the [`InstructionMetrics`].
+    pub fn with_metrics(program_id: Pubkey, mut set: InstructionSet) -> InstructionSet {
+        let (metrics, _) = derive_metrics(&program_id);
+        for instruction in &mut set.instructions {
+            if instruction.program_id == program_id {
+                instruction
+                    .accounts
+                    .push(SolanaAccountMeta::new(metrics, false));
+            }
+        }
+        set
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Starts moving a profile to a new authority, replacing any recovery already in progress.
+/// Counts as the initiating guardian's approval. Once [`GuardianSet::threshold`](crate::accounts::GuardianSet::threshold)
+/// guardians approve with [`ApproveRecovery`](crate::instructions::ApproveRecovery) and [`PlayerProfile::RECOVERY_DELAY`] passes
+/// it can be finalized with [`FinalizeRecovery`](crate::instructions::FinalizeRecovery).
+/// The authority can cancel it in the meantime by setting the guardians with [`UpdateProfile`](crate::instructions::UpdateProfile).
+#[derive(Debug)]
+pub enum InitiateRecovery {}
+
+impl<AI> Instruction<AI> for InitiateRecovery {
+    type Accounts = InitiateRecoveryAccounts<AI>;
+    type Data = InitiateRecoveryData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`InitiateRecovery`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct InitiateRecoveryAccounts<AI> {
+    /// One of the profile's guardians.
+    #[validate(signer)]
+    pub guardian: AI,
+    /// The profile to recover.
+    #[validate(
+        writable,
+        custom = self.player_profile.guardians.threshold > 0,
+        custom = self.player_profile.guardians.index_of(self.guardian.key()).is_some(),
+    )]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`InitiateRecovery`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitiateRecoveryData {
+    /// The authority to move the profile to. Can't be the zero key.
+    pub new_authority: Pubkey,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::PendingRecovery;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, InitiateRecovery> for InitiateRecovery
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = InitiateRecoveryData;
+
+        fn data_to_instruction_arg(
+            data: <InitiateRecovery as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <InitiateRecovery as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<InitiateRecovery as Instruction<AI>>::ReturnType> {
+            if data.new_authority == Pubkey::new_from_array([0; 32]) {
+                return Err(custom_error!("new_authority can't be the zero key").into());
+            }
+            let index = accounts
+                .player_profile
+                .guardians
+                .index_of(accounts.guardian.key())
+                .unwrap();
+            log_event!(
+                info,
+                "recovery_initiated",
+                new_authority = data.new_authority
+            );
+            accounts.player_profile.recovery = PendingRecovery {
+                new_authority: data.new_authority,
+                approvals: 1 << index,
+                initiated_at: Clock::get()?.unix_timestamp,
+            };
+            ProgramEvent::RecoveryInitiated {
+                profile: *accounts.player_profile.info().key(),
+                guardian: *accounts.guardian.key(),
+                new_authority: data.new_authority,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`InitiateRecovery`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Starts recovering a profile to a new authority.
+    #[derive(Debug)]
+    pub struct InitiateRecoveryCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> InitiateRecoveryCPI<'a, AI> {
+        /// Starts recovering a profile to a new authority.
+        pub fn new(
+            guardian: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            recovery_data: &InitiateRecoveryData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<InitiateRecovery>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            recovery_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [guardian.into(), player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for InitiateRecoveryCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = InitiateRecovery;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`InitiateRecovery`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Starts recovering `player_profile` to `new_authority` as `guardian`.
+    pub fn initiate_recovery<'a>(
+        program_id: Pubkey,
+        guardian: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        new_authority: Pubkey,
+    ) -> InstructionSet<'a> {
+        let guardian = guardian.into();
+        InstructionSet {
+            instructions: vec![
+                InitiateRecoveryCPI::new(
+                    SolanaAccountMeta::new_readonly(guardian.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    &InitiateRecoveryData { new_authority },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [guardian].into_iter().collect(),
+        }
+    }
+}
@@ -1,98 +1,194 @@
+use crate::accounts::{Player, ProgramConfig};
+use crate::error::custom_error;
 use crate::pda::GameSignerSeeder;
 use crate::{Game, PlayerProfile, TutorialAccounts};
 use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+use cruiser::solana_program::sysvar::slot_hashes;
 
-/// Joins an already created game.
+/// Joins an already created game, starting it.
+/// Returns a [`GameStart`].
 #[derive(Debug)]
 pub enum JoinGame {}
 
 impl<AI> Instruction<AI> for JoinGame {
     type Accounts = JoinGameAccounts<AI>;
     type Data = JoinGameData;
-    type ReturnType = ();
+    type ReturnType = GameStart;
 }
 
 /// Accounts for [`JoinGame`]
 #[derive(AccountArgument, Debug)]
 #[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
-#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+#[validate(
+    generics = [<'a> where AI: ToSolanaAccountInfo<'a>],
+    data = (join_code: Vec<u8>),
+    custom = self.game.is_valid_join_code(&join_code),
+)]
 pub struct JoinGameAccounts<AI> {
     /// The authority of the joiner
     #[validate(signer)]
     pub authority: AI,
     /// The profile of the joiner
-    #[validate(custom = &self.player_profile.authority == self.authority.key())]
-    pub player_profile: ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>,
-    /// The game to join
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to join.
+    /// Whether it can still be joined, and by this profile, is checked when processing
+    /// so a joiner that loses a race to another gets [`TutorialError::GameAlreadyJoined`](crate::error::TutorialError::GameAlreadyJoined).
     #[validate(
         writable,
-        custom = !self.game.is_started(),
-        custom = self.game.is_valid_other_player(self.player_profile.info().key()),
+        custom = !self.game.is_lock_expired(Clock::get()?.unix_timestamp),
     )]
     pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
-    /// The signer of the game
+    /// The signer of the game, its [`WagerVault`](crate::accounts::WagerVault). Empty for friendly games.
     #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
     pub game_signer: Seeds<AI, GameSignerSeeder>,
-    /// The funder for the wager
+    /// The funder for the wager. Unused for friendly games.
+    /// Can be the same key as `authority` so only one signature is needed.
     #[validate(signer, writable)]
     pub wager_funder: AI,
     /// The system program
     pub system_program: SystemProgram<AI>,
+    /// The slot hashes sysvar, used to pick the first player if the game asks for it.
+    #[validate(custom = self.slot_hashes.key() == &slot_hashes::ID)]
+    pub slot_hashes: AI,
+    /// The program config, for the requirements on profiles joining wagered games.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The joiner's [`PlayerProfile::co_signer`].
+    /// Only needed if they have one and the joiner's wager is above their [`PlayerProfile::co_sign_threshold`].
+    #[from(data = player_profile.needs_co_signer(game.joiner_wager))]
+    #[validate(
+        signer(IfSome),
+        custom = self.co_signer.as_ref().map_or(true, |co_signer| co_signer.key() == &self.player_profile.co_signer),
+    )]
+    pub co_signer: Option<AI>,
 }
 
 /// Data for [`JoinGame`]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
-pub struct JoinGameData {}
+pub struct JoinGameData {
+    /// The code for a private game, see [`Game::join_code_hash`]. Ignored for public games.
+    pub join_code: Vec<u8>,
+}
+
+/// How a game was started by [`JoinGame`].
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct GameStart {
+    /// When the game started, the first turn's clock runs from here.
+    pub started_at: UnixTimestamp,
+    /// Who moves first, which may have been picked at random.
+    pub first_player: Player,
+}
 
 #[cfg(feature = "processor")]
 mod processor {
     use super::*;
-    use crate::accounts::Player;
-    use cruiser::solana_program::clock::Clock;
-    use std::iter::empty;
+    use crate::accounts::WagerVault;
+    use crate::error::TutorialError;
+    use crate::event::ProgramEvent;
+    use crate::instructions::set_return;
+    use cruiser::solana_program::hash::hashv;
+    use std::iter::{empty, once};
 
     impl<'a, AI> InstructionProcessor<AI, JoinGame> for JoinGame
     where
         AI: ToSolanaAccountInfo<'a>,
     {
         type FromAccountsData = ();
-        type ValidateData = ();
+        type ValidateData = Vec<u8>;
         type InstructionData = ();
 
         fn data_to_instruction_arg(
-            _data: <JoinGame as Instruction<AI>>::Data,
+            data: <JoinGame as Instruction<AI>>::Data,
         ) -> CruiserResult<(
             Self::FromAccountsData,
             Self::ValidateData,
             Self::InstructionData,
         )> {
-            Ok(((), (), ()))
+            Ok(((), data.join_code, ()))
         }
 
         fn process(
-            _program_id: &Pubkey,
+            program_id: &Pubkey,
             _data: Self::InstructionData,
             accounts: &mut <JoinGame as Instruction<AI>>::Accounts,
         ) -> CruiserResult<<JoinGame as Instruction<AI>>::ReturnType> {
+            // Checked before anything else so every joiner after the first gets the same error
+            if accounts.game.is_started() {
+                return Err(TutorialError::GameAlreadyJoined.into());
+            }
+            if !accounts
+                .game
+                .is_valid_other_player(accounts.player_profile.info().key())
+            {
+                return Err(custom_error!("profile can't join this game").into());
+            }
+
             // Set the other player
             *match accounts.game.creator {
                 Player::One => &mut accounts.game.player2,
                 Player::Two => &mut accounts.game.player1,
             } = *accounts.player_profile.info().key();
 
+            if accounts.game.random_first_player {
+                // Validators have some sway over slot hashes, fine for picking who goes first.
+                // Layout is a u64 length followed by (slot, hash) entries, newest first.
+                let slot_hashes = accounts.slot_hashes.data();
+                let recent_hash = slot_hashes
+                    .get(16..48)
+                    .ok_or(custom_error!("slot_hashes has no entries"))?;
+                // Mix in the game so games joined in the same slot don't all match
+                let seed = hashv(&[recent_hash, accounts.game.info().key().as_ref()]);
+                accounts.game.next_play = if seed.to_bytes()[0] & 1 == 0 {
+                    Player::One
+                } else {
+                    Player::Two
+                };
+            }
+
             // Start the game by setting the timestamp
-            accounts.game.last_turn = Clock::get()?.unix_timestamp;
+            let now = Clock::get()?.unix_timestamp;
+            accounts.game.last_turn = now;
+            accounts.player_profile.mark_game_joined(now);
 
-            // Transfer the wager to the game
-            accounts.system_program.transfer(
-                CPIChecked,
-                &accounts.wager_funder,
-                accounts.game_signer.info(),
-                accounts.game.wager,
-                empty(),
-            )?;
+            if !accounts.game.friendly {
+                accounts
+                    .config
+                    .check_ranked_profile(&accounts.player_profile, now)?;
+
+                // Transfer the wager to the game
+                accounts.system_program.transfer(
+                    CPIChecked,
+                    &accounts.wager_funder,
+                    accounts.game_signer.info(),
+                    accounts.game.joiner_wager,
+                    empty(),
+                )?;
+                let mut vault = DataAccount::<AI, TutorialAccounts, WagerVault>::from_accounts(
+                    program_id,
+                    &mut once(accounts.game_signer.info().clone()),
+                    (),
+                )?;
+                vault.deposit(accounts.game.joiner_wager);
+                vault.write_back(program_id)?;
+                accounts
+                    .player_profile
+                    .lamports_wagered
+                    .saturating_add_assign(accounts.game.joiner_wager);
+            }
 
-            Ok(())
+            ProgramEvent::GameJoined {
+                game: *accounts.game.info().key(),
+                profile: *accounts.player_profile.info().key(),
+                first_player: accounts.game.next_play,
+                started_at: now,
+            }
+            .emit()?;
+            set_return(GameStart {
+                started_at: now,
+                first_player: accounts.game.next_play,
+            })
         }
     }
 }
@@ -107,9 +203,10 @@ mod cpi {
     use crate::TutorialInstructions;
 
     /// CPI for [`JoinGame`]
+    /// `N` is 9 once [`co_signed`](JoinGameCPI::co_signed).
     #[derive(Debug)]
-    pub struct JoinGameCPI<'a, AI> {
-        accounts: [MaybeOwned<'a, AI>; 6],
+    pub struct JoinGameCPI<'a, AI, const N: usize = 8> {
+        accounts: [MaybeOwned<'a, AI>; N],
         data: Vec<u8>,
     }
     impl<'a, AI> JoinGameCPI<'a, AI> {
@@ -121,11 +218,14 @@ mod cpi {
             game_signer: impl Into<MaybeOwned<'a, AI>>,
             wager_funder: impl Into<MaybeOwned<'a, AI>>,
             system_program: impl Into<MaybeOwned<'a, AI>>,
+            slot_hashes: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            join_data: &JoinGameData,
         ) -> CruiserResult<Self> {
             let mut data = Vec::new();
             <TutorialInstructions as InstructionListItem<JoinGame>>::discriminant_compressed()
                 .serialize(&mut data)?;
-            JoinGameData {}.serialize(&mut data)?;
+            join_data.serialize(&mut data)?;
             Ok(Self {
                 accounts: [
                     authority.into(),
@@ -134,13 +234,47 @@ mod cpi {
                     game_signer.into(),
                     wager_funder.into(),
                     system_program.into(),
+                    slot_hashes.into(),
+                    config.into(),
                 ],
                 data,
             })
         }
+
+        /// Adds the joiner's co-signer, needed when their wager is above their [`PlayerProfile::co_sign_threshold`].
+        pub fn co_signed(self, co_signer: impl Into<MaybeOwned<'a, AI>>) -> JoinGameCPI<'a, AI, 9> {
+            let [a0, a1, a2, a3, a4, a5, a6, a7] = self.accounts;
+            JoinGameCPI {
+                accounts: [a0, a1, a2, a3, a4, a5, a6, a7, co_signer.into()],
+                data: self.data,
+            }
+        }
+    }
+    impl<'a, AI> JoinGameCPI<'a, AI>
+    where
+        AI: AccountInfo,
+    {
+        /// Joins a game passing on the accounts of an already parsed [`JoinGameAccounts`].
+        /// Leaves out any co-signer, add it with [`co_signed`](JoinGameCPI::co_signed).
+        pub fn from_accounts(
+            accounts: &'a JoinGameAccounts<AI>,
+            join_data: &JoinGameData,
+        ) -> CruiserResult<Self> {
+            Self::new(
+                &accounts.authority,
+                accounts.player_profile.info(),
+                accounts.game.info(),
+                accounts.game_signer.info(),
+                &accounts.wager_funder,
+                accounts.system_program.info(),
+                &accounts.slot_hashes,
+                accounts.config.info(),
+                join_data,
+            )
+        }
     }
 
-    impl<'a, AI> CPIClientStatic<'a, 7> for JoinGameCPI<'a, AI>
+    impl<'a, AI> CPIClientStatic<'a, 9> for JoinGameCPI<'a, AI>
     where
         AI: ToSolanaAccountMeta,
     {
@@ -151,7 +285,7 @@ mod cpi {
         fn instruction(
             self,
             program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
-        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
             let program_account = program_account.into();
             let instruction = SolanaInstruction {
                 program_id: *program_account.meta_key(),
@@ -173,22 +307,88 @@ mod cpi {
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
                     accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
                     program_account,
                 ],
             }
         }
     }
+
+    impl<'a, AI> CPIClientStatic<'a, 10> for JoinGameCPI<'a, AI, 9>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = JoinGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 10]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [a0, a1, a2, a3, a4, a5, a6, a7, a8] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [a0, a1, a2, a3, a4, a5, a6, a7, a8, program_account],
+            }
+        }
+    }
+
+    impl<'a, AI, const N: usize> CPIClientDynamic<'a> for JoinGameCPI<'a, AI, N>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = JoinGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts: Vec<_> = self.accounts.into();
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use client::*;
 
 /// Client for [`JoinGame`]
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod client {
     use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
 
-    /// Joins a game.
+    /// Joins a game. `join_code` is only needed for private games.
     pub fn join_game<'a>(
         program_id: Pubkey,
         authority: impl Into<HashedSigner<'a>>,
@@ -196,6 +396,7 @@ mod client {
         game: Pubkey,
         game_signer_bump: u8,
         wager_funder: impl Into<HashedSigner<'a>>,
+        join_code: Vec<u8>,
     ) -> InstructionSet<'a> {
         let authority = authority.into();
         let wager_funder = wager_funder.into();
@@ -203,7 +404,7 @@ mod client {
             instructions: vec![
                 JoinGameCPI::new(
                     SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(player_profile, false),
                     SolanaAccountMeta::new(game, false),
                     SolanaAccountMeta::new(
                         GameSignerSeeder { game }
@@ -213,6 +414,9 @@ mod client {
                     ),
                     SolanaAccountMeta::new(wager_funder.pubkey(), true),
                     SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(slot_hashes::ID, false),
+                    SolanaAccountMeta::new_readonly(derive_config(&program_id).0, false),
+                    &JoinGameData { join_code },
                 )
                 .unwrap()
                 .instruction(SolanaAccountMeta::new_readonly(program_id, false))
@@ -221,4 +425,85 @@ mod client {
             signers: [authority, wager_funder].into_iter().collect(),
         }
     }
+
+    /// Joins a game with the joiner's [`PlayerProfile::co_signer`] also signing,
+    /// needed when the joiner's wager is above their [`PlayerProfile::co_sign_threshold`].
+    /// `join_code` is only needed for private games.
+    #[allow(clippy::too_many_arguments)]
+    pub fn join_game_co_signed<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        co_signer: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        wager_funder: impl Into<HashedSigner<'a>>,
+        join_code: Vec<u8>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let co_signer = co_signer.into();
+        let wager_funder = wager_funder.into();
+        InstructionSet {
+            instructions: vec![
+                JoinGameCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new(wager_funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(slot_hashes::ID, false),
+                    SolanaAccountMeta::new_readonly(derive_config(&program_id).0, false),
+                    &JoinGameData { join_code },
+                )
+                .unwrap()
+                .co_signed(SolanaAccountMeta::new_readonly(co_signer.pubkey(), true))
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, co_signer, wager_funder].into_iter().collect(),
+        }
+    }
+
+    /// Joins a game with the authority paying the wager itself, so it is the only signer needed.
+    /// `join_code` is only needed for private games.
+    pub fn join_game_self_funded<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        join_code: Vec<u8>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                JoinGameCPI::new(
+                    SolanaAccountMeta::new(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(slot_hashes::ID, false),
+                    SolanaAccountMeta::new_readonly(derive_config(&program_id).0, false),
+                    &JoinGameData { join_code },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
 }
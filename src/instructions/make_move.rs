@@ -1,94 +1,111 @@
-use crate::accounts::{CurrentWinner, Player, Space};
+use crate::accounts::{GameResult, PayoutAllowlist, Player, PremoveOutcome};
+use crate::error::custom_error;
+use crate::instructions::EloChange;
+use crate::log::log_event;
 use crate::pda::GameSignerSeeder;
 use crate::{Game, PlayerProfile, TutorialAccounts};
 use cruiser::prelude::*;
 
-/// Makes a move on the board and handles wins.
+/// Makes a move on the board and handles wins and draws.
+/// Returns a [`MoveOutcome`].
+/// Superseded by [`MakeMoveV2`](crate::instructions::MakeMoveV2), which games created while
+/// [`ProgramConfig::reject_deprecated_instructions`](crate::accounts::ProgramConfig::reject_deprecated_instructions) is set require.
 #[derive(Debug)]
 pub enum MakeMove {}
 
 impl<AI> Instruction<AI> for MakeMove {
     type Accounts = MakeMoveAccounts<AI>;
     type Data = MakeMoveData;
-    type ReturnType = ();
+    type ReturnType = MoveOutcome;
 }
 
 /// Accounts for [`MakeMove`]
 #[derive(AccountArgument, Debug)]
 #[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
-#[validate(data = (mov: MakeMoveData), custom = is_valid_move(&*self.game, &mov))]
 pub struct MakeMoveAccounts<AI> {
-    /// The authority for the player
-    #[validate(signer)]
+    /// The authority for the player.
+    /// Must sign, unless the move is relayed with [`RelayMove`](crate::instructions::RelayMove) where an ed25519 signature stands in.
+    /// A program's PDA signs by calling this with `invoke_signed`, see [`sign_as_authority`](crate::instructions::sign_as_authority).
     pub authority: AI,
-    /// The player to make a move for
-    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
-    pub player_profile: DataAccount<AI, TutorialAccounts, PlayerProfile>,
+    /// The player to make a move for.
+    /// The authority may be the profile's [`delegate`](PlayerProfile::delegate) while it hasn't expired.
+    #[validate(writable)]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
     /// The game to make a move on.
-    #[validate(
-        writable,
-        custom = self.game.is_started(),
-        custom = match self.game.next_play {
-            Player::One => &self.game.player1 == self.player_profile.info().key(),
-            Player::Two => &self.game.player2 == self.player_profile.info().key(),
-        },
-    )]
-    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// Left for [`ArchiveGame`](crate::instructions::ArchiveGame) once the game ends.
+    /// Whose turn it is and whether the move is legal are checked when the move is played,
+    /// failing with a [`TutorialError`](crate::error::TutorialError) clients can match on.
+    #[validate(writable, custom = self.game.is_started())]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
     /// The signer for the game.
-    /// Only needed if will win the game.
+    /// Only needed if will win or draw the game.
     #[validate(
         writable(IfSome),
         data = IfSomeArg((GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump)),
     )]
     pub game_signer: Option<Seeds<AI, GameSignerSeeder>>,
-    /// The other player's profile.
-    /// Only needed if will win the game.
+    /// The other player's profile, can't be the player's own.
+    /// Only needed if will win or draw the game.
     #[validate(
         writable(IfSome),
+        custom = self.other_profile.as_ref().map_or(true, |profile| profile.info().key() != self.player_profile.info().key()),
         custom = match (self.other_profile.as_ref(), self.game.next_play) {
             (Some(profile), Player::One) => &self.game.player2 == profile.info().key(),
             (Some(profile), Player::Two) => &self.game.player1 == profile.info().key(),
             _ => true,
         },
     )]
-    pub other_profile: Option<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
-    /// Only needed if will win the game.
+    pub other_profile: Option<Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>>,
+    /// Where the winnings go.
+    /// Must be the player's authority or payout address, or on their payout allowlist if they have one.
+    /// Can't be the game, its signer or any other account owned by the program, see [`TutorialError`](crate::error::TutorialError).
+    /// Only needed if will win or draw the game.
     #[validate(writable(IfSome))]
     pub funds_to: Option<AI>,
-    /// Only needed if will win the game.
+    /// Unused since wagers are paid straight out of the [`WagerVault`](crate::accounts::WagerVault), kept so the account list doesn't change.
+    /// Only needed if will win or draw the game.
     pub system_program: Option<SystemProgram<AI>>,
+    /// The player's payout allowlist.
+    /// Only needed if will win or draw the game and the player's profile has one, it's only read then.
+    #[from(data = funds_to.is_some() && player_profile.has_payout_allowlist())]
+    pub payout_allowlist: Option<ReadOnlyDataAccount<AI, TutorialAccounts, PayoutAllowlist>>,
+    /// The game's [`settlement_hook`](crate::settlement_hook) program followed by the accounts it needs.
+    /// Only needed if will win or draw the game and the game has a hook.
+    pub settlement_hook: Rest<AI>,
 }
 
 /// Data for [`MakeMove`]
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq, OnChainSize)]
 pub struct MakeMoveData {
-    /// Index on the big board
+    /// Index on the big board. Must be `[0, 0]` for classic games.
     pub big_board: [u8; 2],
+    /// Index on the middle board. Must be `[0, 0]` for all but depth-3 games.
+    pub middle_board: [u8; 2],
     /// Index on the small board
     pub small_board: [u8; 2],
 }
 
-fn is_valid_move(game: &Game, mov: &MakeMoveData) -> bool {
-    // Verify valid with last move
-    (game.last_move == [3, 3]
-        || game.board.get(game.last_move).map_or(false, |board| {
-            board.current_winner().is_some() || mov.big_board == game.last_move
-        }))
-        && game
-            .board
-            .get(mov.big_board)
-            .and_then(|board| {
-                board
-                    .get(mov.small_board)
-                    .map(|space| space == &Space::Empty)
-            })
-            .unwrap_or(false)
+/// What a move did, returned by [`MakeMove`] and the instructions sharing its accounts.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct MoveOutcome {
+    /// How the game ended if the move ended it.
+    pub result: Option<GameResult>,
+    /// How the game's end moved the elo of the player that moved and the other player. Zero if it didn't end.
+    pub elo: EloChange,
 }
 
+#[cfg(feature = "processor")]
+pub(crate) use processor::{check_authority_signed, check_not_commit_reveal, play_move};
+
 #[cfg(feature = "processor")]
 mod processor {
     use super::*;
-    use crate::accounts::CurrentWinner;
+    use crate::accounts::WagerVault;
+    use crate::accounts::{update_elo, update_elo_with_score};
+    use crate::error::TutorialError;
+    use crate::event::ProgramEvent;
+    use crate::instructions::{check_funds_to, set_return};
+    use crate::settlement_hook::{notify_settlement, GameSettled};
     use cruiser::solana_program::clock::Clock;
 
     impl<'a, AI> InstructionProcessor<AI, MakeMove> for MakeMove
@@ -96,7 +113,7 @@ mod processor {
         AI: ToSolanaAccountInfo<'a>,
     {
         type FromAccountsData = ();
-        type ValidateData = MakeMoveData;
+        type ValidateData = ();
         type InstructionData = MakeMoveData;
 
         fn data_to_instruction_arg(
@@ -106,7 +123,7 @@ mod processor {
             Self::ValidateData,
             Self::InstructionData,
         )> {
-            Ok(((), data.clone(), data))
+            Ok(((), (), data))
         }
 
         fn process(
@@ -114,74 +131,303 @@ mod processor {
             data: Self::InstructionData,
             accounts: &mut <MakeMove as Instruction<AI>>::Accounts,
         ) -> CruiserResult<<MakeMove as Instruction<AI>>::ReturnType> {
-            let next_play = accounts.game.next_play;
-            accounts
-                .game
-                .board
-                .make_move(next_play, (data.big_board, (data.small_board, ())))?;
-
-            if accounts.game.board.current_winner() == Some(accounts.game.next_play) {
-                let game_signer = accounts.game_signer.as_mut().ok_or(GenericError::Custom {
-                    error: "no game_signer on win".to_string(),
-                })?;
-                let other_profile =
+            if accounts.game.rejects_deprecated {
+                return Err(TutorialError::DeprecatedInstruction.into());
+            }
+            check_authority_signed(accounts)?;
+            check_not_commit_reveal(accounts)?;
+            set_return(play_move(accounts, data)?)
+        }
+    }
+
+    /// Errors if the game is a commit-reveal game, which is played with [`CommitMove`](crate::instructions::CommitMove)
+    /// and [`RevealMove`](crate::instructions::RevealMove) instead.
+    pub(crate) fn check_not_commit_reveal<AI>(accounts: &MakeMoveAccounts<AI>) -> CruiserResult<()>
+    where
+        AI: AccountInfo,
+    {
+        if accounts.game.rules.commit_reveal {
+            Err(
+                custom_error!("Commit-reveal games are played with `CommitMove` and `RevealMove`")
+                    .into(),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Errors if the player's authority didn't sign the transaction.
+    pub(crate) fn check_authority_signed<AI>(accounts: &MakeMoveAccounts<AI>) -> CruiserResult<()>
+    where
+        AI: AccountInfo,
+    {
+        if accounts.authority.is_signer() {
+            Ok(())
+        } else {
+            Err(custom_error!("authority must sign").into())
+        }
+    }
+
+    /// Plays `data` for the player to move, paying out if it ends the game.
+    /// Also used by [`RevealMove`](crate::instructions::RevealMove) once the move is revealed
+    /// and [`RelayMove`](crate::instructions::RelayMove) once the move's signature is found.
+    /// Callers must check the authority approved the move and set the outcome as their return data.
+    pub(crate) fn play_move<'a, AI>(
+        accounts: &mut MakeMoveAccounts<AI>,
+        data: MakeMoveData,
+    ) -> CruiserResult<MoveOutcome>
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        let next_play = accounts.game.next_play;
+        let profile = accounts.player_profile.info().key();
+        if profile != accounts.game.player(next_play) {
+            return Err(if profile == accounts.game.player(next_play.other()) {
+                TutorialError::NotPlayersTurn.into()
+            } else {
+                custom_error!("profile isn't playing this game").into()
+            });
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if !accounts
+            .player_profile
+            .can_move(accounts.authority.key(), now)
+        {
+            return Err(TutorialError::AuthorityCantMove.into());
+        }
+        if !accounts.game.is_valid_move(&data) {
+            return Err(TutorialError::InvalidMove.into());
+        }
+        accounts.player_profile.mark_active(now);
+
+        accounts.game.make_move(
+            next_play,
+            data.big_board,
+            data.middle_board,
+            data.small_board,
+        )?;
+        ProgramEvent::MovePlayed {
+            game: *accounts.game.info().key(),
+            profile: *accounts.player_profile.info().key(),
+            player: next_play,
+            mov: data.clone(),
+        }
+        .emit()?;
+
+        // With no moves left the rules may still pick a winner.
+        let drawn = accounts.game.is_draw();
+        let winner = if drawn {
+            accounts.game.draw_winner()
+        } else {
+            accounts.game.winner()
+        };
+        if winner.is_some() || drawn {
+            let game_signer = accounts
+                .game_signer
+                .as_ref()
+                .ok_or(custom_error!("no game_signer on game end"))?;
+            let other_profile = accounts
+                .other_profile
+                .as_mut()
+                .ok_or(custom_error!("no other_profile on game end"))?;
+            let funds_to = accounts
+                .funds_to
+                .as_ref()
+                .ok_or(custom_error!("no funds_to on game end"))?;
+            check_funds_to(funds_to, accounts.game.info(), game_signer.info())?;
+
+            if !accounts.player_profile.is_valid_payout(
+                accounts
+                    .payout_allowlist
+                    .as_ref()
+                    .map(|allowlist| (allowlist.info().key(), &**allowlist)),
+                funds_to.key(),
+                now,
+            ) {
+                return Err(
+                    custom_error!("funds_to is not a valid payout address for the winner").into(),
+                );
+            }
+
+            let elo_before = (accounts.player_profile.elo, other_profile.elo);
+
+            // Friendly games have no vault to pay out of.
+            let vault = (!accounts.game.friendly).then(|| game_signer.info());
+            let pot = match vault {
+                Some(vault) => WagerVault::escrow(vault)?,
+                None => 0,
+            };
+            let pay_out = |to: &AI, amount: u64| match vault {
+                Some(vault) => WagerVault::pay_out(vault, to, amount),
+                None => Ok(()),
+            };
+
+            match winner {
+                Some(winner) if winner == next_play => {
+                    pay_out(funds_to, pot)?;
+
+                    // Update profiles
+                    accounts.player_profile.wins.saturating_add_assign(1);
+                    other_profile.losses.saturating_add_assign(1);
+
                     accounts
-                        .other_profile
-                        .as_mut()
-                        .ok_or(GenericError::Custom {
-                            error: "no other_profile on win".to_string(),
-                        })?;
-                let funds_to = accounts.funds_to.as_ref().ok_or(GenericError::Custom {
-                    error: "no funds_to on win".to_string(),
-                })?;
-                let system_program =
+                        .player_profile
+                        .lamports_won
+                        .saturating_add_assign(pot);
+                    other_profile
+                        .lamports_lost
+                        .saturating_add_assign(accounts.game.stake(next_play.other()));
+
+                    update_elo(
+                        &mut accounts.player_profile.elo,
+                        &mut other_profile.elo,
+                        PlayerProfile::ELO_K,
+                        true,
+                    );
+                }
+                Some(_) => {
+                    // The other player won on boards.
+                    // Their winnings are held on their profile until they withdraw them.
+                    pay_out(other_profile.info(), pot)?;
+
+                    other_profile.wins.saturating_add_assign(1);
+                    accounts.player_profile.losses.saturating_add_assign(1);
+
+                    other_profile.lamports_won.saturating_add_assign(pot);
                     accounts
-                        .system_program
-                        .as_ref()
-                        .ok_or(GenericError::Custom {
-                            error: "no system_program on win".to_string(),
-                        })?;
+                        .player_profile
+                        .lamports_lost
+                        .saturating_add_assign(accounts.game.stake(next_play));
 
-                let signer_seeds = game_signer.take_seed_set().unwrap();
-                let winnings = *game_signer.lamports();
+                    update_elo(
+                        &mut other_profile.elo,
+                        &mut accounts.player_profile.elo,
+                        PlayerProfile::ELO_K,
+                        true,
+                    );
+                }
+                None => {
+                    // Each player gets their own wager back.
+                    // The other player's is held on their profile until they withdraw it.
+                    let other_stake = accounts.game.stake(next_play.other());
+                    let own_stake = pot.saturating_sub(other_stake);
+                    pay_out(other_profile.info(), other_stake)?;
+                    pay_out(funds_to, own_stake)?;
 
-                system_program.transfer(
-                    CPIChecked,
-                    game_signer.info(),
-                    funds_to,
-                    winnings,
-                    [&signer_seeds],
-                )?;
+                    accounts
+                        .player_profile
+                        .lamports_drawn_back
+                        .saturating_add_assign(own_stake);
+                    other_profile
+                        .lamports_drawn_back
+                        .saturating_add_assign(other_stake);
 
-                // Burn game data
-                accounts.game.player1 = SystemProgram::<()>::KEY;
-                accounts.game.player2 = SystemProgram::<()>::KEY;
+                    accounts.player_profile.draws.saturating_add_assign(1);
+                    other_profile.draws.saturating_add_assign(1);
 
-                // Update profiles
-                accounts.player_profile.wins.saturating_add_assign(1);
-                other_profile.losses.saturating_add_assign(1);
+                    update_elo_with_score(
+                        &mut accounts.player_profile.elo,
+                        &mut other_profile.elo,
+                        PlayerProfile::ELO_K,
+                        0.5,
+                    );
+                }
+            }
 
-                accounts
-                    .player_profile
-                    .lamports_won
-                    .saturating_add_assign(winnings);
-                other_profile.lamports_lost.saturating_add_assign(winnings);
-
-                // Close game
-                let mut game_lamports = game_signer.lamports_mut();
-                *funds_to.lamports_mut() += *game_lamports;
-                *game_lamports = 0;
-            } else {
-                accounts.game.next_play = match accounts.game.next_play {
-                    Player::One => Player::Two,
-                    Player::Two => Player::One,
-                };
+            accounts.player_profile.mark_game_ended(now);
+            other_profile.mark_game_ended(now);
+            let elo = EloChange::new(
+                elo_before.0,
+                accounts.player_profile.elo,
+                elo_before.1,
+                other_profile.elo,
+            );
 
-                accounts.game.last_turn = Clock::get()?.unix_timestamp;
-                accounts.game.last_move = data.small_board;
+            // Leave the game for `ArchiveGame`, its rent goes back to the creator's profile then
+            let creator_profile = if accounts.game.creator == next_play {
+                accounts.player_profile.info()
+            } else {
+                other_profile.info()
+            };
+            let result = winner.map_or(GameResult::Drawn, GameResult::Won);
+            let game_key = *accounts.game.info().key();
+            // Finishing the game clears the players.
+            let (player1, player2) = (accounts.game.player1, accounts.game.player2);
+            let archived = accounts
+                .game
+                .finish(&game_key, creator_profile.key(), result, now)?;
+            log_event!(
+                info,
+                "game_finished",
+                game = game_key,
+                leaf = archived.leaf()?
+            );
+            if let Some(vault) = vault {
+                WagerVault::close(vault, creator_profile)?;
             }
+            let settled = GameSettled {
+                game: game_key,
+                signer_bump: accounts.game.signer_bump,
+                player1,
+                player2,
+                result,
+                forfeited: false,
+                pot,
+                ended_at: now,
+            };
+            ProgramEvent::GameEnded(settled.clone()).emit()?;
+            notify_settlement(
+                &accounts.game,
+                game_signer.info(),
+                accounts.settlement_hook.iter(),
+                &settled,
+            )?;
+            Ok(MoveOutcome {
+                result: Some(result),
+                elo,
+            })
+        } else {
+            accounts.game.next_play = next_play.other();
 
-            Ok(())
+            accounts.game.last_turn = now;
+            accounts.game.last_move = data.small_board;
+            accounts.game.last_move_middle = data.middle_board;
+
+            // The other player may have already queued their reply.
+            match accounts.game.play_premove()? {
+                PremoveOutcome::NotQueued => {}
+                PremoveOutcome::Played(premove) => {
+                    log_event!(info, "premove_played");
+                    ProgramEvent::MovePlayed {
+                        game: *accounts.game.info().key(),
+                        profile: *accounts.game.player(next_play.other()),
+                        player: next_play.other(),
+                        mov: premove.clone(),
+                    }
+                    .emit()?;
+                    accounts.game.next_play = next_play;
+                    accounts.game.last_move = premove.small_board;
+                    accounts.game.last_move_middle = premove.middle_board;
+                }
+                // Dropping it clears it, same as the player clearing it themselves.
+                dropped => {
+                    log_event!(
+                        warn,
+                        "premove_dropped",
+                        player = next_play.other(),
+                        ends_game = matches!(dropped, PremoveOutcome::EndsGame(_))
+                    );
+                    ProgramEvent::PremoveQueued {
+                        game: *accounts.game.info().key(),
+                        premove: None,
+                    }
+                    .emit()?;
+                }
+            }
+            Ok(MoveOutcome {
+                result: None,
+                elo: EloChange::default(),
+            })
         }
     }
 }
@@ -193,6 +439,7 @@ pub use cpi::*;
 #[cfg(feature = "cpi")]
 mod cpi {
     use super::*;
+    use crate::instructions::required;
     use crate::TutorialInstructions;
 
     /// Makes a move
@@ -202,50 +449,136 @@ mod cpi {
         data: Vec<u8>,
     }
     impl<'a, AI> MakeMoveCPI<'a, AI, 3> {
-        /// Makes a move that won't win the game
-        pub fn new(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
-            make_move_data: MakeMoveData,
-        ) -> CruiserResult<MakeMoveCPI<'a, AI, 3>> {
+        /// Starts building a [`MakeMoveCPI`].
+        /// Setting [`win`](MakeMoveCPIBuilder::win) makes it a winning move which needs more accounts.
+        pub fn builder() -> MakeMoveCPIBuilder<'a, AI, (), ()> {
+            MakeMoveCPIBuilder {
+                authority: None,
+                player_profile: None,
+                game: None,
+                data: None,
+                win: (),
+                payout_allowlist: (),
+            }
+        }
+    }
+
+    /// Builder for [`MakeMoveCPI`].
+    /// `W` and `P` are `()` until [`win`](Self::win) or [`payout_allowlist`](Self::payout_allowlist) is set.
+    #[derive(Debug)]
+    pub struct MakeMoveCPIBuilder<'a, AI, W, P> {
+        authority: Option<MaybeOwned<'a, AI>>,
+        player_profile: Option<MaybeOwned<'a, AI>>,
+        game: Option<MaybeOwned<'a, AI>>,
+        data: Option<MakeMoveData>,
+        win: W,
+        payout_allowlist: P,
+    }
+    impl<'a, AI, W, P> MakeMoveCPIBuilder<'a, AI, W, P> {
+        /// Sets the player's authority.
+        pub fn authority(mut self, authority: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.authority = Some(authority.into());
+            self
+        }
+
+        /// Sets the player's profile.
+        pub fn player_profile(mut self, player_profile: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.player_profile = Some(player_profile.into());
+            self
+        }
+
+        /// Sets the game the move is made in.
+        pub fn game(mut self, game: impl Into<MaybeOwned<'a, AI>>) -> Self {
+            self.game = Some(game.into());
+            self
+        }
+
+        /// Sets the instruction data.
+        pub fn data(mut self, data: MakeMoveData) -> Self {
+            self.data = Some(data);
+            self
+        }
+
+        /// Splits into the required accounts, the serialized data, and the optional accounts.
+        #[allow(clippy::type_complexity)]
+        fn into_parts(self) -> CruiserResult<([MaybeOwned<'a, AI>; 3], Vec<u8>, W, P)> {
             let mut data = Vec::new();
             <TutorialInstructions as InstructionListItem<MakeMove>>::discriminant_compressed()
                 .serialize(&mut data)?;
-            make_move_data.serialize(&mut data)?;
-            Ok(MakeMoveCPI {
-                accounts: [authority.into(), player_profile.into(), game.into()],
+            required(self.data, "data")?.serialize(&mut data)?;
+            Ok((
+                [
+                    required(self.authority, "authority")?,
+                    required(self.player_profile, "player_profile")?,
+                    required(self.game, "game")?,
+                ],
                 data,
-            })
+                self.win,
+                self.payout_allowlist,
+            ))
         }
     }
-    impl<'a, AI> MakeMoveCPI<'a, AI, 7> {
-        /// Makes a move that will win the game
-        #[allow(clippy::too_many_arguments)]
-        pub fn new_win(
-            authority: impl Into<MaybeOwned<'a, AI>>,
-            player_profile: impl Into<MaybeOwned<'a, AI>>,
-            game: impl Into<MaybeOwned<'a, AI>>,
+    impl<'a, AI> MakeMoveCPIBuilder<'a, AI, (), ()> {
+        /// Makes this a move that will win or draw the game, adding the accounts needed to pay out.
+        pub fn win(
+            self,
             game_signer: impl Into<MaybeOwned<'a, AI>>,
             other_profile: impl Into<MaybeOwned<'a, AI>>,
             funds_to: impl Into<MaybeOwned<'a, AI>>,
             system_program: impl Into<MaybeOwned<'a, AI>>,
-            make_move_data: MakeMoveData,
-        ) -> CruiserResult<MakeMoveCPI<'a, AI, 7>> {
-            let mut data = Vec::new();
-            <TutorialInstructions as InstructionListItem<MakeMove>>::discriminant_compressed()
-                .serialize(&mut data)?;
-            make_move_data.serialize(&mut data)?;
-            Ok(MakeMoveCPI {
-                accounts: [
-                    authority.into(),
-                    player_profile.into(),
-                    game.into(),
+        ) -> MakeMoveCPIBuilder<'a, AI, [MaybeOwned<'a, AI>; 4], ()> {
+            MakeMoveCPIBuilder {
+                authority: self.authority,
+                player_profile: self.player_profile,
+                game: self.game,
+                data: self.data,
+                win: [
                     game_signer.into(),
                     other_profile.into(),
                     funds_to.into(),
                     system_program.into(),
                 ],
+                payout_allowlist: (),
+            }
+        }
+
+        /// Builds a CPI for a move that won't win the game.
+        pub fn build(self) -> CruiserResult<MakeMoveCPI<'a, AI, 3>> {
+            let (accounts, data, (), ()) = self.into_parts()?;
+            Ok(MakeMoveCPI { accounts, data })
+        }
+    }
+    impl<'a, AI> MakeMoveCPIBuilder<'a, AI, [MaybeOwned<'a, AI>; 4], ()> {
+        /// Sets the player's payout allowlist. Needed if the player's profile has one.
+        pub fn payout_allowlist(
+            self,
+            payout_allowlist: impl Into<MaybeOwned<'a, AI>>,
+        ) -> MakeMoveCPIBuilder<'a, AI, [MaybeOwned<'a, AI>; 4], MaybeOwned<'a, AI>> {
+            MakeMoveCPIBuilder {
+                authority: self.authority,
+                player_profile: self.player_profile,
+                game: self.game,
+                data: self.data,
+                win: self.win,
+                payout_allowlist: payout_allowlist.into(),
+            }
+        }
+
+        /// Builds a CPI for a move that will win the game.
+        pub fn build(self) -> CruiserResult<MakeMoveCPI<'a, AI, 7>> {
+            let ([a0, a1, a2], data, [w0, w1, w2, w3], ()) = self.into_parts()?;
+            Ok(MakeMoveCPI {
+                accounts: [a0, a1, a2, w0, w1, w2, w3],
+                data,
+            })
+        }
+    }
+    impl<'a, AI> MakeMoveCPIBuilder<'a, AI, [MaybeOwned<'a, AI>; 4], MaybeOwned<'a, AI>> {
+        /// Builds a CPI for a move that will win the game, paying out to an address on the player's payout allowlist.
+        pub fn build(self) -> CruiserResult<MakeMoveCPI<'a, AI, 8>> {
+            let ([a0, a1, a2], data, [w0, w1, w2, w3], payout_allowlist) = self.into_parts()?;
+            Ok(MakeMoveCPI {
+                accounts: [a0, a1, a2, w0, w1, w2, w3, payout_allowlist],
                 data,
             })
         }
@@ -326,15 +659,189 @@ mod cpi {
             }
         }
     }
+
+    impl<'a, AI> CPIClientStatic<'a, 9> for MakeMoveCPI<'a, AI, 8>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = MakeMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+
+    /// Makes a move with the optional accounts picked at runtime.
+    #[derive(Debug)]
+    pub struct MakeMoveCPIDynamic<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> MakeMoveCPIDynamic<'a, AI> {
+        /// Makes a move that won't win the game
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            make_move_data: MakeMoveData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<MakeMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            make_move_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: vec![authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+
+        /// Makes a move that will win the game.
+        /// `payout_allowlist` is needed if the player has a payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_win(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: Option<MaybeOwned<'a, AI>>,
+            make_move_data: MakeMoveData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<MakeMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            make_move_data.serialize(&mut data)?;
+            let mut accounts = vec![
+                authority.into(),
+                player_profile.into(),
+                game.into(),
+                game_signer.into(),
+                other_profile.into(),
+                funds_to.into(),
+                system_program.into(),
+            ];
+            accounts.extend(payout_allowlist);
+            Ok(Self { accounts, data })
+        }
+    }
+    impl<'a, AI> MakeMoveCPIDynamic<'a, AI>
+    where
+        AI: AccountInfo,
+    {
+        /// Makes a move passing on the accounts of an already parsed [`MakeMoveAccounts`].
+        pub fn from_accounts(
+            accounts: &'a MakeMoveAccounts<AI>,
+            make_move_data: MakeMoveData,
+        ) -> CruiserResult<Self> {
+            let mut out = Self::new(
+                &accounts.authority,
+                accounts.player_profile.info(),
+                accounts.game.info(),
+                make_move_data,
+            )?;
+            // Optional accounts are only ever missing from the end so keeping the present ones in order matches what was parsed.
+            out.accounts.extend(
+                [
+                    accounts
+                        .game_signer
+                        .as_ref()
+                        .map(|game_signer| game_signer.info()),
+                    accounts
+                        .other_profile
+                        .as_ref()
+                        .map(|other_profile| other_profile.info()),
+                    accounts.funds_to.as_ref(),
+                    accounts
+                        .system_program
+                        .as_ref()
+                        .map(|system_program| system_program.info()),
+                    accounts
+                        .payout_allowlist
+                        .as_ref()
+                        .map(|payout_allowlist| payout_allowlist.info()),
+                ]
+                .into_iter()
+                .flatten()
+                .chain(accounts.settlement_hook.iter())
+                .map(MaybeOwned::from),
+            );
+            Ok(out)
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for MakeMoveCPIDynamic<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = MakeMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
 }
 
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 pub use client::*;
 
 /// Client for [`MakeMove`]
-#[cfg(feature = "client")]
+#[cfg(feature = "client-core")]
 mod client {
     use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
 
     /// Makes a non-winning move
     pub fn make_move<'a>(
@@ -347,21 +854,23 @@ mod client {
         let authority = authority.into();
         InstructionSet {
             instructions: vec![
-                MakeMoveCPI::new(
-                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                    SolanaAccountMeta::new_readonly(player_profile, false),
-                    SolanaAccountMeta::new(game, false),
-                    move_data,
-                )
-                .unwrap()
-                .instruction(SolanaAccountMeta::new_readonly(program_id, true))
-                .instruction,
+                MakeMoveCPI::builder()
+                    .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                    .player_profile(SolanaAccountMeta::new(player_profile, false))
+                    .game(SolanaAccountMeta::new(game, false))
+                    .data(move_data)
+                    .build()
+                    .unwrap()
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, true))
+                    .instruction,
             ],
             signers: [authority].into_iter().collect(),
         }
     }
 
-    /// Makes a winning move
+    /// Makes a move that wins or draws the game.
+    /// On a draw the other player's wager is sent to their profile to withdraw later.
+    /// `payout_allowlist` must be set if the player's profile has one.
     #[allow(clippy::too_many_arguments)]
     pub fn make_winning_move<'a>(
         program_id: Pubkey,
@@ -371,30 +880,52 @@ mod client {
         game_signer_bump: u8,
         other_profile: Pubkey,
         funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
         move_data: MakeMoveData,
     ) -> InstructionSet<'a> {
         let authority = authority.into();
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        let instruction = match payout_allowlist {
+            Some(payout_allowlist) => {
+                MakeMoveCPI::builder()
+                    .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                    .player_profile(SolanaAccountMeta::new(player_profile, false))
+                    .game(SolanaAccountMeta::new(game, false))
+                    .data(move_data)
+                    .win(
+                        SolanaAccountMeta::new(game_signer, false),
+                        SolanaAccountMeta::new(other_profile, false),
+                        SolanaAccountMeta::new(funds_to, false),
+                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    )
+                    .payout_allowlist(SolanaAccountMeta::new_readonly(payout_allowlist, false))
+                    .build()
+                    .unwrap()
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, true))
+                    .instruction
+            }
+            None => {
+                MakeMoveCPI::builder()
+                    .authority(SolanaAccountMeta::new_readonly(authority.pubkey(), true))
+                    .player_profile(SolanaAccountMeta::new(player_profile, false))
+                    .game(SolanaAccountMeta::new(game, false))
+                    .data(move_data)
+                    .win(
+                        SolanaAccountMeta::new(game_signer, false),
+                        SolanaAccountMeta::new(other_profile, false),
+                        SolanaAccountMeta::new(funds_to, false),
+                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    )
+                    .build()
+                    .unwrap()
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, true))
+                    .instruction
+            }
+        };
         InstructionSet {
-            instructions: vec![
-                MakeMoveCPI::new_win(
-                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
-                    SolanaAccountMeta::new(player_profile, false),
-                    SolanaAccountMeta::new(game, false),
-                    SolanaAccountMeta::new(
-                        GameSignerSeeder { game }
-                            .create_address(&program_id, game_signer_bump)
-                            .unwrap(),
-                        false,
-                    ),
-                    SolanaAccountMeta::new(other_profile, false),
-                    SolanaAccountMeta::new(funds_to, false),
-                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
-                    move_data,
-                )
-                .unwrap()
-                .instruction(SolanaAccountMeta::new_readonly(program_id, true))
-                .instruction,
-            ],
+            instructions: vec![instruction],
             signers: [authority].into_iter().collect(),
         }
     }
@@ -0,0 +1,241 @@
+use crate::instructions::{MakeMoveAccounts, MakeMoveData, MoveOutcome};
+use cruiser::prelude::*;
+
+/// Makes a move on the board and handles wins and draws, supersedes [`MakeMove`](crate::instructions::MakeMove).
+/// Takes the same accounts but also the [`Game::last_turn`](crate::accounts::Game::last_turn) the move was picked against,
+/// so a move picked before the board changed under it (such as by a played premove) is rejected instead of played.
+/// Returns a [`MoveOutcome`].
+#[derive(Debug)]
+pub enum MakeMoveV2 {}
+
+impl<AI> Instruction<AI> for MakeMoveV2 {
+    type Accounts = MakeMoveAccounts<AI>;
+    type Data = MakeMoveV2Data;
+    type ReturnType = MoveOutcome;
+}
+
+/// Data for [`MakeMoveV2`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct MakeMoveV2Data {
+    /// The move to make.
+    pub mov: MakeMoveData,
+    /// The [`Game::last_turn`](crate::accounts::Game::last_turn) the move was picked against.
+    pub last_turn: UnixTimestamp,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::error::custom_error;
+    use crate::instructions::{
+        check_authority_signed, check_not_commit_reveal, play_move, set_return,
+    };
+
+    impl<'a, AI> InstructionProcessor<AI, MakeMoveV2> for MakeMoveV2
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = MakeMoveV2Data;
+
+        fn data_to_instruction_arg(
+            data: <MakeMoveV2 as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <MakeMoveV2 as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<MakeMoveV2 as Instruction<AI>>::ReturnType> {
+            check_authority_signed(accounts)?;
+            check_not_commit_reveal(accounts)?;
+            if accounts.game.last_turn != data.last_turn {
+                return Err(custom_error!(
+                    "game changed since the move was picked, last turn is {}",
+                    accounts.game.last_turn
+                )
+                .into());
+            }
+            set_return(play_move(accounts, data.mov)?)
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`MakeMoveV2`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Makes a move with [`MakeMoveV2`].
+    #[derive(Debug)]
+    pub struct MakeMoveV2CPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> MakeMoveV2CPI<'a, AI> {
+        /// Makes a move that won't win the game
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            make_move_data: MakeMoveV2Data,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<MakeMoveV2>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            make_move_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: vec![authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+
+        /// Makes a move that will win the game.
+        /// `payout_allowlist` is needed if the player has a payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_win(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: Option<MaybeOwned<'a, AI>>,
+            make_move_data: MakeMoveV2Data,
+        ) -> CruiserResult<Self> {
+            let mut out = Self::new(authority, player_profile, game, make_move_data)?;
+            out.accounts.extend([
+                game_signer.into(),
+                other_profile.into(),
+                funds_to.into(),
+                system_program.into(),
+            ]);
+            out.accounts.extend(payout_allowlist);
+            Ok(out)
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for MakeMoveV2CPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = MakeMoveV2;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`MakeMoveV2`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_game_signer, HashedSigner, InstructionSet};
+
+    /// Makes a non-winning move against the game as of `last_turn`.
+    pub fn make_move_v2<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        last_turn: UnixTimestamp,
+        move_data: MakeMoveData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                MakeMoveV2CPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    MakeMoveV2Data {
+                        mov: move_data,
+                        last_turn,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+
+    /// Makes a move that wins or draws the game against the game as of `last_turn`.
+    /// `payout_allowlist` must be set if the player's profile has one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_winning_move_v2<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        other_profile: Pubkey,
+        funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
+        last_turn: UnixTimestamp,
+        move_data: MakeMoveData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let (game_signer, _) = derive_game_signer(&program_id, game);
+        InstructionSet {
+            instructions: vec![
+                MakeMoveV2CPI::new_win(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(other_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    payout_allowlist
+                        .map(|allowlist| SolanaAccountMeta::new_readonly(allowlist, false).into()),
+                    MakeMoveV2Data {
+                        mov: move_data,
+                        last_turn,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
+use crate::log::log_event;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Makes several moves in a row, alternating between the players.
+/// Every player that moves must sign. The batch can't end the game, use [`MakeMove`](crate::instructions::MakeMove) for that.
+#[derive(Debug)]
+pub enum MakeMoves {}
+
+impl<AI> Instruction<AI> for MakeMoves {
+    type Accounts = MakeMovesAccounts<AI>;
+    type Data = MakeMovesData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`MakeMoves`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct MakeMovesAccounts<AI> {
+    /// The authority for player one. Must sign if player one moves.
+    pub player_one_authority: AI,
+    /// Player one's profile.
+    #[validate(
+        writable,
+        custom = &self.player_one_profile.authority == self.player_one_authority.key(),
+    )]
+    pub player_one_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The authority for player two. Must sign if player two moves.
+    pub player_two_authority: AI,
+    /// Player two's profile.
+    #[validate(
+        writable,
+        custom = &self.player_two_profile.authority == self.player_two_authority.key(),
+    )]
+    pub player_two_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to make the moves on.
+    #[validate(
+        writable,
+        custom = self.game.is_started(),
+        custom = &self.game.player1 == self.player_one_profile.info().key(),
+        custom = &self.game.player2 == self.player_two_profile.info().key(),
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+}
+
+/// Data for [`MakeMoves`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct MakeMovesData {
+    /// The moves in order, the first is made by the player whose turn it is.
+    pub moves: Vec<MakeMoveData>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::Player;
+    use crate::error::TutorialError;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, MakeMoves> for MakeMoves
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = MakeMovesData;
+
+        fn data_to_instruction_arg(
+            data: <MakeMoves as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <MakeMoves as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<MakeMoves as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            log_event!(debug, "making_moves", count = data.moves.len());
+            // Any premove was queued for a position the batch moves past.
+            accounts.game.premove = Game::NO_PREMOVE;
+
+            for mov in &data.moves {
+                let next_play = accounts.game.next_play;
+                let (authority, profile) = match next_play {
+                    Player::One => (
+                        &accounts.player_one_authority,
+                        &mut accounts.player_one_profile,
+                    ),
+                    Player::Two => (
+                        &accounts.player_two_authority,
+                        &mut accounts.player_two_profile,
+                    ),
+                };
+                if !authority.is_signer() {
+                    return Err(custom_error!("{:?} moved without signing", next_play).into());
+                }
+                if !accounts.game.is_valid_move(mov) {
+                    return Err(TutorialError::InvalidMove.into());
+                }
+                profile.mark_active(now);
+
+                accounts.game.make_move(
+                    next_play,
+                    mov.big_board,
+                    mov.middle_board,
+                    mov.small_board,
+                )?;
+                ProgramEvent::MovePlayed {
+                    game: *accounts.game.info().key(),
+                    profile: *profile.info().key(),
+                    player: next_play,
+                    mov: mov.clone(),
+                }
+                .emit()?;
+                if accounts.game.winner().is_some() || accounts.game.is_draw() {
+                    return Err(
+                        custom_error!("Batched moves can't end the game, use `MakeMove`").into(),
+                    );
+                }
+
+                accounts.game.next_play = next_play.other();
+                accounts.game.last_turn = now;
+                accounts.game.last_move = mov.small_board;
+                accounts.game.last_move_middle = mov.middle_board;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`MakeMoves`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Makes several moves in a row.
+    #[derive(Debug)]
+    pub struct MakeMovesCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> MakeMovesCPI<'a, AI> {
+        /// Makes several moves in a row.
+        pub fn new(
+            player_one_authority: impl Into<MaybeOwned<'a, AI>>,
+            player_one_profile: impl Into<MaybeOwned<'a, AI>>,
+            player_two_authority: impl Into<MaybeOwned<'a, AI>>,
+            player_two_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            make_moves_data: &MakeMovesData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<MakeMoves>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            make_moves_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    player_one_authority.into(),
+                    player_one_profile.into(),
+                    player_two_authority.into(),
+                    player_two_profile.into(),
+                    game.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for MakeMovesCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = MakeMoves;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`MakeMoves`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Makes several moves in a row, signed by both players.
+    pub fn make_moves<'a>(
+        program_id: Pubkey,
+        player_one_authority: impl Into<HashedSigner<'a>>,
+        player_one_profile: Pubkey,
+        player_two_authority: impl Into<HashedSigner<'a>>,
+        player_two_profile: Pubkey,
+        game: Pubkey,
+        moves: Vec<MakeMoveData>,
+    ) -> InstructionSet<'a> {
+        let player_one_authority = player_one_authority.into();
+        let player_two_authority = player_two_authority.into();
+        InstructionSet {
+            instructions: vec![
+                MakeMovesCPI::new(
+                    SolanaAccountMeta::new_readonly(player_one_authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_one_profile, false),
+                    SolanaAccountMeta::new_readonly(player_two_authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_two_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    &MakeMovesData { moves },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [player_one_authority, player_two_authority]
+                .into_iter()
+                .collect(),
+        }
+    }
+}
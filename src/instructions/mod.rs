@@ -1,13 +1,194 @@
 //! Instructions for the program.
+//!
+//! Account arguments for the larger accounts (games, profiles, the config, social graphs, leaderboard shards,
+//! metrics and admin proposals) are boxed so their data lives on the heap.
+//! Solana's stack frames are only 4KB and an instruction's accounts are built in one frame.
 
+mod approve_recovery;
+mod archive_game;
+mod cancel_admin_action;
+mod challenge_settlement;
+mod claim_abandoned_profile;
+mod claim_achievement;
+mod claim_reward;
+mod commit_move;
+mod crank_forfeit;
+mod create_distribution;
 mod create_game;
+mod create_house_game;
+mod create_leaderboard_shard;
+mod create_payout_allowlist;
 mod create_profile;
+mod create_social_graph;
+mod decay_elo;
+mod execute_admin_action;
+mod finalize_recovery;
+mod finalize_settlement;
+mod follow;
 mod forfeit_game;
+mod fulfill_first_player_vrf;
+mod fund_house_vault;
+mod get_game_state;
+mod house_move;
+mod init_config;
+mod init_house;
+mod init_metrics;
+mod initiate_recovery;
 mod join_game;
 mod make_move;
+mod make_move_v2;
+mod make_moves;
+mod post_game_message;
+mod propose_admin_action;
+mod propose_game_recovery;
+mod queue_premove;
+mod recover_stuck_game;
+mod register_forfeit_thread;
+mod relay_move;
+mod request_first_player_vrf;
+mod reveal_move;
+mod set_admin;
+mod set_beneficiary;
+mod set_session_key;
+mod settle_signed_game;
+mod sweep_stale_game;
+mod unfollow;
+mod unlock_game;
+mod update_config;
+mod update_leaderboard;
+mod update_payout_allowlist;
+mod update_profile;
+mod withdraw_fees;
+mod withdraw_house_vault;
+mod withdraw_profile_balance;
 
+pub use approve_recovery::*;
+pub use archive_game::*;
+pub use cancel_admin_action::*;
+pub use challenge_settlement::*;
+pub use claim_abandoned_profile::*;
+pub use claim_achievement::*;
+pub use claim_reward::*;
+pub use commit_move::*;
+pub use crank_forfeit::*;
+pub use create_distribution::*;
 pub use create_game::*;
+pub use create_house_game::*;
+pub use create_leaderboard_shard::*;
+pub use create_payout_allowlist::*;
 pub use create_profile::*;
+pub use create_social_graph::*;
+pub use decay_elo::*;
+pub use execute_admin_action::*;
+pub use finalize_recovery::*;
+pub use finalize_settlement::*;
+pub use follow::*;
 pub use forfeit_game::*;
+pub use fulfill_first_player_vrf::*;
+pub use fund_house_vault::*;
+pub use get_game_state::*;
+pub use house_move::*;
+pub use init_config::*;
+pub use init_house::*;
+pub use init_metrics::*;
+pub use initiate_recovery::*;
 pub use join_game::*;
 pub use make_move::*;
+pub use make_move_v2::*;
+pub use make_moves::*;
+pub use post_game_message::*;
+pub use propose_admin_action::*;
+pub use propose_game_recovery::*;
+pub use queue_premove::*;
+pub use recover_stuck_game::*;
+pub use register_forfeit_thread::*;
+pub use relay_move::*;
+pub use request_first_player_vrf::*;
+pub use reveal_move::*;
+pub use set_admin::*;
+pub use set_beneficiary::*;
+pub use set_session_key::*;
+pub use settle_signed_game::*;
+pub use sweep_stale_game::*;
+pub use unfollow::*;
+pub use unlock_game::*;
+pub use update_config::*;
+pub use update_leaderboard::*;
+pub use update_payout_allowlist::*;
+pub use update_profile::*;
+pub use withdraw_fees::*;
+pub use withdraw_house_vault::*;
+pub use withdraw_profile_balance::*;
+
+use cruiser::prelude::{BorshDeserialize, BorshSerialize};
+
+/// How a game's end moved the players' elo, returned by the instructions ending games.
+/// Which profile is the `player` is documented on each instruction.
+#[derive(Copy, Clone, Debug, Default, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct EloChange {
+    /// The change to the player's elo.
+    pub player: i64,
+    /// The change to the other player's elo.
+    pub other: i64,
+}
+impl EloChange {
+    /// The change between the players' elo before and after the game.
+    pub fn new(player_before: u64, player_after: u64, other_before: u64, other_after: u64) -> Self {
+        Self {
+            player: player_after as i64 - player_before as i64,
+            other: other_after as i64 - other_before as i64,
+        }
+    }
+}
+
+/// Sets `value` as the instruction's return data and returns it, for processors to end with.
+/// Decoded by clients with [`decode_return_data`](crate::client::decode_return_data).
+#[cfg(feature = "processor")]
+fn set_return<T>(value: T) -> cruiser::prelude::CruiserResult<T>
+where
+    T: cruiser::prelude::BorshSerialize,
+{
+    cruiser::solana_program::program::set_return_data(&value.try_to_vec()?);
+    Ok(value)
+}
+
+/// Marks the authority, the first account of a [`CreateProfile`], [`CreateGame`], [`MakeMove`] or [`MakeMoveV2`] CPI, as signing.
+/// Needed when the authority is a PDA of the calling program which signs for it with `invoke_signed`,
+/// as its account info isn't a signer coming into the caller.
+#[cfg(feature = "cpi")]
+pub fn sign_as_authority<A>(
+    mut instruction: cruiser::prelude::InstructionAndAccounts<A>,
+) -> cruiser::prelude::InstructionAndAccounts<A> {
+    instruction.instruction.accounts[0].is_signer = true;
+    instruction
+}
+
+/// Takes a required account or data out of a CPI builder.
+#[cfg(feature = "cpi")]
+fn required<T>(field: Option<T>, name: &str) -> cruiser::prelude::CruiserResult<T> {
+    field.ok_or_else(|| crate::error::custom_error!("CPI builder is missing `{}`", name).into())
+}
+
+/// Errors if `funds_to` would strand a game's payout: the game's signer, the game itself
+/// or any other account owned by the program, which the lamports couldn't be taken back out of.
+#[cfg(feature = "processor")]
+fn check_funds_to<AI>(
+    funds_to: &AI,
+    game: &AI,
+    game_signer: &AI,
+) -> cruiser::prelude::CruiserResult<()>
+where
+    AI: cruiser::prelude::AccountInfo,
+{
+    use crate::error::TutorialError;
+
+    if funds_to.key() == game_signer.key() {
+        Err(TutorialError::FundsToIsGameSigner.into())
+    } else if funds_to.key() == game.key() {
+        Err(TutorialError::FundsToIsGame.into())
+    } else if *funds_to.owner() == *game.owner() {
+        Err(TutorialError::FundsToIsProgramOwned.into())
+    } else {
+        Ok(())
+    }
+}
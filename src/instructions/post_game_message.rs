@@ -0,0 +1,195 @@
+use crate::log::log_event;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Posts a chat message to a game's transaction logs. Only the game's players can post.
+#[derive(Debug)]
+pub enum PostGameMessage {}
+
+impl<AI> Instruction<AI> for PostGameMessage {
+    type Accounts = PostGameMessageAccounts<AI>;
+    type Data = PostGameMessageData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`PostGameMessage`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(
+    data = (message: String),
+    custom = message.len() <= Game::MAX_MESSAGE_LEN,
+)]
+pub struct PostGameMessageAccounts<AI> {
+    /// The authority for the player.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The player posting the message.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game the message is for.
+    #[validate(
+        custom = &self.game.player1 == self.player_profile.info().key()
+            || &self.game.player2 == self.player_profile.info().key(),
+    )]
+    pub game: Box<ReadOnlyDataAccount<AI, TutorialAccounts, Game>>,
+}
+
+/// Data for [`PostGameMessage`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct PostGameMessageData {
+    /// The message, at most [`Game::MAX_MESSAGE_LEN`] bytes.
+    pub message: String,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, PostGameMessage> for PostGameMessage
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = String;
+        type ValidateData = ();
+        type InstructionData = String;
+
+        fn data_to_instruction_arg(
+            data: <PostGameMessage as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.message.clone(), (), data.message))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            message: Self::InstructionData,
+            accounts: &mut <PostGameMessage as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<PostGameMessage as Instruction<AI>>::ReturnType> {
+            // Indexers pick these up by the game and profile keys.
+            log_event!(
+                info,
+                "game_message",
+                game = accounts.game.info().key(),
+                from = accounts.player_profile.info().key(),
+                message = message
+            );
+            ProgramEvent::GameMessagePosted {
+                game: *accounts.game.info().key(),
+                from: *accounts.player_profile.info().key(),
+                message,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`PostGameMessage`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Posts a chat message to a game.
+    #[derive(Debug)]
+    pub struct PostGameMessageCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> PostGameMessageCPI<'a, AI> {
+        /// Posts a chat message to a game.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            message: String,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<PostGameMessage>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            PostGameMessageData { message }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for PostGameMessageCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = PostGameMessage;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`PostGameMessage`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Posts `message` to `game` as `player_profile`.
+    pub fn post_game_message<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        message: String,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                PostGameMessageCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new_readonly(game, false),
+                    message,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::accounts::{AdminAction, ProgramConfig, ProposedAction};
+use crate::log::log_event;
+use crate::pda::ProposedActionSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+use std::iter::once;
+
+/// Proposes an admin action that can run [`ProgramConfig::admin_action_delay`] later
+/// with [`ExecuteAdminAction`](crate::instructions::ExecuteAdminAction). Only the config's admin can.
+///
+/// The action gets the config's [`ProgramConfig::next_admin_action`] as its id.
+#[derive(Debug)]
+pub enum ProposeAdminAction {}
+
+impl<AI> Instruction<AI> for ProposeAdminAction {
+    type Accounts = ProposeAdminActionAccounts<AI>;
+    type Data = ProposeAdminActionData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ProposeAdminAction`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (action: AdminAction, bump: u8))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>], data = (bump: u8))]
+pub struct ProposeAdminActionAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(
+        writable,
+        custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())),
+    )]
+    pub config: Box<DataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The funder for the proposal's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The proposal to create.
+    #[from(data = ProposedAction::new(config.next_admin_action, funder.key(), 0, action, bump))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            ProposedActionSeeder::new(self.config.next_admin_action),
+            bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub proposal: Box<InitAccount<AI, TutorialAccounts, ProposedAction>>,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`ProposeAdminAction`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ProposeAdminActionData {
+    /// The action to propose.
+    pub action: AdminAction,
+    /// The bump seed of the proposal's PDA.
+    pub bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, ProposeAdminAction> for ProposeAdminAction
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = (AdminAction, u8);
+        type ValidateData = u8;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <ProposeAdminAction as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((data.action, data.bump), data.bump, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <ProposeAdminAction as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ProposeAdminAction as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            accounts.proposal.executable_at =
+                now.saturating_add(accounts.config.admin_action_delay);
+            accounts.config.next_admin_action += 1;
+            log_event!(
+                info,
+                "admin_action_proposed",
+                id = accounts.proposal.id,
+                executable_at = accounts.proposal.executable_at,
+                action = accounts.proposal.action
+            );
+            ProgramEvent::AdminActionProposed {
+                id: accounts.proposal.id,
+                executable_at: accounts.proposal.executable_at,
+                action: accounts.proposal.action.clone(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ProposeAdminAction`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Proposes an admin action.
+    #[derive(Debug)]
+    pub struct ProposeAdminActionCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ProposeAdminActionCPI<'a, AI> {
+        /// Proposes an admin action.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            proposal: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            action: AdminAction,
+            bump: u8,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ProposeAdminAction>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            ProposeAdminActionData { action, bump }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    funder.into(),
+                    proposal.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for ProposeAdminActionCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ProposeAdminAction;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ProposeAdminAction`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_proposed_action, HashedSigner, InstructionSet};
+
+    /// Proposes `action`. `id` must be the config's current [`ProgramConfig::next_admin_action`].
+    pub fn propose_admin_action<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        id: u64,
+        action: AdminAction,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, _) = derive_config(&program_id);
+        let (proposal, bump) = derive_proposed_action(&program_id, id);
+        InstructionSet {
+            instructions: vec![
+                ProposeAdminActionCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new(config, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new(proposal, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    action,
+                    bump,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::accounts::{Game, GameRecovery, ProgramConfig};
+use crate::log::log_event;
+use crate::pda::GameRecoverySeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+use std::iter::once;
+
+/// Proposes sending a stuck game's escrow to `funds_to`. Only the config's admin can.
+///
+/// The game has to have gone [`ProgramConfig::recovery_inert_period`] without activity,
+/// and [`RecoverStuckGame`](crate::instructions::RecoverStuckGame) can only run [`ProgramConfig::recovery_delay`] later,
+/// giving the players time to notice and move.
+#[derive(Debug)]
+pub enum ProposeGameRecovery {}
+
+impl<AI> Instruction<AI> for ProposeGameRecovery {
+    type Accounts = ProposeGameRecoveryAccounts<AI>;
+    type Data = ProposeGameRecoveryData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`ProposeGameRecovery`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (funds_to: Pubkey, bump: u8))]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>], data = (bump: u8))]
+pub struct ProposeGameRecoveryAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(
+        custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())),
+        custom = self.config.recovery_inert_period != 0,
+    )]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The stuck game.
+    #[validate(custom = self.game.is_inert(Clock::get()?.unix_timestamp, self.config.recovery_inert_period))]
+    pub game: Box<ReadOnlyDataAccount<AI, TutorialAccounts, Game>>,
+    /// The recovery to create.
+    #[from(data = GameRecovery::new(game.info().key(), &funds_to, game.last_activity(), 0, bump))]
+    #[validate(data = InitArgs{
+        system_program: &self.system_program,
+        space: InitStaticSized,
+        funder: &self.funder,
+        funder_seeds: None,
+        account_seeds: Some(&PDASeedSet::new(
+            GameRecoverySeeder{ game: *self.game.info().key() },
+            bump,
+        )),
+        rent: None,
+        cpi: CPIChecked,
+    })]
+    pub recovery: InitAccount<AI, TutorialAccounts, GameRecovery>,
+    /// The funder for the recovery's rent.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`ProposeGameRecovery`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ProposeGameRecoveryData {
+    /// Where the game's escrow goes.
+    pub funds_to: Pubkey,
+    /// The bump seed of the recovery's PDA.
+    pub bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, ProposeGameRecovery> for ProposeGameRecovery
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = (Pubkey, u8);
+        type ValidateData = u8;
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            data: <ProposeGameRecovery as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((data.funds_to, data.bump), data.bump, ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <ProposeGameRecovery as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<ProposeGameRecovery as Instruction<AI>>::ReturnType> {
+            accounts.recovery.proposed_at = Clock::get()?.unix_timestamp;
+            log_event!(
+                info,
+                "game_recovery_proposed",
+                game = accounts.game.info().key(),
+                funds_to = accounts.recovery.funds_to
+            );
+            ProgramEvent::GameRecoveryProposed {
+                game: *accounts.game.info().key(),
+                funds_to: accounts.recovery.funds_to,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`ProposeGameRecovery`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Proposes recovering a stuck game.
+    #[derive(Debug)]
+    pub struct ProposeGameRecoveryCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> ProposeGameRecoveryCPI<'a, AI> {
+        /// Proposes recovering a stuck game.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            recovery: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: Pubkey,
+            bump: u8,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<ProposeGameRecovery>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            ProposeGameRecoveryData { funds_to, bump }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    game.into(),
+                    recovery.into(),
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for ProposeGameRecoveryCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = ProposeGameRecovery;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`ProposeGameRecovery`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_game_recovery, HashedSigner, InstructionSet};
+
+    /// Proposes sending the escrow of the stuck `game` to `funds_to`.
+    pub fn propose_game_recovery<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        funds_to: Pubkey,
+        funder: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let funder = funder.into();
+        let (config, _) = derive_config(&program_id);
+        let (recovery, bump) = derive_game_recovery(&program_id, game);
+        InstructionSet {
+            instructions: vec![
+                ProposeGameRecoveryCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(game, false),
+                    SolanaAccountMeta::new(recovery, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    funds_to,
+                    bump,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, funder].into_iter().collect(),
+        }
+    }
+}
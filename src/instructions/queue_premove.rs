@@ -0,0 +1,210 @@
+use crate::accounts::Player;
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
+use crate::log::log_event;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Queues the next move of the player waiting on their opponent.
+/// It's played as soon as the opponent moves, or dropped with a `premove_dropped` warning, see [`Game::play_premove`].
+#[derive(Debug)]
+pub enum QueuePremove {}
+
+impl<AI> Instruction<AI> for QueuePremove {
+    type Accounts = QueuePremoveAccounts<AI>;
+    type Data = QueuePremoveData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`QueuePremove`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct QueuePremoveAccounts<AI> {
+    /// The authority for the player.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The player queueing the move. Must be the one not to move.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to queue the move in. Premoves would give away moves in [`RuleSet::commit_reveal`](crate::accounts::RuleSet::commit_reveal) games.
+    #[validate(
+        writable,
+        custom = self.game.is_started(),
+        custom = !self.game.rules.commit_reveal,
+        custom = match self.game.next_play {
+            Player::One => &self.game.player2 == self.player_profile.info().key(),
+            Player::Two => &self.game.player1 == self.player_profile.info().key(),
+        },
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+}
+
+/// Data for [`QueuePremove`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct QueuePremoveData {
+    /// The move to queue, replacing any already queued. [`None`] clears it.
+    pub premove: Option<MakeMoveData>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, QueuePremove> for QueuePremove
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = Option<MakeMoveData>;
+
+        fn data_to_instruction_arg(
+            data: <QueuePremove as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.premove))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            premove: Self::InstructionData,
+            accounts: &mut <QueuePremove as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<QueuePremove as Instruction<AI>>::ReturnType> {
+            let queued = premove.clone();
+            let premove = match premove {
+                Some(premove) => {
+                    let in_range = [premove.big_board, premove.middle_board, premove.small_board]
+                        .iter()
+                        .flatten()
+                        .all(|index| *index < 3);
+                    if !in_range {
+                        return Err(custom_error!("Premove out of range: {:?}", premove).into());
+                    }
+                    log_event!(info, "premove_queued");
+                    premove
+                }
+                None => {
+                    log_event!(info, "premove_cleared");
+                    Game::NO_PREMOVE
+                }
+            };
+            accounts.game.premove = premove;
+            ProgramEvent::PremoveQueued {
+                game: *accounts.game.info().key(),
+                premove: queued,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`QueuePremove`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Queues a premove.
+    #[derive(Debug)]
+    pub struct QueuePremoveCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> QueuePremoveCPI<'a, AI> {
+        /// Queues a premove.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            premove: Option<MakeMoveData>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<QueuePremove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            QueuePremoveData { premove }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for QueuePremoveCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = QueuePremove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`QueuePremove`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Queues `premove` in `game` for `player_profile`, or clears their premove if [`None`].
+    pub fn queue_premove<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        premove: Option<MakeMoveData>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                QueuePremoveCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    premove,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
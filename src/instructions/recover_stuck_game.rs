@@ -0,0 +1,394 @@
+use crate::accounts::{Game, GameRecovery, PlayerProfile, ProgramConfig};
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+use std::iter::once;
+
+/// Carries out a [`ProposeGameRecovery`](crate::instructions::ProposeGameRecovery) once its timelock passes,
+/// refunding each player's stake to their profile and closing the game. Only the config's admin can.
+/// The rent of the game and its [`WagerVault`](crate::accounts::WagerVault) goes to the proposed `funds_to`.
+/// The players' profiles leave the game so they can be claimed with [`ClaimAbandonedProfile`](crate::instructions::ClaimAbandonedProfile) again.
+///
+/// If the game moved since the proposal, the recovery is cancelled and closed instead.
+#[derive(Debug)]
+pub enum RecoverStuckGame {}
+
+impl<AI> Instruction<AI> for RecoverStuckGame {
+    type Accounts = RecoverStuckGameAccounts<AI>;
+    type Data = ();
+    type ReturnType = ();
+}
+
+/// Accounts for [`RecoverStuckGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct RecoverStuckGameAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The proposed recovery.
+    #[validate(writable)]
+    pub recovery: CloseAccount<AI, DataAccount<AI, TutorialAccounts, GameRecovery>>,
+    /// The stuck game.
+    #[validate(writable, custom = self.game.info().key() == &self.recovery.game)]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// Where the rent of the game and its vault goes.
+    #[validate(writable, custom = self.funds_to.key() == &self.recovery.funds_to)]
+    pub funds_to: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The creator's profile, refunded their stake.
+    #[validate(writable, custom = self.creator_profile.info().key() == self.game.creator_profile())]
+    pub creator_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The joiner's profile, refunded their stake. Only needed if the game was joined, see [`Game::is_started`].
+    #[from(data = game.is_started())]
+    #[validate(
+        writable(IfSome),
+        custom = self.joiner_profile.as_ref().map_or(true, |joiner_profile| {
+            joiner_profile.info().key() == self.game.player(self.game.creator.other())
+        }),
+    )]
+    pub joiner_profile: Option<Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, RecoverStuckGame> for RecoverStuckGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <RecoverStuckGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <RecoverStuckGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<RecoverStuckGame as Instruction<AI>>::ReturnType> {
+            accounts.recovery.set_fundee(accounts.funds_to.clone());
+            if accounts.game.last_activity() != accounts.recovery.last_activity {
+                log_event!(
+                    info,
+                    "game_recovery_cancelled",
+                    game = accounts.game.info().key()
+                );
+                ProgramEvent::GameRecoveryCancelled {
+                    game: *accounts.game.info().key(),
+                }
+                .emit()?;
+                return Ok(());
+            }
+            let now = Clock::get()?.unix_timestamp;
+            if !accounts
+                .recovery
+                .is_due(now, accounts.config.recovery_delay)
+            {
+                return Err(custom_error!("Recovery is still timelocked").into());
+            }
+
+            let refunded = if accounts.game.friendly {
+                0
+            } else {
+                let vault = accounts.game_signer.info();
+                let creator = accounts.game.creator;
+                let creator_stake = accounts.game.stake(creator);
+                WagerVault::pay_out(vault, accounts.creator_profile.info(), creator_stake)?;
+                accounts
+                    .creator_profile
+                    .lamports_drawn_back
+                    .saturating_add_assign(creator_stake);
+                let mut refunded = creator_stake;
+                if let Some(joiner_profile) = &mut accounts.joiner_profile {
+                    let joiner_stake = accounts.game.stake(creator.other());
+                    WagerVault::pay_out(vault, joiner_profile.info(), joiner_stake)?;
+                    joiner_profile
+                        .lamports_drawn_back
+                        .saturating_add_assign(joiner_stake);
+                    refunded.saturating_add_assign(joiner_stake);
+                }
+                // Only the vault's rent is left.
+                WagerVault::close(vault, &accounts.funds_to)?;
+                refunded
+            };
+            log_event!(
+                info,
+                "game_recovered",
+                amount = refunded,
+                game = accounts.game.info().key(),
+                funds_to = accounts.funds_to.key()
+            );
+            ProgramEvent::GameRecovered {
+                game: *accounts.game.info().key(),
+                amount: refunded,
+                funds_to: *accounts.funds_to.key(),
+            }
+            .emit()?;
+
+            accounts.creator_profile.leave_game(now);
+            if let Some(joiner_profile) = &mut accounts.joiner_profile {
+                joiner_profile.leave_game(now);
+            }
+
+            // Zero out the players so the game is dead, it can't be re-opened in the same transaction and still be useful.
+            accounts.game.player1 = SystemProgram::<()>::KEY;
+            accounts.game.player2 = SystemProgram::<()>::KEY;
+            accounts.game.set_fundee(accounts.funds_to.clone());
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`RecoverStuckGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Recovers a stuck game.
+    #[derive(Debug)]
+    pub struct RecoverStuckGameCPI<'a, AI, const N: usize> {
+        accounts: [MaybeOwned<'a, AI>; N],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> RecoverStuckGameCPI<'a, AI, 9> {
+        /// Recovers a stuck game that was joined.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            recovery: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            creator_profile: impl Into<MaybeOwned<'a, AI>>,
+            joiner_profile: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RecoverStuckGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    recovery.into(),
+                    game.into(),
+                    game_signer.into(),
+                    funds_to.into(),
+                    system_program.into(),
+                    creator_profile.into(),
+                    joiner_profile.into(),
+                ],
+                data,
+            })
+        }
+    }
+    impl<'a, AI> RecoverStuckGameCPI<'a, AI, 8> {
+        /// Recovers a stuck game nobody joined.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_unjoined(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            recovery: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            creator_profile: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RecoverStuckGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    recovery.into(),
+                    game.into(),
+                    game_signer.into(),
+                    funds_to.into(),
+                    system_program.into(),
+                    creator_profile.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 10> for RecoverStuckGameCPI<'a, AI, 9>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RecoverStuckGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 10]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+    impl<'a, AI> CPIClientStatic<'a, 9> for RecoverStuckGameCPI<'a, AI, 8>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RecoverStuckGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 9]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`RecoverStuckGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_config, derive_game_recovery, derive_game_signer, HashedSigner, InstructionSet,
+    };
+
+    /// Recovers the stuck `game` to the `funds_to` it was proposed with.
+    /// `joiner_profile` must be set if the game was joined, see [`Game::is_started`].
+    pub fn recover_stuck_game<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        funds_to: Pubkey,
+        creator_profile: Pubkey,
+        joiner_profile: Option<Pubkey>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        let (recovery, _) = derive_game_recovery(&program_id, game);
+        let (game_signer, _) = derive_game_signer(&program_id, game);
+        let instruction = match joiner_profile {
+            Some(joiner_profile) => {
+                RecoverStuckGameCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(recovery, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new(creator_profile, false),
+                    SolanaAccountMeta::new(joiner_profile, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction
+            }
+            None => {
+                RecoverStuckGameCPI::new_unjoined(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(recovery, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new(creator_profile, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction
+            }
+        };
+        InstructionSet {
+            instructions: vec![instruction],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::accounts::ProgramConfig;
+use crate::clockwork::THREAD_PROGRAM_ID;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Registers a Clockwork thread that runs [`CrankForfeit`](crate::instructions::CrankForfeit) for a game,
+/// so it resolves once a turn expires without either player coming back.
+///
+/// The thread's authority is the game signer and its id is the game's key,
+/// so there can only be one per game. It is funded by the keeper tips it collects on top of `thread_amount`.
+/// The thread is not deleted when the game ends, its leftover lamports stay with it.
+/// Games with a [`settlement_hook`](crate::settlement_hook) can't register one since the thread's instruction
+/// is fixed here and couldn't pass the accounts the hook needs.
+#[derive(Debug)]
+pub enum RegisterForfeitThread {}
+
+impl<AI> Instruction<AI> for RegisterForfeitThread {
+    type Accounts = RegisterForfeitThreadAccounts<AI>;
+    type Data = RegisterForfeitThreadData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`RegisterForfeitThread`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct RegisterForfeitThreadAccounts<AI> {
+    /// The authority of one of the players.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile of one of the players.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to register the thread for.
+    #[validate(
+        writable,
+        custom = self.game.is_started(),
+        custom = self.game.is_timed(),
+        custom = self.game.forfeit_thread == Pubkey::new_from_array([0; 32]),
+        custom = !self.game.has_settlement_hook(),
+        custom = self.player_profile.info().key() == &self.game.player1
+            || self.player_profile.info().key() == &self.game.player2,
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// The game's signer, the thread's authority.
+    #[validate(data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The thread to create. The thread program checks it is the [`ThreadSeeder`](crate::clockwork::ThreadSeeder) address.
+    #[validate(writable)]
+    pub thread: AI,
+    /// The Clockwork thread program.
+    #[validate(custom = self.thread_program.key() == &THREAD_PROGRAM_ID)]
+    pub thread_program: AI,
+    /// Pays for the thread's rent and `thread_amount`.
+    #[validate(signer, writable)]
+    pub funder: AI,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+    /// The program config, passed on to the thread's [`CrankForfeit`](crate::instructions::CrankForfeit).
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+}
+
+/// Data for [`RegisterForfeitThread`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct RegisterForfeitThreadData {
+    /// Lamports given to the thread for its fees, on top of its rent.
+    pub thread_amount: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::clockwork::{forfeit_schedule, ThreadCreateCPI, Trigger};
+    use crate::event::ProgramEvent;
+    use crate::instructions::CrankForfeit;
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, RegisterForfeitThread> for RegisterForfeitThread
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = RegisterForfeitThreadData;
+
+        fn data_to_instruction_arg(
+            data: <RegisterForfeitThread as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <RegisterForfeitThread as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<RegisterForfeitThread as Instruction<AI>>::ReturnType> {
+            let signer_seeds = accounts.game_signer.take_seed_set().unwrap();
+            let game_key = *accounts.game.info().key();
+
+            let crank = CrankForfeit::thread_instruction(
+                *program_id,
+                *accounts.thread.key(),
+                game_key,
+                *accounts.game_signer.info().key(),
+                accounts.game.player1,
+                accounts.game.player2,
+                *accounts.config.info().key(),
+            )?;
+
+            log_event!(debug, "creating_thread");
+            let InstructionAndAccounts {
+                instruction,
+                accounts: cpi_accounts,
+            } = ThreadCreateCPI::new(
+                accounts.game_signer.info(),
+                &accounts.funder,
+                accounts.system_program.info(),
+                &accounts.thread,
+                data.thread_amount,
+                game_key.as_ref(),
+                &[crank],
+                &Trigger::Cron {
+                    schedule: forfeit_schedule(accounts.game.turn_length),
+                    skippable: true,
+                },
+            )?
+            .instruction(&accounts.thread_program);
+            CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&signer_seeds))?;
+
+            accounts.game.forfeit_thread = *accounts.thread.key();
+
+            ProgramEvent::ForfeitThreadRegistered {
+                game: game_key,
+                thread: *accounts.thread.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`RegisterForfeitThread`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Registers a forfeit thread for a game.
+    #[derive(Debug)]
+    pub struct RegisterForfeitThreadCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 9],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> RegisterForfeitThreadCPI<'a, AI> {
+        /// Registers a forfeit thread for a game.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            thread: impl Into<MaybeOwned<'a, AI>>,
+            thread_program: impl Into<MaybeOwned<'a, AI>>,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            register_data: &RegisterForfeitThreadData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RegisterForfeitThread>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            register_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    game.into(),
+                    game_signer.into(),
+                    thread.into(),
+                    thread_program.into(),
+                    funder.into(),
+                    system_program.into(),
+                    config.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 10> for RegisterForfeitThreadCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RegisterForfeitThread;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 10]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`RegisterForfeitThread`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
+    use crate::clockwork::ThreadSeeder;
+
+    /// Registers a forfeit thread for a game. The thread's address is stored on the game as [`Game::forfeit_thread`].
+    pub fn register_forfeit_thread<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        funder: impl Into<HashedSigner<'a>>,
+        thread_amount: u64,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let funder = funder.into();
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        let (thread, _) = ThreadSeeder {
+            authority: game_signer,
+            id: game.to_bytes().to_vec(),
+        }
+        .find_address(&THREAD_PROGRAM_ID);
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                RegisterForfeitThreadCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new_readonly(game_signer, false),
+                    SolanaAccountMeta::new(thread, false),
+                    SolanaAccountMeta::new_readonly(THREAD_PROGRAM_ID, false),
+                    SolanaAccountMeta::new(funder.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    &RegisterForfeitThreadData { thread_amount },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, funder].into_iter().collect(),
+        }
+    }
+}
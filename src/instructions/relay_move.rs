@@ -0,0 +1,306 @@
+use crate::error::custom_error;
+use crate::instructions::{MakeMoveAccounts, MakeMoveData, MoveOutcome};
+use cruiser::prelude::*;
+use cruiser::solana_program::sysvar::instructions;
+
+/// Makes a move approved by an ed25519 signature of [`relayed_move_message`] instead of the authority signing the transaction,
+/// so a relayer can pay the fees while the player only signs a message.
+/// The signature is checked by an ed25519 program instruction in the same transaction,
+/// see [`ed25519_instruction`](crate::ed25519::ed25519_instruction).
+/// Wins and draws are handled the same as [`MakeMove`](crate::instructions::MakeMove), returning a [`MoveOutcome`].
+#[derive(Debug)]
+pub enum RelayMove {}
+
+impl<AI> Instruction<AI> for RelayMove {
+    type Accounts = RelayMoveAccounts<AI>;
+    type Data = RelayMoveData;
+    type ReturnType = MoveOutcome;
+}
+
+/// Accounts for [`RelayMove`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct RelayMoveAccounts<AI> {
+    /// The instructions sysvar, to find the ed25519 instruction.
+    #[validate(custom = self.instructions.key() == &instructions::ID)]
+    pub instructions: AI,
+    /// The same accounts as [`MakeMove`](crate::instructions::MakeMove).
+    /// The authority doesn't sign.
+    pub make_move: MakeMoveAccounts<AI>,
+}
+
+/// Data for [`RelayMove`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct RelayMoveData {
+    /// The move to make.
+    pub mov: MakeMoveData,
+    /// Must be the player's [`PlayerProfile::relay_nonce`](crate::PlayerProfile::relay_nonce), so the signature can't be replayed.
+    pub nonce: u64,
+}
+
+/// The message the player's authority signs to relay `mov` in `game`.
+pub fn relayed_move_message(game: &Pubkey, mov: &MakeMoveData, nonce: u64) -> [u8; 46] {
+    let mut message = [0; 46];
+    message[..32].copy_from_slice(game.as_ref());
+    message[32..34].copy_from_slice(&mov.big_board);
+    message[34..36].copy_from_slice(&mov.middle_board);
+    message[36..38].copy_from_slice(&mov.small_board);
+    message[38..].copy_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::ed25519::ed25519_signed_messages;
+    use crate::instructions::{play_move, set_return};
+
+    impl<'a, AI> InstructionProcessor<AI, RelayMove> for RelayMove
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = RelayMoveData;
+
+        fn data_to_instruction_arg(
+            data: <RelayMove as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <RelayMove as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<RelayMove as Instruction<AI>>::ReturnType> {
+            let make_move = &mut accounts.make_move;
+            if make_move.game.rules.commit_reveal {
+                return Err(custom_error!(
+                    "Commit-reveal games are played with `CommitMove` and `RevealMove`"
+                )
+                .into());
+            }
+            if data.nonce != make_move.player_profile.relay_nonce {
+                return Err(custom_error!(
+                    "Stale nonce {}, expected {}",
+                    data.nonce,
+                    make_move.player_profile.relay_nonce
+                )
+                .into());
+            }
+
+            let message = relayed_move_message(make_move.game.info().key(), &data.mov, data.nonce);
+            {
+                let sysvar = accounts.instructions.data();
+                let signed = ed25519_signed_messages(&sysvar)
+                    .ok_or(custom_error!("Invalid instructions sysvar"))?;
+                if !signed
+                    .iter()
+                    .any(|(key, signed)| key == make_move.authority.key() && *signed == message)
+                {
+                    return Err(custom_error!("authority didn't sign the relayed move").into());
+                }
+            }
+
+            make_move
+                .player_profile
+                .relay_nonce
+                .saturating_add_assign(1);
+            set_return(play_move(make_move, data.mov)?)
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`RelayMove`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Relays a signed move.
+    #[derive(Debug)]
+    pub struct RelayMoveCPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> RelayMoveCPI<'a, AI> {
+        /// Relays a move that won't win the game.
+        pub fn new(
+            instructions: impl Into<MaybeOwned<'a, AI>>,
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            relay_move_data: &RelayMoveData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RelayMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            relay_move_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: vec![
+                    instructions.into(),
+                    authority.into(),
+                    player_profile.into(),
+                    game.into(),
+                ],
+                data,
+            })
+        }
+
+        /// Relays a move that will win or draw the game.
+        /// `payout_allowlist` is needed if the player has a payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_win(
+            instructions: impl Into<MaybeOwned<'a, AI>>,
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: Option<MaybeOwned<'a, AI>>,
+            relay_move_data: &RelayMoveData,
+        ) -> CruiserResult<Self> {
+            let mut out = Self::new(
+                instructions,
+                authority,
+                player_profile,
+                game,
+                relay_move_data,
+            )?;
+            out.accounts.extend([
+                game_signer.into(),
+                other_profile.into(),
+                funds_to.into(),
+                system_program.into(),
+            ]);
+            out.accounts.extend(payout_allowlist);
+            Ok(out)
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for RelayMoveCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RelayMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`RelayMove`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{InstructionSet, Signature};
+    use crate::ed25519::ed25519_instruction;
+    use crate::pda::GameSignerSeeder;
+
+    /// Relays a move that won't win the game, given `authority`'s signature of [`relayed_move_message`].
+    /// Nothing needs to sign the transaction but the fee payer.
+    pub fn relay_move<'a>(
+        program_id: Pubkey,
+        authority: Pubkey,
+        signature: &Signature,
+        player_profile: Pubkey,
+        game: Pubkey,
+        relay_data: RelayMoveData,
+    ) -> InstructionSet<'a> {
+        let message = relayed_move_message(&game, &relay_data.mov, relay_data.nonce);
+        InstructionSet {
+            instructions: vec![
+                ed25519_instruction(&authority, &message, signature),
+                RelayMoveCPI::new(
+                    SolanaAccountMeta::new_readonly(instructions::ID, false),
+                    SolanaAccountMeta::new_readonly(authority, false),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    &relay_data,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+
+    /// Relays a move that wins or draws the game, see [`make_winning_move`](crate::instructions::make_winning_move).
+    #[allow(clippy::too_many_arguments)]
+    pub fn relay_winning_move<'a>(
+        program_id: Pubkey,
+        authority: Pubkey,
+        signature: &Signature,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        other_profile: Pubkey,
+        funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
+        relay_data: RelayMoveData,
+    ) -> InstructionSet<'a> {
+        let message = relayed_move_message(&game, &relay_data.mov, relay_data.nonce);
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        InstructionSet {
+            instructions: vec![
+                ed25519_instruction(&authority, &message, signature),
+                RelayMoveCPI::new_win(
+                    SolanaAccountMeta::new_readonly(instructions::ID, false),
+                    SolanaAccountMeta::new_readonly(authority, false),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(other_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    payout_allowlist.map(|payout_allowlist| {
+                        SolanaAccountMeta::new_readonly(payout_allowlist, false).into()
+                    }),
+                    &relay_data,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
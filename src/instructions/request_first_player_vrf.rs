@@ -0,0 +1,360 @@
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::switchboard::SWITCHBOARD_PROGRAM_ID;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Requests Switchboard VRF randomness to decide who moves first, for games with enough at stake
+/// that [`random_first_player`](crate::instructions::CreateGameData::random_first_player) is too weak.
+///
+/// The creator requests it before anyone joins so the joiner knows the terms.
+/// The VRF account must already exist with the game signer as its authority and
+/// [`FulfillFirstPlayerVrf`](crate::instructions::FulfillFirstPlayerVrf) as its callback.
+/// No moves can be made until the callback runs. If the oracle never gets to it,
+/// either player can request again with the same VRF account before the first move.
+#[derive(Debug)]
+pub enum RequestFirstPlayerVrf {}
+
+impl<AI> Instruction<AI> for RequestFirstPlayerVrf {
+    type Accounts = RequestFirstPlayerVrfAccounts<AI>;
+    type Data = RequestFirstPlayerVrfData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`RequestFirstPlayerVrf`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct RequestFirstPlayerVrfAccounts<AI> {
+    /// The authority of one of the players.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile of one of the players.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to decide the first player of.
+    #[validate(
+        writable,
+        custom = !self.game.random_first_player,
+        custom = self.game.last_move == [3, 3],
+        custom = if self.game.is_first_player_pending() {
+            &self.game.first_player_vrf == self.vrf.key()
+        } else {
+            !self.game.is_started()
+        },
+        custom = self.player_profile.info().key() == &self.game.player1
+            || self.player_profile.info().key() == &self.game.player2,
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// The game's signer, the VRF account's authority.
+    #[validate(data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The VRF account. The oracle program checks the rest of the accounts against it.
+    #[validate(writable)]
+    pub vrf: AI,
+    /// The oracle queue the VRF account belongs to.
+    #[validate(writable)]
+    pub oracle_queue: AI,
+    /// The oracle queue's authority.
+    pub queue_authority: AI,
+    /// The oracle queue's data buffer.
+    pub data_buffer: AI,
+    /// The VRF account's permission on the queue.
+    #[validate(writable)]
+    pub permission: AI,
+    /// The VRF account's escrow.
+    #[validate(writable)]
+    pub escrow: AI,
+    /// The token account paying for the request.
+    #[validate(writable)]
+    pub payer_wallet: AI,
+    /// The authority of `payer_wallet`.
+    #[validate(signer)]
+    pub payer_authority: AI,
+    /// The recent blockhashes sysvar.
+    pub recent_blockhashes: AI,
+    /// The oracle program's state.
+    pub switchboard_state: AI,
+    /// The SPL token program.
+    pub token_program: AI,
+    /// The Switchboard oracle program.
+    #[validate(custom = self.switchboard_program.key() == &SWITCHBOARD_PROGRAM_ID)]
+    pub switchboard_program: AI,
+}
+
+/// Data for [`RequestFirstPlayerVrf`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct RequestFirstPlayerVrfData {
+    /// The bump of the VRF account's permission.
+    pub permission_bump: u8,
+    /// The bump of the oracle program's state.
+    pub switchboard_state_bump: u8,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use crate::switchboard::VrfRequestRandomnessCPI;
+    use std::iter::once;
+
+    impl<'a, AI> InstructionProcessor<AI, RequestFirstPlayerVrf> for RequestFirstPlayerVrf
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = RequestFirstPlayerVrfData;
+
+        fn data_to_instruction_arg(
+            data: <RequestFirstPlayerVrf as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <RequestFirstPlayerVrf as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<RequestFirstPlayerVrf as Instruction<AI>>::ReturnType> {
+            let signer_seeds = accounts.game_signer.take_seed_set().unwrap();
+
+            log_event!(debug, "requesting_randomness");
+            let InstructionAndAccounts {
+                instruction,
+                accounts: cpi_accounts,
+            } = VrfRequestRandomnessCPI::new(
+                accounts.game_signer.info(),
+                &accounts.vrf,
+                &accounts.oracle_queue,
+                &accounts.queue_authority,
+                &accounts.data_buffer,
+                &accounts.permission,
+                &accounts.escrow,
+                &accounts.payer_wallet,
+                &accounts.payer_authority,
+                &accounts.recent_blockhashes,
+                &accounts.switchboard_state,
+                &accounts.token_program,
+                data.permission_bump,
+                data.switchboard_state_bump,
+            )?
+            .instruction(&accounts.switchboard_program);
+            CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&signer_seeds))?;
+
+            accounts.game.first_player_vrf = *accounts.vrf.key();
+
+            ProgramEvent::FirstPlayerVrfRequested {
+                game: *accounts.game.info().key(),
+                vrf: *accounts.vrf.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`RequestFirstPlayerVrf`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Requests VRF randomness to decide a game's first player.
+    #[derive(Debug)]
+    pub struct RequestFirstPlayerVrfCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 16],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> RequestFirstPlayerVrfCPI<'a, AI> {
+        /// Requests VRF randomness to decide a game's first player.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            vrf: impl Into<MaybeOwned<'a, AI>>,
+            oracle_queue: impl Into<MaybeOwned<'a, AI>>,
+            queue_authority: impl Into<MaybeOwned<'a, AI>>,
+            data_buffer: impl Into<MaybeOwned<'a, AI>>,
+            permission: impl Into<MaybeOwned<'a, AI>>,
+            escrow: impl Into<MaybeOwned<'a, AI>>,
+            payer_wallet: impl Into<MaybeOwned<'a, AI>>,
+            payer_authority: impl Into<MaybeOwned<'a, AI>>,
+            recent_blockhashes: impl Into<MaybeOwned<'a, AI>>,
+            switchboard_state: impl Into<MaybeOwned<'a, AI>>,
+            token_program: impl Into<MaybeOwned<'a, AI>>,
+            switchboard_program: impl Into<MaybeOwned<'a, AI>>,
+            request_data: &RequestFirstPlayerVrfData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RequestFirstPlayerVrf>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            request_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    game.into(),
+                    game_signer.into(),
+                    vrf.into(),
+                    oracle_queue.into(),
+                    queue_authority.into(),
+                    data_buffer.into(),
+                    permission.into(),
+                    escrow.into(),
+                    payer_wallet.into(),
+                    payer_authority.into(),
+                    recent_blockhashes.into(),
+                    switchboard_state.into(),
+                    token_program.into(),
+                    switchboard_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 17> for RequestFirstPlayerVrfCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RequestFirstPlayerVrf;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 17]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`RequestFirstPlayerVrf`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+    use crate::switchboard::{RECENT_BLOCKHASHES_ID, TOKEN_PROGRAM_ID};
+
+    /// The Switchboard accounts for a VRF request, as set up when the VRF account was created.
+    #[derive(Copy, Clone, Debug)]
+    pub struct VrfRequestKeys {
+        /// The VRF account.
+        pub vrf: Pubkey,
+        /// The oracle queue the VRF account belongs to.
+        pub oracle_queue: Pubkey,
+        /// The oracle queue's authority.
+        pub queue_authority: Pubkey,
+        /// The oracle queue's data buffer.
+        pub data_buffer: Pubkey,
+        /// The VRF account's permission on the queue.
+        pub permission: Pubkey,
+        /// The bump of `permission`.
+        pub permission_bump: u8,
+        /// The VRF account's escrow.
+        pub escrow: Pubkey,
+        /// The oracle program's state.
+        pub switchboard_state: Pubkey,
+        /// The bump of `switchboard_state`.
+        pub switchboard_state_bump: u8,
+    }
+
+    /// Requests VRF randomness to decide a game's first player.
+    /// `payer_wallet` is a wrapped SOL token account owned by `payer_authority`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn request_first_player_vrf<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        keys: VrfRequestKeys,
+        payer_wallet: Pubkey,
+        payer_authority: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let payer_authority = payer_authority.into();
+        InstructionSet {
+            instructions: vec![
+                RequestFirstPlayerVrfCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new_readonly(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new(keys.vrf, false),
+                    SolanaAccountMeta::new(keys.oracle_queue, false),
+                    SolanaAccountMeta::new_readonly(keys.queue_authority, false),
+                    SolanaAccountMeta::new_readonly(keys.data_buffer, false),
+                    SolanaAccountMeta::new(keys.permission, false),
+                    SolanaAccountMeta::new(keys.escrow, false),
+                    SolanaAccountMeta::new(payer_wallet, false),
+                    SolanaAccountMeta::new_readonly(payer_authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(RECENT_BLOCKHASHES_ID, false),
+                    SolanaAccountMeta::new_readonly(keys.switchboard_state, false),
+                    SolanaAccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                    SolanaAccountMeta::new_readonly(SWITCHBOARD_PROGRAM_ID, false),
+                    &RequestFirstPlayerVrfData {
+                        permission_bump: keys.permission_bump,
+                        switchboard_state_bump: keys.switchboard_state_bump,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority, payer_authority].into_iter().collect(),
+        }
+    }
+}
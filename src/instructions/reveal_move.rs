@@ -0,0 +1,232 @@
+use crate::error::custom_error;
+use crate::instructions::{MakeMoveAccounts, MakeMoveData, MoveOutcome};
+use crate::log::log_event;
+use cruiser::prelude::*;
+
+/// Plays the move committed to with [`CommitMove`](crate::instructions::CommitMove).
+/// Takes the same accounts as [`MakeMove`](crate::instructions::MakeMove) and handles wins and draws the same way.
+/// Returns a [`MoveOutcome`].
+#[derive(Debug)]
+pub enum RevealMove {}
+
+impl<AI> Instruction<AI> for RevealMove {
+    type Accounts = MakeMoveAccounts<AI>;
+    type Data = RevealMoveData;
+    type ReturnType = MoveOutcome;
+}
+
+/// Data for [`RevealMove`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct RevealMoveData {
+    /// The committed move.
+    pub mov: MakeMoveData,
+    /// The salt it was hashed with, see [`Game::hash_move`](crate::Game::hash_move).
+    pub salt: [u8; 32],
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::instructions::{check_authority_signed, play_move, set_return};
+
+    impl<'a, AI> InstructionProcessor<AI, RevealMove> for RevealMove
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = RevealMoveData;
+
+        fn data_to_instruction_arg(
+            data: <RevealMove as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <RevealMove as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<RevealMove as Instruction<AI>>::ReturnType> {
+            check_authority_signed(accounts)?;
+            if !accounts.game.is_committed_move(&data.mov, &data.salt) {
+                return Err(custom_error!("Revealed move doesn't match the commitment").into());
+            }
+            log_event!(info, "move_revealed");
+            accounts.game.move_commitment = [0; 32];
+            set_return(play_move(accounts, data.mov)?)
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`RevealMove`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Reveals a committed move.
+    #[derive(Debug)]
+    pub struct RevealMoveCPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> RevealMoveCPI<'a, AI> {
+        /// Reveals a move that won't win the game.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            reveal_move_data: &RevealMoveData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<RevealMove>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            reveal_move_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: vec![authority.into(), player_profile.into(), game.into()],
+                data,
+            })
+        }
+
+        /// Reveals a move that will win or draw the game.
+        /// `payout_allowlist` is needed if the player has a payout allowlist.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new_win(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            other_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: Option<MaybeOwned<'a, AI>>,
+            reveal_move_data: &RevealMoveData,
+        ) -> CruiserResult<Self> {
+            let mut out = Self::new(authority, player_profile, game, reveal_move_data)?;
+            out.accounts.extend([
+                game_signer.into(),
+                other_profile.into(),
+                funds_to.into(),
+                system_program.into(),
+            ]);
+            out.accounts.extend(payout_allowlist);
+            Ok(out)
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for RevealMoveCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = RevealMove;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`RevealMove`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+    use crate::pda::GameSignerSeeder;
+
+    /// Reveals a committed move that won't win the game.
+    pub fn reveal_move<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        reveal_data: RevealMoveData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                RevealMoveCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    &reveal_data,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+
+    /// Reveals a committed move that wins or draws the game, see [`make_winning_move`](crate::instructions::make_winning_move).
+    #[allow(clippy::too_many_arguments)]
+    pub fn reveal_winning_move<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        other_profile: Pubkey,
+        funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
+        reveal_data: RevealMoveData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let game_signer = GameSignerSeeder { game }
+            .create_address(&program_id, game_signer_bump)
+            .unwrap();
+        InstructionSet {
+            instructions: vec![
+                RevealMoveCPI::new_win(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(game_signer, false),
+                    SolanaAccountMeta::new(other_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    payout_allowlist.map(|payout_allowlist| {
+                        SolanaAccountMeta::new_readonly(payout_allowlist, false).into()
+                    }),
+                    &reveal_data,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
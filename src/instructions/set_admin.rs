@@ -0,0 +1,210 @@
+use crate::accounts::ProgramConfig;
+use crate::governance::is_governance;
+use crate::log::log_event;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Hands the program config to a new admin. Only the current admin can.
+///
+/// The new admin has to sign so the config can't be handed to a key nobody holds,
+/// unless it is a Realms governance, which only signs when a proposal executes.
+/// An admin multisig is turned off, handing everything to the new admin.
+#[derive(Debug)]
+pub enum SetAdmin {}
+
+impl<AI> Instruction<AI> for SetAdmin {
+    type Accounts = SetAdminAccounts<AI>;
+    type Data = ();
+    type ReturnType = ();
+}
+
+/// Accounts for [`SetAdmin`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct SetAdminAccounts<AI> {
+    /// The config's current admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The config to hand over.
+    #[validate(writable, custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<DataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The new admin. Either a signer or a governance.
+    #[validate(custom = self.new_admin.is_signer() || is_governance(&*self.new_admin.owner(), &*self.new_admin.data()))]
+    pub new_admin: AI,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, SetAdmin> for SetAdmin
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <SetAdmin as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <SetAdmin as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<SetAdmin as Instruction<AI>>::ReturnType> {
+            log_event!(
+                info,
+                "admin_changed",
+                from = accounts.config.admin,
+                to = accounts.new_admin.key()
+            );
+            ProgramEvent::AdminChanged {
+                from: accounts.config.admin,
+                to: *accounts.new_admin.key(),
+            }
+            .emit()?;
+            accounts.config.admin = *accounts.new_admin.key();
+            accounts.config.admin_threshold = 0;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`SetAdmin`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Hands the program config to a new admin.
+    #[derive(Debug)]
+    pub struct SetAdminCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> SetAdminCPI<'a, AI> {
+        /// Hands the program config to a new admin.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            new_admin: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<SetAdmin>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            Ok(Self {
+                accounts: [admin.into(), config.into(), new_admin.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for SetAdminCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = SetAdmin;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`SetAdmin`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
+
+    /// Hands the program config to `new_admin`, which signs to show it is held.
+    pub fn set_admin<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        new_admin: impl Into<HashedSigner<'a>>,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let new_admin = new_admin.into();
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                SetAdminCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new(config, false),
+                    SolanaAccountMeta::new_readonly(new_admin.pubkey(), true),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin, new_admin].into_iter().collect(),
+        }
+    }
+
+    /// Hands the program config to a Realms `governance`.
+    /// From then on config changes go through proposals, see [`update_config_proposal`].
+    pub fn set_admin_to_governance<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        governance: Pubkey,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                SetAdminCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new(config, false),
+                    SolanaAccountMeta::new_readonly(governance, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+}
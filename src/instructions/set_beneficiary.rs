@@ -0,0 +1,196 @@
+use crate::accounts::Beneficiary;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Queues a new beneficiary that can claim the profile once it's abandoned.
+/// The change takes effect after [`PlayerProfile::BENEFICIARY_DELAY`].
+#[derive(Debug)]
+pub enum SetBeneficiary {}
+
+impl<AI> Instruction<AI> for SetBeneficiary {
+    type Accounts = SetBeneficiaryAccounts<AI>;
+    type Data = SetBeneficiaryData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`SetBeneficiary`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(
+    data = (set_data: SetBeneficiaryData),
+    custom = set_data.inactivity_period >= PlayerProfile::MIN_INACTIVITY_PERIOD,
+)]
+pub struct SetBeneficiaryAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile to set the beneficiary on.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`SetBeneficiary`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetBeneficiaryData {
+    /// The new beneficiary. The zero key removes the beneficiary.
+    pub beneficiary: Pubkey,
+    /// How long in seconds the profile must be inactive before it can be claimed.
+    /// Must be at least [`PlayerProfile::MIN_INACTIVITY_PERIOD`].
+    pub inactivity_period: UnixTimestamp,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, SetBeneficiary> for SetBeneficiary
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = SetBeneficiaryData;
+        type ValidateData = ();
+        type InstructionData = SetBeneficiaryData;
+
+        fn data_to_instruction_arg(
+            data: <SetBeneficiary as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.clone(), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <SetBeneficiary as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<SetBeneficiary as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            accounts.player_profile.propose_beneficiary(
+                Beneficiary {
+                    key: data.beneficiary,
+                    inactivity_period: data.inactivity_period,
+                },
+                now,
+            );
+            accounts.player_profile.mark_active(now);
+            ProgramEvent::BeneficiaryProposed {
+                profile: *accounts.player_profile.info().key(),
+                beneficiary: data.beneficiary,
+                inactivity_period: data.inactivity_period,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`SetBeneficiary`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Queues a new beneficiary for a profile.
+    #[derive(Debug)]
+    pub struct SetBeneficiaryCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> SetBeneficiaryCPI<'a, AI> {
+        /// Queues a new beneficiary for a profile.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            set_data: &SetBeneficiaryData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<SetBeneficiary>>::discriminant_compressed(
+            )
+            .serialize(&mut data)?;
+            set_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for SetBeneficiaryCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = SetBeneficiary;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`SetBeneficiary`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Queues a new beneficiary for a profile.
+    pub fn set_beneficiary<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        beneficiary: Pubkey,
+        inactivity_period: UnixTimestamp,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                SetBeneficiaryCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    &SetBeneficiaryData {
+                        beneficiary,
+                        inactivity_period,
+                    },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
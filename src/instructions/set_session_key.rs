@@ -0,0 +1,187 @@
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Sets the session key that can make moves for a profile until it expires, replacing any earlier one.
+/// It is accepted by [`MakeMove`](crate::instructions::MakeMove) and the instructions sharing its accounts,
+/// which can only pay out to the profile's own payout addresses, and nothing else.
+#[derive(Debug)]
+pub enum SetSessionKey {}
+
+impl<AI> Instruction<AI> for SetSessionKey {
+    type Accounts = SetSessionKeyAccounts<AI>;
+    type Data = SetSessionKeyData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`SetSessionKey`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct SetSessionKeyAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile to set the session key on.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+}
+
+/// Data for [`SetSessionKey`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetSessionKeyData {
+    /// The new session key. The zero key removes the session key.
+    pub delegate: Pubkey,
+    /// When the session key stops being accepted. Must be in the future unless removing it.
+    pub expiry: UnixTimestamp,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, SetSessionKey> for SetSessionKey
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = SetSessionKeyData;
+
+        fn data_to_instruction_arg(
+            data: <SetSessionKey as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <SetSessionKey as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<SetSessionKey as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            let removing = data.delegate == Pubkey::new_from_array([0; 32]);
+            if !removing && data.expiry <= now {
+                return Err(custom_error!("Session key expiry must be in the future").into());
+            }
+            accounts.player_profile.delegate = data.delegate;
+            accounts.player_profile.delegate_expiry = if removing { 0 } else { data.expiry };
+            accounts.player_profile.mark_active(now);
+            ProgramEvent::SessionKeySet {
+                profile: *accounts.player_profile.info().key(),
+                delegate: accounts.player_profile.delegate,
+                expiry: accounts.player_profile.delegate_expiry,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`SetSessionKey`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Sets a profile's session key.
+    #[derive(Debug)]
+    pub struct SetSessionKeyCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> SetSessionKeyCPI<'a, AI> {
+        /// Sets a profile's session key.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            set_data: &SetSessionKeyData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<SetSessionKey>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            set_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for SetSessionKeyCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = SetSessionKey;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`SetSessionKey`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Sets the session key for a profile, the zero key removes it.
+    pub fn set_session_key<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        delegate: Pubkey,
+        expiry: UnixTimestamp,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                SetSessionKeyCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    &SetSessionKeyData { delegate, expiry },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use crate::accounts::{GameBoard, ProgramConfig};
+use crate::error::custom_error;
+use crate::instructions::MakeMoveData;
+use crate::log::log_event;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::hash::hash;
+use cruiser::solana_program::sysvar::instructions;
+
+/// Settles a game played off-chain.
+/// Both players sign the game's moves, see [`settlement_message`], and either posts the final board with a bond.
+/// The signatures are checked by ed25519 program instructions earlier in the transaction,
+/// see [`ed25519_instruction`](crate::ed25519::ed25519_instruction).
+///
+/// The result is paid out by [`FinalizeSettlement`](crate::instructions::FinalizeSettlement) once
+/// [`ProgramConfig::settlement_dispute_window`] passes.
+/// Until then anyone with the moves can show the board is wrong with [`ChallengeSettlement`](crate::instructions::ChallengeSettlement).
+#[derive(Debug)]
+pub enum SettleSignedGame {}
+
+impl<AI> Instruction<AI> for SettleSignedGame {
+    type Accounts = SettleSignedGameAccounts<AI>;
+    type Data = SettleSignedGameData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`SettleSignedGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct SettleSignedGameAccounts<AI> {
+    /// The authority of either player, posting the bond.
+    #[validate(
+        signer,
+        writable,
+        custom = self.settler.key() == &self.player1_profile.authority
+            || self.settler.key() == &self.player2_profile.authority,
+    )]
+    pub settler: AI,
+    /// The game to settle.
+    #[validate(
+        writable,
+        custom = self.game.is_started(),
+        custom = !self.game.is_settling(),
+    )]
+    pub game: Box<DataAccount<AI, TutorialAccounts, Game>>,
+    /// The first player's profile.
+    #[validate(custom = &self.game.player1 == self.player1_profile.info().key())]
+    pub player1_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The second player's profile.
+    #[validate(custom = &self.game.player2 == self.player2_profile.info().key())]
+    pub player2_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The program config, for the bond.
+    /// Only [`InitConfig`](crate::instructions::InitConfig) can create a [`ProgramConfig`] so owner and discriminant checks are enough.
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The instructions sysvar, to find the ed25519 instructions.
+    #[validate(custom = self.instructions.key() == &instructions::ID)]
+    pub instructions: AI,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`SettleSignedGame`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SettleSignedGameData {
+    /// The board the moves end on.
+    pub board: GameBoard,
+    /// The hash of the moves both players signed, see [`settlement_moves_hash`].
+    pub moves_hash: [u8; 32],
+}
+
+/// Hashes the moves played off-chain, from the game's position on-chain, for [`settlement_message`].
+pub fn settlement_moves_hash(moves: &[MakeMoveData]) -> [u8; 32] {
+    let bytes = moves
+        .iter()
+        .flat_map(|mov| [mov.big_board, mov.middle_board, mov.small_board])
+        .flatten()
+        .collect::<Vec<_>>();
+    hash(&bytes).to_bytes()
+}
+
+/// The message both players sign to settle `game` with the moves hashed to `moves_hash`.
+pub fn settlement_message(game: &Pubkey, moves_hash: &[u8; 32]) -> [u8; 64] {
+    let mut message = [0; 64];
+    message[..32].copy_from_slice(game.as_ref());
+    message[32..].copy_from_slice(moves_hash);
+    message
+}
+
+#[cfg(feature = "processor")]
+pub(crate) use processor::pay_out_settlement;
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::{
+        update_elo, update_elo_with_score, GameResult, PendingSettlement, Player, WagerVault,
+    };
+    use crate::ed25519::ed25519_signed_messages;
+    use crate::event::ProgramEvent;
+    use crate::settlement_hook::GameSettled;
+    use cruiser::solana_program::clock::Clock;
+    use std::iter::empty;
+
+    impl<'a, AI> InstructionProcessor<AI, SettleSignedGame> for SettleSignedGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = SettleSignedGameData;
+
+        fn data_to_instruction_arg(
+            data: <SettleSignedGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <SettleSignedGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<SettleSignedGame as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+
+            let message = settlement_message(accounts.game.info().key(), &data.moves_hash);
+            {
+                let sysvar = accounts.instructions.data();
+                let signed = ed25519_signed_messages(&sysvar)
+                    .ok_or(custom_error!("Invalid instructions sysvar"))?;
+                for profile in [&accounts.player1_profile, &accounts.player2_profile] {
+                    if !signed
+                        .iter()
+                        .any(|(key, signed)| key == &profile.authority && *signed == message)
+                    {
+                        return Err(custom_error!(
+                            "{} didn't sign the settlement",
+                            profile.authority
+                        )
+                        .into());
+                    }
+                }
+            }
+
+            if data.board.variant() != accounts.game.variant() {
+                return Err(custom_error!("The final board is for a different variant").into());
+            }
+            let mut claimed = Game::clone(&accounts.game);
+            claimed.board = data.board;
+            let result = claimed
+                .result()
+                .ok_or(custom_error!("The final board isn't finished"))?;
+
+            let bond = accounts.config.settlement_bond;
+            if bond > 0 {
+                // The game account holds the bond until the settlement is final or challenged.
+                accounts.system_program.transfer(
+                    CPIChecked,
+                    &accounts.settler,
+                    accounts.game.info(),
+                    bond,
+                    empty(),
+                )?;
+            }
+
+            log_event!(info, "settlement_posted", result = result);
+            ProgramEvent::SettlementPosted {
+                game: *accounts.game.info().key(),
+                result,
+                settler: *accounts.settler.key(),
+                bond,
+            }
+            .emit()?;
+            accounts.game.settlement = PendingSettlement {
+                result,
+                moves_hash: data.moves_hash,
+                posted_at: now,
+                settler: *accounts.settler.key(),
+                bond,
+            };
+            Ok(())
+        }
+    }
+
+    /// Pays out a game settled off-chain with `result` and finishes it.
+    /// Winnings are held on the profiles until they withdraw them.
+    pub(crate) fn pay_out_settlement<'a, AI>(
+        game: &mut CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>,
+        game_signer: &AI,
+        player1_profile: &mut DataAccount<AI, TutorialAccounts, PlayerProfile>,
+        player2_profile: &mut DataAccount<AI, TutorialAccounts, PlayerProfile>,
+        result: GameResult,
+        now: UnixTimestamp,
+    ) -> CruiserResult<()>
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        // Friendly games have no vault to pay out of.
+        let vault = (!game.friendly).then(|| game_signer);
+        let pot = match vault {
+            Some(vault) => WagerVault::escrow(vault)?,
+            None => 0,
+        };
+        let pay_out = |to: &AI, amount: u64| match vault {
+            Some(vault) => WagerVault::pay_out(vault, to, amount),
+            None => Ok(()),
+        };
+        match result {
+            GameResult::Won(winner) => {
+                let (winner_profile, loser_profile) = match winner {
+                    Player::One => (&mut *player1_profile, &mut *player2_profile),
+                    Player::Two => (&mut *player2_profile, &mut *player1_profile),
+                };
+                pay_out(winner_profile.info(), pot)?;
+
+                winner_profile.wins.saturating_add_assign(1);
+                loser_profile.losses.saturating_add_assign(1);
+                winner_profile.lamports_won.saturating_add_assign(pot);
+                loser_profile
+                    .lamports_lost
+                    .saturating_add_assign(game.stake(winner.other()));
+
+                update_elo(
+                    &mut winner_profile.elo,
+                    &mut loser_profile.elo,
+                    PlayerProfile::ELO_K,
+                    true,
+                );
+            }
+            GameResult::Drawn => {
+                for (player, profile) in [
+                    (Player::One, &mut *player1_profile),
+                    (Player::Two, &mut *player2_profile),
+                ] {
+                    let stake = game.stake(player);
+                    pay_out(profile.info(), stake)?;
+                    profile.lamports_drawn_back.saturating_add_assign(stake);
+                    profile.draws.saturating_add_assign(1);
+                }
+
+                update_elo_with_score(
+                    &mut player1_profile.elo,
+                    &mut player2_profile.elo,
+                    PlayerProfile::ELO_K,
+                    0.5,
+                );
+            }
+        }
+        player1_profile.mark_game_ended(now);
+        player2_profile.mark_game_ended(now);
+
+        // Same as a finished game, left for `ArchiveGame`.
+        let creator_profile = match game.creator {
+            Player::One => player1_profile.info(),
+            Player::Two => player2_profile.info(),
+        };
+        let game_key = *game.info().key();
+        let settled = GameSettled {
+            game: game_key,
+            signer_bump: game.signer_bump,
+            player1: game.player1,
+            player2: game.player2,
+            result,
+            forfeited: false,
+            pot,
+            ended_at: now,
+        };
+        let archived = game.finish(&game_key, creator_profile.key(), result, now)?;
+        log_event!(
+            info,
+            "game_finished",
+            game = game_key,
+            leaf = archived.leaf()?
+        );
+        if let Some(vault) = vault {
+            WagerVault::close(vault, creator_profile)?;
+        }
+        ProgramEvent::GameEnded(settled).emit()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`SettleSignedGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Posts the settlement of a game played off-chain.
+    #[derive(Debug)]
+    pub struct SettleSignedGameCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 7],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> SettleSignedGameCPI<'a, AI> {
+        /// Posts the settlement of a game played off-chain.
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            settler: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            player1_profile: impl Into<MaybeOwned<'a, AI>>,
+            player2_profile: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            instructions: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            settle_data: &SettleSignedGameData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<SettleSignedGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            settle_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    settler.into(),
+                    game.into(),
+                    player1_profile.into(),
+                    player2_profile.into(),
+                    config.into(),
+                    instructions.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 8> for SettleSignedGameCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = SettleSignedGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 8]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`SettleSignedGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet, Signature};
+    use crate::ed25519::ed25519_instruction;
+    use std::iter::once;
+
+    /// Posts the settlement of `game` as `settler`, given each player's authority and their signature of [`settlement_message`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn settle_signed_game<'a>(
+        program_id: Pubkey,
+        settler: impl Into<HashedSigner<'a>>,
+        game: Pubkey,
+        player1_profile: Pubkey,
+        player2_profile: Pubkey,
+        settle_data: SettleSignedGameData,
+        signatures: [(Pubkey, Signature); 2],
+    ) -> InstructionSet<'a> {
+        let settler = settler.into();
+        let (config, _) = derive_config(&program_id);
+        let message = settlement_message(&game, &settle_data.moves_hash);
+        let settle = SettleSignedGameCPI::new(
+            SolanaAccountMeta::new(settler.pubkey(), true),
+            SolanaAccountMeta::new(game, false),
+            SolanaAccountMeta::new_readonly(player1_profile, false),
+            SolanaAccountMeta::new_readonly(player2_profile, false),
+            SolanaAccountMeta::new_readonly(config, false),
+            SolanaAccountMeta::new_readonly(instructions::ID, false),
+            SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+            &settle_data,
+        )
+        .unwrap()
+        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+        .instruction;
+        InstructionSet {
+            instructions: signatures
+                .iter()
+                .map(|(authority, signature)| ed25519_instruction(authority, &message, signature))
+                .chain(once(settle))
+                .collect(),
+            signers: [settler].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::accounts::Player;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Closes a game nobody joined within its [`Game::join_ttl`], refunding the wager and rent to the creator.
+/// Anyone can call this so dead games don't pile up.
+#[derive(Debug)]
+pub enum SweepStaleGame {}
+
+impl<AI> Instruction<AI> for SweepStaleGame {
+    type Accounts = SweepStaleGameAccounts<AI>;
+    type Data = SweepStaleGameData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`SweepStaleGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct SweepStaleGameAccounts<AI> {
+    /// The game to sweep.
+    #[validate(writable, custom = self.game.is_stale(Clock::get()?.unix_timestamp))]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The creator's profile.
+    #[validate(
+        writable,
+        custom = match self.game.creator {
+            Player::One => self.creator_profile.info().key() == &self.game.player1,
+            Player::Two => self.creator_profile.info().key() == &self.game.player2,
+        },
+    )]
+    pub creator_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The creator's authority, which gets the refund.
+    #[validate(writable, custom = self.creator_authority.key() == &self.creator_profile.authority)]
+    pub creator_authority: AI,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`SweepStaleGame`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SweepStaleGameData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, SweepStaleGame> for SweepStaleGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <SweepStaleGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <SweepStaleGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<SweepStaleGame as Instruction<AI>>::ReturnType> {
+            let refund = if accounts.game.friendly {
+                0
+            } else {
+                log_event!(debug, "refunding_wager");
+                let refund = WagerVault::escrow(accounts.game_signer.info())?;
+                WagerVault::close(accounts.game_signer.info(), &accounts.creator_authority)?;
+                accounts
+                    .creator_profile
+                    .lamports_drawn_back
+                    .saturating_add_assign(refund);
+                refund
+            };
+
+            accounts
+                .creator_profile
+                .leave_game(Clock::get()?.unix_timestamp);
+
+            // Zero out the players so the game is dead, it can't be re-opened in the same transaction and still be useful.
+            accounts.game.player1 = SystemProgram::<()>::KEY;
+            accounts.game.player2 = SystemProgram::<()>::KEY;
+            accounts.game.set_fundee(accounts.creator_authority.clone());
+            ProgramEvent::StaleGameSwept {
+                game: *accounts.game.info().key(),
+                refund,
+            }
+            .emit()?;
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`SweepStaleGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Sweeps a game nobody joined.
+    #[derive(Debug)]
+    pub struct SweepStaleGameCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> SweepStaleGameCPI<'a, AI> {
+        /// Sweeps a game nobody joined.
+        pub fn new(
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            creator_profile: impl Into<MaybeOwned<'a, AI>>,
+            creator_authority: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<SweepStaleGame>>::discriminant_compressed(
+            )
+            .serialize(&mut data)?;
+            SweepStaleGameData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    game.into(),
+                    game_signer.into(),
+                    creator_profile.into(),
+                    creator_authority.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for SweepStaleGameCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = SweepStaleGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`SweepStaleGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::InstructionSet;
+
+    /// Sweeps a game nobody joined. Only succeeds once [`Game::is_stale`], so check that before sending.
+    /// Needs no signer other than the fee payer.
+    pub fn sweep_stale_game<'a>(
+        program_id: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        creator_profile: Pubkey,
+        creator_authority: Pubkey,
+    ) -> InstructionSet<'a> {
+        InstructionSet {
+            instructions: vec![
+                SweepStaleGameCPI::new(
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new(creator_profile, false),
+                    SolanaAccountMeta::new(creator_authority, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
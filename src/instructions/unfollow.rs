@@ -0,0 +1,184 @@
+use crate::accounts::SocialGraph;
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Removes a profile from a profile's social graph.
+/// Takes the followed profile's key rather than its account so closed profiles can be removed.
+#[derive(Debug)]
+pub enum Unfollow {}
+
+impl<AI> Instruction<AI> for Unfollow {
+    type Accounts = UnfollowAccounts<AI>;
+    type Data = UnfollowData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`Unfollow`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct UnfollowAccounts<AI> {
+    /// The authority for the follower's profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The follower's profile.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The follower's social graph.
+    #[validate(writable, custom = &self.social_graph.profile == self.player_profile.info().key())]
+    pub social_graph: Box<DataAccount<AI, TutorialAccounts, SocialGraph>>,
+}
+
+/// Data for [`Unfollow`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UnfollowData {
+    /// The profile to unfollow.
+    pub profile: Pubkey,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, Unfollow> for Unfollow
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = Pubkey;
+
+        fn data_to_instruction_arg(
+            data: <Unfollow as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.profile))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            profile: Self::InstructionData,
+            accounts: &mut <Unfollow as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<Unfollow as Instruction<AI>>::ReturnType> {
+            if !accounts.social_graph.unfollow(&profile) {
+                return Err(custom_error!("Profile is not followed").into());
+            }
+            ProgramEvent::Unfollowed {
+                social_graph: *accounts.social_graph.info().key(),
+                unfollowed: profile,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`Unfollow`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Unfollows a profile.
+    #[derive(Debug)]
+    pub struct UnfollowCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UnfollowCPI<'a, AI> {
+        /// Unfollows a profile.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            social_graph: impl Into<MaybeOwned<'a, AI>>,
+            profile: Pubkey,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<Unfollow>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            UnfollowData { profile }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into(), social_graph.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for UnfollowCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = Unfollow;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`Unfollow`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_social_graph, HashedSigner, InstructionSet};
+
+    /// Unfollows `profile` from `player_profile`'s social graph.
+    pub fn unfollow<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        profile: Pubkey,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let (social_graph, _) = derive_social_graph(&program_id, player_profile);
+        InstructionSet {
+            instructions: vec![
+                UnfollowCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(social_graph, false),
+                    profile,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::accounts::Player;
+use crate::log::log_event;
+use crate::pda::GameSignerSeeder;
+use crate::{Game, PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Lets the creator of a game whose locked other player didn't join by [`Game::lock_expires_at`]
+/// either reclaim their wager and the game's rent or open the game to anyone.
+#[derive(Debug)]
+pub enum UnlockGame {}
+
+impl<AI> Instruction<AI> for UnlockGame {
+    type Accounts = UnlockGameAccounts<AI>;
+    type Data = UnlockGameData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`UnlockGame`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct UnlockGameAccounts<AI> {
+    /// The authority for the creator's profile, which gets the refund when reclaiming.
+    #[validate(signer, writable)]
+    pub authority: AI,
+    /// The creator's profile.
+    #[validate(
+        writable,
+        custom = &self.creator_profile.authority == self.authority.key(),
+        custom = match self.game.creator {
+            Player::One => self.creator_profile.info().key() == &self.game.player1,
+            Player::Two => self.creator_profile.info().key() == &self.game.player2,
+        },
+    )]
+    pub creator_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The game to unlock.
+    #[validate(writable, custom = self.game.is_lock_expired(Clock::get()?.unix_timestamp))]
+    pub game: Box<CloseAccount<AI, DataAccount<AI, TutorialAccounts, Game>>>,
+    /// The game's signer.
+    #[validate(writable, data = (GameSignerSeeder{ game: *self.game.info().key() }, self.game.signer_bump))]
+    pub game_signer: Seeds<AI, GameSignerSeeder>,
+    /// The system program
+    pub system_program: SystemProgram<AI>,
+}
+
+/// Data for [`UnlockGame`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UnlockGameData {
+    /// What to do with the game.
+    pub action: UnlockAction,
+}
+
+/// What [`UnlockGame`] does with a game its locked other player didn't join.
+#[derive(Copy, Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub enum UnlockAction {
+    /// Closes the game, refunding the wager and rent to the creator.
+    Reclaim,
+    /// Clears the locked other player so anyone [`Game::invited`] can join.
+    Open,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::accounts::WagerVault;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, UnlockGame> for UnlockGame
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = UnlockAction;
+
+        fn data_to_instruction_arg(
+            data: <UnlockGame as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.action))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <UnlockGame as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<UnlockGame as Instruction<AI>>::ReturnType> {
+            match data {
+                UnlockAction::Reclaim => {
+                    if !accounts.game.friendly {
+                        log_event!(debug, "refunding_wager");
+                        let refund = WagerVault::escrow(accounts.game_signer.info())?;
+                        WagerVault::close(accounts.game_signer.info(), &accounts.authority)?;
+                        accounts
+                            .creator_profile
+                            .lamports_drawn_back
+                            .saturating_add_assign(refund);
+                    }
+
+                    accounts
+                        .creator_profile
+                        .leave_game(Clock::get()?.unix_timestamp);
+
+                    // Same as a sweep, kill the game before closing it.
+                    accounts.game.player1 = SystemProgram::<()>::KEY;
+                    accounts.game.player2 = SystemProgram::<()>::KEY;
+                    accounts.game.set_fundee(accounts.authority.clone());
+                }
+                UnlockAction::Open => {
+                    *match accounts.game.creator {
+                        Player::One => &mut accounts.game.player2,
+                        Player::Two => &mut accounts.game.player1,
+                    } = Pubkey::new_from_array([0; 32]);
+                    accounts.game.lock_expires_at = 0;
+                }
+            }
+
+            log_event!(info, "game_unlocked");
+            ProgramEvent::GameUnlocked {
+                game: *accounts.game.info().key(),
+                action: data,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`UnlockGame`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Unlocks a game its locked other player didn't join.
+    #[derive(Debug)]
+    pub struct UnlockGameCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UnlockGameCPI<'a, AI> {
+        /// Unlocks a game its locked other player didn't join.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            creator_profile: impl Into<MaybeOwned<'a, AI>>,
+            game: impl Into<MaybeOwned<'a, AI>>,
+            game_signer: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            action: UnlockAction,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<UnlockGame>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            UnlockGameData { action }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    creator_profile.into(),
+                    game.into(),
+                    game_signer.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for UnlockGameCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UnlockGame;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`UnlockGame`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Unlocks a game its locked other player didn't join. Only succeeds once [`Game::is_lock_expired`],
+    /// so check that before sending.
+    pub fn unlock_game<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        creator_profile: Pubkey,
+        game: Pubkey,
+        game_signer_bump: u8,
+        action: UnlockAction,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                UnlockGameCPI::new(
+                    SolanaAccountMeta::new(authority.pubkey(), true),
+                    SolanaAccountMeta::new(creator_profile, false),
+                    SolanaAccountMeta::new(game, false),
+                    SolanaAccountMeta::new(
+                        GameSignerSeeder { game }
+                            .create_address(&program_id, game_signer_bump)
+                            .unwrap(),
+                        false,
+                    ),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    action,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use crate::accounts::{MetadataUri, ProgramConfig};
+use crate::log::log_event;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::fmt::Debug;
+use std::iter::once;
+
+/// Changes the program config's settings. Only the config's admin can.
+/// Each change is logged with its old and new value.
+///
+/// Setting [`ProgramConfig::admin_threshold`] hands this and the other admin instructions to the multisig,
+/// whose signers past the first are passed after the instruction's accounts.
+/// Once [`ProgramConfig::admin_action_delay`] is set, changes have to go through
+/// [`ProposeAdminAction`](crate::instructions::ProposeAdminAction) instead.
+#[derive(Debug)]
+pub enum UpdateConfig {}
+
+impl<AI> Instruction<AI> for UpdateConfig {
+    type Accounts = UpdateConfigAccounts<AI>;
+    type Data = UpdateConfigData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`UpdateConfig`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct UpdateConfigAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The config to update.
+    #[validate(
+        writable,
+        custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())),
+        custom = self.config.admin_action_delay == 0,
+    )]
+    pub config: Box<DataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`UpdateConfig`]. Fields that are [`None`] are left unchanged.
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize, Eq, PartialEq, OnChainSize)]
+pub struct UpdateConfigData {
+    /// The new [`ProgramConfig::keeper_tip_bps`].
+    pub keeper_tip_bps: Option<u16>,
+    /// The new [`ProgramConfig::max_house_wager`].
+    pub max_house_wager: Option<u64>,
+    /// The new [`ProgramConfig::max_house_exposure_bps`].
+    pub max_house_exposure_bps: Option<u16>,
+    /// The new [`ProgramConfig::elo_decay_period`].
+    pub elo_decay_period: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::elo_decay_step`].
+    pub elo_decay_step: Option<u64>,
+    /// The new [`ProgramConfig::min_wager`].
+    pub min_wager: Option<u64>,
+    /// The new [`ProgramConfig::max_wager`].
+    pub max_wager: Option<u64>,
+    /// The new [`ProgramConfig::min_turn_length`].
+    pub min_turn_length: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::max_turn_length`].
+    pub max_turn_length: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::paused`].
+    pub paused: Option<bool>,
+    /// The new [`ProgramConfig::admin_multisig`].
+    pub admin_multisig: Option<[Pubkey; ProgramConfig::MAX_ADMIN_SIGNERS]>,
+    /// The new [`ProgramConfig::admin_threshold`].
+    pub admin_threshold: Option<u8>,
+    /// The new [`ProgramConfig::treasury`].
+    pub treasury: Option<Pubkey>,
+    /// The new [`ProgramConfig::recovery_inert_period`].
+    pub recovery_inert_period: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::recovery_delay`].
+    pub recovery_delay: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::admin_action_delay`].
+    pub admin_action_delay: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::settlement_bond`].
+    pub settlement_bond: Option<u64>,
+    /// The new [`ProgramConfig::settlement_dispute_window`].
+    pub settlement_dispute_window: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::badge_tree`].
+    pub badge_tree: Option<Pubkey>,
+    /// The new [`ProgramConfig::badge_uri`].
+    pub badge_uri: Option<MetadataUri>,
+    /// The new [`ProgramConfig::min_ranked_games`].
+    pub min_ranked_games: Option<u64>,
+    /// The new [`ProgramConfig::min_profile_age`].
+    pub min_profile_age: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::game_creation_cooldown`].
+    pub game_creation_cooldown: Option<UnixTimestamp>,
+    /// The new [`ProgramConfig::reject_deprecated_instructions`].
+    pub reject_deprecated_instructions: Option<bool>,
+    /// The new [`ProgramConfig::settlement_hook`], the zero key to remove it.
+    pub settlement_hook: Option<Pubkey>,
+}
+
+impl UpdateConfigData {
+    /// Sets the changes on `config`, logging each, and checks the resulting settings.
+    pub fn apply(self, config: &mut ProgramConfig) -> CruiserResult<()> {
+        update(
+            "keeper_tip_bps",
+            &mut config.keeper_tip_bps,
+            self.keeper_tip_bps,
+        );
+        update(
+            "max_house_wager",
+            &mut config.max_house_wager,
+            self.max_house_wager,
+        );
+        update(
+            "max_house_exposure_bps",
+            &mut config.max_house_exposure_bps,
+            self.max_house_exposure_bps,
+        );
+        update(
+            "elo_decay_period",
+            &mut config.elo_decay_period,
+            self.elo_decay_period,
+        );
+        update(
+            "elo_decay_step",
+            &mut config.elo_decay_step,
+            self.elo_decay_step,
+        );
+        update("min_wager", &mut config.min_wager, self.min_wager);
+        update("max_wager", &mut config.max_wager, self.max_wager);
+        update(
+            "min_turn_length",
+            &mut config.min_turn_length,
+            self.min_turn_length,
+        );
+        update(
+            "max_turn_length",
+            &mut config.max_turn_length,
+            self.max_turn_length,
+        );
+        update("paused", &mut config.paused, self.paused);
+        update(
+            "admin_multisig",
+            &mut config.admin_multisig,
+            self.admin_multisig,
+        );
+        update(
+            "admin_threshold",
+            &mut config.admin_threshold,
+            self.admin_threshold,
+        );
+        update("treasury", &mut config.treasury, self.treasury);
+        update(
+            "recovery_inert_period",
+            &mut config.recovery_inert_period,
+            self.recovery_inert_period,
+        );
+        update(
+            "recovery_delay",
+            &mut config.recovery_delay,
+            self.recovery_delay,
+        );
+        update(
+            "admin_action_delay",
+            &mut config.admin_action_delay,
+            self.admin_action_delay,
+        );
+        update(
+            "settlement_bond",
+            &mut config.settlement_bond,
+            self.settlement_bond,
+        );
+        update(
+            "settlement_dispute_window",
+            &mut config.settlement_dispute_window,
+            self.settlement_dispute_window,
+        );
+        update("badge_tree", &mut config.badge_tree, self.badge_tree);
+        update("badge_uri", &mut config.badge_uri, self.badge_uri);
+        update(
+            "min_ranked_games",
+            &mut config.min_ranked_games,
+            self.min_ranked_games,
+        );
+        update(
+            "min_profile_age",
+            &mut config.min_profile_age,
+            self.min_profile_age,
+        );
+        update(
+            "game_creation_cooldown",
+            &mut config.game_creation_cooldown,
+            self.game_creation_cooldown,
+        );
+        update(
+            "reject_deprecated_instructions",
+            &mut config.reject_deprecated_instructions,
+            self.reject_deprecated_instructions,
+        );
+        update(
+            "settlement_hook",
+            &mut config.settlement_hook,
+            self.settlement_hook,
+        );
+        config.check_settings()
+    }
+}
+
+/// Sets `field` to `value` if there is one, logging the change.
+fn update<T: Debug>(name: &str, field: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        log_event!(
+            info,
+            "config_changed",
+            field = name,
+            from = field,
+            to = value
+        );
+        *field = value;
+    }
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, UpdateConfig> for UpdateConfig
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = UpdateConfigData;
+
+        fn data_to_instruction_arg(
+            data: <UpdateConfig as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <UpdateConfig as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<UpdateConfig as Instruction<AI>>::ReturnType> {
+            data.apply(&mut accounts.config)?;
+            ProgramEvent::ConfigUpdated {
+                config: *accounts.config.info().key(),
+            }
+            .emit()
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`UpdateConfig`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Changes the program config's settings.
+    #[derive(Debug)]
+    pub struct UpdateConfigCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 2],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UpdateConfigCPI<'a, AI> {
+        /// Changes the program config's settings.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            update_data: &UpdateConfigData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<UpdateConfig>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            update_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [admin.into(), config.into()],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for UpdateConfigCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateConfig;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`UpdateConfig`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, HashedSigner, InstructionSet};
+
+    /// Changes the program config's settings that are [`Some`] in `data`.
+    pub fn update_config<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        data: &UpdateConfigData,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        InstructionSet {
+            instructions: vec![
+                UpdateConfigCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new(config, false),
+                    data,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+
+    /// The [`UpdateConfig`] instruction for a proposal of the Realms `governance` that is the config's admin.
+    /// The governance signs it when the proposal executes, so there are no signers to return.
+    pub fn update_config_proposal(
+        program_id: Pubkey,
+        governance: Pubkey,
+        data: &UpdateConfigData,
+    ) -> SolanaInstruction {
+        let (config, _) = derive_config(&program_id);
+        UpdateConfigCPI::new(
+            SolanaAccountMeta::new_readonly(governance, true),
+            SolanaAccountMeta::new(config, false),
+            data,
+        )
+        .unwrap()
+        .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+        .instruction
+    }
+
+    /// Adds the admin multisig's `co_signers` to the program's admin instructions in `set`.
+    /// The instructions are built with one of the multisig's keys as admin.
+    /// Apply before [`with_metrics`](crate::instructions::with_metrics) as the metrics account has to come last.
+    pub fn with_admin_co_signers<'a>(
+        program_id: Pubkey,
+        mut set: InstructionSet<'a>,
+        co_signers: impl IntoIterator<Item = impl Into<HashedSigner<'a>>>,
+    ) -> InstructionSet<'a> {
+        let co_signers = co_signers.into_iter().map(Into::into).collect::<Vec<_>>();
+        for instruction in &mut set.instructions {
+            if instruction.program_id == program_id {
+                instruction.accounts.extend(
+                    co_signers
+                        .iter()
+                        .map(|signer| SolanaAccountMeta::new_readonly(signer.pubkey(), true)),
+                );
+            }
+        }
+        set.signers.extend(co_signers);
+        set
+    }
+}
@@ -0,0 +1,207 @@
+use crate::accounts::{LeaderboardEntry, LeaderboardShard, ShardInsert};
+use crate::error::custom_error;
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Lists a profile's current elo on a page of its elo band, removing it from the page it was on before.
+/// Anyone can call this, the elo comes from the profile.
+#[derive(Debug)]
+pub enum UpdateLeaderboard {}
+
+impl<AI> Instruction<AI> for UpdateLeaderboard {
+    type Accounts = UpdateLeaderboardAccounts<AI>;
+    type Data = UpdateLeaderboardData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`UpdateLeaderboard`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct UpdateLeaderboardAccounts<AI> {
+    /// The profile to list.
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The page to list the profile on. Must be in the profile's current band.
+    #[validate(
+        writable,
+        custom = self.shard.band == LeaderboardShard::band_for_elo(self.player_profile.elo),
+    )]
+    pub shard: Box<DataAccount<AI, TutorialAccounts, LeaderboardShard>>,
+    /// The page the profile is listed on now, if it is a different page.
+    #[validate(writable(IfSome))]
+    pub old_shard: Option<Box<DataAccount<AI, TutorialAccounts, LeaderboardShard>>>,
+}
+
+/// Data for [`UpdateLeaderboard`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UpdateLeaderboardData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, UpdateLeaderboard> for UpdateLeaderboard
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <UpdateLeaderboard as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <UpdateLeaderboard as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<UpdateLeaderboard as Instruction<AI>>::ReturnType> {
+            let profile = *accounts.player_profile.info().key();
+            if let Some(old_shard) = &mut accounts.old_shard {
+                if !old_shard.remove(&profile) {
+                    return Err(custom_error!("Profile is not listed on old_shard").into());
+                }
+            }
+
+            let evicted = match accounts.shard.insert(LeaderboardEntry {
+                profile,
+                elo: accounts.player_profile.elo,
+            }) {
+                ShardInsert::Inserted => None,
+                ShardInsert::Evicted(evicted) => {
+                    log_event!(info, "leaderboard_evicted", profile = evicted.profile);
+                    Some(evicted.profile)
+                }
+                ShardInsert::Full => {
+                    return Err(custom_error!(
+                        "Leaderboard page is full of higher ratings, try the next page"
+                    )
+                    .into())
+                }
+            };
+            ProgramEvent::LeaderboardUpdated {
+                shard: *accounts.shard.info().key(),
+                profile,
+                elo: accounts.player_profile.elo,
+                evicted,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`UpdateLeaderboard`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Lists a profile on the leaderboard.
+    #[derive(Debug)]
+    pub struct UpdateLeaderboardCPI<'a, AI> {
+        accounts: Vec<MaybeOwned<'a, AI>>,
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UpdateLeaderboardCPI<'a, AI> {
+        /// Lists a profile on the leaderboard.
+        pub fn new(
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            shard: impl Into<MaybeOwned<'a, AI>>,
+            old_shard: Option<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<UpdateLeaderboard>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            UpdateLeaderboardData {}.serialize(&mut data)?;
+            let mut accounts = vec![player_profile.into(), shard.into()];
+            accounts.extend(old_shard);
+            Ok(Self { accounts, data })
+        }
+    }
+
+    impl<'a, AI> CPIClientDynamic<'a> for UpdateLeaderboardCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateLeaderboard;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<Vec<MaybeOwned<'a, Self::AccountInfo>>> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts;
+            accounts.push(program_account);
+            InstructionAndAccounts {
+                instruction,
+                accounts,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`UpdateLeaderboard`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_leaderboard_shard, InstructionSet};
+
+    /// Lists `player_profile` on `page` of the band for `elo`, its current elo.
+    /// `old_page` is the `(band, page)` it is listed on now, if that is a different page.
+    pub fn update_leaderboard<'a>(
+        program_id: Pubkey,
+        player_profile: Pubkey,
+        elo: u64,
+        page: u16,
+        old_page: Option<(u16, u16)>,
+    ) -> InstructionSet<'a> {
+        let (shard, _) =
+            derive_leaderboard_shard(&program_id, LeaderboardShard::band_for_elo(elo), page);
+        InstructionSet {
+            instructions: vec![
+                UpdateLeaderboardCPI::new(
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(shard, false),
+                    old_page.map(|(band, page)| {
+                        SolanaAccountMeta::new(
+                            derive_leaderboard_shard(&program_id, band, page).0,
+                            false,
+                        )
+                        .into()
+                    }),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: Default::default(),
+        }
+    }
+}
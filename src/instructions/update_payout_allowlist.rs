@@ -0,0 +1,193 @@
+use crate::accounts::PayoutAllowlist;
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Queues a change to a profile's payout allowlist.
+/// The new list takes effect after [`PayoutAllowlist::UPDATE_DELAY`].
+#[derive(Debug)]
+pub enum UpdatePayoutAllowlist {}
+
+impl<AI> Instruction<AI> for UpdatePayoutAllowlist {
+    type Accounts = UpdatePayoutAllowlistAccounts<AI>;
+    type Data = UpdatePayoutAllowlistData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`UpdatePayoutAllowlist`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct UpdatePayoutAllowlistAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile the allowlist belongs to.
+    #[validate(custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<ReadOnlyDataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// The allowlist to update.
+    #[validate(
+        writable,
+        custom = &self.payout_allowlist.profile == self.player_profile.info().key(),
+    )]
+    pub payout_allowlist: DataAccount<AI, TutorialAccounts, PayoutAllowlist>,
+}
+
+/// Data for [`UpdatePayoutAllowlist`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UpdatePayoutAllowlistData {
+    /// The new allowed payout addresses. At most [`PayoutAllowlist::MAX_ADDRESSES`].
+    pub addresses: Vec<Pubkey>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::clock::Clock;
+
+    impl<'a, AI> InstructionProcessor<AI, UpdatePayoutAllowlist> for UpdatePayoutAllowlist
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = [Pubkey; PayoutAllowlist::MAX_ADDRESSES];
+
+        fn data_to_instruction_arg(
+            data: <UpdatePayoutAllowlist as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            let addresses = PayoutAllowlist::address_array(&data.addresses)
+                .ok_or(custom_error!("Too many payout addresses"))?;
+            Ok(((), (), addresses))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <UpdatePayoutAllowlist as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<UpdatePayoutAllowlist as Instruction<AI>>::ReturnType> {
+            let now = Clock::get()?.unix_timestamp;
+            accounts.payout_allowlist.propose(data, now);
+            ProgramEvent::PayoutAllowlistUpdateProposed {
+                payout_allowlist: *accounts.payout_allowlist.info().key(),
+                proposed_at: now,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`UpdatePayoutAllowlist`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Queues a change to a payout allowlist.
+    #[derive(Debug)]
+    pub struct UpdatePayoutAllowlistCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 3],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UpdatePayoutAllowlistCPI<'a, AI> {
+        /// Queues a change to a payout allowlist.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: impl Into<MaybeOwned<'a, AI>>,
+            update_data: &UpdatePayoutAllowlistData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<UpdatePayoutAllowlist>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            update_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    payout_allowlist.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for UpdatePayoutAllowlistCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdatePayoutAllowlist;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`UpdatePayoutAllowlist`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Queues a change to a profile's payout allowlist.
+    pub fn update_payout_allowlist<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        payout_allowlist: Pubkey,
+        addresses: Vec<Pubkey>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        InstructionSet {
+            instructions: vec![
+                UpdatePayoutAllowlistCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(player_profile, false),
+                    SolanaAccountMeta::new(payout_allowlist, false),
+                    &UpdatePayoutAllowlistData { addresses },
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use crate::accounts::{GuardianSet, PendingRecovery, ProfileMetadataUri};
+use crate::error::custom_error;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+
+/// Updates the settings on a player profile.
+///
+/// Setting [`PlayerProfile::metadata_uri`] reallocates the profile to fit it,
+/// `funder` pays the rent for a longer URI and the authority gets the rent back for a shorter one.
+#[derive(Debug)]
+pub enum UpdateProfile {}
+
+impl<AI> Instruction<AI> for UpdateProfile {
+    type Accounts = UpdateProfileAccounts<AI>;
+    type Data = UpdateProfileData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`UpdateProfile`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[from(data = (metadata_uri_len: Option<usize>))]
+pub struct UpdateProfileAccounts<AI> {
+    /// The authority for the profile, gets the rent back for a shorter metadata URI.
+    #[validate(signer, writable)]
+    pub authority: AI,
+    /// The profile to update.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// Pays the rent for a longer metadata URI. Only needed if `metadata_uri` is longer than the profile's current one.
+    #[from(data = metadata_uri_len.map_or(false, |len| len > player_profile.metadata_uri.len()))]
+    #[validate(signer(IfSome), writable(IfSome))]
+    pub funder: Option<AI>,
+    /// The system program, needed with `funder`.
+    #[from(data = funder.is_some())]
+    pub system_program: Option<SystemProgram<AI>>,
+    /// The profile's current [`PlayerProfile::co_signer`].
+    /// Only needed if it has one and `co_signer` or `co_sign_threshold` is being changed.
+    #[validate(signer(IfSome))]
+    pub co_signer: Option<AI>,
+}
+
+/// Data for [`UpdateProfile`]. Fields that are [`None`] are left unchanged.
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct UpdateProfileData {
+    /// The new payout address. The zero key pays out to the authority only.
+    pub payout_address: Option<Pubkey>,
+    /// The new metadata URI, at most [`PlayerProfile::MAX_METADATA_URI_LEN`] bytes. Empty clears it and refunds its rent.
+    pub metadata_uri: Option<String>,
+    /// The new guardians, cancelling any recovery in progress. A threshold of 0 turns recovery off.
+    pub guardians: Option<GuardianSet>,
+    /// The new co-signer, see [`PlayerProfile::co_signer`]. The zero key removes it.
+    pub co_signer: Option<Pubkey>,
+    /// The new [`PlayerProfile::co_sign_threshold`].
+    pub co_sign_threshold: Option<u64>,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::rent::Rent;
+    use std::iter::empty;
+
+    impl<'a, AI> InstructionProcessor<AI, UpdateProfile> for UpdateProfile
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = Option<usize>;
+        type ValidateData = ();
+        type InstructionData = UpdateProfileData;
+
+        fn data_to_instruction_arg(
+            data: <UpdateProfile as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok((data.metadata_uri.as_ref().map(String::len), (), data))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            data: Self::InstructionData,
+            accounts: &mut <UpdateProfile as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<UpdateProfile as Instruction<AI>>::ReturnType> {
+            if let Some(payout_address) = data.payout_address {
+                accounts.player_profile.payout_address = payout_address;
+            }
+            if let Some(metadata_uri) = data.metadata_uri {
+                let metadata_uri = ProfileMetadataUri::new(metadata_uri)
+                    .ok_or(custom_error!("metadata_uri is too long"))?;
+                // Fit the profile to the new URI, the funder pays for growing and the authority is refunded for shrinking.
+                let profile = accounts.player_profile.info();
+                let old_size = profile.data().len();
+                let new_size =
+                    old_size - accounts.player_profile.metadata_uri.len() + metadata_uri.len();
+                let rent = Rent::get()?;
+                let old_rent = rent.minimum_balance(old_size);
+                let new_rent = rent.minimum_balance(new_size);
+                match (&accounts.funder, &accounts.system_program) {
+                    (Some(funder), Some(system_program)) => {
+                        system_program.transfer(
+                            CPIChecked,
+                            funder,
+                            profile,
+                            new_rent - old_rent,
+                            empty(),
+                        )?;
+                    }
+                    _ => {
+                        // The profile is owned by the program so we can move its lamports directly.
+                        *profile.lamports_mut() -= old_rent - new_rent;
+                        *accounts.authority.lamports_mut() += old_rent - new_rent;
+                    }
+                }
+                profile.realloc(new_size, false)?;
+                accounts.player_profile.metadata_uri = metadata_uri;
+            }
+            if let Some(guardians) = data.guardians {
+                if !guardians.is_valid() {
+                    return Err(custom_error!(
+                        "guardians must be distinct and at least the threshold"
+                    )
+                    .into());
+                }
+                accounts.player_profile.guardians = guardians;
+                accounts.player_profile.recovery = PendingRecovery::default();
+            }
+            if data.co_signer.is_some() || data.co_sign_threshold.is_some() {
+                let current = accounts.player_profile.co_signer;
+                if current != Pubkey::new_from_array([0; 32])
+                    && accounts.co_signer.as_ref().map(AccountInfo::key) != Some(&current)
+                {
+                    return Err(
+                        custom_error!("The current co_signer must sign to change it").into(),
+                    );
+                }
+                if let Some(co_signer) = data.co_signer {
+                    accounts.player_profile.co_signer = co_signer;
+                }
+                if let Some(co_sign_threshold) = data.co_sign_threshold {
+                    accounts.player_profile.co_sign_threshold = co_sign_threshold;
+                }
+            }
+            ProgramEvent::ProfileUpdated {
+                profile: *accounts.player_profile.info().key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`UpdateProfile`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Updates the settings on a player profile.
+    /// `N` is 4 once [`funded`](UpdateProfileCPI::funded) and one more once [`co_signed`](UpdateProfileCPI::co_signed).
+    #[derive(Debug)]
+    pub struct UpdateProfileCPI<'a, AI, const N: usize = 2> {
+        accounts: [MaybeOwned<'a, AI>; N],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> UpdateProfileCPI<'a, AI> {
+        /// Updates the settings on a player profile.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            update_data: &UpdateProfileData,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<UpdateProfile>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            update_data.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into()],
+                data,
+            })
+        }
+
+        /// Adds the funder paying for a longer metadata URI, needed if `metadata_uri` is longer than the profile's current one.
+        pub fn funded(
+            self,
+            funder: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+        ) -> UpdateProfileCPI<'a, AI, 4> {
+            let [authority, player_profile] = self.accounts;
+            UpdateProfileCPI {
+                accounts: [
+                    authority,
+                    player_profile,
+                    funder.into(),
+                    system_program.into(),
+                ],
+                data: self.data,
+            }
+        }
+
+        /// Adds the profile's current co-signer, needed to change the co-signer settings once one is set.
+        pub fn co_signed(
+            self,
+            co_signer: impl Into<MaybeOwned<'a, AI>>,
+        ) -> UpdateProfileCPI<'a, AI, 3> {
+            let [authority, player_profile] = self.accounts;
+            UpdateProfileCPI {
+                accounts: [authority, player_profile, co_signer.into()],
+                data: self.data,
+            }
+        }
+    }
+    impl<'a, AI> UpdateProfileCPI<'a, AI, 4> {
+        /// Adds the profile's current co-signer, needed to change the co-signer settings once one is set.
+        pub fn co_signed(
+            self,
+            co_signer: impl Into<MaybeOwned<'a, AI>>,
+        ) -> UpdateProfileCPI<'a, AI, 5> {
+            let [authority, player_profile, funder, system_program] = self.accounts;
+            UpdateProfileCPI {
+                accounts: [
+                    authority,
+                    player_profile,
+                    funder,
+                    system_program,
+                    co_signer.into(),
+                ],
+                data: self.data,
+            }
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 3> for UpdateProfileCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateProfile;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 3]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+    impl<'a, AI> CPIClientStatic<'a, 4> for UpdateProfileCPI<'a, AI, 3>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateProfile;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [authority, player_profile, co_signer] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [authority, player_profile, co_signer, program_account],
+            }
+        }
+    }
+    impl<'a, AI> CPIClientStatic<'a, 5> for UpdateProfileCPI<'a, AI, 4>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateProfile;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [authority, player_profile, funder, system_program] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    authority,
+                    player_profile,
+                    funder,
+                    system_program,
+                    program_account,
+                ],
+            }
+        }
+    }
+    impl<'a, AI> CPIClientStatic<'a, 6> for UpdateProfileCPI<'a, AI, 5>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = UpdateProfile;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let [authority, player_profile, funder, system_program, co_signer] = self.accounts;
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    authority,
+                    player_profile,
+                    funder,
+                    system_program,
+                    co_signer,
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`UpdateProfile`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+    use std::iter::once;
+
+    /// Updates the settings on a player profile.
+    /// `funder` pays the rent of a metadata URI, it must be set if the URI is longer than the profile's current one.
+    pub fn update_profile<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        funder: Option<HashedSigner<'a>>,
+        data: UpdateProfileData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let update = UpdateProfileCPI::new(
+            SolanaAccountMeta::new(authority.pubkey(), true),
+            SolanaAccountMeta::new(player_profile, false),
+            &data,
+        )
+        .unwrap();
+        let instruction = match &funder {
+            Some(funder) => {
+                update
+                    .funded(
+                        SolanaAccountMeta::new(funder.pubkey(), true),
+                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    )
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction
+            }
+            None => {
+                update
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction
+            }
+        };
+        InstructionSet {
+            instructions: vec![instruction],
+            signers: once(authority).chain(funder).collect(),
+        }
+    }
+
+    /// Updates the settings on a player profile with its current [`PlayerProfile::co_signer`] also signing,
+    /// needed to change `co_signer` or `co_sign_threshold` once a co-signer is set.
+    /// `funder` is the same as for [`update_profile`].
+    pub fn update_profile_co_signed<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        co_signer: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        funder: Option<HashedSigner<'a>>,
+        data: UpdateProfileData,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let co_signer = co_signer.into();
+        let update = UpdateProfileCPI::new(
+            SolanaAccountMeta::new(authority.pubkey(), true),
+            SolanaAccountMeta::new(player_profile, false),
+            &data,
+        )
+        .unwrap();
+        let co_signer_meta = SolanaAccountMeta::new_readonly(co_signer.pubkey(), true);
+        let instruction = match &funder {
+            Some(funder) => {
+                update
+                    .funded(
+                        SolanaAccountMeta::new(funder.pubkey(), true),
+                        SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    )
+                    .co_signed(co_signer_meta)
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction
+            }
+            None => {
+                update
+                    .co_signed(co_signer_meta)
+                    .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                    .instruction
+            }
+        };
+        InstructionSet {
+            instructions: vec![instruction],
+            signers: [authority, co_signer].into_iter().chain(funder).collect(),
+        }
+    }
+}
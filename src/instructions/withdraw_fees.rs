@@ -0,0 +1,227 @@
+use crate::accounts::ProgramConfig;
+use crate::log::log_event;
+use crate::pda::FeeVaultSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Sends collected protocol fees from the fee vault to the config's treasury. Only the config's admin can.
+/// The amount is logged for off-chain accounting.
+/// Once [`ProgramConfig::admin_action_delay`] is set, withdrawals have to go through
+/// [`ProposeAdminAction`](crate::instructions::ProposeAdminAction) instead.
+#[derive(Debug)]
+pub enum WithdrawFees {}
+
+impl<AI> Instruction<AI> for WithdrawFees {
+    type Accounts = WithdrawFeesAccounts<AI>;
+    type Data = WithdrawFeesData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`WithdrawFees`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct WithdrawFeesAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(
+        custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())),
+        custom = self.config.admin_action_delay == 0,
+    )]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The vault holding the fees.
+    #[validate(writable, data = (FeeVaultSeeder, self.config.fee_vault_bump))]
+    pub fee_vault: Seeds<AI, FeeVaultSeeder>,
+    /// The config's treasury. Fees can't be withdrawn until one is set.
+    #[validate(
+        writable,
+        custom = self.config.treasury != Pubkey::new_from_array([0; 32]),
+        custom = self.treasury.key() == &self.config.treasury,
+    )]
+    pub treasury: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`WithdrawFees`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct WithdrawFeesData {
+    /// The lamports to take out. The vault must keep 0 or at least the rent exempt minimum.
+    pub amount: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, WithdrawFees> for WithdrawFees
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = u64;
+
+        fn data_to_instruction_arg(
+            data: <WithdrawFees as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.amount))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            amount: Self::InstructionData,
+            accounts: &mut <WithdrawFees as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<WithdrawFees as Instruction<AI>>::ReturnType> {
+            let vault_seeds = accounts.fee_vault.take_seed_set().unwrap();
+            accounts.system_program.transfer(
+                CPIChecked,
+                accounts.fee_vault.info(),
+                &accounts.treasury,
+                amount,
+                once(&vault_seeds),
+            )?;
+            log_event!(
+                info,
+                "fees_withdrawn",
+                amount = amount,
+                treasury = accounts.treasury.key()
+            );
+            ProgramEvent::FeesWithdrawn {
+                amount,
+                treasury: *accounts.treasury.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`WithdrawFees`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Sends collected protocol fees to the treasury.
+    #[derive(Debug)]
+    pub struct WithdrawFeesCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 5],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> WithdrawFeesCPI<'a, AI> {
+        /// Sends collected protocol fees to the treasury.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            fee_vault: impl Into<MaybeOwned<'a, AI>>,
+            treasury: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            amount: u64,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<WithdrawFees>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            WithdrawFeesData { amount }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    fee_vault.into(),
+                    treasury.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 6> for WithdrawFeesCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = WithdrawFees;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 6]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`WithdrawFees`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{derive_config, derive_fee_vault, HashedSigner, InstructionSet};
+
+    /// Sends `amount` lamports of collected protocol fees to the config's `treasury`.
+    pub fn withdraw_fees<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        treasury: Pubkey,
+        amount: u64,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        let (fee_vault, _) = derive_fee_vault(&program_id);
+        InstructionSet {
+            instructions: vec![
+                WithdrawFeesCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new(fee_vault, false),
+                    SolanaAccountMeta::new(treasury, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    amount,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::accounts::{House, ProgramConfig};
+use crate::pda::HouseVaultSeeder;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Takes lamports out of the house's bankroll. Only the config's admin can.
+///
+/// Wagers in running house games are held by their game signers so aren't affected.
+#[derive(Debug)]
+pub enum WithdrawHouseVault {}
+
+impl<AI> Instruction<AI> for WithdrawHouseVault {
+    type Accounts = WithdrawHouseVaultAccounts<AI>;
+    type Data = WithdrawHouseVaultData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`WithdrawHouseVault`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+#[validate(generics = [<'a> where AI: ToSolanaAccountInfo<'a>])]
+pub struct WithdrawHouseVaultAccounts<AI> {
+    /// The config's admin, or one of the admin multisig's keys.
+    #[validate(signer)]
+    pub admin: AI,
+    /// The program config.
+    #[validate(custom = self.config.admin_signed(once(&self.admin).chain(self.co_signers.iter())))]
+    pub config: Box<ReadOnlyDataAccount<AI, TutorialAccounts, ProgramConfig>>,
+    /// The house.
+    pub house: ReadOnlyDataAccount<AI, TutorialAccounts, House>,
+    /// The vault to withdraw from.
+    #[validate(writable, data = (HouseVaultSeeder, self.house.vault_bump))]
+    pub house_vault: Seeds<AI, HouseVaultSeeder>,
+    /// Where the lamports go.
+    #[validate(writable)]
+    pub funds_to: AI,
+    /// The system program.
+    pub system_program: SystemProgram<AI>,
+    /// The rest of the admin multisig's signers.
+    pub co_signers: Rest<AI>,
+}
+
+/// Data for [`WithdrawHouseVault`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct WithdrawHouseVaultData {
+    /// The lamports to take out. The vault must keep 0 or at least the rent exempt minimum.
+    pub amount: u64,
+}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+
+    impl<'a, AI> InstructionProcessor<AI, WithdrawHouseVault> for WithdrawHouseVault
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = u64;
+
+        fn data_to_instruction_arg(
+            data: <WithdrawHouseVault as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), data.amount))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            amount: Self::InstructionData,
+            accounts: &mut <WithdrawHouseVault as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<WithdrawHouseVault as Instruction<AI>>::ReturnType> {
+            let vault_seeds = accounts.house_vault.take_seed_set().unwrap();
+            accounts.system_program.transfer(
+                CPIChecked,
+                accounts.house_vault.info(),
+                &accounts.funds_to,
+                amount,
+                once(&vault_seeds),
+            )?;
+            ProgramEvent::HouseVaultWithdrawn {
+                funds_to: *accounts.funds_to.key(),
+                amount,
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`WithdrawHouseVault`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Takes lamports out of the house's bankroll.
+    #[derive(Debug)]
+    pub struct WithdrawHouseVaultCPI<'a, AI> {
+        accounts: [MaybeOwned<'a, AI>; 6],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> WithdrawHouseVaultCPI<'a, AI> {
+        /// Takes lamports out of the house's bankroll.
+        pub fn new(
+            admin: impl Into<MaybeOwned<'a, AI>>,
+            config: impl Into<MaybeOwned<'a, AI>>,
+            house: impl Into<MaybeOwned<'a, AI>>,
+            house_vault: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            system_program: impl Into<MaybeOwned<'a, AI>>,
+            amount: u64,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<WithdrawHouseVault>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            WithdrawHouseVaultData { amount }.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    admin.into(),
+                    config.into(),
+                    house.into(),
+                    house_vault.into(),
+                    funds_to.into(),
+                    system_program.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 7> for WithdrawHouseVaultCPI<'a, AI>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = WithdrawHouseVault;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 7]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`WithdrawHouseVault`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{
+        derive_config, derive_house, derive_house_vault, HashedSigner, InstructionSet,
+    };
+
+    /// Takes `amount` lamports out of the house's bankroll to `funds_to`.
+    pub fn withdraw_house_vault<'a>(
+        program_id: Pubkey,
+        admin: impl Into<HashedSigner<'a>>,
+        funds_to: Pubkey,
+        amount: u64,
+    ) -> InstructionSet<'a> {
+        let admin = admin.into();
+        let (config, _) = derive_config(&program_id);
+        let (house, _) = derive_house(&program_id);
+        let (house_vault, _) = derive_house_vault(&program_id);
+        InstructionSet {
+            instructions: vec![
+                WithdrawHouseVaultCPI::new(
+                    SolanaAccountMeta::new_readonly(admin.pubkey(), true),
+                    SolanaAccountMeta::new_readonly(config, false),
+                    SolanaAccountMeta::new_readonly(house, false),
+                    SolanaAccountMeta::new(house_vault, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(SystemProgram::<()>::KEY, false),
+                    amount,
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction,
+            ],
+            signers: [admin].into_iter().collect(),
+        }
+    }
+}
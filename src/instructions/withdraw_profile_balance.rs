@@ -0,0 +1,267 @@
+use crate::accounts::PayoutAllowlist;
+use crate::log::log_event;
+use crate::{PlayerProfile, TutorialAccounts};
+use cruiser::prelude::*;
+use cruiser::solana_program::clock::Clock;
+
+/// Withdraws the lamports a profile holds above its rent, such as wagers refunded on a draw.
+#[derive(Debug)]
+pub enum WithdrawProfileBalance {}
+
+impl<AI> Instruction<AI> for WithdrawProfileBalance {
+    type Accounts = WithdrawProfileBalanceAccounts<AI>;
+    type Data = WithdrawProfileBalanceData;
+    type ReturnType = ();
+}
+
+/// Accounts for [`WithdrawProfileBalance`]
+#[derive(AccountArgument, Debug)]
+#[account_argument(account_info = AI, generics = [where AI: AccountInfo])]
+pub struct WithdrawProfileBalanceAccounts<AI> {
+    /// The authority for the profile.
+    #[validate(signer)]
+    pub authority: AI,
+    /// The profile to withdraw from.
+    #[validate(writable, custom = &self.player_profile.authority == self.authority.key())]
+    pub player_profile: Box<DataAccount<AI, TutorialAccounts, PlayerProfile>>,
+    /// Where the lamports should go to.
+    /// Must be the authority or payout address, or on the payout allowlist if there is one.
+    #[validate(writable)]
+    pub funds_to: AI,
+    /// The profile's payout allowlist. Required if the profile has one.
+    #[validate(custom = self.player_profile.is_valid_payout(
+        self.payout_allowlist.as_ref().map(|allowlist| (allowlist.info().key(), &**allowlist)),
+        self.funds_to.key(),
+        Clock::get()?.unix_timestamp,
+    ))]
+    pub payout_allowlist: Option<ReadOnlyDataAccount<AI, TutorialAccounts, PayoutAllowlist>>,
+}
+
+/// Data for [`WithdrawProfileBalance`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct WithdrawProfileBalanceData {}
+
+#[cfg(feature = "processor")]
+mod processor {
+    use super::*;
+    use crate::event::ProgramEvent;
+    use cruiser::solana_program::rent::Rent;
+
+    impl<'a, AI> InstructionProcessor<AI, WithdrawProfileBalance> for WithdrawProfileBalance
+    where
+        AI: ToSolanaAccountInfo<'a>,
+    {
+        type FromAccountsData = ();
+        type ValidateData = ();
+        type InstructionData = ();
+
+        fn data_to_instruction_arg(
+            _data: <WithdrawProfileBalance as Instruction<AI>>::Data,
+        ) -> CruiserResult<(
+            Self::FromAccountsData,
+            Self::ValidateData,
+            Self::InstructionData,
+        )> {
+            Ok(((), (), ()))
+        }
+
+        fn process(
+            _program_id: &Pubkey,
+            _data: Self::InstructionData,
+            accounts: &mut <WithdrawProfileBalance as Instruction<AI>>::Accounts,
+        ) -> CruiserResult<<WithdrawProfileBalance as Instruction<AI>>::ReturnType> {
+            let rent = Rent::get()?.minimum_balance(accounts.player_profile.info().data().len());
+            // The profile is owned by the program so we can move its lamports directly.
+            let mut profile_lamports = accounts.player_profile.info().lamports_mut();
+            let amount = profile_lamports.saturating_sub(rent);
+            log_event!(info, "profile_withdrawal", amount = amount);
+            *profile_lamports -= amount;
+            *accounts.funds_to.lamports_mut() += amount;
+            ProgramEvent::ProfileWithdrawal {
+                profile: *accounts.player_profile.info().key(),
+                amount,
+                funds_to: *accounts.funds_to.key(),
+            }
+            .emit()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "cpi")]
+pub use cpi::*;
+
+/// CPI for [`WithdrawProfileBalance`]
+#[cfg(feature = "cpi")]
+mod cpi {
+    use super::*;
+    use crate::TutorialInstructions;
+
+    /// Withdraws a profile's balance.
+    #[derive(Debug)]
+    pub struct WithdrawProfileBalanceCPI<'a, AI, const N: usize> {
+        accounts: [MaybeOwned<'a, AI>; N],
+        data: Vec<u8>,
+    }
+    impl<'a, AI> WithdrawProfileBalanceCPI<'a, AI, 3> {
+        /// Withdraws a profile's balance.
+        pub fn new(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<WithdrawProfileBalance>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            WithdrawProfileBalanceData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [authority.into(), player_profile.into(), funds_to.into()],
+                data,
+            })
+        }
+    }
+    impl<'a, AI> WithdrawProfileBalanceCPI<'a, AI, 4> {
+        /// Withdraws a profile's balance to an address on its payout allowlist.
+        pub fn new_with_payout_allowlist(
+            authority: impl Into<MaybeOwned<'a, AI>>,
+            player_profile: impl Into<MaybeOwned<'a, AI>>,
+            funds_to: impl Into<MaybeOwned<'a, AI>>,
+            payout_allowlist: impl Into<MaybeOwned<'a, AI>>,
+        ) -> CruiserResult<Self> {
+            let mut data = Vec::new();
+            <TutorialInstructions as InstructionListItem<WithdrawProfileBalance>>::discriminant_compressed()
+                .serialize(&mut data)?;
+            WithdrawProfileBalanceData {}.serialize(&mut data)?;
+            Ok(Self {
+                accounts: [
+                    authority.into(),
+                    player_profile.into(),
+                    funds_to.into(),
+                    payout_allowlist.into(),
+                ],
+                data,
+            })
+        }
+    }
+
+    impl<'a, AI> CPIClientStatic<'a, 4> for WithdrawProfileBalanceCPI<'a, AI, 3>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = WithdrawProfileBalance;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 4]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+    impl<'a, AI> CPIClientStatic<'a, 5> for WithdrawProfileBalanceCPI<'a, AI, 4>
+    where
+        AI: ToSolanaAccountMeta,
+    {
+        type InstructionList = TutorialInstructions;
+        type Instruction = WithdrawProfileBalance;
+        type AccountInfo = AI;
+
+        fn instruction(
+            self,
+            program_account: impl Into<MaybeOwned<'a, Self::AccountInfo>>,
+        ) -> InstructionAndAccounts<[MaybeOwned<'a, Self::AccountInfo>; 5]> {
+            let program_account = program_account.into();
+            let instruction = SolanaInstruction {
+                program_id: *program_account.meta_key(),
+                accounts: self
+                    .accounts
+                    .iter()
+                    .map(MaybeOwned::as_ref)
+                    .map(AI::to_solana_account_meta)
+                    .collect(),
+                data: self.data,
+            };
+            let mut accounts = self.accounts.into_iter();
+            InstructionAndAccounts {
+                instruction,
+                accounts: [
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    accounts.next().unwrap(),
+                    program_account,
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client-core")]
+pub use client::*;
+
+/// Client for [`WithdrawProfileBalance`]
+#[cfg(feature = "client-core")]
+mod client {
+    use super::*;
+    use crate::client::{HashedSigner, InstructionSet};
+
+    /// Withdraws a profile's balance.
+    /// `payout_allowlist` must be set if the profile has one.
+    pub fn withdraw_profile_balance<'a>(
+        program_id: Pubkey,
+        authority: impl Into<HashedSigner<'a>>,
+        player_profile: Pubkey,
+        funds_to: Pubkey,
+        payout_allowlist: Option<Pubkey>,
+    ) -> InstructionSet<'a> {
+        let authority = authority.into();
+        let instruction = match payout_allowlist {
+            Some(payout_allowlist) => {
+                WithdrawProfileBalanceCPI::new_with_payout_allowlist(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                    SolanaAccountMeta::new_readonly(payout_allowlist, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction
+            }
+            None => {
+                WithdrawProfileBalanceCPI::new(
+                    SolanaAccountMeta::new_readonly(authority.pubkey(), true),
+                    SolanaAccountMeta::new(player_profile, false),
+                    SolanaAccountMeta::new(funds_to, false),
+                )
+                .unwrap()
+                .instruction(SolanaAccountMeta::new_readonly(program_id, false))
+                .instruction
+            }
+        };
+        InstructionSet {
+            instructions: vec![instruction],
+            signers: [authority].into_iter().collect(),
+        }
+    }
+}
@@ -2,16 +2,39 @@
 
 //! The tutorial example for cruiser.
 
+pub mod account_compression;
 pub mod accounts;
+pub mod allocator;
+pub mod bubblegum;
+#[cfg(feature = "client-core")]
+pub mod client;
+pub mod clockwork;
+pub mod ed25519;
+pub mod error;
+pub mod event;
+pub mod governance;
 pub mod instructions;
+pub mod log;
+pub mod merkle;
+#[cfg(feature = "processor")]
+pub mod metrics;
+#[cfg(all(feature = "processor", not(target_os = "solana")))]
+pub mod native;
 pub mod pda;
+pub mod settlement_hook;
+pub mod sizes;
+pub mod switchboard;
 
-use crate::accounts::{Game, PlayerProfile};
+use crate::accounts::{
+    ClaimReceipt, Distribution, Game, GameRecovery, House, InstructionMetrics, LeaderboardShard,
+    PayoutAllowlist, PlayerProfile, ProgramConfig, ProposedAction, SocialGraph, WagerVault,
+};
 use cruiser::prelude::*;
 
 // This uses your instruction list as the entrypoint to the program.
+// It goes through `process_metered` so appending the metrics account counts the instruction.
 #[cfg(feature = "entrypoint")]
-entrypoint_list!(TutorialInstructions, TutorialInstructions);
+cruiser::entrypoint!(metrics::process_metered);
 
 /// This is the list of instructions for your program, we will add more later.
 ///
@@ -22,6 +45,12 @@ entrypoint_list!(TutorialInstructions, TutorialInstructions);
 /// This derive also implements [`InstructionListItem`] for each item
 /// in the list and [`InstructionListProcessor`].
 /// All these traits can be manually implemented if you need custom logic.
+///
+/// Variants are never reordered or removed since their position is their discriminant.
+/// When an instruction needs to change incompatibly it's superseded by a new variant with a `V2` suffix
+/// appended at the end, sharing its processor code with the old one, and the old variant keeps working.
+/// Games created while [`ProgramConfig::reject_deprecated_instructions`](accounts::ProgramConfig::reject_deprecated_instructions)
+/// is set reject the superseded variant with [`TutorialError::DeprecatedInstruction`](error::TutorialError::DeprecatedInstruction).
 #[derive(Debug, InstructionList, Copy, Clone)]
 #[instruction_list(
     account_list = TutorialAccounts,
@@ -44,6 +73,162 @@ pub enum TutorialInstructions {
     /// Makes a move.
     #[instruction(instruction_type = instructions::MakeMove)]
     MakeMove,
+    /// Creates a payout allowlist for a profile.
+    #[instruction(instruction_type = instructions::CreatePayoutAllowlist)]
+    CreatePayoutAllowlist,
+    /// Queues a timelocked update to a payout allowlist.
+    #[instruction(instruction_type = instructions::UpdatePayoutAllowlist)]
+    UpdatePayoutAllowlist,
+    /// Queues a timelocked beneficiary change for a profile.
+    #[instruction(instruction_type = instructions::SetBeneficiary)]
+    SetBeneficiary,
+    /// Lets a beneficiary claim an abandoned profile.
+    #[instruction(instruction_type = instructions::ClaimAbandonedProfile)]
+    ClaimAbandonedProfile,
+    /// Updates the settings on a profile.
+    #[instruction(instruction_type = instructions::UpdateProfile)]
+    UpdateProfile,
+    /// Withdraws the lamports a profile holds above its rent.
+    #[instruction(instruction_type = instructions::WithdrawProfileBalance)]
+    WithdrawProfileBalance,
+    /// Makes several moves in a row.
+    #[instruction(instruction_type = instructions::MakeMoves)]
+    MakeMoves,
+    /// Creates the program config.
+    #[instruction(instruction_type = instructions::InitConfig)]
+    InitConfig,
+    /// Forfeits whoever's turn expired, for automation.
+    #[instruction(instruction_type = instructions::CrankForfeit)]
+    CrankForfeit,
+    /// Registers an automation thread to forfeit a game.
+    #[instruction(instruction_type = instructions::RegisterForfeitThread)]
+    RegisterForfeitThread,
+    /// Closes a game nobody joined in time.
+    #[instruction(instruction_type = instructions::SweepStaleGame)]
+    SweepStaleGame,
+    /// Requests VRF randomness to pick a game's first player
+    #[instruction(instruction_type = instructions::RequestFirstPlayerVrf)]
+    RequestFirstPlayerVrf,
+    /// Picks a game's first player from its VRF
+    #[instruction(instruction_type = instructions::FulfillFirstPlayerVrf)]
+    FulfillFirstPlayerVrf,
+    /// Creates the house
+    #[instruction(instruction_type = instructions::InitHouse)]
+    InitHouse,
+    /// Creates a game against the house
+    #[instruction(instruction_type = instructions::CreateHouseGame)]
+    CreateHouseGame,
+    /// Makes the house's move in a game against it
+    #[instruction(instruction_type = instructions::HouseMove)]
+    HouseMove,
+    /// Tops up the house's bankroll
+    #[instruction(instruction_type = instructions::FundHouseVault)]
+    FundHouseVault,
+    /// Takes lamports out of the house's bankroll
+    #[instruction(instruction_type = instructions::WithdrawHouseVault)]
+    WithdrawHouseVault,
+    /// Creates a profile's social graph
+    #[instruction(instruction_type = instructions::CreateSocialGraph)]
+    CreateSocialGraph,
+    /// Follows a profile
+    #[instruction(instruction_type = instructions::Follow)]
+    Follow,
+    /// Unfollows a profile
+    #[instruction(instruction_type = instructions::Unfollow)]
+    Unfollow,
+    /// Posts a chat message to a game
+    #[instruction(instruction_type = instructions::PostGameMessage)]
+    PostGameMessage,
+    /// Decays an inactive profile's elo toward the baseline
+    #[instruction(instruction_type = instructions::DecayElo)]
+    DecayElo,
+    /// Creates a page of the leaderboard for an elo band
+    #[instruction(instruction_type = instructions::CreateLeaderboardShard)]
+    CreateLeaderboardShard,
+    /// Lists a profile on the leaderboard page for its elo
+    #[instruction(instruction_type = instructions::UpdateLeaderboard)]
+    UpdateLeaderboard,
+    /// Creates the instruction usage metrics
+    #[instruction(instruction_type = instructions::InitMetrics)]
+    InitMetrics,
+    /// Changes the program config's settings
+    #[instruction(instruction_type = instructions::UpdateConfig)]
+    UpdateConfig,
+    /// Hands the program config to a new admin, which may be a Realms governance
+    #[instruction(instruction_type = instructions::SetAdmin)]
+    SetAdmin,
+    /// Sends collected protocol fees to the config's treasury
+    #[instruction(instruction_type = instructions::WithdrawFees)]
+    WithdrawFees,
+    /// Proposes recovering a stuck game's escrow after a timelock
+    #[instruction(instruction_type = instructions::ProposeGameRecovery)]
+    ProposeGameRecovery,
+    /// Recovers a stuck game's escrow once its proposal's timelock passes
+    #[instruction(instruction_type = instructions::RecoverStuckGame)]
+    RecoverStuckGame,
+    /// Proposes an admin action that executes after the config's delay
+    #[instruction(instruction_type = instructions::ProposeAdminAction)]
+    ProposeAdminAction,
+    /// Executes a proposed admin action once its delay has passed
+    #[instruction(instruction_type = instructions::ExecuteAdminAction)]
+    ExecuteAdminAction,
+    /// Cancels a proposed admin action
+    #[instruction(instruction_type = instructions::CancelAdminAction)]
+    CancelAdminAction,
+    /// Queues a move for the player waiting on their opponent.
+    #[instruction(instruction_type = instructions::QueuePremove)]
+    QueuePremove,
+    /// Commits to a move in a commit-reveal game.
+    #[instruction(instruction_type = instructions::CommitMove)]
+    CommitMove,
+    /// Reveals and plays a committed move.
+    #[instruction(instruction_type = instructions::RevealMove)]
+    RevealMove,
+    /// Settles a game played off-chain from its final board signed by both players.
+    #[instruction(instruction_type = instructions::SettleSignedGame)]
+    SettleSignedGame,
+    /// Pays out a signed settlement once its dispute window passes.
+    #[instruction(instruction_type = instructions::FinalizeSettlement)]
+    FinalizeSettlement,
+    /// Challenges a signed settlement with the moves it was signed over.
+    #[instruction(instruction_type = instructions::ChallengeSettlement)]
+    ChallengeSettlement,
+    /// Makes a move approved by an ed25519 signature so a relayer can pay the fees.
+    #[instruction(instruction_type = instructions::RelayMove)]
+    RelayMove,
+    /// Sets the session key that can make moves for a profile.
+    #[instruction(instruction_type = instructions::SetSessionKey)]
+    SetSessionKey,
+    /// Starts recovering a profile to a new authority as one of its guardians.
+    #[instruction(instruction_type = instructions::InitiateRecovery)]
+    InitiateRecovery,
+    /// Approves a profile recovery as one of its guardians.
+    #[instruction(instruction_type = instructions::ApproveRecovery)]
+    ApproveRecovery,
+    /// Moves a profile to its recovered authority once approved and delayed.
+    #[instruction(instruction_type = instructions::FinalizeRecovery)]
+    FinalizeRecovery,
+    /// Archives a finished game into a compression tree and closes it.
+    #[instruction(instruction_type = instructions::ArchiveGame)]
+    ArchiveGame,
+    /// Mints a compressed NFT badge for an achievement the profile has reached.
+    #[instruction(instruction_type = instructions::ClaimAchievement)]
+    ClaimAchievement,
+    /// Reclaims or opens a game its locked other player didn't join in time.
+    #[instruction(instruction_type = instructions::UnlockGame)]
+    UnlockGame,
+    /// Makes a move against the game as of its last turn, supersedes [`MakeMove`](Self::MakeMove).
+    #[instruction(instruction_type = instructions::MakeMoveV2)]
+    MakeMoveV2,
+    /// Returns a summary of a game without writing to it.
+    #[instruction(instruction_type = instructions::GetGameState)]
+    GetGameState,
+    /// Creates a pool of lamports paid out to claims under a Merkle root.
+    #[instruction(instruction_type = instructions::CreateDistribution)]
+    CreateDistribution,
+    /// Claims from a distribution with a Merkle proof.
+    #[instruction(instruction_type = instructions::ClaimReward)]
+    ClaimReward,
 }
 
 /// This is the list of accounts used by the program.
@@ -58,4 +243,26 @@ pub enum TutorialAccounts {
     Game(Game),
     /// A player's profile
     PlayerProfile(PlayerProfile),
+    /// Where a player's winnings may be paid out to
+    PayoutAllowlist(PayoutAllowlist),
+    /// The program wide settings.
+    ProgramConfig(ProgramConfig),
+    /// The house players can play against.
+    House(House),
+    /// The profiles a profile follows.
+    SocialGraph(SocialGraph),
+    /// A page of the leaderboard for an elo band.
+    LeaderboardShard(LeaderboardShard),
+    /// Usage counters for each instruction.
+    InstructionMetrics(InstructionMetrics),
+    /// A proposed recovery of a stuck game's escrow.
+    GameRecovery(GameRecovery),
+    /// An admin action waiting out its timelock.
+    ProposedAction(ProposedAction),
+    /// The escrow holding a game's wagers.
+    WagerVault(WagerVault),
+    /// A pool of lamports paid out to claims under a Merkle root.
+    Distribution(Distribution),
+    /// The record of a claim made on a distribution.
+    ClaimReceipt(ClaimReceipt),
 }
@@ -0,0 +1,213 @@
+//! Leveled, structured logging.
+//!
+//! Events are logged with [`log_event!`] as a level tag, the event's name and `key=value` fields,
+//! values debug formatted, e.g. `INFO event=fees_withdrawn amount=5000 treasury=9xQe...`.
+//! [`LogEvent::parse`] reads them back out of a transaction's logs.
+//!
+//! The `log-errors-only` feature leaves only [`Level::Error`] events in the program
+//! and `log-off` leaves out all of them, with their strings.
+//!
+//! These are for reading logs, indexers should decode the binary [`ProgramEvent`](crate::event::ProgramEvent)s instead.
+
+/// How important an event is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Level {
+    /// Nothing is logged at this level, only used for [`MAX_LEVEL`].
+    Off,
+    /// An instruction failed.
+    Error,
+    /// Something unexpected that didn't fail the instruction.
+    Warn,
+    /// A change to the program's state.
+    Info,
+    /// A step of an instruction.
+    Debug,
+}
+impl Level {
+    /// The tag the level is logged with.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        }
+    }
+
+    /// The level logged with `tag`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        [Level::Error, Level::Warn, Level::Info, Level::Debug]
+            .into_iter()
+            .find(|level| level.tag() == tag)
+    }
+}
+
+/// The least important level the program logs, set by the `log-errors-only` and `log-off` features.
+pub const MAX_LEVEL: Level = if cfg!(feature = "log-off") {
+    Level::Off
+} else if cfg!(feature = "log-errors-only") {
+    Level::Error
+} else {
+    Level::Debug
+};
+
+/// Logs an event at a level (`error`, `warn`, `info` or `debug`) with its name and `key = value` fields.
+/// Values must implement [`Debug`](std::fmt::Debug) and are only evaluated if the level is logged.
+///
+/// ```ignore
+/// log_event!(info, "fees_withdrawn", amount = amount, treasury = accounts.treasury.key());
+/// ```
+macro_rules! log_event {
+    (error, $($event:tt)+) => {
+        $crate::log::log_event!(@emit Error, "ERROR", $($event)+)
+    };
+    (warn, $($event:tt)+) => {
+        $crate::log::log_event!(@emit Warn, "WARN", $($event)+)
+    };
+    (info, $($event:tt)+) => {
+        $crate::log::log_event!(@emit Info, "INFO", $($event)+)
+    };
+    (debug, $($event:tt)+) => {
+        $crate::log::log_event!(@emit Debug, "DEBUG", $($event)+)
+    };
+    (@emit $level:ident, $tag:literal, $event:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::log::Level::$level as u8 <= $crate::log::MAX_LEVEL as u8 {
+            ::cruiser::prelude::msg!(
+                ::std::concat!($tag, " event=", $event $(, " ", ::std::stringify!($key), "={:?}")*)
+                $(, $value)*
+            );
+        }
+    };
+}
+pub(crate) use log_event;
+
+/// An event logged with [`log_event!`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogEvent {
+    /// The event's level.
+    pub level: Level,
+    /// The event's name.
+    pub event: String,
+    /// The event's fields in order, values still debug formatted.
+    pub fields: Vec<(String, String)>,
+}
+impl LogEvent {
+    /// Parses an event from a log line, with or without the runtime's `Program log: ` prefix.
+    /// [`None`] if the line isn't an event.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.strip_prefix("Program log: ").unwrap_or(line);
+        let (tag, rest) = line.split_once(' ')?;
+        let level = Level::from_tag(tag)?;
+        let rest = rest.strip_prefix("event=")?;
+        let (event, mut rest) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        let mut fields = Vec::new();
+        while !rest.is_empty() {
+            let (key, value_start) = rest.split_once('=')?;
+            let end = value_end(value_start);
+            fields.push((key.to_string(), value_start[..end].to_string()));
+            rest = value_start[end..].strip_prefix(' ').unwrap_or("");
+        }
+        Some(Self {
+            level,
+            event: event.to_string(),
+            fields,
+        })
+    }
+
+    /// The value of the field `key`, debug formatted.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value of the field `key` parsed, e.g. to a number or [`Pubkey`](cruiser::prelude::Pubkey).
+    pub fn parse_field<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+        self.field(key)?.parse().ok()
+    }
+
+    /// The value of a string field, without its quotes and escapes.
+    pub fn str_field(&self, key: &str) -> Option<String> {
+        let value = self.field(key)?.strip_prefix('"')?.strip_suffix('"')?;
+        let mut out = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(char) = chars.next() {
+            out.push(match char {
+                '\\' => match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    other => other,
+                },
+                char => char,
+            });
+        }
+        Some(out)
+    }
+}
+
+/// Where a debug formatted value starting `rest` ends.
+/// Quoted strings run to their closing quote, anything else to the next ` key=`.
+fn value_end(rest: &str) -> usize {
+    if rest.starts_with('"') {
+        let mut escaped = false;
+        for (index, char) in rest.char_indices().skip(1) {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return index + 1,
+                _ => {}
+            }
+        }
+        return rest.len();
+    }
+    let mut search = 0;
+    while let Some(space) = rest[search..].find(' ') {
+        let next = &rest[search + space + 1..];
+        let key_len = next
+            .find(|char: char| !(char.is_ascii_alphanumeric() || char == '_'))
+            .unwrap_or(next.len());
+        if key_len > 0 && next[key_len..].starts_with('=') {
+            return search + space;
+        }
+        search += space + 1;
+    }
+    rest.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let event = LogEvent::parse(
+            "Program log: INFO event=admin_action_proposed id=3 executable_at=100 action=WithdrawFees { amount: 5 }",
+        )
+        .unwrap();
+        assert_eq!(event.level, Level::Info);
+        assert_eq!(event.event, "admin_action_proposed");
+        assert_eq!(event.parse_field::<u64>("id"), Some(3));
+        assert_eq!(event.parse_field::<i64>("executable_at"), Some(100));
+        assert_eq!(event.field("action"), Some("WithdrawFees { amount: 5 }"));
+
+        let event =
+            LogEvent::parse(r#"INFO event=game_message message="gg x=1 \"nice\"" from=abc"#)
+                .unwrap();
+        assert_eq!(
+            event.str_field("message").as_deref(),
+            Some(r#"gg x=1 "nice""#)
+        );
+        assert_eq!(event.field("from"), Some("abc"));
+
+        let event = LogEvent::parse("DEBUG event=transferring").unwrap();
+        assert_eq!(event.level, Level::Debug);
+        assert!(event.fields.is_empty());
+
+        assert_eq!(LogEvent::parse("Program log: Transferring"), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! The Merkle tree [`Distribution`](crate::accounts::Distribution)s are published as.
+//!
+//! Each leaf is a claim of `amount` lamports by `claimant` at `index`, see [`leaf`].
+//! Pairs are hashed in sorted order so proofs don't need to say which side each sibling is on,
+//! and leaves and nodes are hashed with different prefixes so a node can't be passed off as a leaf.
+
+use cruiser::prelude::*;
+use cruiser::solana_program::hash::hashv;
+
+/// The prefix a leaf is hashed with.
+const LEAF_PREFIX: [u8; 1] = [0];
+/// The prefix a pair of nodes is hashed with.
+const NODE_PREFIX: [u8; 1] = [1];
+
+/// The leaf for a claim of `amount` lamports by `claimant` at `index`.
+pub fn leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        &LEAF_PREFIX,
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// The parent of two nodes.
+fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&NODE_PREFIX, low, high]).to_bytes()
+}
+
+/// Tells whether `proof` takes `leaf` up to `root`.
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |hash, sibling| node(&hash, sibling))
+        == *root
+}
+
+/// A Merkle tree of claims, built off-chain to publish a distribution's root and hand out proofs.
+#[cfg(feature = "client-core")]
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// The leaves first, up to the root.
+    layers: Vec<Vec<[u8; 32]>>,
+    /// The sum of the claims.
+    total: u64,
+}
+#[cfg(feature = "client-core")]
+impl MerkleTree {
+    /// Builds the tree for `claims` of `(claimant, amount)`, each claim's index being its position.
+    /// Errors if there are no claims or they add up past [`u64::MAX`].
+    pub fn new(claims: &[(Pubkey, u64)]) -> CruiserResult<Self> {
+        use crate::error::custom_error;
+
+        if claims.is_empty() {
+            return Err(custom_error!("a Merkle tree needs at least one claim").into());
+        }
+        let total = claims
+            .iter()
+            .try_fold(0u64, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(custom_error!("claims add up past u64::MAX"))?;
+        let mut layers = vec![claims
+            .iter()
+            .enumerate()
+            .map(|(index, (claimant, amount))| leaf(index as u64, claimant, *amount))
+            .collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            // An odd node out is carried up as is
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Ok(Self { layers, total })
+    }
+
+    /// The root to publish.
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// The sum of the claims, what the distribution must be funded with.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The number of claims in the tree.
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Always false, trees have at least one claim.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The proof for the claim at `index`, [`None`] if there is no such claim.
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut index = index;
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+#[cfg(all(test, feature = "client-core"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proofs() {
+        for count in 1..10 {
+            let claims = (0..count)
+                .map(|amount| (Pubkey::new_unique(), amount))
+                .collect::<Vec<_>>();
+            let tree = MerkleTree::new(&claims).unwrap();
+            assert_eq!(tree.len(), claims.len());
+            assert_eq!(
+                tree.total(),
+                claims.iter().map(|(_, amount)| amount).sum::<u64>()
+            );
+            for (index, (claimant, amount)) in claims.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(verify(
+                    &proof,
+                    &tree.root(),
+                    leaf(index as u64, claimant, *amount)
+                ));
+                assert!(!verify(
+                    &proof,
+                    &tree.root(),
+                    leaf(index as u64, claimant, amount + 1)
+                ));
+                assert!(!verify(
+                    &proof,
+                    &tree.root(),
+                    leaf(index as u64 + 1, claimant, *amount)
+                ));
+            }
+            assert_eq!(tree.proof(claims.len()), None);
+        }
+        assert!(MerkleTree::new(&[]).is_err());
+        assert!(
+            MerkleTree::new(&[(Pubkey::new_unique(), u64::MAX), (Pubkey::new_unique(), 1)])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_node_not_leaf() {
+        let claims = (0..4)
+            .map(|amount| (Pubkey::new_unique(), amount))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(&claims).unwrap();
+        // The proof of a node one layer up is a valid path but the node isn't a leaf of any claim
+        let proof = tree.proof(0).unwrap();
+        let parent = node(&tree.layers[0][0], &tree.layers[0][1]);
+        assert!(verify(&proof[1..], &tree.root(), parent));
+        assert_ne!(parent, leaf(0, &claims[0].0, claims[0].1));
+    }
+}
@@ -0,0 +1,146 @@
+//! Counting instruction usage in the [`InstructionMetrics`].
+
+use crate::accounts::InstructionMetrics;
+use crate::log::log_event;
+use crate::{TutorialAccounts, TutorialInstructions};
+use cruiser::prelude::*;
+use std::iter::once;
+
+/// Processes an instruction like the plain instruction list entrypoint would,
+/// then counts it if the [`InstructionMetrics`] account was appended after the instruction's own accounts.
+/// The metrics account is taken off before the instruction is processed, so instructions ending in [`Rest`]
+/// like [`MakeMove`](crate::instructions::MakeMove)'s settlement hook accounts never see it.
+///
+/// Failures are only logged, see [`InstructionMetrics`] for why they can't be counted.
+pub fn process_metered<'a, AI>(
+    program_id: &'static Pubkey,
+    accounts: &mut impl AccountInfoIterator<Item = AI>,
+    data: &[u8],
+) -> CruiserResult<()>
+where
+    AI: ToSolanaAccountInfo<'a> + Clone,
+{
+    let mut accounts = accounts.collect::<Vec<_>>();
+    let metrics = take_metrics_account(program_id, &mut accounts);
+
+    let result =
+        <TutorialInstructions as InstructionListProcessor<AI, TutorialInstructions>>::process_instruction(
+            program_id,
+            &mut accounts.into_iter(),
+            data,
+        );
+    if let Err(error) = &result {
+        log_event!(
+            error,
+            "instruction_failed",
+            instruction = data.first(),
+            error = error
+        );
+    }
+    result?;
+
+    // The list uses `u8` discriminants, so the first byte is the instruction.
+    if let (Some(metrics), Some(discriminant)) = (metrics, data.first()) {
+        let mut metrics = DataAccount::<AI, TutorialAccounts, InstructionMetrics>::from_accounts(
+            program_id,
+            &mut once(metrics),
+            (),
+        )?;
+        metrics.record(*discriminant);
+        metrics.write_back(program_id)?;
+    }
+    #[cfg(all(feature = "custom-heap", target_os = "solana"))]
+    log_event!(debug, "heap_used", bytes = crate::allocator::heap_used());
+    Ok(())
+}
+
+/// Takes the [`InstructionMetrics`] account off the end of `accounts` if it was appended.
+fn take_metrics_account<'a, AI>(program_id: &Pubkey, accounts: &mut Vec<AI>) -> Option<AI>
+where
+    AI: ToSolanaAccountInfo<'a>,
+{
+    if accounts
+        .last()
+        .map_or(false, |account| is_metrics_account(program_id, account))
+    {
+        accounts.pop()
+    } else {
+        None
+    }
+}
+
+/// Tells whether `account` is a writable [`InstructionMetrics`].
+/// Only [`InitMetrics`](crate::instructions::InitMetrics) creates one and only at its PDA,
+/// so checking the owner and discriminant is enough.
+fn is_metrics_account<'a, AI>(program_id: &Pubkey, account: &AI) -> bool
+where
+    AI: ToSolanaAccountInfo<'a>,
+{
+    let mut discriminant = Vec::new();
+    if <TutorialAccounts as AccountListItem<InstructionMetrics>>::compressed_discriminant()
+        .serialize(&mut discriminant)
+        .is_err()
+    {
+        return false;
+    }
+    account.is_writable()
+        && &*account.owner() == program_id
+        && account.data().starts_with(&discriminant)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cruiser::solana_program::account_info::AccountInfo as SolanaAccountInfo;
+
+    #[test]
+    fn test_take_metrics_account() {
+        let program_id = Pubkey::new_unique();
+        let (key, other_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = Vec::new();
+        <TutorialAccounts as AccountListItem<InstructionMetrics>>::compressed_discriminant()
+            .serialize(&mut data)
+            .unwrap();
+        InstructionMetrics::new(255).serialize(&mut data).unwrap();
+        let (mut lamports, mut other_lamports) = (0, 0);
+        let mut other_data = vec![0; data.len()];
+        let metrics = SolanaAccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let other = SolanaAccountInfo::new(
+            &other_key,
+            false,
+            true,
+            &mut other_lamports,
+            &mut other_data,
+            &program_id,
+            false,
+            0,
+        );
+
+        // Taken off so a trailing `Rest` doesn't get it
+        let mut accounts = vec![other.clone(), metrics.clone()];
+        let taken = take_metrics_account(&program_id, &mut accounts);
+        assert_eq!(taken.map(|account| account.key), Some(&key));
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].key, &other_key);
+
+        // Only when it's last
+        let mut accounts = vec![metrics.clone(), other];
+        assert!(take_metrics_account(&program_id, &mut accounts).is_none());
+        assert_eq!(accounts.len(), 2);
+
+        // Only when it's writable and the program's
+        let mut read_only = metrics.clone();
+        read_only.is_writable = false;
+        assert!(take_metrics_account(&program_id, &mut vec![read_only]).is_none());
+        assert!(take_metrics_account(&Pubkey::new_unique(), &mut vec![metrics]).is_none());
+    }
+}
@@ -0,0 +1,291 @@
+//! Running the program natively, off chain.
+
+use crate::metrics::process_metered;
+use cruiser::prelude::*;
+use cruiser::solana_program::account_info::AccountInfo as SolanaAccountInfo;
+use cruiser::solana_program::entrypoint::ProgramResult;
+
+/// Processes an instruction the same as the program's entrypoint, metrics included, with the signature
+/// `solana-program-test` expects so it can run the program natively, such as with
+/// `ProgramTest::new("cruiser_tutorial", program_id, processor!(process_instruction))`.
+/// Unit tests can also call it directly with their own accounts, without a validator.
+///
+/// The program id is copied and leaked for each instruction since processing takes it as `'static`,
+/// as it is on chain, which is fine for tests but means this isn't for long running processes.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[SolanaAccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let program_id: &'static Pubkey = Box::leak(Box::new(*program_id));
+    process_metered(program_id, &mut accounts.iter().cloned(), data)
+        .map_err(|error| error.to_program_error())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{
+        Beneficiary, Game, GameRecovery, GameVariant, Player, PlayerProfile, ProgramConfig,
+        RuleSet, WagerVault,
+    };
+    use crate::instructions::{ClaimAbandonedProfile, ClaimAbandonedProfileData, RecoverStuckGame};
+    use crate::pda::GameSignerSeeder;
+    use crate::{TutorialAccounts, TutorialInstructions};
+    use cruiser::solana_program::clock::{Clock, UnixTimestamp};
+    use cruiser::solana_program::entrypoint::{deserialize, MAX_PERMITTED_DATA_INCREASE, SUCCESS};
+    use cruiser::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use cruiser::solana_program::rent::Rent;
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// The time [`TestStubs`] gives the program.
+    static NOW: AtomicI64 = AtomicI64::new(0);
+
+    /// Gives the program the clock at [`NOW`] and the default rent, which the default stubs don't.
+    struct TestStubs;
+    impl SyscallStubs for TestStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: NOW.load(Ordering::SeqCst),
+                    ..Clock::default()
+                }
+            };
+            SUCCESS
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            SUCCESS
+        }
+    }
+
+    /// An account passed to [`process`].
+    #[derive(Clone)]
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        is_signer: bool,
+        is_writable: bool,
+    }
+    impl TestAccount {
+        fn new(key: Pubkey, is_signer: bool, is_writable: bool) -> Self {
+            Self {
+                key,
+                owner: SystemProgram::<()>::KEY,
+                lamports: 0,
+                data: Vec::new(),
+                is_signer,
+                is_writable,
+            }
+        }
+
+        /// A program owned account holding `value`, funded with its rent.
+        fn data<T>(program_id: &Pubkey, key: Pubkey, value: &T) -> Self
+        where
+            TutorialAccounts: AccountListItem<T>,
+            T: BorshSerialize,
+        {
+            let mut data = Vec::new();
+            <TutorialAccounts as AccountListItem<T>>::compressed_discriminant()
+                .serialize(&mut data)
+                .unwrap();
+            value.serialize(&mut data).unwrap();
+            Self {
+                key,
+                owner: *program_id,
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                is_signer: false,
+                is_writable: true,
+            }
+        }
+
+        /// Reads the account's value back.
+        fn read<T>(&self) -> T
+        where
+            TutorialAccounts: AccountListItem<T>,
+            T: BorshDeserialize,
+        {
+            let mut discriminant = Vec::new();
+            <TutorialAccounts as AccountListItem<T>>::compressed_discriminant()
+                .serialize(&mut discriminant)
+                .unwrap();
+            assert!(self.data.starts_with(&discriminant));
+            T::deserialize(&mut &self.data[discriminant.len()..]).unwrap()
+        }
+    }
+
+    /// Runs [`process_instruction`] on accounts laid out the way the loader passes them,
+    /// so they have the room after their data that reallocating and closing expect. Writes the results back to `accounts`.
+    fn process(program_id: &Pubkey, accounts: &mut [TestAccount], data: &[u8]) -> ProgramResult {
+        let mut input = Vec::new();
+        input.extend((accounts.len() as u64).to_le_bytes());
+        for account in accounts.iter() {
+            // Not a duplicate, signer, writable, executable, then padding
+            input.extend([
+                u8::MAX,
+                account.is_signer as u8,
+                account.is_writable as u8,
+                (account.key == SystemProgram::<()>::KEY) as u8,
+                0,
+                0,
+                0,
+                0,
+            ]);
+            input.extend(account.key.as_ref());
+            input.extend(account.owner.as_ref());
+            input.extend(account.lamports.to_le_bytes());
+            input.extend((account.data.len() as u64).to_le_bytes());
+            input.extend(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize((input.len() + 7) / 8 * 8, 0);
+            // Rent epoch
+            input.extend(0u64.to_le_bytes());
+        }
+        input.extend((data.len() as u64).to_le_bytes());
+        input.extend(data);
+        input.extend(program_id.as_ref());
+
+        // Backed by `u64`s so it's aligned like the loader's input
+        let mut aligned = vec![0u64; (input.len() + 7) / 8];
+        let aligned_bytes = aligned.as_mut_ptr() as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping(input.as_ptr(), aligned_bytes, input.len()) };
+        let (_, infos, data) = unsafe { deserialize(aligned_bytes) };
+
+        let result = process_instruction(program_id, &infos, data);
+        for (account, info) in accounts.iter_mut().zip(&infos) {
+            account.lamports = info.lamports();
+            account.data = info.data.borrow().to_vec();
+        }
+        result
+    }
+
+    /// The discriminant of instruction `I` followed by `data`.
+    fn instruction_data<I>(data: impl BorshSerialize) -> Vec<u8>
+    where
+        TutorialInstructions: InstructionListItem<I>,
+    {
+        let mut out = Vec::new();
+        <TutorialInstructions as InstructionListItem<I>>::discriminant_compressed()
+            .serialize(&mut out)
+            .unwrap();
+        data.serialize(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_process_instruction() {
+        let program_id = Pubkey::new_unique();
+        // No discriminant and one past the last instruction
+        assert!(process_instruction(&program_id, &[], &[]).is_err());
+        assert!(process_instruction(&program_id, &[], &[u8::MAX]).is_err());
+    }
+
+    #[test]
+    fn test_recover_stuck_game_then_claim_profile() {
+        set_syscall_stubs(Box::new(TestStubs));
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let beneficiary = Pubkey::new_unique();
+        let funds_to = Pubkey::new_unique();
+        let started: UnixTimestamp = 1_000_000;
+        let wager = 1_000_000;
+
+        let (creator_key, joiner_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let profiles = [creator_key, joiner_key].map(|key| {
+            let mut profile = PlayerProfile::new(&Pubkey::new_unique());
+            profile.beneficiary = Beneficiary {
+                key: beneficiary,
+                inactivity_period: PlayerProfile::MIN_INACTIVITY_PERIOD,
+            };
+            profile.last_active = started;
+            profile.mark_game_joined(started);
+            TestAccount::data(&program_id, key, &profile)
+        });
+
+        let game_key = Pubkey::new_unique();
+        let (game_signer, signer_bump) =
+            GameSignerSeeder { game: game_key }.find_address(&program_id);
+        let mut game = Game::new(
+            &creator_key,
+            Player::One,
+            signer_bump,
+            wager,
+            wager,
+            60 * 60 * 24,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        game.player2 = joiner_key;
+        game.created_at = started;
+        game.last_turn = started;
+
+        let config = ProgramConfig::new(&admin, 255, 0, 0, 0, 0, 0);
+        let proposed_at = started + ProgramConfig::MIN_RECOVERY_INERT_PERIOD;
+        let recovery =
+            GameRecovery::new(&game_key, &funds_to, game.last_activity(), proposed_at, 255);
+        let mut vault = WagerVault::new(&game_key);
+        vault.deposit(wager * 2);
+        let mut vault = TestAccount::data(&program_id, game_signer, &vault);
+        vault.lamports += wager * 2;
+
+        let claim = |profile: &TestAccount| {
+            let mut accounts = [
+                TestAccount::new(beneficiary, true, false),
+                profile.clone(),
+                TestAccount::new(beneficiary, false, true),
+            ];
+            let result = process(
+                &program_id,
+                &mut accounts,
+                &instruction_data::<ClaimAbandonedProfile>(ClaimAbandonedProfileData {}),
+            );
+            (result, accounts[1].lamports)
+        };
+
+        NOW.store(proposed_at + config.recovery_delay, Ordering::SeqCst);
+        // Still seated in the stuck game
+        assert!(claim(&profiles[0]).0.is_err());
+
+        let profile_rent = profiles[0].lamports;
+        let vault_rent = vault.lamports - wager * 2;
+        let [creator_profile, joiner_profile] = profiles;
+        let mut accounts = [
+            TestAccount::new(admin, true, false),
+            TestAccount {
+                is_writable: false,
+                ..TestAccount::data(&program_id, Pubkey::new_unique(), &config)
+            },
+            TestAccount::data(&program_id, Pubkey::new_unique(), &recovery),
+            TestAccount::data(&program_id, game_key, &game),
+            vault,
+            TestAccount::new(funds_to, false, true),
+            TestAccount::new(SystemProgram::<()>::KEY, false, false),
+            creator_profile,
+            joiner_profile,
+        ];
+        let closed_rent = accounts[2].lamports + accounts[3].lamports;
+        process(
+            &program_id,
+            &mut accounts,
+            &instruction_data::<RecoverStuckGame>(()),
+        )
+        .unwrap();
+        for profile in &accounts[7..] {
+            // Each player gets their stake back, only the rent goes to `funds_to`
+            assert_eq!(profile.lamports, profile_rent + wager);
+            assert_eq!(profile.read::<PlayerProfile>().lamports_drawn_back, wager);
+            assert_eq!(profile.read::<PlayerProfile>().games_in_progress, 0);
+        }
+        assert_eq!(accounts[4].lamports, 0);
+        assert_eq!(accounts[5].lamports, vault_rent + closed_rent);
+
+        let (result, lamports) = claim(&accounts[7]);
+        result.unwrap();
+        assert_eq!(lamports, 0);
+    }
+}
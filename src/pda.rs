@@ -1,7 +1,78 @@
 //! PDAs for the program.
 
+use crate::error::{custom_error, TutorialError};
 use cruiser::prelude::*;
 
+/// Errors if `bump` isn't `seeder`'s canonical bump under `program_id`.
+/// Any bump that derives an address passes a [`Seeds`] check but clients only ever look for the canonical one.
+pub fn check_canonical_bump(
+    seeder: &impl PDASeeder,
+    bump: u8,
+    program_id: &Pubkey,
+) -> CruiserResult<()> {
+    if seeder.find_address(program_id).1 == bump {
+        Ok(())
+    } else {
+        Err(TutorialError::NonCanonicalBump.into())
+    }
+}
+
+/// A PDA's key and bump kept together with its seeder, so the bump doesn't have to be re-derived or passed around on its own.
+#[derive(Debug, Clone)]
+pub struct KnownPda<S> {
+    seeder: S,
+    key: Pubkey,
+    bump: u8,
+}
+impl<S> KnownPda<S>
+where
+    S: PDASeeder,
+{
+    /// Derives the PDA at `seeder`'s canonical bump.
+    pub fn find(seeder: S, program_id: &Pubkey) -> Self {
+        let (key, bump) = seeder.find_address(program_id);
+        Self { seeder, key, bump }
+    }
+
+    /// The PDA at `bump`, errors if no address derives from it.
+    /// Check the bump with [`KnownPda::verify`] if it came from outside the program.
+    pub fn with_bump(seeder: S, bump: u8, program_id: &Pubkey) -> CruiserResult<Self> {
+        let key = seeder.create_address(program_id, bump)?;
+        Ok(Self { seeder, key, bump })
+    }
+
+    /// The PDA's key.
+    pub fn key(&self) -> &Pubkey {
+        &self.key
+    }
+
+    /// The PDA's bump.
+    pub fn bump(&self) -> u8 {
+        self.bump
+    }
+
+    /// The seeder the PDA was derived from.
+    pub fn seeder(&self) -> &S {
+        &self.seeder
+    }
+
+    /// Errors unless the PDA derives from `program_id` at the canonical bump, see [`check_canonical_bump`].
+    pub fn verify(&self, program_id: &Pubkey) -> CruiserResult<()> {
+        if self.seeder.create_address(program_id, self.bump)? != self.key {
+            return Err(custom_error!("PDA doesn't derive from the program").into());
+        }
+        check_canonical_bump(&self.seeder, self.bump, program_id)
+    }
+
+    /// The seeds for the PDA to sign with or be created at.
+    pub fn signer_seeds(&self) -> PDASeedSet<'_>
+    where
+        S: Clone,
+    {
+        PDASeedSet::new(self.seeder.clone(), self.bump)
+    }
+}
+
 /// The static seed for [`GameSignerSeeder`].
 pub const GAME_SIGNER_SEED: &str = "game_signer";
 
@@ -20,3 +91,260 @@ impl PDASeeder for GameSignerSeeder {
         Box::new([&GAME_SIGNER_SEED as &dyn PDASeed, &self.game].into_iter())
     }
 }
+
+/// The static seed for [`ProfileSeeder`].
+pub const PROFILE_SEED: &str = "profile";
+
+/// The seeder for a [`PlayerProfile`](crate::accounts::PlayerProfile) created at a PDA of its authority.
+/// Profiles can also be plain keypairs, see [`CreateProfile`](crate::instructions::CreateProfile).
+/// The profile keeps its address if its authority changes later.
+#[derive(Debug, Clone)]
+pub struct ProfileSeeder {
+    /// The authority the profile was created for.
+    pub authority: Pubkey,
+}
+impl PDASeeder for ProfileSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&PROFILE_SEED as &dyn PDASeed, &self.authority].into_iter())
+    }
+}
+
+/// The static seed for [`ConfigSeeder`].
+pub const CONFIG_SEED: &str = "config";
+
+/// The seeder for the [`ProgramConfig`](crate::accounts::ProgramConfig).
+/// It has no dynamic seeds as there is only one config.
+#[derive(Debug, Clone)]
+pub struct ConfigSeeder;
+impl PDASeeder for ConfigSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&CONFIG_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`HouseSeeder`].
+pub const HOUSE_SEED: &str = "house";
+
+/// The seeder for the [`House`](crate::accounts::House).
+/// It has no dynamic seeds as there is only one house.
+#[derive(Debug, Clone)]
+pub struct HouseSeeder;
+impl PDASeeder for HouseSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&HOUSE_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`HouseVaultSeeder`].
+pub const HOUSE_VAULT_SEED: &str = "house_vault";
+
+/// The seeder for the system account holding the house's bankroll.
+#[derive(Debug, Clone)]
+pub struct HouseVaultSeeder;
+impl PDASeeder for HouseVaultSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&HOUSE_VAULT_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`FeeVaultSeeder`].
+pub const FEE_VAULT_SEED: &str = "fee_vault";
+
+/// The seeder for the system account collecting protocol fees until [`WithdrawFees`](crate::instructions::WithdrawFees).
+#[derive(Debug, Clone)]
+pub struct FeeVaultSeeder;
+impl PDASeeder for FeeVaultSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&FEE_VAULT_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`HouseProfileSeeder`].
+pub const HOUSE_PROFILE_SEED: &str = "house_profile";
+
+/// The seeder for the house's [`PlayerProfile`](crate::accounts::PlayerProfile).
+#[derive(Debug, Clone)]
+pub struct HouseProfileSeeder;
+impl PDASeeder for HouseProfileSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&HOUSE_PROFILE_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`SocialGraphSeeder`].
+pub const SOCIAL_GRAPH_SEED: &str = "social_graph";
+
+/// The seeder for a profile's [`SocialGraph`](crate::accounts::SocialGraph).
+#[derive(Debug, Clone)]
+pub struct SocialGraphSeeder {
+    /// The profile's key.
+    pub profile: Pubkey,
+}
+impl PDASeeder for SocialGraphSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&SOCIAL_GRAPH_SEED as &dyn PDASeed, &self.profile].into_iter())
+    }
+}
+
+/// The static seed for [`LeaderboardShardSeeder`].
+pub const LEADERBOARD_SHARD_SEED: &str = "leaderboard_shard";
+
+/// The seeder for a [`LeaderboardShard`](crate::accounts::LeaderboardShard) page.
+#[derive(Debug, Clone)]
+pub struct LeaderboardShardSeeder {
+    /// The little endian band followed by the little endian page.
+    band_page: [u8; 4],
+}
+impl LeaderboardShardSeeder {
+    /// Creates the seeder for `page` of `band`.
+    pub fn new(band: u16, page: u16) -> Self {
+        let mut band_page = [0; 4];
+        band_page[..2].copy_from_slice(&band.to_le_bytes());
+        band_page[2..].copy_from_slice(&page.to_le_bytes());
+        Self { band_page }
+    }
+}
+impl PDASeeder for LeaderboardShardSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        // Integers aren't seeds themselves, so they're stored as bytes.
+        Box::new([&LEADERBOARD_SHARD_SEED as &dyn PDASeed, &self.band_page].into_iter())
+    }
+}
+
+/// The static seed for [`MetricsSeeder`].
+pub const METRICS_SEED: &str = "metrics";
+
+/// The seeder for the [`InstructionMetrics`](crate::accounts::InstructionMetrics).
+/// It has no dynamic seeds as there is only one.
+#[derive(Debug, Clone)]
+pub struct MetricsSeeder;
+impl PDASeeder for MetricsSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&METRICS_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`GameRecoverySeeder`].
+pub const GAME_RECOVERY_SEED: &str = "game_recovery";
+
+/// The seeder for a game's [`GameRecovery`](crate::accounts::GameRecovery).
+#[derive(Debug, Clone)]
+pub struct GameRecoverySeeder {
+    /// The game being recovered.
+    pub game: Pubkey,
+}
+impl PDASeeder for GameRecoverySeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&GAME_RECOVERY_SEED as &dyn PDASeed, &self.game].into_iter())
+    }
+}
+
+/// The static seed for [`ProposedActionSeeder`].
+pub const PROPOSED_ACTION_SEED: &str = "proposed_action";
+
+/// The seeder for a [`ProposedAction`](crate::accounts::ProposedAction).
+#[derive(Debug, Clone)]
+pub struct ProposedActionSeeder {
+    /// The little endian id.
+    id: [u8; 8],
+}
+impl ProposedActionSeeder {
+    /// Creates the seeder for the action with `id`.
+    pub fn new(id: u64) -> Self {
+        Self {
+            id: id.to_le_bytes(),
+        }
+    }
+}
+impl PDASeeder for ProposedActionSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&PROPOSED_ACTION_SEED as &dyn PDASeed, &self.id].into_iter())
+    }
+}
+
+/// The static seed for [`ArchiveAuthoritySeeder`].
+pub const ARCHIVE_AUTHORITY_SEED: &str = "archive_authority";
+
+/// The seeder for the authority of the trees finished games are archived into, see [`ArchiveGame`](crate::instructions::ArchiveGame).
+/// It has no dynamic seeds, any tree it has authority over can be archived into.
+#[derive(Debug, Clone)]
+pub struct ArchiveAuthoritySeeder;
+impl PDASeeder for ArchiveAuthoritySeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&ARCHIVE_AUTHORITY_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`BadgeAuthoritySeeder`].
+pub const BADGE_AUTHORITY_SEED: &str = "badge_authority";
+
+/// The seeder for the tree delegate and verified creator of achievement badges, see [`ClaimAchievement`](crate::instructions::ClaimAchievement).
+/// It has no dynamic seeds as all badges come from the same authority.
+#[derive(Debug, Clone)]
+pub struct BadgeAuthoritySeeder;
+impl PDASeeder for BadgeAuthoritySeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new([&BADGE_AUTHORITY_SEED as &dyn PDASeed].into_iter())
+    }
+}
+
+/// The static seed for [`ClaimReceiptSeeder`].
+pub const CLAIM_RECEIPT_SEED: &str = "claim_receipt";
+
+/// The seeder for the [`ClaimReceipt`](crate::accounts::ClaimReceipt) of a claim on a [`Distribution`](crate::accounts::Distribution).
+#[derive(Debug, Clone)]
+pub struct ClaimReceiptSeeder {
+    /// The distribution's key.
+    distribution: Pubkey,
+    /// The little endian claim index.
+    index: [u8; 8],
+}
+impl ClaimReceiptSeeder {
+    /// Creates the seeder for the claim at `index` of `distribution`.
+    pub fn new(distribution: Pubkey, index: u64) -> Self {
+        Self {
+            distribution,
+            index: index.to_le_bytes(),
+        }
+    }
+}
+impl PDASeeder for ClaimReceiptSeeder {
+    fn seeds<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn PDASeed> + 'a> {
+        Box::new(
+            [
+                &CLAIM_RECEIPT_SEED as &dyn PDASeed,
+                &self.distribution,
+                &self.index,
+            ]
+            .into_iter(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_known_pda() {
+        let program_id = Pubkey::new_unique();
+        let seeder = GameSignerSeeder {
+            game: Pubkey::new_unique(),
+        };
+        let (key, bump) = seeder.find_address(&program_id);
+
+        let pda = KnownPda::find(seeder.clone(), &program_id);
+        assert_eq!(pda.key(), &key);
+        assert_eq!(pda.bump(), bump);
+        assert!(pda.verify(&program_id).is_ok());
+        assert!(pda.verify(&Pubkey::new_unique()).is_err());
+
+        let other_bump = (0..bump)
+            .rev()
+            .find(|bump| seeder.create_address(&program_id, *bump).is_ok())
+            .unwrap();
+        let pda = KnownPda::with_bump(seeder, other_bump, &program_id).unwrap();
+        assert_ne!(pda.key(), &key);
+        assert!(pda.verify(&program_id).is_err());
+    }
+}
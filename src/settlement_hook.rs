@@ -0,0 +1,153 @@
+//! The interface for programs notified when a game settles, such as a tournament or ladder.
+//!
+//! A hook program is registered as [`ProgramConfig::settlement_hook`](crate::accounts::ProgramConfig::settlement_hook)
+//! and copied onto games as they are created, see [`Game::settlement_hook`].
+//! When one of those games is won, drawn or forfeited the hook is called with [`GameSettled`] as part of
+//! the same instruction, so a hook that fails fails the settlement.
+//!
+//! The settling instruction takes the hook program and the accounts the hook needs after its own accounts.
+//! The hook is called with the game's signer first, signing so the hook can check the call came from this program
+//! by deriving [`GameSignerSeeder`] from [`GameSettled::game`] and [`GameSettled::signer_bump`],
+//! followed by the rest of those accounts.
+
+use crate::accounts::GameResult;
+#[cfg(feature = "client-core")]
+use crate::client::InstructionSet;
+use crate::pda::GameSignerSeeder;
+use crate::Game;
+use cruiser::prelude::*;
+
+/// The discriminant [`GameSettled`] is sent with, Anchor's for a `game_settled` instruction.
+pub const GAME_SETTLED_DISCRIMINANT: [u8; 8] = [34, 80, 194, 120, 79, 251, 67, 71];
+
+/// The instruction a settlement hook is called with, after [`GAME_SETTLED_DISCRIMINANT`].
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, Eq, PartialEq)]
+pub struct GameSettled {
+    /// The game that settled.
+    pub game: Pubkey,
+    /// The bump of the game's signer.
+    pub signer_bump: u8,
+    /// The first player's profile.
+    pub player1: Pubkey,
+    /// The second player's profile.
+    pub player2: Pubkey,
+    /// How the game ended.
+    pub result: GameResult,
+    /// Whether the loser ran out of time rather than losing on the board.
+    pub forfeited: bool,
+    /// The wagers held for the game, including any keeper tip paid out of them.
+    pub pot: u64,
+    /// When the game settled.
+    pub ended_at: UnixTimestamp,
+}
+impl GameSettled {
+    /// The instruction data a hook is called with.
+    pub fn instruction_data(&self) -> CruiserResult<Vec<u8>> {
+        let mut data = GAME_SETTLED_DISCRIMINANT.to_vec();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Calls `game`'s settlement hook with `settled`, if it has one.
+/// `hook_accounts` are the accounts past the settling instruction's own, starting with the hook program.
+#[cfg(feature = "processor")]
+pub(crate) fn notify_settlement<'a, 'b, AI>(
+    game: &Game,
+    game_signer: &'b AI,
+    hook_accounts: impl IntoIterator<Item = &'b AI>,
+    settled: &GameSettled,
+) -> CruiserResult<()>
+where
+    AI: ToSolanaAccountInfo<'a> + 'b,
+{
+    use crate::error::custom_error;
+    use crate::log::log_event;
+    use std::iter::once;
+
+    if !game.has_settlement_hook() {
+        return Ok(());
+    }
+    let mut hook_accounts = hook_accounts.into_iter();
+    let hook_program = hook_accounts
+        .next()
+        .filter(|hook_program| hook_program.key() == &game.settlement_hook)
+        .ok_or(custom_error!(
+            "settlement hook {} must follow the instruction's accounts",
+            game.settlement_hook
+        ))?;
+
+    let mut cpi_accounts = vec![MaybeOwned::from(game_signer)];
+    cpi_accounts.extend(hook_accounts.map(MaybeOwned::from));
+    let mut accounts = cpi_accounts
+        .iter()
+        .map(MaybeOwned::as_ref)
+        .map(AI::to_solana_account_meta)
+        .collect::<Vec<_>>();
+    accounts[0].is_signer = true;
+    let instruction = SolanaInstruction {
+        program_id: game.settlement_hook,
+        accounts,
+        data: settled.instruction_data()?,
+    };
+    cpi_accounts.push(MaybeOwned::from(hook_program));
+
+    let signer_seeds =
+        PDASeedSet::new(GameSignerSeeder { game: settled.game }, settled.signer_bump);
+    log_event!(
+        debug,
+        "calling_settlement_hook",
+        hook = game.settlement_hook
+    );
+    CPIChecked.invoke_signed(&instruction, &cpi_accounts, once(&signer_seeds))?;
+    Ok(())
+}
+
+/// Adds the accounts a game's settlement hook needs to the end of the last instruction in `instructions`,
+/// which should be the one settling the game.
+#[cfg(feature = "client-core")]
+pub fn with_settlement_hook(
+    mut instructions: InstructionSet<'_>,
+    hook_program: Pubkey,
+    hook_accounts: impl IntoIterator<Item = SolanaAccountMeta>,
+) -> InstructionSet<'_> {
+    if let Some(instruction) = instructions.instructions.last_mut() {
+        instruction
+            .accounts
+            .push(SolanaAccountMeta::new_readonly(hook_program, false));
+        instruction.accounts.extend(hook_accounts);
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::Player;
+    use cruiser::solana_program::hash::hash;
+
+    #[test]
+    fn test_discriminant() {
+        assert_eq!(
+            hash(b"global:game_settled").to_bytes()[..8],
+            GAME_SETTLED_DISCRIMINANT
+        );
+    }
+
+    #[test]
+    fn test_instruction_data() {
+        let settled = GameSettled {
+            game: Pubkey::new_unique(),
+            signer_bump: 254,
+            player1: Pubkey::new_unique(),
+            player2: Pubkey::new_unique(),
+            result: GameResult::Won(Player::One),
+            forfeited: false,
+            pot: 100,
+            ended_at: 1_000,
+        };
+        let data = settled.instruction_data().unwrap();
+        assert_eq!(data[..8], GAME_SETTLED_DISCRIMINANT);
+        assert_eq!(GameSettled::try_from_slice(&data[8..]).unwrap(), settled);
+    }
+}
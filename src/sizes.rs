@@ -0,0 +1,298 @@
+//! Pinned account layouts.
+//!
+//! Changing the size of [`Game`] or [`PlayerProfile`] changes their rent and breaks every
+//! client filtering on their fields, so it fails the build here until the expected values are updated.
+//! Offsets are from the start of the account's data after its [`TutorialAccounts`](crate::TutorialAccounts) discriminant.
+//!
+//! [`account_sizes`] lists the size and rent of every account, print it with `cargo run --example account_sizes`.
+
+use crate::accounts::{
+    ClaimReceipt, Distribution, FinishedGame, Game, GameBoard, GameRecovery, GuardianSet, House,
+    InstructionMetrics, LeaderboardShard, PayoutAllowlist, PendingRecovery, PendingSettlement,
+    PlayerProfile, ProfileMetadataUri, ProgramConfig, ProposedAction, RuleSet, SocialGraph,
+    WagerVault,
+};
+use crate::instructions::MakeMoveData;
+use crate::TutorialAccounts;
+use cruiser::prelude::*;
+use cruiser::solana_program::rent::Rent;
+
+/// The expected [`Game::ON_CHAIN_SIZE`].
+pub const GAME_SIZE: usize = 1524;
+/// The offset of [`Game::player1`].
+pub const GAME_PLAYER1_OFFSET: usize = 1;
+/// The offset of [`Game::player2`].
+pub const GAME_PLAYER2_OFFSET: usize = 33;
+/// The offset of [`Game::creator`].
+pub const GAME_CREATOR_OFFSET: usize = 65;
+/// The offset of [`Game::next_play`].
+pub const GAME_NEXT_PLAY_OFFSET: usize = 387;
+/// The offset of [`Game::wager`].
+pub const GAME_WAGER_OFFSET: usize = 389;
+/// The offset of [`Game::last_turn`]. 0 there means the game hasn't started.
+pub const GAME_LAST_TURN_OFFSET: usize = 429;
+/// The offset of [`Game::board`], starting with its variant.
+pub const GAME_BOARD_OFFSET: usize = 441;
+/// The offset of [`Game::forfeit_thread`].
+pub const GAME_FORFEIT_THREAD_OFFSET: usize = 1266;
+/// The offset of [`Game::friendly`].
+pub const GAME_FRIENDLY_OFFSET: usize = 1298;
+/// The offset of [`Game::premove`].
+pub const GAME_PREMOVE_OFFSET: usize = 1299;
+/// The offset of [`Game::move_commitment`].
+pub const GAME_MOVE_COMMITMENT_OFFSET: usize = 1305;
+/// The offset of [`Game::settlement`].
+pub const GAME_SETTLEMENT_OFFSET: usize = 1337;
+/// The offset of [`Game::finished`].
+pub const GAME_FINISHED_OFFSET: usize = 1419;
+/// The offset of [`Game::lock_expires_at`].
+pub const GAME_LOCK_EXPIRES_AT_OFFSET: usize = 1483;
+/// The offset of [`Game::rejects_deprecated`].
+pub const GAME_REJECTS_DEPRECATED_OFFSET: usize = 1491;
+/// The offset of [`Game::settlement_hook`].
+pub const GAME_SETTLEMENT_HOOK_OFFSET: usize = 1492;
+
+/// The expected [`PlayerProfile::ON_CHAIN_SIZE`], with an empty [`PlayerProfile::metadata_uri`].
+/// Profiles grow by the length of their URI.
+pub const PLAYER_PROFILE_SIZE: usize = 530;
+/// The offset of [`PlayerProfile::authority`].
+pub const PLAYER_PROFILE_AUTHORITY_OFFSET: usize = 0;
+/// The offset of [`PlayerProfile::elo`].
+pub const PLAYER_PROFILE_ELO_OFFSET: usize = 96;
+/// The offset of [`PlayerProfile::beneficiary`].
+pub const PLAYER_PROFILE_BENEFICIARY_OFFSET: usize = 168;
+/// The offset of [`PlayerProfile::last_active`].
+pub const PLAYER_PROFILE_LAST_ACTIVE_OFFSET: usize = 256;
+/// The offset of [`PlayerProfile::relay_nonce`].
+pub const PLAYER_PROFILE_RELAY_NONCE_OFFSET: usize = 280;
+/// The offset of [`PlayerProfile::delegate`].
+pub const PLAYER_PROFILE_DELEGATE_OFFSET: usize = 288;
+/// The offset of [`PlayerProfile::guardians`].
+pub const PLAYER_PROFILE_GUARDIANS_OFFSET: usize = 328;
+/// The offset of [`PlayerProfile::recovery`].
+pub const PLAYER_PROFILE_RECOVERY_OFFSET: usize = 425;
+/// The offset of [`PlayerProfile::co_signer`].
+pub const PLAYER_PROFILE_CO_SIGNER_OFFSET: usize = 466;
+/// The offset of [`PlayerProfile::achievements`].
+pub const PLAYER_PROFILE_ACHIEVEMENTS_OFFSET: usize = 506;
+/// The offset of [`PlayerProfile::created_at`].
+pub const PLAYER_PROFILE_CREATED_AT_OFFSET: usize = 508;
+/// The offset of [`PlayerProfile::last_game_created`].
+pub const PLAYER_PROFILE_LAST_GAME_CREATED_OFFSET: usize = 516;
+/// The offset of [`PlayerProfile::games_in_progress`].
+pub const PLAYER_PROFILE_GAMES_IN_PROGRESS_OFFSET: usize = 524;
+/// The offset of [`PlayerProfile::metadata_uri`].
+pub const PLAYER_PROFILE_METADATA_URI_OFFSET: usize = 526;
+
+const _: () = assert!(Game::ON_CHAIN_SIZE == GAME_SIZE);
+const _: () = assert!(
+    GAME_BOARD_OFFSET + GameBoard::ON_CHAIN_SIZE + RuleSet::ON_CHAIN_SIZE
+        == GAME_FORFEIT_THREAD_OFFSET
+);
+const _: () = assert!(GAME_FORFEIT_THREAD_OFFSET + Pubkey::ON_CHAIN_SIZE == GAME_FRIENDLY_OFFSET);
+const _: () = assert!(GAME_FRIENDLY_OFFSET + bool::ON_CHAIN_SIZE == GAME_PREMOVE_OFFSET);
+const _: () =
+    assert!(GAME_PREMOVE_OFFSET + MakeMoveData::ON_CHAIN_SIZE == GAME_MOVE_COMMITMENT_OFFSET);
+const _: () =
+    assert!(GAME_MOVE_COMMITMENT_OFFSET + <[u8; 32]>::ON_CHAIN_SIZE == GAME_SETTLEMENT_OFFSET);
+const _: () =
+    assert!(GAME_SETTLEMENT_OFFSET + PendingSettlement::ON_CHAIN_SIZE == GAME_FINISHED_OFFSET);
+const _: () =
+    assert!(GAME_FINISHED_OFFSET + FinishedGame::ON_CHAIN_SIZE == GAME_LOCK_EXPIRES_AT_OFFSET);
+const _: () = assert!(
+    GAME_LOCK_EXPIRES_AT_OFFSET + UnixTimestamp::ON_CHAIN_SIZE == GAME_REJECTS_DEPRECATED_OFFSET
+);
+const _: () =
+    assert!(GAME_REJECTS_DEPRECATED_OFFSET + bool::ON_CHAIN_SIZE == GAME_SETTLEMENT_HOOK_OFFSET);
+const _: () = assert!(GAME_SETTLEMENT_HOOK_OFFSET + Pubkey::ON_CHAIN_SIZE == GAME_SIZE);
+
+const _: () = assert!(PlayerProfile::ON_CHAIN_SIZE == PLAYER_PROFILE_SIZE);
+const _: () = assert!(
+    PLAYER_PROFILE_LAST_ACTIVE_OFFSET + 3 * UnixTimestamp::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_RELAY_NONCE_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_RELAY_NONCE_OFFSET + u64::ON_CHAIN_SIZE == PLAYER_PROFILE_DELEGATE_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_DELEGATE_OFFSET + Pubkey::ON_CHAIN_SIZE + UnixTimestamp::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_GUARDIANS_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_GUARDIANS_OFFSET + GuardianSet::ON_CHAIN_SIZE == PLAYER_PROFILE_RECOVERY_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_RECOVERY_OFFSET + PendingRecovery::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_CO_SIGNER_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_CO_SIGNER_OFFSET + Pubkey::ON_CHAIN_SIZE + u64::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_ACHIEVEMENTS_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_ACHIEVEMENTS_OFFSET + u16::ON_CHAIN_SIZE == PLAYER_PROFILE_CREATED_AT_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_CREATED_AT_OFFSET + UnixTimestamp::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_LAST_GAME_CREATED_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_LAST_GAME_CREATED_OFFSET + UnixTimestamp::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_GAMES_IN_PROGRESS_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_GAMES_IN_PROGRESS_OFFSET + u16::ON_CHAIN_SIZE
+        == PLAYER_PROFILE_METADATA_URI_OFFSET
+);
+const _: () = assert!(
+    PLAYER_PROFILE_METADATA_URI_OFFSET + ProfileMetadataUri::ON_CHAIN_SIZE == PLAYER_PROFILE_SIZE
+);
+
+/// The size and rent of an account, see [`account_sizes`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSize {
+    /// The account's type.
+    pub name: String,
+    /// The account's data length, its discriminant included.
+    pub data_len: usize,
+    /// The lamports to make the account rent exempt at the default rent.
+    pub rent_exempt: u64,
+}
+impl AccountSize {
+    /// An account whose data is `data_len` bytes long.
+    pub fn new(name: impl Into<String>, data_len: usize) -> Self {
+        Self {
+            name: name.into(),
+            data_len,
+            rent_exempt: Rent::default().minimum_balance(data_len),
+        }
+    }
+
+    /// The [`TutorialAccounts`] variant `T`, its discriminant and [`OnChainSize::ON_CHAIN_SIZE`].
+    pub fn existing<T>(name: impl Into<String>) -> Self
+    where
+        TutorialAccounts: AccountListItem<T>,
+        T: OnChainSize,
+    {
+        let discriminant_len = <TutorialAccounts as AccountListItem<T>>::compressed_discriminant()
+            .try_to_vec()
+            .unwrap()
+            .len();
+        Self::new(name, discriminant_len + T::ON_CHAIN_SIZE)
+    }
+
+    /// An account not in [`TutorialAccounts`] yet whose type's [`OnChainSize::ON_CHAIN_SIZE`] is `on_chain_size`,
+    /// for weighing up a new account or new fields against the ones in [`account_sizes`].
+    pub fn planned(name: impl Into<String>, on_chain_size: usize) -> Self {
+        Self::new(name, discriminant_len() + on_chain_size)
+    }
+}
+
+/// The length of a [`TutorialAccounts`] discriminant.
+fn discriminant_len() -> usize {
+    <TutorialAccounts as AccountListItem<Game>>::compressed_discriminant()
+        .try_to_vec()
+        .unwrap()
+        .len()
+}
+
+/// Implements [`account_sizes`] over every [`TutorialAccounts`] variant.
+/// The match fails to compile if a variant is added without being listed here.
+macro_rules! account_sizes {
+    ($($account:ident),* $(,)?) => {
+        /// The size and rent of every [`TutorialAccounts`] variant, in declaration order.
+        pub fn account_sizes() -> Vec<AccountSize> {
+            let _ = |account: TutorialAccounts| match account {
+                $(TutorialAccounts::$account(_) => {})*
+            };
+            vec![$(AccountSize::existing::<$account>(stringify!($account))),*]
+        }
+    };
+}
+
+account_sizes!(
+    Game,
+    PlayerProfile,
+    PayoutAllowlist,
+    ProgramConfig,
+    House,
+    SocialGraph,
+    LeaderboardShard,
+    InstructionMetrics,
+    GameRecovery,
+    ProposedAction,
+    WagerVault,
+    Distribution,
+    ClaimReceipt,
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::accounts::{GameVariant, Player};
+
+    #[test]
+    fn test_offsets() {
+        let profile_key = Pubkey::new_unique();
+        let mut game = Game::new(
+            &profile_key,
+            Player::Two,
+            255,
+            7,
+            7,
+            0,
+            0,
+            GameVariant::Deep,
+            RuleSet::default(),
+        );
+        game.last_turn = 1234;
+        let data = game.try_to_vec().unwrap();
+        assert_eq!(
+            &data[GAME_PLAYER2_OFFSET..GAME_PLAYER2_OFFSET + 32],
+            profile_key.as_ref()
+        );
+        assert_eq!(data[GAME_CREATOR_OFFSET], 1);
+        assert_eq!(
+            data[GAME_WAGER_OFFSET..GAME_WAGER_OFFSET + 8],
+            7u64.to_le_bytes()
+        );
+        assert_eq!(
+            data[GAME_LAST_TURN_OFFSET..GAME_LAST_TURN_OFFSET + 8],
+            1234i64.to_le_bytes()
+        );
+        // The deep board is the largest so the game's data is its full size.
+        assert_eq!(data.len(), GAME_SIZE);
+
+        let mut profile = PlayerProfile::new(&profile_key);
+        profile.last_active = 99;
+        let data = profile.try_to_vec().unwrap();
+        assert_eq!(
+            data[PLAYER_PROFILE_ELO_OFFSET..PLAYER_PROFILE_ELO_OFFSET + 8],
+            PlayerProfile::INITIAL_ELO.to_le_bytes()
+        );
+        assert_eq!(
+            data[PLAYER_PROFILE_LAST_ACTIVE_OFFSET..PLAYER_PROFILE_LAST_ACTIVE_OFFSET + 8],
+            99i64.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn test_account_sizes() {
+        let sizes = account_sizes();
+        assert_eq!(sizes[0], AccountSize::existing::<Game>("Game"));
+        assert_eq!(
+            sizes[1],
+            AccountSize::existing::<PlayerProfile>("PlayerProfile")
+        );
+        assert_eq!(sizes[0].data_len, GAME_SIZE + discriminant_len());
+        assert_eq!(sizes[1].data_len, PLAYER_PROFILE_SIZE + discriminant_len());
+        // A planned account is sized the same as an existing one of the same size
+        assert_eq!(
+            AccountSize::planned("Game", GAME_SIZE).data_len,
+            sizes[0].data_len
+        );
+        // Every byte costs rent on top of the account's fixed overhead
+        let bigger = AccountSize::planned("Game", GAME_SIZE + 32);
+        assert!(bigger.rent_exempt > sizes[0].rent_exempt);
+    }
+}
@@ -0,0 +1,154 @@
+//! Glue for [Switchboard](https://switchboard.xyz) V2 VRF accounts.
+//!
+//! Only the parts of the oracle program's interface this program uses are mirrored here,
+//! so there is no dependency on the Switchboard crates.
+
+use cruiser::prelude::*;
+
+/// The Switchboard V2 oracle program.
+pub const SWITCHBOARD_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    6, 136, 81, 198, 140, 104, 50, 240, 47, 165, 129, 177, 191, 73, 27, 119, 202, 65, 119, 107,
+    162, 185, 136, 181, 166, 250, 186, 142, 227, 162, 236, 144,
+]);
+
+/// The SPL token program, which the oracle program pays its fees through.
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133, 237,
+    95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+]);
+
+/// The deprecated recent blockhashes sysvar, which `vrf_request_randomness` still takes.
+pub const RECENT_BLOCKHASHES_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 25, 44, 86, 142, 224, 138, 132, 95, 115, 210, 151, 136, 207, 3, 92, 49, 69,
+    178, 26, 179, 68, 216, 6, 46, 169, 64, 0, 0,
+]);
+
+/// The Anchor discriminant of the oracle program's `vrf_request_randomness` instruction.
+const VRF_REQUEST_RANDOMNESS_DISCRIMINANT: [u8; 8] = [230, 121, 14, 164, 28, 222, 117, 118];
+
+/// Where `current_round.result` sits in a VRF account's data.
+///
+/// The account is zero copy and packed: an 8 byte Anchor discriminant, then `status`, `counter`,
+/// `authority`, `oracle_queue`, `escrow`, `callback`, `batch_size`, eight `builders`,
+/// `builders_len`, `test_mode`, then the current round's `alpha`, `alpha_len`, `request_slot`,
+/// and `request_timestamp` before the result.
+const VRF_RESULT_OFFSET: usize = 27742;
+
+/// Reads the randomness out of a VRF account's data, [`None`] if it hasn't been fulfilled.
+pub fn vrf_result(data: &[u8]) -> Option<[u8; 32]> {
+    let result: [u8; 32] = data
+        .get(VRF_RESULT_OFFSET..VRF_RESULT_OFFSET + 32)?
+        .try_into()
+        .unwrap();
+    if result == [0; 32] {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// CPI for the oracle program's `vrf_request_randomness`.
+#[derive(Debug)]
+pub struct VrfRequestRandomnessCPI<'a, AI> {
+    accounts: [MaybeOwned<'a, AI>; 12],
+    data: Vec<u8>,
+}
+impl<'a, AI> VrfRequestRandomnessCPI<'a, AI> {
+    /// Requests new randomness for `vrf`. `authority` must be the VRF account's authority.
+    /// The oracle calls the VRF account's callback once the randomness is verified.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        authority: impl Into<MaybeOwned<'a, AI>>,
+        vrf: impl Into<MaybeOwned<'a, AI>>,
+        oracle_queue: impl Into<MaybeOwned<'a, AI>>,
+        queue_authority: impl Into<MaybeOwned<'a, AI>>,
+        data_buffer: impl Into<MaybeOwned<'a, AI>>,
+        permission: impl Into<MaybeOwned<'a, AI>>,
+        escrow: impl Into<MaybeOwned<'a, AI>>,
+        payer_wallet: impl Into<MaybeOwned<'a, AI>>,
+        payer_authority: impl Into<MaybeOwned<'a, AI>>,
+        recent_blockhashes: impl Into<MaybeOwned<'a, AI>>,
+        program_state: impl Into<MaybeOwned<'a, AI>>,
+        token_program: impl Into<MaybeOwned<'a, AI>>,
+        permission_bump: u8,
+        state_bump: u8,
+    ) -> CruiserResult<Self> {
+        let mut data = VRF_REQUEST_RANDOMNESS_DISCRIMINANT.to_vec();
+        permission_bump.serialize(&mut data)?;
+        state_bump.serialize(&mut data)?;
+        Ok(Self {
+            accounts: [
+                authority.into(),
+                vrf.into(),
+                oracle_queue.into(),
+                queue_authority.into(),
+                data_buffer.into(),
+                permission.into(),
+                escrow.into(),
+                payer_wallet.into(),
+                payer_authority.into(),
+                recent_blockhashes.into(),
+                program_state.into(),
+                token_program.into(),
+            ],
+            data,
+        })
+    }
+}
+impl<'a, AI> VrfRequestRandomnessCPI<'a, AI>
+where
+    AI: ToSolanaAccountMeta,
+{
+    /// Gets the instruction and accounts to call the oracle program with.
+    pub fn instruction(
+        self,
+        switchboard_program: impl Into<MaybeOwned<'a, AI>>,
+    ) -> InstructionAndAccounts<[MaybeOwned<'a, AI>; 13]> {
+        let switchboard_program = switchboard_program.into();
+        let instruction = SolanaInstruction {
+            program_id: *switchboard_program.meta_key(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(MaybeOwned::as_ref)
+                .map(AI::to_solana_account_meta)
+                .collect(),
+            data: self.data,
+        };
+        let mut accounts = self.accounts.into_iter();
+        InstructionAndAccounts {
+            instruction,
+            accounts: [
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                accounts.next().unwrap(),
+                switchboard_program,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vrf_result() {
+        let mut data = vec![0; VRF_RESULT_OFFSET + 32 + 4];
+        assert_eq!(vrf_result(&data), None);
+        data[VRF_RESULT_OFFSET + 31] = 7;
+        let mut expected = [0; 32];
+        expected[31] = 7;
+        assert_eq!(vrf_result(&data), Some(expected));
+        assert_eq!(vrf_result(&data[..VRF_RESULT_OFFSET + 16]), None);
+    }
+}
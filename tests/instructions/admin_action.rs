@@ -0,0 +1,154 @@
+use crate::instructions::{new_funder, setup_validator_reset};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{AdminAction, ProgramConfig};
+use cruiser_tutorial::instructions::{
+    execute_admin_action, propose_admin_action, update_config, UpdateConfigData,
+};
+use cruiser_tutorial::pda::ConfigSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::sleep;
+
+async fn get_config(rpc: &RpcClient, program_id: Pubkey) -> Result<ProgramConfig, Box<dyn Error>> {
+    let account = rpc
+        .get_account_with_commitment(
+            &ConfigSeeder.find_address(&program_id).0,
+            CommitmentConfig::confirmed(),
+        )
+        .await?
+        .value
+        .ok_or("Config not found")?;
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<ProgramConfig>>::compressed_discriminant()
+    );
+    Ok(ProgramConfig::deserialize(&mut data)?)
+}
+
+#[tokio::test]
+async fn admin_action_test() -> Result<(), Box<dyn Error>> {
+    // Turning the timelock on would block other tests' config changes
+    let guard = setup_validator_reset().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+    let fails = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..send_config
+    };
+
+    let funder = new_funder(rpc).await?;
+
+    // Turn the timelock on
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            guard.admin(),
+            &UpdateConfigData {
+                admin_action_delay: Some(5),
+                ..Default::default()
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let change = UpdateConfigData {
+        max_wager: Some(LAMPORTS_PER_SOL),
+        ..Default::default()
+    };
+
+    // Direct changes are blocked now
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(program_id, guard.admin(), &change))
+        .send_and_confirm_transaction(
+            rpc,
+            fails,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Config changed directly with the timelock on"
+    );
+
+    let id = get_config(rpc, program_id).await?.next_admin_action;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(propose_admin_action(
+            program_id,
+            guard.admin(),
+            id,
+            AdminAction::UpdateConfig(change),
+            &funder,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Too early
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(execute_admin_action(program_id, id, funder.pubkey(), None))
+        .send_and_confirm_transaction(
+            rpc,
+            fails,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Admin action executed before its delay"
+    );
+    assert_eq!(get_config(rpc, program_id).await?.max_wager, 0);
+
+    sleep(Duration::from_secs(6)).await;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(execute_admin_action(program_id, id, funder.pubkey(), None))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    let config = get_config(rpc, program_id).await?;
+    assert_eq!(config.max_wager, LAMPORTS_PER_SOL);
+    assert_eq!(config.next_admin_action, id + 1);
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -1,7 +1,13 @@
-use crate::instructions::setup_validator;
+use crate::instructions::diff::fetch_account;
+use crate::instructions::{
+    create_profiles, friendly_game, new_funder, setup_validator, wagered_game,
+};
 use cruiser::prelude::*;
-use cruiser_tutorial::accounts::{Game, Player};
-use cruiser_tutorial::instructions::{create_game, create_profile, CreateGameClientData};
+use cruiser_tutorial::accounts::{Game, GameVariant, Player, RuleSet};
+use cruiser_tutorial::client::create_zeroed_account;
+use cruiser_tutorial::instructions::{
+    create_game, create_game_from_zeroed, create_game_zeroed, CreateGameClientData,
+};
 use cruiser_tutorial::TutorialAccounts;
 use std::error::Error;
 use std::time::Duration;
@@ -11,29 +17,18 @@ async fn create_game_test() -> Result<(), Box<dyn Error>> {
     let guard = setup_validator().await;
 
     let rpc = guard.rpc();
-    let funder = Keypair::new();
 
-    // Airdrop SOL to the funder
-    let blockhash = rpc.get_latest_blockhash().await?;
-    let sig = rpc
-        .request_airdrop_with_blockhash(&funder.pubkey(), LAMPORTS_PER_SOL * 10, &blockhash)
-        .await?;
-    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
-        .await?;
+    let funder = new_funder(rpc).await?;
 
     // Create random authority and profile
     let authority = Keypair::new();
     let profile = Keypair::new();
     let game = Keypair::new();
 
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
     // Send transaction
     let (sig, result) = TransactionBuilder::new(&funder)
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority,
-            &profile,
-            &funder,
-        ))
         .signed_instructions(create_game(
             guard.program_id(),
             &authority,
@@ -42,11 +37,7 @@ async fn create_game_test() -> Result<(), Box<dyn Error>> {
             &funder,
             &funder,
             None,
-            CreateGameClientData {
-                creator_player: Player::One,
-                wager: LAMPORTS_PER_SOL,
-                turn_length: 60 * 60 * 24, // 1 day
-            },
+            wagered_game(LAMPORTS_PER_SOL),
         ))
         .send_and_confirm_transaction(
             rpc,
@@ -102,16 +93,226 @@ async fn create_game_test() -> Result<(), Box<dyn Error>> {
         <TutorialAccounts as AccountListItem<Game>>::compressed_discriminant()
     );
     let board = Game::deserialize(&mut data)?;
-    assert_eq!(
-        board,
-        Game::new(
+    assert!(board.created_at > 0);
+    let mut expected = Game::new(
+        &profile.pubkey(),
+        Player::One,
+        board.signer_bump,
+        LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL,
+        60 * 60 * 24,
+        0,
+        GameVariant::Ultimate,
+        RuleSet::default(),
+    );
+    expected.created_at = board.created_at;
+    assert_eq!(board, expected);
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_game_zero_wager_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let game = Keypair::new();
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // A game with no wager has to be created as friendly
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            None,
+            CreateGameClientData {
+                variant: GameVariant::Classic,
+                friendly: false,
+                ..friendly_game()
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Game created with no wager"
+    );
+
+    // Friendly games can
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            None,
+            CreateGameClientData {
+                variant: GameVariant::Classic,
+                ..friendly_game()
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    let friendly_game = fetch_account::<Game>(rpc, &game.pubkey()).await?;
+    assert!(friendly_game.friendly);
+    assert_eq!(friendly_game.wager, 0);
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_game_zeroed_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let init_game = Keypair::new();
+    let zeroed_game = Keypair::new();
+    let from_zeroed_game = Keypair::new();
+    let game_data = || wagered_game(LAMPORTS_PER_SOL);
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // The same game through the init path and both zeroed paths, each in its own transaction to stay under the size limit
+    let mut from_zeroed = create_zeroed_account(
+        rpc,
+        program_id,
+        &funder,
+        &from_zeroed_game,
+        Game::ON_CHAIN_SIZE,
+    )
+    .await?;
+    from_zeroed.add_set(create_game_from_zeroed(
+        program_id,
+        &authority,
+        profile.pubkey(),
+        from_zeroed_game.pubkey(),
+        &funder,
+        None,
+        game_data(),
+    ));
+    let transactions = [
+        TransactionBuilder::new(&funder).signed_instructions(create_game(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            &init_game,
+            &funder,
+            &funder,
+            None,
+            game_data(),
+        )),
+        TransactionBuilder::new(&funder).signed_instructions(
+            create_game_zeroed(
+                program_id,
+                &authority,
+                profile.pubkey(),
+                &zeroed_game,
+                &funder,
+                &funder,
+                None,
+                game_data(),
+                |size| rpc.get_minimum_balance_for_rent_exemption(size),
+            )
+            .await?,
+        ),
+        TransactionBuilder::new(&funder).signed_instructions(from_zeroed),
+    ];
+    for transaction in transactions {
+        let (_, result) = transaction
+            .send_and_confirm_transaction(
+                rpc,
+                RpcSendTransactionConfig {
+                    skip_preflight: false,
+                    preflight_commitment: Some(CommitmentLevel::Confirmed),
+                    encoding: None,
+                    max_retries: None,
+                },
+                CommitmentConfig::confirmed(),
+                Duration::from_millis(500),
+            )
+            .await?;
+        match result {
+            ConfirmationResult::Success => {}
+            ConfirmationResult::Failure(error) => return Err(error.into()),
+            ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+        }
+    }
+
+    for game in [&init_game, &zeroed_game, &from_zeroed_game] {
+        let account = rpc
+            .get_account_with_commitment(&game.pubkey(), CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_else(|| {
+                panic!("Account not found");
+            });
+        assert_eq!(account.owner, program_id);
+        assert_eq!(
+            account.lamports,
+            rpc.get_minimum_balance_for_rent_exemption(account.data.len())
+                .await?
+        );
+
+        let game_data: Game = fetch_account(rpc, &game.pubkey()).await?;
+        let mut expected = Game::new(
             &profile.pubkey(),
             Player::One,
-            board.signer_bump,
+            game_data.signer_bump,
             LAMPORTS_PER_SOL,
-            60 * 60 * 24
-        )
-    );
+            LAMPORTS_PER_SOL,
+            60 * 60 * 24,
+            0,
+            GameVariant::Ultimate,
+            RuleSet::default(),
+        );
+        expected.created_at = game_data.created_at;
+        assert_eq!(game_data, expected);
+    }
 
     guard.drop_self().await;
     Ok(())
@@ -1,25 +1,23 @@
-use crate::instructions::setup_validator;
+use crate::instructions::diff::fetch_account;
+use crate::instructions::{new_funder, setup_validator, setup_validator_reset};
 use cruiser::prelude::*;
 use cruiser_tutorial::accounts::PlayerProfile;
-use cruiser_tutorial::instructions::create_profile;
+use cruiser_tutorial::client::{decode_program_events, fetch_return_data};
+use cruiser_tutorial::event::ProgramEvent;
+use cruiser_tutorial::instructions::{create_profile, create_profile_pda};
+use cruiser_tutorial::pda::ProfileSeeder;
 use cruiser_tutorial::TutorialAccounts;
 use std::error::Error;
 use std::time::Duration;
 
 #[tokio::test]
 async fn create_profile_test() -> Result<(), Box<dyn Error>> {
-    let guard = setup_validator().await;
+    // Uses a reset validator so the profile is checked against a clean ledger.
+    let guard = setup_validator_reset().await;
 
     let rpc = guard.rpc();
-    let funder = Keypair::new();
 
-    // Airdrop SOL to the funder
-    let blockhash = rpc.get_latest_blockhash().await?;
-    let sig = rpc
-        .request_airdrop_with_blockhash(&funder.pubkey(), LAMPORTS_PER_SOL * 10, &blockhash)
-        .await?;
-    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
-        .await?;
+    let funder = new_funder(rpc).await?;
 
     // Create random authority and profile
     let authority = Keypair::new();
@@ -54,23 +52,36 @@ async fn create_profile_test() -> Result<(), Box<dyn Error>> {
     }
 
     // Print logs for debugging
-    println!(
-        "Logs: {:#?}",
-        rpc.get_transaction_with_config(
+    let logs = rpc
+        .get_transaction_with_config(
             &sig,
             RpcTransactionConfig {
                 encoding: None,
                 commitment: Some(CommitmentConfig::confirmed()),
-                max_supported_transaction_version: None
-            }
+                max_supported_transaction_version: None,
+            },
         )
         .await?
         .transaction
         .meta
         .unwrap()
         .log_messages
+        .unwrap_or_default();
+    println!("Logs: {:#?}", logs);
+
+    // Indexers see the profile created
+    assert_eq!(
+        decode_program_events(&guard.program_id(), &logs),
+        vec![ProgramEvent::ProfileCreated {
+            profile: profile.pubkey(),
+            authority: authority.pubkey(),
+        }]
     );
 
+    // The profile's key comes back as return data
+    let returned: Option<Pubkey> = fetch_return_data(rpc, &guard.program_id(), &sig).await?;
+    assert_eq!(returned, Some(profile.pubkey()));
+
     // Check account data is what we expect
     let account = rpc
         .get_account_with_commitment(&profile.pubkey(), CommitmentConfig::confirmed())
@@ -87,7 +98,53 @@ async fn create_profile_test() -> Result<(), Box<dyn Error>> {
         <TutorialAccounts as AccountListItem<PlayerProfile>>::compressed_discriminant()
     );
     let profile = PlayerProfile::deserialize(&mut data)?;
-    assert_eq!(profile, PlayerProfile::new(&authority.pubkey()));
+    assert!(profile.last_active > 0);
+    let mut expected = PlayerProfile::new(&authority.pubkey());
+    expected.last_active = profile.last_active;
+    expected.created_at = profile.created_at;
+    assert_eq!(profile, expected);
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_profile_pda_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // Only the authority signs, the profile is found from it.
+    let authority = Keypair::new();
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_profile_pda(guard.program_id(), &authority, &funder))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let (profile_key, _) = ProfileSeeder {
+        authority: authority.pubkey(),
+    }
+    .find_address(&guard.program_id());
+    let profile: PlayerProfile = fetch_account(rpc, &profile_key).await?;
+    assert_eq!(profile.authority, authority.pubkey());
+    assert!(profile.created_at > 0);
 
     guard.drop_self().await;
     Ok(())
@@ -0,0 +1,138 @@
+//! Field level diffs of accounts for checking exactly what an instruction changed.
+
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{Game, PlayerProfile};
+use cruiser_tutorial::TutorialAccounts;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Debug;
+
+/// An account whose fields can be compared one by one.
+pub trait FieldDiff {
+    /// The names of the fields that differ between `before` and `after`.
+    fn changed_fields(before: &Self, after: &Self) -> BTreeSet<&'static str>;
+}
+
+/// Implements [`FieldDiff`] over every field of a struct.
+/// The destructure fails to compile if a field is added without being listed here.
+macro_rules! impl_field_diff {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl FieldDiff for $ty {
+            fn changed_fields(before: &Self, after: &Self) -> BTreeSet<&'static str> {
+                let $ty { $($field: _),* } = before;
+                let mut changed = BTreeSet::new();
+                $(
+                    if before.$field != after.$field {
+                        changed.insert(stringify!($field));
+                    }
+                )*
+                changed
+            }
+        }
+    };
+}
+
+impl_field_diff!(Game {
+    version,
+    player1,
+    player2,
+    creator,
+    random_first_player,
+    first_player_vrf,
+    join_code_hash,
+    invited,
+    next_play,
+    signer_bump,
+    wager,
+    joiner_wager,
+    turn_length,
+    join_ttl,
+    created_at,
+    last_turn,
+    last_move,
+    last_move_middle,
+    board,
+    rules,
+    forfeit_thread,
+    friendly,
+    premove,
+    move_commitment,
+    settlement,
+    finished,
+    lock_expires_at,
+    rejects_deprecated,
+    settlement_hook,
+});
+
+impl_field_diff!(PlayerProfile {
+    authority,
+    wins,
+    losses,
+    draws,
+    lamports_won,
+    lamports_lost,
+    lamports_wagered,
+    lamports_drawn_back,
+    fees_paid,
+    elo,
+    payout_address,
+    payout_allowlist,
+    beneficiary,
+    pending_beneficiary,
+    pending_beneficiary_effective,
+    last_active,
+    last_game_timestamp,
+    last_elo_decay,
+    metadata_uri,
+    relay_nonce,
+    delegate,
+    delegate_expiry,
+    guardians,
+    recovery,
+    co_signer,
+    co_sign_threshold,
+    achievements,
+    created_at,
+    last_game_created,
+    games_in_progress,
+});
+
+/// Fetches and deserializes the program account `T` at `key`, checking its discriminant.
+pub async fn fetch_account<T>(rpc: &RpcClient, key: &Pubkey) -> Result<T, Box<dyn Error>>
+where
+    T: BorshDeserialize,
+    TutorialAccounts: AccountListItem<T>,
+{
+    let account = rpc
+        .get_account_with_commitment(key, CommitmentConfig::confirmed())
+        .await?
+        .value
+        .ok_or_else(|| format!("Account {} not found", key))?;
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<T>>::compressed_discriminant()
+    );
+    Ok(T::deserialize(&mut data)?)
+}
+
+/// Asserts no fields besides `allowed` changed between `before` and `after`.
+/// The allowed fields may still be unchanged, like a timestamp landing in the same second.
+pub fn assert_only_changed<T>(before: &T, after: &T, allowed: &[&str])
+where
+    T: FieldDiff + Debug,
+{
+    let unexpected = T::changed_fields(before, after)
+        .into_iter()
+        .filter(|field| !allowed.contains(field))
+        .collect::<Vec<_>>();
+    assert!(
+        unexpected.is_empty(),
+        "Unexpected fields changed: {:?}\nBefore: {:#?}\nAfter: {:#?}",
+        unexpected,
+        before,
+        after
+    );
+}
@@ -0,0 +1,149 @@
+use crate::instructions::{new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::Distribution;
+use cruiser_tutorial::instructions::{claim_reward, create_distribution};
+use cruiser_tutorial::merkle::MerkleTree;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn distribution_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let authority = Keypair::new();
+    let distribution = Keypair::new();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    let funder = new_funder(rpc).await?;
+
+    let claims = (1..=3)
+        .map(|share| (Keypair::new().pubkey(), LAMPORTS_PER_SOL / 10 * share))
+        .collect::<Vec<_>>();
+    let tree = MerkleTree::new(&claims)?;
+
+    // Create the distribution and make two of the claims
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_distribution(
+            program_id,
+            &authority,
+            &distribution,
+            &funder,
+            &tree,
+        ))
+        .signed_instructions(
+            claim_reward(
+                program_id,
+                distribution.pubkey(),
+                &tree,
+                0,
+                claims[0].0,
+                claims[0].1,
+                &funder,
+            )
+            .unwrap(),
+        )
+        .signed_instructions(
+            claim_reward(
+                program_id,
+                distribution.pubkey(),
+                &tree,
+                2,
+                claims[2].0,
+                claims[2].1,
+                &funder,
+            )
+            .unwrap(),
+        )
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    for (claimant, amount) in [claims[0], claims[2]] {
+        assert_eq!(
+            rpc.get_balance_with_commitment(&claimant, CommitmentConfig::confirmed())
+                .await?
+                .value,
+            amount
+        );
+    }
+
+    // Claims can't be made twice or for more than their amount
+    for (index, amount) in [(0, claims[0].1), (1, claims[1].1 + 1)] {
+        let (_, result) = TransactionBuilder::new(&funder)
+            .signed_instructions(
+                claim_reward(
+                    program_id,
+                    distribution.pubkey(),
+                    &tree,
+                    index,
+                    claims[index as usize].0,
+                    amount,
+                    &funder,
+                )
+                .unwrap(),
+            )
+            .send_and_confirm_transaction(
+                rpc,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    ..send_config
+                },
+                CommitmentConfig::confirmed(),
+                Duration::from_millis(500),
+            )
+            .await?;
+        assert!(
+            matches!(result, ConfirmationResult::Failure(_)),
+            "Claim {} of {} was paid",
+            index,
+            amount
+        );
+    }
+
+    // Check account data is what we expect
+    let account = rpc
+        .get_account_with_commitment(&distribution.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<Distribution>>::compressed_discriminant()
+    );
+    let distribution_data = Distribution::deserialize(&mut data)?;
+    assert_eq!(distribution_data.root, tree.root());
+    assert_eq!(distribution_data.total, tree.total());
+    assert_eq!(distribution_data.claimed, claims[0].1 + claims[2].1);
+    assert_eq!(distribution_data.claims_made, 2);
+    assert_eq!(
+        account.lamports
+            - rpc
+                .get_minimum_balance_for_rent_exemption(account.data.len())
+                .await?,
+        claims[1].1
+    );
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use crate::instructions::setup_validator;
+use crate::instructions::diff::fetch_account;
+use crate::instructions::{
+    create_profiles, new_funder, setup_validator, wagered_game, TEST_KEEPER_TIP_BPS,
+};
 use cruiser::prelude::*;
-use cruiser_tutorial::accounts::Player;
+use cruiser_tutorial::accounts::Game;
 use cruiser_tutorial::instructions::*;
 use cruiser_tutorial::pda::GameSignerSeeder;
 use std::error::Error;
@@ -12,15 +15,8 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
     let guard = setup_validator().await;
 
     let rpc = guard.rpc();
-    let funder = Keypair::new();
 
-    // Airdrop SOL to the funder
-    let blockhash = rpc.get_latest_blockhash().await?;
-    let sig = rpc
-        .request_airdrop_with_blockhash(&funder.pubkey(), LAMPORTS_PER_SOL * 10, &blockhash)
-        .await?;
-    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
-        .await?;
+    let funder = new_funder(rpc).await?;
 
     // Create random authority and profile
     let authority1 = Keypair::new();
@@ -28,24 +24,33 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
     let authority2 = Keypair::new();
     let profile2 = Keypair::new();
     let game = Keypair::new();
+    // Winnings may only go to the winner's authority or payout address
+    let receiver = Keypair::new().pubkey();
+    // Anyone can forfeit an expired game for a tip
+    let keeper = Keypair::new();
     let game_signer_bump = GameSignerSeeder {
         game: game.pubkey(),
     }
     .find_address(&guard.program_id())
     .1;
 
+    create_profiles(
+        &guard,
+        &funder,
+        &[(&authority1, &profile1), (&authority2, &profile2)],
+    )
+    .await?;
+
     let (sig, result) = TransactionBuilder::new(&funder)
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority1,
-            &profile1,
-            &funder,
-        ))
-        .signed_instructions(create_profile(
+        .signed_instructions(update_profile(
             guard.program_id(),
             &authority2,
-            &profile2,
-            &funder,
+            profile2.pubkey(),
+            None,
+            UpdateProfileData {
+                payout_address: Some(receiver),
+                ..Default::default()
+            },
         ))
         .signed_instructions(create_game(
             guard.program_id(),
@@ -56,9 +61,8 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
             &funder,
             Some(profile2.pubkey()),
             CreateGameClientData {
-                creator_player: Player::One,
-                wager: LAMPORTS_PER_SOL,
                 turn_length: 1, // 1 second
+                ..wagered_game(LAMPORTS_PER_SOL)
             },
         ))
         .signed_instructions(join_game(
@@ -68,6 +72,7 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
             game.pubkey(),
             game_signer_bump,
             &funder,
+            vec![],
         ))
         .send_and_confirm_transaction(
             rpc,
@@ -111,17 +116,16 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
     // This value may need to be adjusted to be longer, we are working on very small timescales
     sleep(Duration::from_millis(2000)).await;
 
-    let receiver = Keypair::new().pubkey();
-
     let (sig, result) = TransactionBuilder::new(&funder)
         .signed_instructions(forfeit_game(
             guard.program_id(),
-            &authority2,
+            &keeper,
             profile2.pubkey(),
             profile1.pubkey(),
             game.pubkey(),
             game_signer_bump,
             receiver,
+            None,
         ))
         .send_and_confirm_transaction(
             rpc,
@@ -163,17 +167,21 @@ async fn forfeit_game_test() -> Result<(), Box<dyn Error>> {
 
     let accounts = rpc
         .get_multiple_accounts_with_commitment(
-            &[game.pubkey(), receiver],
+            &[game.pubkey(), receiver, keeper.pubkey()],
             CommitmentConfig::confirmed(),
         )
         .await?
         .value;
-    if let Some(game) = &accounts[0] {
-        assert_eq!(game.lamports, 0);
-        assert_eq!(game.owner, SystemProgram::<()>::KEY);
-    }
+    // The game is left for `ArchiveGame`, its rent and the vault's go to the creator's profile
+    assert!(accounts[0].is_some());
+    let game = fetch_account::<Game>(rpc, &game.pubkey()).await?;
+    assert!(game.finished.is_finished());
+    assert_eq!(game.finished.creator_profile, profile1.pubkey());
+    let keeper_tip = LAMPORTS_PER_SOL * 2 * TEST_KEEPER_TIP_BPS as u64 / 10_000;
     let receiver = accounts[1].as_ref().unwrap();
-    assert!(receiver.lamports > LAMPORTS_PER_SOL * 2);
+    assert_eq!(receiver.lamports, LAMPORTS_PER_SOL * 2 - keeper_tip);
+    let keeper = accounts[2].as_ref().unwrap();
+    assert_eq!(keeper.lamports, keeper_tip);
 
     guard.drop_self().await;
     Ok(())
@@ -0,0 +1,275 @@
+//! Plays whole games move by move, checking the game after every move.
+
+use crate::instructions::diff::fetch_account;
+use crate::instructions::{airdrop, create_profiles, setup_validator, wagered_game, TestGuard};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{Game, GameResult, GameVariant, Player, PlayerProfile, Space};
+use cruiser_tutorial::instructions::{
+    create_game, join_game, make_move, make_winning_move, CreateGameClientData, MakeMoveData,
+};
+use cruiser_tutorial::pda::GameSignerSeeder;
+use std::error::Error;
+use std::time::Duration;
+
+/// A game for [`play_scripted_game`] to play.
+pub struct ScriptedGame {
+    /// The board to play on.
+    pub variant: GameVariant,
+    /// What each player wagers.
+    pub wager: u64,
+    /// Every move of the game in order, starting with [`Player::One`].
+    /// The last move must win the game for the player making it and no earlier move may end it.
+    pub moves: Vec<MakeMoveData>,
+}
+
+/// Creates and joins `script`'s game between two new profiles and plays its moves one transaction at a time.
+///
+/// The game is checked against the expected board and turn after every move,
+/// and the payout and both profiles are checked once the last move wins.
+/// Returns the finished game.
+pub async fn play_scripted_game(
+    guard: &TestGuard,
+    script: ScriptedGame,
+) -> Result<Game, Box<dyn Error>> {
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let funder = Keypair::new();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    // Airdrop SOL to the funder
+    airdrop(rpc, &funder.pubkey(), LAMPORTS_PER_SOL + script.wager * 2).await?;
+
+    // Player one creates the game and moves first
+    let authorities = [Keypair::new(), Keypair::new()];
+    let profiles = [Keypair::new(), Keypair::new()];
+    let game = Keypair::new();
+    let game_key = game.pubkey();
+    let game_signer_bump = GameSignerSeeder { game: game_key }
+        .find_address(&program_id)
+        .1;
+
+    create_profiles(
+        guard,
+        &funder,
+        &[
+            (&authorities[0], &profiles[0]),
+            (&authorities[1], &profiles[1]),
+        ],
+    )
+    .await?;
+
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            program_id,
+            &authorities[0],
+            profiles[0].pubkey(),
+            &game,
+            &funder,
+            &funder,
+            Some(profiles[1].pubkey()),
+            CreateGameClientData {
+                variant: script.variant,
+                ..wagered_game(script.wager)
+            },
+        ))
+        .signed_instructions(join_game(
+            program_id,
+            &authorities[1],
+            profiles[1].pubkey(),
+            game_key,
+            game_signer_bump,
+            &funder,
+            vec![],
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let profiles_before: [PlayerProfile; 2] = [
+        fetch_account(rpc, &profiles[0].pubkey()).await?,
+        fetch_account(rpc, &profiles[1].pubkey()).await?,
+    ];
+    let mut expected: Game = fetch_account(rpc, &game_key).await?;
+    assert_eq!(expected.next_play, Player::One);
+
+    let move_count = script.moves.len();
+    for (index, mov) in script.moves.into_iter().enumerate() {
+        let (player, mover, other) = if index % 2 == 0 {
+            (Player::One, 0, 1)
+        } else {
+            (Player::Two, 1, 0)
+        };
+        let last = index + 1 == move_count;
+        let instructions = if last {
+            make_winning_move(
+                program_id,
+                &authorities[mover],
+                profiles[mover].pubkey(),
+                game_key,
+                game_signer_bump,
+                profiles[other].pubkey(),
+                authorities[mover].pubkey(),
+                None,
+                mov.clone(),
+            )
+        } else {
+            make_move(
+                program_id,
+                &authorities[mover],
+                profiles[mover].pubkey(),
+                game_key,
+                mov.clone(),
+            )
+        };
+        let (_, result) = TransactionBuilder::new(&funder)
+            .signed_instructions(instructions)
+            .send_and_confirm_transaction(
+                rpc,
+                send_config,
+                CommitmentConfig::confirmed(),
+                Duration::from_millis(500),
+            )
+            .await?;
+        match result {
+            ConfirmationResult::Success => {}
+            ConfirmationResult::Failure(error) => {
+                return Err(format!("Move {} {:?} failed: {}", index, mov, error).into())
+            }
+            ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+        }
+
+        let space = expected
+            .board
+            .get_space_mut(mov.big_board, mov.middle_board, mov.small_board)
+            .unwrap_or_else(|| panic!("Move {} {:?} is off the board", index, mov));
+        assert_eq!(*space, Space::Empty, "Move {} {:?} is taken", index, mov);
+        *space = Space::from(player);
+        if last {
+            break;
+        }
+
+        let game_data: Game = fetch_account(rpc, &game_key).await?;
+        expected.next_play = player.other();
+        expected.last_turn = game_data.last_turn;
+        expected.last_move = mov.small_board;
+        expected.last_move_middle = mov.middle_board;
+        assert_eq!(game_data, expected, "After move {}\n{}", index, game_data);
+    }
+
+    // The last move won the game and paid the pot to the winner
+    let winner = if move_count % 2 == 1 { 0 } else { 1 };
+    let loser = 1 - winner;
+    let game_data: Game = fetch_account(rpc, &game_key).await?;
+    assert!(game_data.finished.is_finished(), "\n{}", game_data);
+    assert_eq!(
+        game_data.result(),
+        Some(GameResult::Won(if winner == 0 {
+            Player::One
+        } else {
+            Player::Two
+        })),
+        "\n{}",
+        game_data
+    );
+    assert_eq!(game_data.board, expected.board, "\n{}", game_data);
+    assert_eq!(
+        rpc.get_balance_with_commitment(
+            &authorities[winner].pubkey(),
+            CommitmentConfig::confirmed()
+        )
+        .await?
+        .value,
+        script.wager * 2
+    );
+
+    let winner_profile: PlayerProfile = fetch_account(rpc, &profiles[winner].pubkey()).await?;
+    let loser_profile: PlayerProfile = fetch_account(rpc, &profiles[loser].pubkey()).await?;
+    assert_eq!(winner_profile.wins, profiles_before[winner].wins + 1);
+    assert_eq!(winner_profile.losses, profiles_before[winner].losses);
+    assert_eq!(
+        winner_profile.lamports_won,
+        profiles_before[winner].lamports_won + script.wager * 2
+    );
+    assert!(winner_profile.elo > profiles_before[winner].elo);
+    assert_eq!(loser_profile.losses, profiles_before[loser].losses + 1);
+    assert_eq!(loser_profile.wins, profiles_before[loser].wins);
+    assert_eq!(
+        loser_profile.lamports_lost,
+        profiles_before[loser].lamports_lost + script.wager
+    );
+    assert!(loser_profile.elo < profiles_before[loser].elo);
+
+    Ok(game_data)
+}
+
+/// A move on a classic board.
+fn classic(small_board: [u8; 2]) -> MakeMoveData {
+    MakeMoveData {
+        big_board: [0, 0],
+        middle_board: [0, 0],
+        small_board,
+    }
+}
+
+#[tokio::test]
+async fn full_game_player_one_wins_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    play_scripted_game(
+        &guard,
+        ScriptedGame {
+            variant: GameVariant::Classic,
+            wager: LAMPORTS_PER_SOL,
+            moves: vec![
+                classic([0, 0]),
+                classic([0, 1]),
+                classic([1, 0]),
+                classic([1, 1]),
+                classic([2, 0]),
+            ],
+        },
+    )
+    .await?;
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_game_player_two_wins_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    play_scripted_game(
+        &guard,
+        ScriptedGame {
+            variant: GameVariant::Classic,
+            wager: LAMPORTS_PER_SOL,
+            moves: vec![
+                classic([0, 0]),
+                classic([2, 0]),
+                classic([0, 1]),
+                classic([1, 1]),
+                classic([2, 2]),
+                classic([0, 2]),
+            ],
+        },
+    )
+    .await?;
+
+    guard.drop_self().await;
+    Ok(())
+}
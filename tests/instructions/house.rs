@@ -0,0 +1,238 @@
+use crate::instructions::{
+    create_profiles, new_funder, setup_validator, TEST_MAX_HOUSE_EXPOSURE_BPS,
+};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{GameVariant, Player, PlayerProfile};
+use cruiser_tutorial::instructions::{
+    create_house_game, fund_house_vault, house_move, make_move, make_winning_move,
+    withdraw_house_vault, MakeMoveData,
+};
+use cruiser_tutorial::pda::{GameSignerSeeder, HouseProfileSeeder, HouseVaultSeeder};
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+fn classic_move(small_board: [u8; 2]) -> MakeMoveData {
+    MakeMoveData {
+        big_board: [0, 0],
+        middle_board: [0, 0],
+        small_board,
+    }
+}
+
+#[tokio::test]
+async fn house_game_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let (house_vault, _) = HouseVaultSeeder.find_address(&program_id);
+    let (house_profile, _) = HouseProfileSeeder.find_address(&program_id);
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let game = Keypair::new();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // Give the house a bankroll
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(fund_house_vault(
+            program_id,
+            guard.admin(),
+            &funder,
+            LAMPORTS_PER_SOL * 2,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    let vault_before = rpc
+        .get_balance_with_commitment(&house_vault, CommitmentConfig::confirmed())
+        .await?
+        .value;
+
+    // The house only puts half its vault into one game
+    let over_limit = vault_before * TEST_MAX_HOUSE_EXPOSURE_BPS as u64 / 10_000 + 1;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_house_game(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            &Keypair::new(),
+            &funder,
+            &funder,
+            Player::One,
+            over_limit,
+            0,
+            GameVariant::Classic,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..send_config
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Wager over the house's limit was matched"
+    );
+
+    // The player goes first and takes the top row while the house plays the middle row
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_house_game(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            Player::One,
+            LAMPORTS_PER_SOL,
+            0,
+            GameVariant::Classic,
+        ))
+        .signed_instructions(make_move(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            game.pubkey(),
+            classic_move([0, 0]),
+        ))
+        .signed_instructions(house_move(
+            program_id,
+            guard.house_operator(),
+            game.pubkey(),
+            classic_move([1, 0]),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(make_move(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            game.pubkey(),
+            classic_move([0, 1]),
+        ))
+        .signed_instructions(house_move(
+            program_id,
+            guard.house_operator(),
+            game.pubkey(),
+            classic_move([1, 1]),
+        ))
+        .signed_instructions(make_winning_move(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            game.pubkey(),
+            GameSignerSeeder {
+                game: game.pubkey(),
+            }
+            .find_address(&program_id)
+            .1,
+            house_profile,
+            authority.pubkey(),
+            None,
+            classic_move([0, 2]),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // The house paid its wager out of the vault and recorded the loss
+    let vault_after = rpc
+        .get_balance_with_commitment(&house_vault, CommitmentConfig::confirmed())
+        .await?
+        .value;
+    assert_eq!(vault_after, vault_before - LAMPORTS_PER_SOL);
+
+    let account = rpc
+        .get_account_with_commitment(&house_profile, CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("House profile not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<PlayerProfile>>::compressed_discriminant()
+    );
+    let house = PlayerProfile::deserialize(&mut data)?;
+    assert_eq!(house.authority, house_vault);
+    assert!(house.losses >= 1);
+    assert!(house.lamports_lost >= LAMPORTS_PER_SOL);
+
+    // Take the rest of the bankroll back out
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(withdraw_house_vault(
+            program_id,
+            guard.admin(),
+            funder.pubkey(),
+            vault_after,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    assert_eq!(
+        rpc.get_balance_with_commitment(&house_vault, CommitmentConfig::confirmed())
+            .await?
+            .value,
+        0
+    );
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -1,8 +1,12 @@
-use crate::instructions::setup_validator;
+use crate::instructions::{
+    airdrop, create_profiles, new_funder, setup_validator, wagered_game, TEST_FUNDER_LAMPORTS,
+};
 use cruiser::prelude::*;
-use cruiser_tutorial::accounts::{Game, Player};
+use cruiser_tutorial::accounts::{Game, GameVariant, Player, PlayerProfile, RuleSet};
+use cruiser_tutorial::client::program_error;
+use cruiser_tutorial::error::TutorialError;
 use cruiser_tutorial::instructions::{
-    create_game, create_profile, join_game, CreateGameClientData,
+    create_game, join_game, join_game_self_funded, CreateGameClientData,
 };
 use cruiser_tutorial::pda::GameSignerSeeder;
 use cruiser_tutorial::TutorialAccounts;
@@ -14,15 +18,8 @@ async fn join_game_test() -> Result<(), Box<dyn Error>> {
     let guard = setup_validator().await;
 
     let rpc = guard.rpc();
-    let funder = Keypair::new();
 
-    // Airdrop SOL to the funder
-    let blockhash = rpc.get_latest_blockhash().await?;
-    let sig = rpc
-        .request_airdrop_with_blockhash(&funder.pubkey(), LAMPORTS_PER_SOL * 10, &blockhash)
-        .await?;
-    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
-        .await?;
+    let funder = new_funder(rpc).await?;
 
     // Create random authority and profile
     let authority1 = Keypair::new();
@@ -31,19 +28,14 @@ async fn join_game_test() -> Result<(), Box<dyn Error>> {
     let profile2 = Keypair::new();
     let game = Keypair::new();
 
+    create_profiles(
+        &guard,
+        &funder,
+        &[(&authority1, &profile1), (&authority2, &profile2)],
+    )
+    .await?;
+
     let (sig, result) = TransactionBuilder::new(&funder)
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority1,
-            &profile1,
-            &funder,
-        ))
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority2,
-            &profile2,
-            &funder,
-        ))
         .signed_instructions(create_game(
             guard.program_id(),
             &authority1,
@@ -52,11 +44,7 @@ async fn join_game_test() -> Result<(), Box<dyn Error>> {
             &funder,
             &funder,
             Some(profile2.pubkey()),
-            CreateGameClientData {
-                creator_player: Player::One,
-                wager: LAMPORTS_PER_SOL,
-                turn_length: 60 * 60 * 24, // 1 day
-            },
+            wagered_game(LAMPORTS_PER_SOL),
         ))
         .signed_instructions(join_game(
             guard.program_id(),
@@ -69,6 +57,7 @@ async fn join_game_test() -> Result<(), Box<dyn Error>> {
             .find_address(&guard.program_id())
             .1,
             &funder,
+            vec![],
         ))
         .send_and_confirm_transaction(
             rpc,
@@ -130,12 +119,271 @@ async fn join_game_test() -> Result<(), Box<dyn Error>> {
         Player::One,
         game.signer_bump,
         LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL,
         60 * 60 * 24,
+        0,
+        GameVariant::Ultimate,
+        RuleSet::default(),
     );
     expected.player2 = profile2.pubkey();
+    expected.created_at = game.created_at;
     expected.last_turn = game.last_turn;
 
-    assert_eq!(game, expected);
+    assert_eq!(game, expected, "\n{}", game);
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_game_self_funded_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let funder = Keypair::new();
+    // The joiner pays their own wager
+    let authority2 = Keypair::new();
+
+    // Airdrop SOL to the funder and joiner
+    for key in [funder.pubkey(), authority2.pubkey()] {
+        airdrop(rpc, &key, TEST_FUNDER_LAMPORTS).await?;
+    }
+
+    // Create random authority and profile
+    let authority1 = Keypair::new();
+    let profile1 = Keypair::new();
+    let profile2 = Keypair::new();
+    let game = Keypair::new();
+
+    create_profiles(
+        &guard,
+        &funder,
+        &[(&authority1, &profile1), (&authority2, &profile2)],
+    )
+    .await?;
+
+    let (sig, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authority1,
+            profile1.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            Some(profile2.pubkey()),
+            CreateGameClientData {
+                random_first_player: true,
+                join_code_hash: Game::hash_join_code(b"private link"),
+                ..wagered_game(LAMPORTS_PER_SOL)
+            },
+        ))
+        .signed_instructions(join_game_self_funded(
+            guard.program_id(),
+            &authority2,
+            profile2.pubkey(),
+            game.pubkey(),
+            GameSignerSeeder {
+                game: game.pubkey(),
+            }
+            .find_address(&guard.program_id())
+            .1,
+            b"private link".to_vec(),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Print logs for debugging
+    println!(
+        "Logs: {:#?}",
+        rpc.get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None
+            }
+        )
+        .await?
+        .transaction
+        .meta
+        .unwrap()
+        .log_messages
+    );
+
+    // The joiner's wager came out of their own wallet
+    let balance = rpc
+        .get_balance_with_commitment(&authority2.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value;
+    assert_eq!(balance, LAMPORTS_PER_SOL * 9);
+
+    let account = rpc
+        .get_account_with_commitment(&game.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    let game: Game = Game::deserialize(&mut data)?;
+    assert!(game.is_started());
+    assert_eq!(game.player2, profile2.pubkey());
+    // Either player may have been picked to go first
+    assert!(game.random_first_player);
+    assert_eq!(game.join_code_hash, Game::hash_join_code(b"private link"));
+
+    guard.drop_self().await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_game_race_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // A creator and two joiners racing for the same public game
+    let authorities = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let profiles = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let game = Keypair::new();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    create_profiles(
+        &guard,
+        &funder,
+        &authorities.iter().zip(&profiles).collect::<Vec<_>>(),
+    )
+    .await?;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authorities[0],
+            profiles[0].pubkey(),
+            &game,
+            &funder,
+            &funder,
+            None,
+            wagered_game(LAMPORTS_PER_SOL),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Send both joins at once, skipping preflight so both land whichever is first
+    let game_signer_bump = GameSignerSeeder {
+        game: game.pubkey(),
+    }
+    .find_address(&guard.program_id())
+    .1;
+    let join = |joiner: usize| {
+        TransactionBuilder::new(&funder)
+            .signed_instructions(join_game(
+                guard.program_id(),
+                &authorities[joiner],
+                profiles[joiner].pubkey(),
+                game.pubkey(),
+                game_signer_bump,
+                &funder,
+                vec![],
+            ))
+            .send_and_confirm_transaction(
+                rpc,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    ..send_config
+                },
+                CommitmentConfig::confirmed(),
+                Duration::from_millis(500),
+            )
+    };
+    let (first, second) = tokio::join!(join(1), join(2));
+    let results = [first?.1, second?.1];
+
+    // Exactly one joins, the other fails cleanly with the typed error
+    let mut winner = None;
+    for (joiner, result) in results.into_iter().enumerate() {
+        match result {
+            ConfirmationResult::Success => {
+                assert!(winner.is_none(), "Both joins succeeded");
+                winner = Some(joiner + 1);
+            }
+            ConfirmationResult::Failure(error) => assert_eq!(
+                program_error(&error),
+                Some((0, TutorialError::GameAlreadyJoined)),
+                "{:?}",
+                error
+            ),
+            ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+        }
+    }
+    let winner = winner.expect("Neither join succeeded");
+
+    // Only the winner is in the game and only their wager was taken
+    let account = rpc
+        .get_account_with_commitment(&game.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    let game_data: Game = Game::deserialize(&mut data)?;
+    assert!(game_data.is_started());
+    assert_eq!(game_data.player1, profiles[0].pubkey());
+    assert_eq!(game_data.player2, profiles[winner].pubkey());
+    for joiner in [1, 2] {
+        let account = rpc
+            .get_account_with_commitment(&profiles[joiner].pubkey(), CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_else(|| {
+                panic!("Account not found");
+            });
+        let mut data = account.data.as_slice();
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+        let profile = PlayerProfile::deserialize(&mut data)?;
+        let expected = if joiner == winner {
+            LAMPORTS_PER_SOL
+        } else {
+            0
+        };
+        assert_eq!(profile.lamports_wagered, expected, "Joiner {}", joiner);
+    }
 
     guard.drop_self().await;
     Ok(())
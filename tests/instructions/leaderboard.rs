@@ -0,0 +1,110 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{LeaderboardEntry, LeaderboardShard, PlayerProfile};
+use cruiser_tutorial::client::{fetch_program_accounts, fetch_static_program_accounts};
+use cruiser_tutorial::instructions::{create_leaderboard_shard, update_leaderboard};
+use cruiser_tutorial::pda::LeaderboardShardSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn leaderboard_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+
+    let funder = new_funder(rpc).await?;
+
+    // New profiles start in the initial elo's band
+    let band = LeaderboardShard::band_for_elo(PlayerProfile::INITIAL_ELO);
+    let profile = Keypair::new();
+    create_profiles(&guard, &funder, &[(&Keypair::new(), &profile)]).await?;
+
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_leaderboard_shard(program_id, &funder, band, 0))
+        .signed_instructions(create_leaderboard_shard(program_id, &funder, band, 1))
+        .signed_instructions(update_leaderboard(
+            program_id,
+            profile.pubkey(),
+            PlayerProfile::INITIAL_ELO,
+            0,
+            None,
+        ))
+        // Move it to the next page
+        .signed_instructions(update_leaderboard(
+            program_id,
+            profile.pubkey(),
+            PlayerProfile::INITIAL_ELO,
+            1,
+            Some((band, 0)),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Check the profile is only on the second page
+    for (page, listed) in [(0, false), (1, true)] {
+        let (shard, _) = LeaderboardShardSeeder::new(band, page).find_address(&program_id);
+        let account = rpc
+            .get_account_with_commitment(&shard, CommitmentConfig::confirmed())
+            .await?
+            .value
+            .unwrap_or_else(|| {
+                panic!("Account not found");
+            });
+        let mut data = account.data.as_slice();
+        let discriminant =
+            <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+        assert_eq!(
+            discriminant,
+            <TutorialAccounts as AccountListItem<LeaderboardShard>>::compressed_discriminant()
+        );
+        let shard = LeaderboardShard::deserialize(&mut data)?;
+        assert_eq!(shard.band, band);
+        assert_eq!(shard.page, page);
+        if listed {
+            assert_eq!(
+                shard.listed().collect::<Vec<_>>(),
+                vec![&LeaderboardEntry {
+                    profile: profile.pubkey(),
+                    elo: PlayerProfile::INITIAL_ELO,
+                }]
+            );
+        } else {
+            assert_eq!(shard.listed().count(), 0);
+        }
+    }
+
+    // The filtered fetches only return accounts of the type asked for
+    let shards = fetch_static_program_accounts::<LeaderboardShard>(rpc, &program_id).await?;
+    for page in [0, 1] {
+        let (shard, _) = LeaderboardShardSeeder::new(band, page).find_address(&program_id);
+        assert!(shards.iter().any(|(key, _)| key == &shard));
+    }
+    let profiles = fetch_program_accounts::<PlayerProfile>(rpc, &program_id).await?;
+    assert!(profiles.iter().any(|(key, _)| key == &profile.pubkey()));
+    assert!(!profiles
+        .iter()
+        .any(|(key, _)| shards.iter().any(|(shard, _)| shard == key)));
+
+    guard.drop_self().await;
+    Ok(())
+}
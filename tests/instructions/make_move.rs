@@ -1,8 +1,9 @@
-use crate::instructions::setup_validator;
+use crate::instructions::diff::{assert_only_changed, fetch_account};
+use crate::instructions::{create_profiles, new_funder, setup_validator, wagered_game};
 use cruiser::prelude::*;
-use cruiser_tutorial::accounts::{Game, Player, Space};
+use cruiser_tutorial::accounts::{Game, GameVariant, Player, PlayerProfile, RuleSet, Space};
 use cruiser_tutorial::instructions::{
-    create_game, create_profile, join_game, make_move, CreateGameClientData, MakeMoveData,
+    create_game, join_game, make_move, make_move_v2, MakeMoveData,
 };
 use cruiser_tutorial::pda::GameSignerSeeder;
 use cruiser_tutorial::TutorialAccounts;
@@ -14,15 +15,8 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
     let guard = setup_validator().await;
 
     let rpc = guard.rpc();
-    let funder = Keypair::new();
 
-    // Airdrop SOL to the funder
-    let blockhash = rpc.get_latest_blockhash().await?;
-    let sig = rpc
-        .request_airdrop_with_blockhash(&funder.pubkey(), LAMPORTS_PER_SOL * 10, &blockhash)
-        .await?;
-    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
-        .await?;
+    let funder = new_funder(rpc).await?;
 
     // Create random authority and profile
     let authority1 = Keypair::new();
@@ -30,20 +24,16 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
     let authority2 = Keypair::new();
     let profile2 = Keypair::new();
     let game = Keypair::new();
+    let game_key = game.pubkey();
+
+    create_profiles(
+        &guard,
+        &funder,
+        &[(&authority1, &profile1), (&authority2, &profile2)],
+    )
+    .await?;
 
     let (sig, result) = TransactionBuilder::new(&funder)
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority1,
-            &profile1,
-            &funder,
-        ))
-        .signed_instructions(create_profile(
-            guard.program_id(),
-            &authority2,
-            &profile2,
-            &funder,
-        ))
         .signed_instructions(create_game(
             guard.program_id(),
             &authority1,
@@ -52,11 +42,7 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
             &funder,
             &funder,
             Some(profile2.pubkey()),
-            CreateGameClientData {
-                creator_player: Player::One,
-                wager: LAMPORTS_PER_SOL,
-                turn_length: 60 * 60 * 24, // 1 day
-            },
+            wagered_game(LAMPORTS_PER_SOL),
         ))
         .signed_instructions(join_game(
             guard.program_id(),
@@ -69,6 +55,7 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
             .find_address(&guard.program_id())
             .1,
             &funder,
+            vec![],
         ))
         .signed_instructions(make_move(
             guard.program_id(),
@@ -77,6 +64,7 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
             game.pubkey(),
             MakeMoveData {
                 big_board: [0, 0],
+                middle_board: [0, 0],
                 small_board: [0, 0],
             },
         ))
@@ -140,20 +128,107 @@ async fn make_move_test() -> Result<(), Box<dyn Error>> {
         Player::One,
         game.signer_bump,
         LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL,
         60 * 60 * 24,
+        0,
+        GameVariant::Ultimate,
+        RuleSet::default(),
     );
     expected.player2 = profile2.pubkey();
+    expected.created_at = game.created_at;
     expected.last_turn = game.last_turn;
     expected.next_play = Player::Two;
     expected.last_move = [0, 0];
+    expected.last_move_middle = [0, 0];
     *expected
         .board
-        .get_mut([0, 0])
-        .unwrap()
-        .get_mut([0, 0])
+        .get_space_mut([0, 0], [0, 0], [0, 0])
         .unwrap() = Space::PlayerOne;
 
-    assert_eq!(game, expected);
+    assert_eq!(game, expected, "\n{}", game);
+
+    // The second player's move only touches the turn
+    let profile2_before: PlayerProfile = fetch_account(rpc, &profile2.pubkey()).await?;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(make_move(
+            guard.program_id(),
+            &authority2,
+            profile2.pubkey(),
+            game_key,
+            MakeMoveData {
+                big_board: [0, 0],
+                middle_board: [0, 0],
+                small_board: [1, 1],
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let game_after: Game = fetch_account(rpc, &game_key).await?;
+    assert_only_changed(
+        &game,
+        &game_after,
+        &[
+            "next_play",
+            "last_turn",
+            "last_move",
+            "last_move_middle",
+            "board",
+        ],
+    );
+    assert_eq!(game_after.next_play, Player::One);
+    let profile2_after: PlayerProfile = fetch_account(rpc, &profile2.pubkey()).await?;
+    assert_only_changed(&profile2_before, &profile2_after, &["last_active"]);
+
+    // `MakeMoveV2` plays against the game as of its last turn
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(make_move_v2(
+            guard.program_id(),
+            &authority1,
+            profile1.pubkey(),
+            game_key,
+            game_after.last_turn,
+            MakeMoveData {
+                big_board: [1, 1],
+                middle_board: [0, 0],
+                small_board: [2, 2],
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    let game_v2: Game = fetch_account(rpc, &game_key).await?;
+    assert_eq!(game_v2.next_play, Player::Two);
+    assert_eq!(game_v2.last_move, [2, 2]);
 
     guard.drop_self().await;
     Ok(())
@@ -0,0 +1,169 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator, wagered_game};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{Game, GameVariant, Player, RuleSet, Space};
+use cruiser_tutorial::instructions::{create_game, join_game, make_moves, MakeMoveData};
+use cruiser_tutorial::pda::GameSignerSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn make_moves_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // Create random authority and profile
+    let authority1 = Keypair::new();
+    let profile1 = Keypair::new();
+    let authority2 = Keypair::new();
+    let profile2 = Keypair::new();
+    let game = Keypair::new();
+
+    create_profiles(
+        &guard,
+        &funder,
+        &[(&authority1, &profile1), (&authority2, &profile2)],
+    )
+    .await?;
+
+    let (sig, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authority1,
+            profile1.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            Some(profile2.pubkey()),
+            wagered_game(LAMPORTS_PER_SOL),
+        ))
+        .signed_instructions(join_game(
+            guard.program_id(),
+            &authority2,
+            profile2.pubkey(),
+            game.pubkey(),
+            GameSignerSeeder {
+                game: game.pubkey(),
+            }
+            .find_address(&guard.program_id())
+            .1,
+            &funder,
+            vec![],
+        ))
+        .signed_instructions(make_moves(
+            guard.program_id(),
+            &authority1,
+            profile1.pubkey(),
+            &authority2,
+            profile2.pubkey(),
+            game.pubkey(),
+            vec![
+                MakeMoveData {
+                    big_board: [0, 0],
+                    middle_board: [0, 0],
+                    small_board: [1, 1],
+                },
+                MakeMoveData {
+                    big_board: [1, 1],
+                    middle_board: [0, 0],
+                    small_board: [0, 0],
+                },
+                MakeMoveData {
+                    big_board: [0, 0],
+                    middle_board: [0, 0],
+                    small_board: [2, 2],
+                },
+            ],
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Print logs for debugging
+    println!(
+        "Logs: {:#?}",
+        rpc.get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None
+            }
+        )
+        .await?
+        .transaction
+        .meta
+        .unwrap()
+        .log_messages
+    );
+
+    // Check account data is what we expect
+    let account = rpc
+        .get_account_with_commitment(&game.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<Game>>::compressed_discriminant()
+    );
+    let game: Game = Game::deserialize(&mut data)?;
+    assert!(game.last_turn > 0);
+    let mut expected = Game::new(
+        &profile1.pubkey(),
+        Player::One,
+        game.signer_bump,
+        LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL,
+        60 * 60 * 24,
+        0,
+        GameVariant::Ultimate,
+        RuleSet::default(),
+    );
+    expected.player2 = profile2.pubkey();
+    expected.created_at = game.created_at;
+    expected.last_turn = game.last_turn;
+    // Player one moved last so it's player two's turn
+    expected.next_play = Player::Two;
+    expected.last_move = [2, 2];
+    expected.last_move_middle = [0, 0];
+    for (big_board, small_board, space) in [
+        ([0, 0], [1, 1], Space::PlayerOne),
+        ([1, 1], [0, 0], Space::PlayerTwo),
+        ([0, 0], [2, 2], Space::PlayerOne),
+    ] {
+        *expected
+            .board
+            .get_space_mut(big_board, [0, 0], small_board)
+            .unwrap() = space;
+    }
+
+    assert_eq!(game, expected, "\n{}", game);
+
+    guard.drop_self().await;
+    Ok(())
+}
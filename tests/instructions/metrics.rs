@@ -0,0 +1,78 @@
+use crate::instructions::{new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::InstructionMetrics;
+use cruiser_tutorial::instructions::{create_profile, init_metrics, with_metrics, CreateProfile};
+use cruiser_tutorial::pda::MetricsSeeder;
+use cruiser_tutorial::{TutorialAccounts, TutorialInstructions};
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn metrics_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+
+    let funder = new_funder(rpc).await?;
+
+    // Only the metered profile is counted
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(init_metrics(program_id, guard.admin(), &funder))
+        .signed_instructions(with_metrics(
+            program_id,
+            create_profile(program_id, &Keypair::new(), &Keypair::new(), &funder),
+        ))
+        .signed_instructions(create_profile(
+            program_id,
+            &Keypair::new(),
+            &Keypair::new(),
+            &funder,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Check account data is what we expect
+    let (metrics, _) = MetricsSeeder.find_address(&program_id);
+    let account = rpc
+        .get_account_with_commitment(&metrics, CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<InstructionMetrics>>::compressed_discriminant()
+    );
+    let metrics = InstructionMetrics::deserialize(&mut data)?;
+
+    let mut create_profile_discriminant = Vec::new();
+    <TutorialInstructions as InstructionListItem<CreateProfile>>::discriminant_compressed()
+        .serialize(&mut create_profile_discriminant)?;
+    assert_eq!(metrics.invocations_of(create_profile_discriminant[0]), 1);
+    assert_eq!(metrics.invocations.iter().sum::<u64>(), 1);
+
+    guard.drop_self().await;
+    Ok(())
+}
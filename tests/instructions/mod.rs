@@ -1,42 +1,206 @@
+mod admin_action;
 mod create_game;
 mod create_profile;
+mod diff;
+mod distribution;
 mod forfeit_game;
+mod full_game;
+mod house;
 mod join_game;
+mod leaderboard;
 mod make_move;
+mod make_moves;
+mod metrics;
+mod payout_allowlist;
+mod program_error;
+mod set_beneficiary;
+mod social_graph;
+mod sweep_stale_game;
+mod update_config;
+mod update_profile;
+mod withdraw_fees;
 
 use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{GameVariant, Player, RuleSet};
+use cruiser_tutorial::instructions::{
+    create_profile, init_config, init_house, CreateGameClientData,
+};
 use reqwest::Client;
 use std::cell::UnsafeCell;
-use std::path::Path;
+use std::env;
+use std::ffi::OsString;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
 use std::sync::atomic::{AtomicIsize, Ordering};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::process::{Child, Command};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tokio::task::{spawn_blocking, yield_now};
 use tokio::time::sleep;
 
 static SETUP: Setup = Setup::new();
 
+/// The keeper tip the program config is created with.
+pub const TEST_KEEPER_TIP_BPS: u16 = 100;
+/// The most the house matches in one game in the program config.
+pub const TEST_MAX_HOUSE_WAGER: u64 = LAMPORTS_PER_SOL * 2;
+/// The share of the house vault the house matches at most in the program config.
+pub const TEST_MAX_HOUSE_EXPOSURE_BPS: u16 = 5_000;
+/// How long a profile can go without a game before its elo decays in the program config.
+pub const TEST_ELO_DECAY_PERIOD: UnixTimestamp = 60 * 60 * 24 * 30;
+/// How far elo decays per period in the program config.
+pub const TEST_ELO_DECAY_STEP: u64 = 25;
+
 /// All tests that need validator access should call this function
 /// and call [`TestGuard::drop_self`] when done with the validator.
+/// See [`ValidatorBackend`] for where the validator runs.
 pub async fn setup_validator() -> TestGuard {
-    SETUP.setup().await
+    SETUP.setup(false).await
+}
+
+/// Like [`setup_validator`] but the test gets the validator to itself,
+/// starting from the ledger snapshot taken right after the program was deployed.
+/// Use this when a test needs to see no accounts from other tests.
+/// Panics with an [external](ValidatorBackend::External) validator, which has no snapshot to restart from.
+pub async fn setup_validator_reset() -> TestGuard {
+    SETUP.setup(true).await
+}
+
+/// What [`new_funder`] airdrops.
+pub const TEST_FUNDER_LAMPORTS: u64 = LAMPORTS_PER_SOL * 10;
+
+/// Airdrops `lamports` to `to` and waits for it to confirm.
+pub async fn airdrop(
+    rpc: &RpcClient,
+    to: &Pubkey,
+    lamports: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let sig = rpc
+        .request_airdrop_with_blockhash(to, lamports, &blockhash)
+        .await?;
+    rpc.confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::confirmed())
+        .await?;
+    Ok(())
+}
+
+/// A new keypair holding [`TEST_FUNDER_LAMPORTS`] to pay for a test's transactions.
+pub async fn new_funder(rpc: &RpcClient) -> Result<Keypair, Box<dyn std::error::Error>> {
+    let funder = Keypair::new();
+    airdrop(rpc, &funder.pubkey(), TEST_FUNDER_LAMPORTS).await?;
+    Ok(funder)
+}
+
+/// Creates a profile for every `(authority, profile)` in `profiles` in one transaction paid by `funder`.
+pub async fn create_profiles(
+    guard: &TestGuard,
+    funder: &Keypair,
+    profiles: &[(&Keypair, &Keypair)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = TransactionBuilder::new(funder);
+    for (authority, profile) in profiles {
+        builder = builder.signed_instructions(create_profile(
+            guard.program_id(),
+            *authority,
+            *profile,
+            funder,
+        ));
+    }
+    let (_, result) = builder
+        .send_and_confirm_transaction(
+            guard.rpc(),
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => Ok(()),
+        ConfirmationResult::Failure(error) => Err(error.into()),
+        ConfirmationResult::Dropped => Err("Profile transaction dropped".into()),
+    }
+}
+
+/// A public ultimate game where both players wager `wager` and get a day per turn.
+/// Tests override only the fields they exercise.
+pub fn wagered_game(wager: u64) -> CreateGameClientData {
+    CreateGameClientData {
+        creator_player: Player::One,
+        wager,
+        joiner_wager: wager,
+        turn_length: 60 * 60 * 24, // 1 day
+        join_ttl: 0,
+        variant: GameVariant::Ultimate,
+        rules: RuleSet::default(),
+        random_first_player: false,
+        join_code_hash: [0; 32],
+        invited: Default::default(),
+        friendly: false,
+        lock_ttl: 0,
+    }
+}
+
+/// Like [`wagered_game`] but untimed and played for nothing.
+pub fn friendly_game() -> CreateGameClientData {
+    CreateGameClientData {
+        wager: 0,
+        joiner_wager: 0,
+        turn_length: 0,
+        friendly: true,
+        ..wagered_game(0)
+    }
 }
 
 struct Setup {
     test_count: AtomicIsize,
     program_id: UnsafeCell<Option<Pubkey>>,
-    validator: UnsafeCell<Option<Child>>,
+    program_path: UnsafeCell<Option<PathBuf>>,
+    ports: UnsafeCell<Option<ValidatorPorts>>,
+    rpc_url: UnsafeCell<Option<String>>,
+    /// [`None`] with an external validator.
+    validator: UnsafeCell<Option<Validator>>,
+    admin: UnsafeCell<Option<Keypair>>,
+    house_operator: UnsafeCell<Option<Keypair>>,
+    /// Shared by normal tests, held exclusively by reset tests.
+    ledger_lock: RwLock<()>,
 }
 impl Setup {
     const fn new() -> Self {
         Self {
             test_count: AtomicIsize::new(0),
             program_id: UnsafeCell::new(None),
+            program_path: UnsafeCell::new(None),
+            ports: UnsafeCell::new(None),
+            rpc_url: UnsafeCell::new(None),
             validator: UnsafeCell::new(None),
+            admin: UnsafeCell::new(None),
+            house_operator: UnsafeCell::new(None),
+            ledger_lock: RwLock::const_new(()),
         }
     }
 
-    async fn setup(&'static self) -> TestGuard {
+    async fn setup(&'static self, reset: bool) -> TestGuard {
+        if reset
+            && matches!(
+                ValidatorBackend::from_env(),
+                Ok(ValidatorBackend::External { .. })
+            )
+        {
+            panic!("Reset tests need a validator the suite starts, unset `CRUISER_TUTORIAL_RPC_URL` to run them");
+        }
+        // A reset test waits for all other tests to drop their guards,
+        // which kills the validator so it is restarted from the snapshot below.
+        let ledger_guard = if reset {
+            LedgerGuard::Exclusive(self.ledger_lock.write().await)
+        } else {
+            LedgerGuard::Shared(self.ledger_lock.read().await)
+        };
         let mut count = self.test_count.load(Ordering::SeqCst);
         let should_start = loop {
             let should_start = match count {
@@ -44,6 +208,7 @@ impl Setup {
                 -1 => {
                     // Validator is being killed
                     sleep(Duration::from_millis(100)).await;
+                    count = self.test_count.load(Ordering::SeqCst);
                     continue;
                 }
                 0 => true,
@@ -65,10 +230,10 @@ impl Setup {
             }
         };
         if should_start {
-            match start_validator().await {
+            match self.start().await {
                 Ok((program_id, validator)) => unsafe {
                     *self.program_id.get() = Some(program_id);
-                    *self.validator.get() = Some(validator);
+                    *self.validator.get() = validator;
                 },
                 Err(e) => {
                     self.test_count.store(-2, Ordering::SeqCst);
@@ -76,32 +241,389 @@ impl Setup {
                 }
             }
         }
-        let out = TestGuard::new(self);
+        let out = TestGuard::new(self, ledger_guard);
         let client = Client::new();
         loop {
             if self.test_count.load(Ordering::SeqCst) == -2 {
                 panic!("Validator could not be started");
             }
-            if client
-                .get("http://localhost:8899/health")
-                .send()
-                .await
-                .map_or(false, |res| res.status().is_success())
-            {
+            if is_healthy(&client, out.rpc_url()).await {
                 break;
             }
             sleep(Duration::from_millis(500)).await;
         }
         out
     }
+
+    /// Starts the validator, [`None`] if it is [external](ValidatorBackend::External).
+    /// The first start deploys the program (building it if needed) and creates the program config and house
+    /// then snapshots the ledger, later starts restore that snapshot instead.
+    async fn start(&self) -> Result<(Pubkey, Option<Validator>), Box<dyn std::error::Error>> {
+        // Make sure the last validator has fully exited before touching its ledger.
+        if let Some(mut old) = unsafe { (*self.validator.get()).take() } {
+            old.wait().await?;
+        }
+
+        let backend = ValidatorBackend::from_env()?;
+        if let ValidatorBackend::External {
+            rpc_url,
+            program_id,
+        } = &backend
+        {
+            // The config only needs creating the first time
+            if unsafe { (*self.admin.get()).is_none() } {
+                println!("Using validator at `{}`", rpc_url);
+                let (admin, house_operator) = create_config(*program_id, rpc_url).await?;
+                unsafe {
+                    *self.rpc_url.get() = Some(rpc_url.clone());
+                    *self.admin.get() = Some(admin);
+                    *self.house_operator.get() = Some(house_operator);
+                }
+            }
+            return Ok((*program_id, None));
+        }
+
+        if let (Some(program_id), Some(program_path), Some(ports)) = unsafe {
+            (
+                *self.program_id.get(),
+                (*self.program_path.get()).as_deref(),
+                *self.ports.get(),
+            )
+        } {
+            println!("Restoring ledger snapshot...");
+            copy_ledger(&snapshot_dir(), &ledger_dir()).await?;
+            return Ok((
+                program_id,
+                Some(Validator::spawn(
+                    &backend,
+                    program_id,
+                    program_path,
+                    ports,
+                    false,
+                )?),
+            ));
+        }
+
+        let ports = ValidatorPorts::from_env()?;
+        unsafe {
+            *self.ports.get() = Some(ports);
+            *self.rpc_url.get() = Some(ports.rpc_url());
+        }
+        let program_path = program_artifact().await?;
+        let program_id = Keypair::new().pubkey();
+        println!("Program ID: `{}`", program_id);
+        let mut validator = Validator::spawn(&backend, program_id, &program_path, ports, true)?;
+        let client = Client::new();
+        while !is_healthy(&client, &ports.rpc_url()).await {
+            if let Some(status) = validator.try_wait()? {
+                return Err(format!("Validator exited early: {}", status).into());
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+        let (admin, house_operator) = create_config(program_id, &ports.rpc_url()).await?;
+        unsafe {
+            *self.admin.get() = Some(admin);
+            *self.house_operator.get() = Some(house_operator);
+        }
+
+        println!("Taking ledger snapshot...");
+        validator.start_kill()?;
+        validator.wait().await?;
+        copy_ledger(&ledger_dir(), &snapshot_dir()).await?;
+        let validator = Validator::spawn(&backend, program_id, &program_path, ports, false)?;
+        unsafe { *self.program_path.get() = Some(program_path) };
+        Ok((program_id, Some(validator)))
+    }
 }
 unsafe impl Sync for Setup {}
 
-async fn start_validator() -> Result<(Pubkey, Child), Box<dyn std::error::Error>> {
-    let deploy_dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+/// Where the tests' validator runs, picked with env vars.
+///
+/// By default `solana-test-validator` is run from the `PATH`.
+/// With `CRUISER_TUTORIAL_DOCKER_IMAGE` set, such as to `solanalabs/solana:v1.10.8`, it is run in a container of that image instead
+/// so machines without the Solana toolchain can run the tests. The program must still be built, see [`program_artifact`].
+/// With `CRUISER_TUTORIAL_RPC_URL` set the tests use the validator there instead of starting one,
+/// which must be fresh, have a faucet and have the program deployed at `CRUISER_TUTORIAL_PROGRAM_ID`.
+pub enum ValidatorBackend {
+    /// `solana-test-validator` on this machine.
+    Local,
+    /// `solana-test-validator` in a docker container.
+    Docker {
+        /// The image to run.
+        image: String,
+    },
+    /// A validator the tests don't manage.
+    External {
+        /// Where to send requests.
+        rpc_url: String,
+        /// The program's id on the validator.
+        program_id: Pubkey,
+    },
+}
+impl ValidatorBackend {
+    fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        if let Ok(rpc_url) = env::var("CRUISER_TUTORIAL_RPC_URL") {
+            let program_id = env::var("CRUISER_TUTORIAL_PROGRAM_ID")
+                .map_err(|_| "`CRUISER_TUTORIAL_RPC_URL` needs `CRUISER_TUTORIAL_PROGRAM_ID`")?
+                .parse()?;
+            Ok(Self::External {
+                rpc_url,
+                program_id,
+            })
+        } else if let Ok(image) = env::var("CRUISER_TUTORIAL_DOCKER_IMAGE") {
+            Ok(Self::Docker { image })
+        } else {
+            Ok(Self::Local)
+        }
+    }
+}
+
+/// A validator started by the tests.
+enum Validator {
+    Local(Child),
+    Docker {
+        /// The `docker run` process, which exits once the container stops.
+        child: Child,
+        container: String,
+    },
+}
+impl Validator {
+    /// Starts the validator, see [`validator_args`].
+    fn spawn(
+        backend: &ValidatorBackend,
+        program_id: Pubkey,
+        program_path: &Path,
+        ports: ValidatorPorts,
+        reset: bool,
+    ) -> std::io::Result<Self> {
+        let args = validator_args(program_id, program_path, ports, reset);
+        match backend {
+            ValidatorBackend::Local => {
+                let mut command = Command::new("solana-test-validator");
+                command.args(args);
+                println!("Starting local validator...");
+                println!("{:?}", command);
+                Ok(Self::Local(command.spawn()?))
+            }
+            ValidatorBackend::Docker { image } => {
+                let container = format!("cruiser-tutorial-validator-{}", ports.rpc);
+                let mut command = Command::new("docker");
+                command
+                    .arg("run")
+                    .arg("--rm")
+                    .arg("--name")
+                    .arg(&container)
+                    .arg("--user")
+                    .arg(docker_user()?);
+                // The ledger and program are mounted at the same paths so the arguments work unchanged
+                let program_dir = program_path.parent().unwrap();
+                for dir in [Path::new(env!("CARGO_TARGET_TMPDIR")), program_dir] {
+                    command.arg("-v").arg(format!("{0}:{0}", dir.display()));
+                }
+                for port in [ports.rpc, ports.rpc + 1, ports.faucet] {
+                    command.arg("-p").arg(format!("{0}:{0}", port));
+                }
+                command.arg(image).arg("solana-test-validator").args(args);
+                println!("Starting validator in docker...");
+                println!("{:?}", command);
+                Ok(Self::Docker {
+                    child: command.spawn()?,
+                    container,
+                })
+            }
+            ValidatorBackend::External { .. } => {
+                unreachable!("External validators aren't started")
+            }
+        }
+    }
+
+    fn child(&mut self) -> &mut Child {
+        match self {
+            Self::Local(child) | Self::Docker { child, .. } => child,
+        }
+    }
+
+    /// Starts stopping the validator, [`Validator::wait`] for it to stop.
+    fn start_kill(&mut self) -> std::io::Result<()> {
+        if let Self::Docker { container, .. } = self {
+            // Killing `docker run` would leave the container running
+            std::process::Command::new("docker")
+                .arg("kill")
+                .arg(&*container)
+                .status()?;
+        }
+        self.child().start_kill()
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<ExitStatus>> {
+        self.child().try_wait()
+    }
+
+    async fn wait(&mut self) -> std::io::Result<ExitStatus> {
+        self.child().wait().await
+    }
+}
+
+/// The user to run the validator container as, so the ledger it writes belongs to this user.
+fn docker_user() -> std::io::Result<String> {
+    let id = |flag| -> std::io::Result<String> {
+        let output = std::process::Command::new("id").arg(flag).output()?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    Ok(format!("{}:{}", id("-u")?, id("-g")?))
+}
+
+/// The ports the test validator listens on.
+/// Set with the `CRUISER_TUTORIAL_RPC_PORT` and `CRUISER_TUTORIAL_FAUCET_PORT` env vars,
+/// otherwise free ports are picked so the suite doesn't collide with other local validators.
+#[derive(Copy, Clone, Debug)]
+pub struct ValidatorPorts {
+    /// The RPC port. The websocket port is always the next port up.
+    pub rpc: u16,
+    /// The faucet port.
+    pub faucet: u16,
+}
+impl ValidatorPorts {
+    fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let rpc = match env::var("CRUISER_TUTORIAL_RPC_PORT") {
+            Ok(port) => port.parse()?,
+            Err(_) => free_rpc_port()?,
+        };
+        let faucet = match env::var("CRUISER_TUTORIAL_FAUCET_PORT") {
+            Ok(port) => port.parse()?,
+            Err(_) => free_port()?,
+        };
+        Ok(Self { rpc, faucet })
+    }
+
+    /// The url for RPC requests.
+    pub fn rpc_url(&self) -> String {
+        format!("http://localhost:{}", self.rpc)
+    }
+}
+
+fn free_port() -> std::io::Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// Finds a free port where the next port up is also free for the websocket.
+fn free_rpc_port() -> std::io::Result<u16> {
+    loop {
+        let port = free_port()?;
+        if port < u16::MAX && TcpListener::bind(("127.0.0.1", port + 1)).is_ok() {
+            return Ok(port);
+        }
+    }
+}
+
+async fn is_healthy(client: &Client, rpc_url: &str) -> bool {
+    client
+        .get(format!("{}/health", rpc_url))
+        .send()
+        .await
+        .map_or(false, |res| res.status().is_success())
+}
+
+fn ledger_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_ledger")
+}
+
+fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_ledger_snapshot")
+}
+
+/// Replaces `to` with a copy of `from`.
+async fn copy_ledger(from: &Path, to: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if to.exists() {
+        tokio::fs::remove_dir_all(to).await?;
+    }
+    let copy = Command::new("cp")
+        .arg("-a")
+        .arg(from)
+        .arg(to)
+        .spawn()?
+        .wait()
+        .await?;
+    if !copy.success() {
+        return Err(copy.to_string().into());
+    }
+    Ok(())
+}
+
+/// Finds the program's `.so`, building it only when there is no up to date artifact.
+/// `CRUISER_TUTORIAL_SO` points at a prebuilt artifact, otherwise the `cargo build-bpf` output is used.
+/// An artifact older than any file in `src` or `Cargo.toml` is stale and triggers a rebuild,
+/// unless it came from `CRUISER_TUTORIAL_SO` which fails instead so the tests never run against something else.
+async fn program_artifact() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let default_path = Path::new(env!("CARGO_TARGET_TMPDIR"))
         .parent()
         .unwrap()
-        .join("deploy");
+        .join("deploy")
+        .join(format!("{}.so", env!("CARGO_PKG_NAME")));
+    let explicit_path = env::var_os("CRUISER_TUTORIAL_SO").map(PathBuf::from);
+    let path = explicit_path
+        .clone()
+        .unwrap_or_else(|| default_path.clone());
+
+    let sources_modified = newest_modification(&[
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"),
+    ])?;
+    match path.metadata().and_then(|metadata| metadata.modified()) {
+        Ok(modified) if modified >= sources_modified => {
+            println!("Using program artifact `{}`", path.display());
+            return Ok(path);
+        }
+        Ok(_) if explicit_path.is_some() => {
+            return Err(format!(
+                "CRUISER_TUTORIAL_SO artifact `{}` is older than the sources, rebuild it or unset CRUISER_TUTORIAL_SO",
+                path.display()
+            )
+            .into())
+        }
+        Err(error) if explicit_path.is_some() => {
+            return Err(format!(
+                "CRUISER_TUTORIAL_SO artifact `{}` can't be read: {}",
+                path.display(),
+                error
+            )
+            .into())
+        }
+        Ok(_) => println!(
+            "Program artifact `{}` is stale, rebuilding...",
+            path.display()
+        ),
+        Err(_) => println!(
+            "Program artifact `{}` not found, building...",
+            path.display()
+        ),
+    }
+
+    build_program().await?;
+    Ok(default_path)
+}
+
+/// The latest modification time of the given files and everything under the given directories.
+fn newest_modification(paths: &[&Path]) -> std::io::Result<SystemTime> {
+    let mut newest = SystemTime::UNIX_EPOCH;
+    let mut to_visit = paths
+        .iter()
+        .map(|path| path.to_path_buf())
+        .collect::<Vec<_>>();
+    while let Some(path) = to_visit.pop() {
+        let metadata = path.metadata()?;
+        newest = newest.max(metadata.modified()?);
+        if metadata.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                to_visit.push(entry?.path());
+            }
+        }
+    }
+    Ok(newest)
+}
+
+/// Builds the program.
+async fn build_program() -> Result<(), Box<dyn std::error::Error>> {
     let build = Command::new("cargo")
         .env("RUSTFLAGS", "-D warnings")
         .arg("build-bpf")
@@ -112,37 +634,103 @@ async fn start_validator() -> Result<(Pubkey, Child), Box<dyn std::error::Error>
     if !build.success() {
         return Err(build.to_string().into());
     }
-    let program_id = Keypair::new().pubkey();
-    println!("Program ID: `{}`", program_id);
+    Ok(())
+}
 
-    let mut local_validator = Command::new("solana-test-validator");
-    local_validator
-        .arg("-r")
-        .arg("--bpf-program")
-        .arg(program_id.to_string())
-        .arg(deploy_dir.join(format!("{}.so", env!("CARGO_PKG_NAME"))))
-        .arg("--deactivate-feature")
-        .arg("5ekBxc8itEnPv4NzGJtr8BVVQLNMQuLMNQQj7pHoLNZ9") // transaction wide compute cap
-        .arg("--deactivate-feature")
-        .arg("75m6ysz33AfLA5DDEzWM1obBrnPQRSsdVQ2nRmc8Vuu1") // support account data reallocation
-        .arg("--ledger")
-        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("test_ledger"));
+/// The arguments to `solana-test-validator`. `reset` starts from a fresh ledger with the program deployed.
+fn validator_args(
+    program_id: Pubkey,
+    program_path: &Path,
+    ports: ValidatorPorts,
+    reset: bool,
+) -> Vec<OsString> {
+    let mut args = Vec::<OsString>::new();
+    if reset {
+        args.push("-r".into());
+    }
+    args.extend([
+        "--bpf-program".into(),
+        program_id.to_string().into(),
+        program_path.into(),
+        "--deactivate-feature".into(),
+        "5ekBxc8itEnPv4NzGJtr8BVVQLNMQuLMNQQj7pHoLNZ9".into(), // transaction wide compute cap
+        // Account data reallocation stays on, `UpdateProfile` resizes profiles, see the `update_profile` tests.
+        "--rpc-port".into(),
+        ports.rpc.to_string().into(),
+        "--faucet-port".into(),
+        ports.faucet.to_string().into(),
+        "--ledger".into(),
+        ledger_dir().into(),
+    ]);
+    args
+}
+
+/// Creates the program config and the house, returning the config's admin and the house's operator.
+async fn create_config(
+    program_id: Pubkey,
+    rpc_url: &str,
+) -> Result<(Keypair, Keypair), Box<dyn std::error::Error>> {
+    let rpc = RpcClient::new(rpc_url.to_string());
+    let admin = Keypair::new();
+    let house_operator = Keypair::new();
+
+    airdrop(&rpc, &admin.pubkey(), LAMPORTS_PER_SOL).await?;
+
+    let (_, result) = TransactionBuilder::new(&admin)
+        .signed_instructions(init_config(
+            program_id,
+            &admin,
+            &admin,
+            TEST_KEEPER_TIP_BPS,
+            TEST_MAX_HOUSE_WAGER,
+            TEST_MAX_HOUSE_EXPOSURE_BPS,
+            TEST_ELO_DECAY_PERIOD,
+            TEST_ELO_DECAY_STEP,
+        ))
+        .signed_instructions(init_house(
+            program_id,
+            &admin,
+            house_operator.pubkey(),
+            &admin,
+        ))
+        .send_and_confirm_transaction(
+            &rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => Ok((admin, house_operator)),
+        ConfirmationResult::Failure(error) => Err(error.into()),
+        ConfirmationResult::Dropped => Err("Config transaction dropped".into()),
+    }
+}
 
-    println!("Starting local validator...");
-    println!("{:?}", local_validator);
-    Ok((program_id, local_validator.spawn()?))
+/// Access to the validator's ledger held by a [`TestGuard`].
+enum LedgerGuard {
+    Shared(RwLockReadGuard<'static, ()>),
+    Exclusive(RwLockWriteGuard<'static, ()>),
 }
 
 #[must_use]
 pub struct TestGuard {
     setup: &'static Setup,
     rpc: RpcClient,
+    // Dropped after `Drop::drop` so the validator is killed before another reset can start.
+    _ledger_guard: LedgerGuard,
 }
 impl TestGuard {
-    fn new(setup: &'static Setup) -> Self {
+    fn new(setup: &'static Setup, ledger_guard: LedgerGuard) -> Self {
         Self {
             setup,
-            rpc: RpcClient::new("http://localhost:8899".to_string()),
+            rpc: RpcClient::new(unsafe { (*setup.rpc_url.get()).clone().unwrap() }),
+            _ledger_guard: ledger_guard,
         }
     }
 
@@ -150,10 +738,25 @@ impl TestGuard {
         unsafe { (*self.setup.program_id.get()).unwrap() }
     }
 
+    /// The url for RPC requests.
+    pub fn rpc_url(&self) -> &str {
+        unsafe { (*self.setup.rpc_url.get()).as_deref().unwrap() }
+    }
+
     pub fn rpc(&self) -> &RpcClient {
         &self.rpc
     }
 
+    /// The program config's admin.
+    pub fn admin(&self) -> &Keypair {
+        unsafe { (*self.setup.admin.get()).as_ref().unwrap() }
+    }
+
+    /// The key that plays for the house.
+    pub fn house_operator(&self) -> &Keypair {
+        unsafe { (*self.setup.house_operator.get()).as_ref().unwrap() }
+    }
+
     pub async fn drop_self(self) {
         spawn_blocking(move || {
             drop(self);
@@ -184,8 +787,10 @@ impl Drop for TestGuard {
             }
         };
         if should_kill {
-            let mut local = unsafe { (&mut *self.setup.validator.get()).take().unwrap() };
-            local.start_kill().unwrap();
+            // The killed validator is left in place so the next start can wait for it to exit.
+            if let Some(validator) = unsafe { (&mut *self.setup.validator.get()).as_mut() } {
+                validator.start_kill().unwrap();
+            }
             assert_eq!(self.setup.test_count.fetch_add(1, Ordering::SeqCst), -1);
             println!("Validator cleaned up properly");
         }
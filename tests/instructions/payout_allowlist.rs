@@ -0,0 +1,125 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{PayoutAllowlist, PlayerProfile};
+use cruiser_tutorial::instructions::{create_payout_allowlist, update_payout_allowlist};
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn payout_allowlist_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // Create random authority, profile, and payout addresses
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let payout_allowlist = Keypair::new();
+    let payout_address = Keypair::new().pubkey();
+    let new_payout_address = Keypair::new().pubkey();
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // Send transaction
+    let (sig, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_payout_allowlist(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            &payout_allowlist,
+            &funder,
+            vec![payout_address],
+        ))
+        .signed_instructions(update_payout_allowlist(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            payout_allowlist.pubkey(),
+            vec![payout_address, new_payout_address],
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Print logs for debugging
+    println!(
+        "Logs: {:#?}",
+        rpc.get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None
+            }
+        )
+        .await?
+        .transaction
+        .meta
+        .unwrap()
+        .log_messages
+    );
+
+    // Check account data is what we expect
+    let accounts = rpc
+        .get_multiple_accounts_with_commitment(
+            &[profile.pubkey(), payout_allowlist.pubkey()],
+            CommitmentConfig::confirmed(),
+        )
+        .await?
+        .value;
+
+    let mut data = accounts[0].as_ref().unwrap().data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<PlayerProfile>>::compressed_discriminant()
+    );
+    let profile_data = PlayerProfile::deserialize(&mut data)?;
+    assert_eq!(profile_data.payout_allowlist, payout_allowlist.pubkey());
+
+    let mut data = accounts[1].as_ref().unwrap().data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<PayoutAllowlist>>::compressed_discriminant()
+    );
+    let allowlist = PayoutAllowlist::deserialize(&mut data)?;
+    assert_eq!(allowlist.profile, profile.pubkey());
+    assert_eq!(
+        allowlist.addresses,
+        PayoutAllowlist::address_array(&[payout_address]).unwrap()
+    );
+    assert_eq!(
+        allowlist.pending_addresses,
+        PayoutAllowlist::address_array(&[payout_address, new_payout_address]).unwrap()
+    );
+    assert!(allowlist.pending_effective > 0);
+    // The update is timelocked so only the original address is allowed now.
+    let now = allowlist.pending_effective - PayoutAllowlist::UPDATE_DELAY;
+    assert!(allowlist.is_allowed(&payout_address, now));
+    assert!(!allowlist.is_allowed(&new_payout_address, now));
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -0,0 +1,237 @@
+//! Transactions that should fail with a specific [`TutorialError`].
+
+use crate::instructions::{create_profiles, new_funder, setup_validator, wagered_game};
+use cruiser::prelude::*;
+use cruiser_tutorial::client::{send_instruction_set, RetryConfig, SendOutcome};
+use cruiser_tutorial::error::TutorialError;
+use cruiser_tutorial::instructions::{create_game, join_game, make_move, MakeMoveData};
+use cruiser_tutorial::pda::GameSignerSeeder;
+use std::error::Error;
+use std::time::Duration;
+
+/// Sends `instructions` paid for by `payer` and asserts the transaction fails its preflight simulation with `expected`.
+pub async fn expect_program_error<'a>(
+    rpc: &RpcClient,
+    payer: &'a Keypair,
+    instructions: InstructionSet<'a>,
+    expected: TutorialError,
+) -> Result<(), Box<dyn Error>> {
+    let mut instructions = Some(instructions);
+    let outcome = send_instruction_set(
+        rpc,
+        payer,
+        || instructions.take().expect("Sent once"),
+        RpcSendTransactionConfig {
+            skip_preflight: false,
+            preflight_commitment: Some(CommitmentLevel::Confirmed),
+            encoding: None,
+            max_retries: None,
+        },
+        CommitmentConfig::confirmed(),
+        RetryConfig {
+            max_attempts: 1,
+            ..RetryConfig::default()
+        },
+    )
+    .await?;
+    match outcome {
+        SendOutcome::ProgramFailed(sig, _, error) => {
+            assert_eq!(sig, None, "Transaction landed, expected a failed preflight");
+            assert_eq!(error, expected);
+            Ok(())
+        }
+        outcome => panic!(
+            "Transaction ended with {:?}, expected {:?}",
+            outcome, expected
+        ),
+    }
+}
+
+#[tokio::test]
+async fn program_error_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+
+    let funder = new_funder(rpc).await?;
+
+    // Start a game between two profiles, a third wants in too
+    let authorities = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let profiles = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let game = Keypair::new();
+    let game_signer_bump = GameSignerSeeder {
+        game: game.pubkey(),
+    }
+    .find_address(&program_id)
+    .1;
+
+    create_profiles(
+        &guard,
+        &funder,
+        &authorities.iter().zip(&profiles).collect::<Vec<_>>(),
+    )
+    .await?;
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            program_id,
+            &authorities[0],
+            profiles[0].pubkey(),
+            &game,
+            &funder,
+            &funder,
+            None,
+            wagered_game(LAMPORTS_PER_SOL),
+        ))
+        .signed_instructions(join_game(
+            program_id,
+            &authorities[1],
+            profiles[1].pubkey(),
+            game.pubkey(),
+            game_signer_bump,
+            &funder,
+            vec![],
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let first_move = MakeMoveData {
+        big_board: [0, 0],
+        middle_board: [0, 0],
+        small_board: [0, 0],
+    };
+
+    // Someone else's key can't move for player one
+    let stranger = Keypair::new();
+    expect_program_error(
+        rpc,
+        &funder,
+        make_move(
+            program_id,
+            &stranger,
+            profiles[0].pubkey(),
+            game.pubkey(),
+            first_move.clone(),
+        ),
+        TutorialError::AuthorityCantMove,
+    )
+    .await?;
+
+    // Player two can't move first
+    expect_program_error(
+        rpc,
+        &funder,
+        make_move(
+            program_id,
+            &authorities[1],
+            profiles[1].pubkey(),
+            game.pubkey(),
+            first_move.clone(),
+        ),
+        TutorialError::NotPlayersTurn,
+    )
+    .await?;
+
+    // Off the board and on a board the variant doesn't use
+    for illegal in [
+        MakeMoveData {
+            big_board: [0, 0],
+            middle_board: [0, 0],
+            small_board: [3, 0],
+        },
+        MakeMoveData {
+            big_board: [0, 0],
+            middle_board: [1, 1],
+            small_board: [0, 0],
+        },
+    ] {
+        expect_program_error(
+            rpc,
+            &funder,
+            make_move(
+                program_id,
+                &authorities[0],
+                profiles[0].pubkey(),
+                game.pubkey(),
+                illegal,
+            ),
+            TutorialError::InvalidMove,
+        )
+        .await?;
+    }
+
+    // The game is already joined
+    expect_program_error(
+        rpc,
+        &funder,
+        join_game(
+            program_id,
+            &authorities[2],
+            profiles[2].pubkey(),
+            game.pubkey(),
+            game_signer_bump,
+            &funder,
+            vec![],
+        ),
+        TutorialError::GameAlreadyJoined,
+    )
+    .await?;
+
+    // Once player one moves, the square is taken for player two
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(make_move(
+            program_id,
+            &authorities[0],
+            profiles[0].pubkey(),
+            game.pubkey(),
+            first_move.clone(),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    expect_program_error(
+        rpc,
+        &funder,
+        make_move(
+            program_id,
+            &authorities[1],
+            profiles[1].pubkey(),
+            game.pubkey(),
+            first_move,
+        ),
+        TutorialError::InvalidMove,
+    )
+    .await?;
+
+    guard.drop_self().await;
+    Ok(())
+}
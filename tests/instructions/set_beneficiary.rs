@@ -0,0 +1,104 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{Beneficiary, PlayerProfile};
+use cruiser_tutorial::instructions::set_beneficiary;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn set_beneficiary_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // Create random authority, profile, and beneficiary
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let beneficiary = Keypair::new().pubkey();
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // Send transaction
+    let (sig, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(set_beneficiary(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            beneficiary,
+            PlayerProfile::MIN_INACTIVITY_PERIOD,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Print logs for debugging
+    println!(
+        "Logs: {:#?}",
+        rpc.get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None
+            }
+        )
+        .await?
+        .transaction
+        .meta
+        .unwrap()
+        .log_messages
+    );
+
+    // Check account data is what we expect
+    let account = rpc
+        .get_account_with_commitment(&profile.pubkey(), CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<PlayerProfile>>::compressed_discriminant()
+    );
+    let profile = PlayerProfile::deserialize(&mut data)?;
+    // The beneficiary is timelocked so isn't active yet.
+    assert_eq!(profile.beneficiary, Beneficiary::default());
+    assert_eq!(
+        profile.pending_beneficiary,
+        Beneficiary {
+            key: beneficiary,
+            inactivity_period: PlayerProfile::MIN_INACTIVITY_PERIOD,
+        }
+    );
+    assert_eq!(
+        profile.pending_beneficiary_effective,
+        profile.last_active + PlayerProfile::BENEFICIARY_DELAY
+    );
+    assert!(!profile.is_claimable_by(&beneficiary, profile.last_active));
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::SocialGraph;
+use cruiser_tutorial::instructions::{create_social_graph, follow, unfollow};
+use cruiser_tutorial::pda::SocialGraphSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn social_graph_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    // Create a follower and two profiles to follow
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let friend1 = Keypair::new();
+    let friend2 = Keypair::new();
+
+    create_profiles(
+        &guard,
+        &funder,
+        &[
+            (&authority, &profile),
+            (&Keypair::new(), &friend1),
+            (&Keypair::new(), &friend2),
+        ],
+    )
+    .await?;
+
+    // Follow both then drop one
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_social_graph(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            &funder,
+        ))
+        .signed_instructions(follow(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            friend1.pubkey(),
+        ))
+        .signed_instructions(follow(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            friend2.pubkey(),
+        ))
+        .signed_instructions(unfollow(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            friend1.pubkey(),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Check account data is what we expect
+    let (social_graph, bump) = SocialGraphSeeder {
+        profile: profile.pubkey(),
+    }
+    .find_address(&guard.program_id());
+    let account = rpc
+        .get_account_with_commitment(&social_graph, CommitmentConfig::confirmed())
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<SocialGraph>>::compressed_discriminant()
+    );
+    let graph = SocialGraph::deserialize(&mut data)?;
+    let mut expected = SocialGraph::new(&profile.pubkey(), bump);
+    expected.following[1] = friend2.pubkey();
+    assert_eq!(graph, expected);
+
+    guard.drop_self().await;
+    Ok(())
+}
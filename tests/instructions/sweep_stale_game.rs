@@ -0,0 +1,143 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator, wagered_game};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::PlayerProfile;
+use cruiser_tutorial::instructions::*;
+use cruiser_tutorial::pda::GameSignerSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[tokio::test]
+async fn sweep_stale_game_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    let game = Keypair::new();
+    let game_signer_bump = GameSignerSeeder {
+        game: game.pubkey(),
+    }
+    .find_address(&guard.program_id())
+    .1;
+
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(create_game(
+            guard.program_id(),
+            &authority,
+            profile.pubkey(),
+            &game,
+            &funder,
+            &funder,
+            None,
+            CreateGameClientData {
+                join_ttl: 1, // 1 second
+                ..wagered_game(LAMPORTS_PER_SOL)
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Wait for nobody to join
+    // This value may need to be adjusted to be longer, we are working on very small timescales
+    sleep(Duration::from_millis(2000)).await;
+
+    // Only the funder pays and signs, sweeping needs no player
+    let (sig, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(sweep_stale_game(
+            guard.program_id(),
+            game.pubkey(),
+            game_signer_bump,
+            profile.pubkey(),
+            authority.pubkey(),
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: false,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+
+    // Check result
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Print logs for debugging
+    println!(
+        "Logs: {:#?}",
+        rpc.get_transaction_with_config(
+            &sig,
+            RpcTransactionConfig {
+                encoding: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: None
+            }
+        )
+        .await?
+        .transaction
+        .meta
+        .unwrap()
+        .log_messages
+    );
+
+    let accounts = rpc
+        .get_multiple_accounts_with_commitment(
+            &[game.pubkey(), authority.pubkey(), profile.pubkey()],
+            CommitmentConfig::confirmed(),
+        )
+        .await?
+        .value;
+    if let Some(game) = &accounts[0] {
+        assert_eq!(game.lamports, 0);
+        assert_eq!(game.owner, SystemProgram::<()>::KEY);
+    }
+    // Wager and game rent
+    let authority = accounts[1].as_ref().unwrap();
+    assert!(authority.lamports > LAMPORTS_PER_SOL);
+
+    let mut data = accounts[2].as_ref().unwrap().data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<PlayerProfile>>::compressed_discriminant()
+    );
+    let profile = PlayerProfile::deserialize(&mut data)?;
+    assert_eq!(profile.lamports_wagered, LAMPORTS_PER_SOL);
+    assert_eq!(profile.lamports_drawn_back, LAMPORTS_PER_SOL);
+
+    guard.drop_self().await;
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use crate::instructions::{create_profiles, new_funder, setup_validator_reset, wagered_game};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::{GameVariant, ProgramConfig};
+use cruiser_tutorial::instructions::{
+    create_game, update_config, CreateGameClientData, UpdateConfigData,
+};
+use cruiser_tutorial::pda::ConfigSeeder;
+use cruiser_tutorial::TutorialAccounts;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn update_config_test() -> Result<(), Box<dyn Error>> {
+    // Pausing would break other tests' games, so this gets the validator to itself
+    let guard = setup_validator_reset().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+
+    // Pause and bound wagers
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            guard.admin(),
+            &UpdateConfigData {
+                min_wager: Some(LAMPORTS_PER_SOL / 10),
+                max_wager: Some(LAMPORTS_PER_SOL),
+                paused: Some(true),
+                ..Default::default()
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    let account = rpc
+        .get_account_with_commitment(
+            &ConfigSeeder.find_address(&program_id).0,
+            CommitmentConfig::confirmed(),
+        )
+        .await?
+        .value
+        .unwrap_or_else(|| {
+            panic!("Account not found");
+        });
+    let mut data = account.data.as_slice();
+    let discriminant =
+        <TutorialAccounts as AccountList>::DiscriminantCompressed::deserialize(&mut data)?;
+    assert_eq!(
+        discriminant,
+        <TutorialAccounts as AccountListItem<ProgramConfig>>::compressed_discriminant()
+    );
+    let config = ProgramConfig::deserialize(&mut data)?;
+    assert!(config.paused);
+    assert_eq!(config.min_wager, LAMPORTS_PER_SOL / 10);
+    assert_eq!(config.max_wager, LAMPORTS_PER_SOL);
+
+    let games = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let new_game = |game, wager| {
+        create_game(
+            program_id,
+            &authority,
+            profile.pubkey(),
+            game,
+            &funder,
+            &funder,
+            None,
+            CreateGameClientData {
+                turn_length: 0,
+                variant: GameVariant::Classic,
+                ..wagered_game(wager)
+            },
+        )
+    };
+    let fails = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..send_config
+    };
+
+    // Paused
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(new_game(&games[0], LAMPORTS_PER_SOL))
+        .send_and_confirm_transaction(
+            rpc,
+            fails,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Game created while paused"
+    );
+
+    // Unpaused but over the wager bound
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            guard.admin(),
+            &UpdateConfigData {
+                paused: Some(false),
+                ..Default::default()
+            },
+        ))
+        .signed_instructions(new_game(&games[1], LAMPORTS_PER_SOL + 1))
+        .send_and_confirm_transaction(
+            rpc,
+            fails,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Game created over the max wager"
+    );
+
+    // Unpaused and in bounds
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            guard.admin(),
+            &UpdateConfigData {
+                paused: Some(false),
+                ..Default::default()
+            },
+        ))
+        .signed_instructions(new_game(&games[2], LAMPORTS_PER_SOL))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+
+    // Only the admin can update
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            &funder,
+            &UpdateConfigData {
+                paused: Some(true),
+                ..Default::default()
+            },
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            fails,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Non-admin updated the config"
+    );
+
+    guard.drop_self().await;
+    Ok(())
+}
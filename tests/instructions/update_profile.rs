@@ -0,0 +1,174 @@
+use crate::instructions::diff::fetch_account;
+use crate::instructions::{airdrop, create_profiles, new_funder, setup_validator, TestGuard};
+use cruiser::prelude::*;
+use cruiser_tutorial::accounts::PlayerProfile;
+use cruiser_tutorial::instructions::{update_profile, UpdateProfileData};
+use std::error::Error;
+use std::time::Duration;
+
+/// Sends `instructions` paid for by `funder`, returning how the transaction ended.
+async fn send(
+    rpc: &RpcClient,
+    funder: &Keypair,
+    instructions: InstructionSet<'_>,
+) -> Result<ConfirmationResult, Box<dyn Error>> {
+    let (_, result) = TransactionBuilder::new(funder)
+        .signed_instructions(instructions)
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                preflight_commitment: Some(CommitmentLevel::Confirmed),
+                encoding: None,
+                max_retries: None,
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    Ok(result)
+}
+
+/// Sets the metadata URI of `profile` to `uri`, with `funder` paying for a longer URI if `funded`.
+async fn set_uri(
+    guard: &TestGuard,
+    funder: &Keypair,
+    authority: &Keypair,
+    profile: Pubkey,
+    uri: String,
+    funded: bool,
+) -> Result<ConfirmationResult, Box<dyn Error>> {
+    send(
+        guard.rpc(),
+        funder,
+        update_profile(
+            guard.program_id(),
+            authority,
+            profile,
+            funded.then(|| funder.into()),
+            UpdateProfileData {
+                metadata_uri: Some(uri),
+                ..Default::default()
+            },
+        ),
+    )
+    .await
+}
+
+/// The data length and lamports of the `key` account.
+async fn size_and_lamports(rpc: &RpcClient, key: &Pubkey) -> Result<(usize, u64), Box<dyn Error>> {
+    let account = rpc
+        .get_account_with_commitment(key, CommitmentConfig::confirmed())
+        .await?
+        .value
+        .ok_or("Account not found")?;
+    Ok((account.data.len(), account.lamports))
+}
+
+/// The lamports of the `key` account.
+async fn balance(rpc: &RpcClient, key: &Pubkey) -> Result<u64, Box<dyn Error>> {
+    Ok(rpc
+        .get_balance_with_commitment(key, CommitmentConfig::confirmed())
+        .await?
+        .value)
+}
+
+#[tokio::test]
+async fn update_profile_metadata_uri_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+
+    let funder = new_funder(rpc).await?;
+
+    let authority = Keypair::new();
+    let profile = Keypair::new();
+    create_profiles(&guard, &funder, &[(&authority, &profile)]).await?;
+    // The authority gets the refunds, give it enough to stay rent exempt.
+    airdrop(rpc, &authority.pubkey(), LAMPORTS_PER_SOL).await?;
+    let (created_size, created_lamports) = size_and_lamports(rpc, &profile.pubkey()).await?;
+
+    // Growing the profile needs the funder
+    let uri = "https://example.com/profile.json".to_string();
+    let result = set_uri(
+        &guard,
+        &funder,
+        &authority,
+        profile.pubkey(),
+        uri.clone(),
+        false,
+    )
+    .await?;
+    assert!(matches!(result, ConfirmationResult::Failure(_)));
+
+    // Setting a URI grows the profile to fit it, the funder pays the rent
+    for uri in [uri.clone(), uri.clone() + "?size=large"] {
+        match set_uri(
+            &guard,
+            &funder,
+            &authority,
+            profile.pubkey(),
+            uri.clone(),
+            true,
+        )
+        .await?
+        {
+            ConfirmationResult::Success => {}
+            ConfirmationResult::Failure(error) => return Err(error.into()),
+            ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+        }
+        let (size, lamports) = size_and_lamports(rpc, &profile.pubkey()).await?;
+        assert_eq!(size, created_size + uri.len());
+        assert_eq!(
+            lamports,
+            rpc.get_minimum_balance_for_rent_exemption(size).await?
+        );
+        let profile_data: PlayerProfile = fetch_account(rpc, &profile.pubkey()).await?;
+        assert_eq!(&*profile_data.metadata_uri, uri);
+    }
+
+    // A URI that's too long is rejected
+    let result = set_uri(
+        &guard,
+        &funder,
+        &authority,
+        profile.pubkey(),
+        "a".repeat(PlayerProfile::MAX_METADATA_URI_LEN + 1),
+        true,
+    )
+    .await?;
+    assert!(matches!(result, ConfirmationResult::Failure(_)));
+
+    // Shrinking and then clearing it shrinks the profile back, the rent goes back to the authority
+    for uri in [uri.clone(), String::new()] {
+        let (_, lamports_before) = size_and_lamports(rpc, &profile.pubkey()).await?;
+        let authority_before = balance(rpc, &authority.pubkey()).await?;
+        match set_uri(
+            &guard,
+            &funder,
+            &authority,
+            profile.pubkey(),
+            uri.clone(),
+            false,
+        )
+        .await?
+        {
+            ConfirmationResult::Success => {}
+            ConfirmationResult::Failure(error) => return Err(error.into()),
+            ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+        }
+        let (size, lamports) = size_and_lamports(rpc, &profile.pubkey()).await?;
+        assert_eq!(size, created_size + uri.len());
+        assert_eq!(
+            balance(rpc, &authority.pubkey()).await?,
+            authority_before + lamports_before - lamports
+        );
+    }
+    assert_eq!(
+        size_and_lamports(rpc, &profile.pubkey()).await?,
+        (created_size, created_lamports)
+    );
+
+    guard.drop_self().await;
+    Ok(())
+}
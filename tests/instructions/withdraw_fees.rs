@@ -0,0 +1,94 @@
+use crate::instructions::{airdrop, setup_validator};
+use cruiser::prelude::*;
+use cruiser_tutorial::instructions::{update_config, withdraw_fees, UpdateConfigData};
+use cruiser_tutorial::pda::FeeVaultSeeder;
+use std::error::Error;
+use std::time::Duration;
+
+#[tokio::test]
+async fn withdraw_fees_test() -> Result<(), Box<dyn Error>> {
+    let guard = setup_validator().await;
+
+    let rpc = guard.rpc();
+    let program_id = guard.program_id();
+    let funder = Keypair::new();
+    let treasury = Keypair::new();
+    let (fee_vault, _) = FeeVaultSeeder.find_address(&program_id);
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: false,
+        preflight_commitment: Some(CommitmentLevel::Confirmed),
+        encoding: None,
+        max_retries: None,
+    };
+
+    // Airdrop SOL to the funder and stand in for collected fees
+    for to in [funder.pubkey(), fee_vault] {
+        airdrop(rpc, &to, LAMPORTS_PER_SOL).await?;
+    }
+
+    // No treasury is set yet
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(withdraw_fees(
+            program_id,
+            guard.admin(),
+            treasury.pubkey(),
+            LAMPORTS_PER_SOL,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..send_config
+            },
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    assert!(
+        matches!(result, ConfirmationResult::Failure(_)),
+        "Fees were withdrawn without a treasury"
+    );
+
+    let (_, result) = TransactionBuilder::new(&funder)
+        .signed_instructions(update_config(
+            program_id,
+            guard.admin(),
+            &UpdateConfigData {
+                treasury: Some(treasury.pubkey()),
+                ..Default::default()
+            },
+        ))
+        .signed_instructions(withdraw_fees(
+            program_id,
+            guard.admin(),
+            treasury.pubkey(),
+            LAMPORTS_PER_SOL,
+        ))
+        .send_and_confirm_transaction(
+            rpc,
+            send_config,
+            CommitmentConfig::confirmed(),
+            Duration::from_millis(500),
+        )
+        .await?;
+    match result {
+        ConfirmationResult::Success => {}
+        ConfirmationResult::Failure(error) => return Err(error.into()),
+        ConfirmationResult::Dropped => return Err("Transaction dropped".into()),
+    }
+    assert_eq!(
+        rpc.get_balance_with_commitment(&treasury.pubkey(), CommitmentConfig::confirmed())
+            .await?
+            .value,
+        LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        rpc.get_balance_with_commitment(&fee_vault, CommitmentConfig::confirmed())
+            .await?
+            .value,
+        0
+    );
+
+    guard.drop_self().await;
+    Ok(())
+}